@@ -0,0 +1,324 @@
+mod requests;
+
+use crate::requests::{AuditLogResponse, AuditQueryParams};
+
+use shared::audit_logger::audit_table_name;
+use shared::aws::lambda_events::{
+    middleware::preferred_language,
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{DefaultClientManager, DynamoDbClientManager};
+use shared::entity::audit_event::AuditAction;
+use shared::entity::user::{Role, User};
+use shared::errors::{LambdaError, LambdaResult};
+use shared::pagination::decode_token;
+use shared::repository::audit_repository::{AuditRepository, AuditRepositoryImpl};
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, instrument, warn};
+
+/// Page size when `limit` is omitted
+const DEFAULT_LIMIT: i32 = 50;
+/// Largest page size a caller may request
+const MAX_LIMIT: i32 = 100;
+/// Time range when `from` is omitted: the last 24 hours
+const DEFAULT_RANGE_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Organization admins may read their own organization's audit log; SuperAdmins any
+fn check_audit_access(caller: &User, organization_id: &str) -> LambdaResult<()> {
+    let is_org_admin =
+        caller.roles.contains(&Role::Admin) && caller.organization_id == organization_id;
+    if is_org_admin || caller.roles.contains(&Role::SuperAdmin) {
+        Ok(())
+    } else {
+        warn!(
+            "User {} is not allowed to read the audit log of {}",
+            caller.id, organization_id
+        );
+        Err(LambdaError::InsufficientPermissions)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(
+    request: &ApiGatewayProxyRequest,
+    name: &str,
+) -> LambdaResult<Option<T>> {
+    request
+        .query_string_parameters
+        .first(name)
+        .map(|value| {
+            value.parse::<T>().map_err(|_| {
+                LambdaError::InvalidRequest(format!("{name} must be a number, got: {value}"))
+            })
+        })
+        .transpose()
+}
+
+/// Parse `?from=&to=&action=&limit=&nextToken=`, defaulting to the last 24 hours up to `now`
+fn parse_query_params(
+    request: &ApiGatewayProxyRequest,
+    now: u64,
+) -> LambdaResult<AuditQueryParams> {
+    let to = parse_number::<u64>(request, "to")?.unwrap_or(now);
+    let from = parse_number::<u64>(request, "from")?.unwrap_or(to.saturating_sub(DEFAULT_RANGE_MS));
+    if from > to {
+        return Err(LambdaError::InvalidRequest(
+            "from must not be after to".to_string(),
+        ));
+    }
+
+    let action = match request.query_string_parameters.first("action") {
+        Some(action) => {
+            let action: AuditAction = action.parse().map_err(|_| {
+                LambdaError::InvalidRequest(format!("unknown audit action: {action}"))
+            })?;
+            Some(action.to_string())
+        }
+        None => None,
+    };
+
+    let limit = parse_number::<i32>(request, "limit")?.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(LambdaError::InvalidRequest(format!(
+            "limit must be between 1 and {MAX_LIMIT}"
+        )));
+    }
+
+    let next_token = request
+        .query_string_parameters
+        .first("nextToken")
+        .map(str::to_string);
+    if let Some(token) = &next_token {
+        decode_token(token)
+            .map_err(|e| LambdaError::InvalidRequest(format!("invalid nextToken: {e}")))?;
+    }
+
+    Ok(AuditQueryParams {
+        from,
+        to,
+        action,
+        limit,
+        next_token,
+    })
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.admin.audit.list_audit_events_handler")]
+async fn list_audit_events_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+    let organization_id = event
+        .payload
+        .path_parameters
+        .get("organizationId")
+        .cloned()
+        .ok_or(LambdaError::MissingOrganizationId)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::from(LambdaError::internal("read system clock", e)))?
+        .as_millis() as u64;
+    let params = match parse_query_params(&event.payload, now) {
+        Ok(params) => params,
+        Err(e) => return create_error_response(e, &event.payload),
+    };
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+
+    // Permission check
+    let caller = repository
+        .get_user_by_id(user_id.clone(), false)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+    if let Err(e) = check_audit_access(&caller, &organization_id) {
+        return create_error_response(e, &event.payload);
+    }
+
+    let page = audit_repository
+        .query(
+            &organization_id,
+            params.from,
+            params.to,
+            params.action,
+            params.limit,
+            params.next_token,
+        )
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(e, |detail| {
+                LambdaError::internal("query audit events", detail)
+            }))
+        })?;
+    debug!("Returning {} audit events", page.events.len());
+
+    let response = AuditLogResponse {
+        events: page.events,
+        next_token: page.next_token,
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.admin.audit.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations/{organizationId}/audit",
+        list_audit_events_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting admin audit function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lambda_events::query_map::QueryMap;
+    use shared::pagination::encode_token;
+    use std::collections::{HashMap, HashSet};
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn create_test_user(organization_id: &str, role: Role) -> User {
+        User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            organization_id.to_string(),
+            "Example".to_string(),
+            HashSet::from([role]),
+        )
+    }
+
+    fn create_test_request(params: &[(&str, &str)]) -> ApiGatewayProxyRequest {
+        let params: HashMap<String, String> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ApiGatewayProxyRequest {
+            query_string_parameters: QueryMap::from(params),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_org_admin_and_super_admin_can_read_audit_log() {
+        assert!(check_audit_access(&create_test_user("org-1", Role::Admin), "org-1").is_ok());
+        assert!(check_audit_access(&create_test_user("org-2", Role::SuperAdmin), "org-1").is_ok());
+    }
+
+    #[test]
+    fn test_others_cannot_read_audit_log() {
+        for role in [Role::Reader, Role::Writer] {
+            assert!(matches!(
+                check_audit_access(&create_test_user("org-1", role), "org-1"),
+                Err(LambdaError::InsufficientPermissions)
+            ));
+        }
+        assert!(matches!(
+            check_audit_access(&create_test_user("org-2", Role::Admin), "org-1"),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+    }
+
+    #[test]
+    fn test_time_range_defaults_to_last_day() {
+        let params = parse_query_params(&create_test_request(&[]), NOW).unwrap();
+        assert_eq!(params.to, NOW);
+        assert_eq!(params.from, NOW - DEFAULT_RANGE_MS);
+        assert_eq!(params.limit, DEFAULT_LIMIT);
+        assert_eq!(params.action, None);
+    }
+
+    #[test]
+    fn test_time_range_and_action_filters() {
+        let request = create_test_request(&[
+            ("from", "1000"),
+            ("to", "2000"),
+            ("action", "DeleteUser"),
+            ("limit", "10"),
+        ]);
+        let params = parse_query_params(&request, NOW).unwrap();
+        assert_eq!((params.from, params.to), (1000, 2000));
+        assert_eq!(params.action.as_deref(), Some("DeleteUser"));
+        assert_eq!(params.limit, 10);
+    }
+
+    #[test]
+    fn test_invalid_filters_are_rejected() {
+        for params in [
+            vec![("from", "2000"), ("to", "1000")],
+            vec![("from", "yesterday")],
+            vec![("action", "Reboot")],
+            vec![("limit", "0")],
+            vec![("limit", "1000")],
+            vec![("nextToken", "not a token!")],
+        ] {
+            assert!(matches!(
+                parse_query_params(&create_test_request(&params), NOW),
+                Err(LambdaError::InvalidRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_next_token_is_passed_through() {
+        let token = encode_token(&HashMap::new()).unwrap();
+        let params =
+            parse_query_params(&create_test_request(&[("nextToken", &token)]), NOW).unwrap();
+        assert_eq!(params.next_token, Some(token));
+    }
+}
@@ -0,0 +1,22 @@
+use shared::entity::audit_event::AuditEvent;
+
+use serde::{Deserialize, Serialize};
+
+/// Filters parsed from the query string
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct AuditQueryParams {
+    /// Start of the time range, milliseconds since the Unix epoch (inclusive)
+    pub from: u64,
+    /// End of the time range, milliseconds since the Unix epoch (inclusive)
+    pub to: u64,
+    pub action: Option<String>,
+    pub limit: i32,
+    pub next_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct AuditLogResponse {
+    pub events: Vec<AuditEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
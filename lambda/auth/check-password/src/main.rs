@@ -0,0 +1,166 @@
+mod requests;
+
+use crate::requests::{CheckPasswordRequest, CheckPasswordResponse};
+
+use shared::aws::lambda_events::{
+    middleware::{with_body, with_standard_error_handling},
+    request::LambdaEventRequestHandler,
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{DefaultClientManager, DynamoDbClientManager};
+use shared::config::get_config;
+use shared::errors::LambdaError;
+use shared::session_store::{check_rate_limit, SessionStore};
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use tracing::{debug, info, instrument};
+
+/// Rate limit key for an unauthenticated caller, by source IP
+fn rate_limit_key(request: &ApiGatewayProxyRequest) -> String {
+    let source_ip = request
+        .request_context
+        .identity
+        .source_ip
+        .as_deref()
+        .unwrap_or("unknown");
+    format!("check-password#{source_ip}")
+}
+
+#[instrument(
+    skip(check_request),
+    name = "lambda.auth.check_password.check_password_handler"
+)]
+async fn check_password_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+    check_request: CheckPasswordRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let session_store = SessionStore::from_env((*dynamodb_client).clone());
+
+    // The endpoint is public, so limit per source IP; handle_requests turns this into a 429
+    let config = get_config();
+    if !check_rate_limit(
+        session_store.as_ref(),
+        &rate_limit_key(&event.payload),
+        config.rate_limit_max,
+        config.rate_limit_window,
+    )
+    .await
+    {
+        return Err(Error::from(LambdaError::Throttled));
+    }
+
+    let response = CheckPasswordResponse::check(&config.password_policy, &check_request.password);
+    debug!("password check failed rules: {:?}", response.failed_rules);
+
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.auth.check_password.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/check-password",
+        with_standard_error_handling(with_body(check_password_handler)),
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting auth check password function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::utils::password::PasswordPolicy;
+    use shared::validation::FieldErrorCode;
+
+    fn strict_policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 12,
+            require_symbol: true,
+            allow_spaces: false,
+            ..PasswordPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_strong_password_is_valid() {
+        let response = CheckPasswordResponse::check(&strict_policy(), "Correct-Horse-42");
+
+        assert!(response.valid);
+        assert!(response.failed_rules.is_empty());
+        assert!(response.strength > 50);
+    }
+
+    #[test]
+    fn test_each_rule_is_reported_distinctly() {
+        let policy = strict_policy();
+        let cases = [
+            ("Short-1a", FieldErrorCode::PasswordTooShort),
+            (
+                "Correct Horse-42",
+                FieldErrorCode::PasswordContainsWhitespace,
+            ),
+            ("correct-horse-42", FieldErrorCode::PasswordMissingUppercase),
+            ("CORRECT-HORSE-42", FieldErrorCode::PasswordMissingLowercase),
+            ("Correct-Horse-xy", FieldErrorCode::PasswordMissingDigit),
+            ("CorrectHorse4242", FieldErrorCode::PasswordMissingSymbol),
+        ];
+
+        for (password, rule) in cases {
+            let response = CheckPasswordResponse::check(&policy, password);
+            assert!(!response.valid, "{password} should be invalid");
+            assert_eq!(response.failed_rules, vec![rule], "{password}");
+        }
+    }
+
+    #[test]
+    fn test_failed_rules_use_field_error_codes() {
+        let response = CheckPasswordResponse::check(&PasswordPolicy::default(), "abc");
+        let body = serde_json::to_value(&response).unwrap();
+
+        assert_eq!(body["valid"], false);
+        assert_eq!(
+            body["failed_rules"],
+            serde_json::json!([
+                "PASSWORD_TOO_SHORT",
+                "PASSWORD_MISSING_UPPERCASE",
+                "PASSWORD_MISSING_DIGIT"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_key_is_per_source_ip() {
+        let mut request = ApiGatewayProxyRequest::default();
+        assert_eq!(rate_limit_key(&request), "check-password#unknown");
+
+        request.request_context.identity.source_ip = Some("203.0.113.7".to_string());
+        assert_eq!(rate_limit_key(&request), "check-password#203.0.113.7");
+    }
+}
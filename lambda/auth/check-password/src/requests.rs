@@ -0,0 +1,38 @@
+use shared::errors::LambdaError;
+use shared::utils::password::{password_strength, PasswordPolicy};
+use shared::validation::{FieldErrorCode, Validate};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+pub(super) struct CheckPasswordRequest {
+    pub password: String,
+}
+
+impl Validate for CheckPasswordRequest {
+    /// Any password can be checked; broken rules are reported in the response instead
+    fn validate(&self) -> Result<(), LambdaError> {
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub(super) struct CheckPasswordResponse {
+    pub valid: bool,
+    /// Every policy rule the password breaks, empty when it is valid
+    pub failed_rules: Vec<FieldErrorCode>,
+    /// Heuristic strength from 0 to 100, for strength meters
+    pub strength: u8,
+}
+
+impl CheckPasswordResponse {
+    /// Check a password against the policy without creating anything
+    pub fn check(policy: &PasswordPolicy, password: &str) -> Self {
+        let failed_rules = policy.check(password);
+        Self {
+            valid: failed_rules.is_empty(),
+            failed_rules,
+            strength: password_strength(password),
+        }
+    }
+}
@@ -1,15 +1,21 @@
 mod requests;
 
-use crate::requests::{LoginRequest, LoginResponse};
+use crate::requests::{LoginChallengeResponse, LoginRequest, LoginResponse};
 
-use shared::aws::lambda_events::{request::LambdaEventRequestHandler, response::apigw_response};
+use shared::aws::lambda_events::{
+    middleware::{with_body, with_standard_error_handling},
+    request::LambdaEventRequestHandler,
+    response::apigw_response,
+};
 use shared::cache_manager::get_cache_manager;
 use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
-use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
+use shared::entity::user::User;
+use shared::errors::{LambdaError, LambdaResult};
 use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
 use shared::utils::env::get_env;
 
 use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_sdk_cognitoidentityprovider::operation::initiate_auth::InitiateAuthOutput;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 use lambda_runtime::{service_fn, Error, LambdaEvent};
 use serde::{Deserialize, Serialize};
@@ -40,6 +46,40 @@ fn extract_user_id_from_token(token: &str) -> Result<String, Box<dyn std::error:
     Ok(token_data.claims.sub)
 }
 
+/// Build the challenge response for an `initiate_auth` result that did not return tokens
+fn build_challenge_response(output: &InitiateAuthOutput) -> Option<LoginChallengeResponse> {
+    let challenge_name = output.challenge_name()?;
+    let code_delivery_destination = output
+        .challenge_parameters()
+        .and_then(|params| params.get("CODE_DELIVERY_DESTINATION"))
+        .cloned();
+
+    Some(LoginChallengeResponse {
+        challenge_name: challenge_name.as_str().to_string(),
+        session: output.session().map(str::to_string),
+        code_delivery_destination,
+    })
+}
+
+/// Cognito username to authenticate: the stored user's if one was found for the email,
+/// otherwise the request's (the normalized email by default)
+fn login_username(request: &LoginRequest, stored_user: Option<&User>) -> String {
+    match stored_user {
+        Some(user) => user.cognito_username().to_string(),
+        None => request.cognito_username(),
+    }
+}
+
+/// Reject users that are suspended, e.g. because their organization is suspended
+fn ensure_active(user: &User) -> LambdaResult<()> {
+    if user.is_suspended() {
+        info!("Rejecting login for suspended user: {}", user.id);
+        Err(LambdaError::UserSuspended)
+    } else {
+        Ok(())
+    }
+}
+
 /// Calculate hash with improved caching
 async fn calculate_hash_with_cache(
     client: &shared::aws::cognito::client::CognitoClient,
@@ -57,7 +97,7 @@ async fn calculate_hash_with_cache(
     let hash = client
         .calculate_hash(username.to_string())
         .await
-        .map_err(|e| LambdaError::InternalError(e.to_string()))?;
+        .map_err(|e| LambdaError::internal("calculate secret hash", e))?;
 
     cache_manager
         .set_hash(username.to_string(), hash.clone())
@@ -65,40 +105,12 @@ async fn calculate_hash_with_cache(
     Ok(hash)
 }
 
-/// Create standardized error response
-fn create_error_response(error: LambdaError) -> Result<ApiGatewayProxyResponse, Error> {
-    let error_response = serde_json::json!({
-        "error": error.to_string(),
-        "message": error.user_message()
-    });
-
-    Ok(apigw_response(
-        error.status_code(),
-        Some(serde_json::to_string(&error_response)?.into()),
-        None,
-    ))
-}
-
-#[instrument(name = "lambda.auth.login.login_handler")]
+#[instrument(skip(login_request), name = "lambda.auth.login.login_handler")]
 async fn login_handler(
-    event: LambdaEvent<ApiGatewayProxyRequest>,
+    _event: LambdaEvent<ApiGatewayProxyRequest>,
+    login_request: LoginRequest,
 ) -> Result<ApiGatewayProxyResponse, Error> {
-    let client_manager = DefaultClientManager::new("ap-northeast-1".to_string());
-
-    // Zero-copy deserialization and validation
-    let body = event
-        .payload
-        .body
-        .as_deref()
-        .ok_or_else(|| Error::from(LambdaError::MissingBody))?;
-
-    let login_request: LoginRequest =
-        serde_json::from_slice(body.as_bytes()).map_err(|e| Error::from(e.to_lambda_error()))?;
-
-    // Validation
-    if let Err(e) = login_request.validate() {
-        return create_error_response(e);
-    }
+    let client_manager = DefaultClientManager::from_env();
 
     // Get clients using abstraction with explicit trait disambiguation
     let cognito_client = CognitoClientManager::get_client(&client_manager)
@@ -108,18 +120,34 @@ async fn login_handler(
         .await
         .map_err(Error::from)?;
 
-    // Use email as username for Cognito authentication
-    let username = login_request.email.clone();
-    let hash = calculate_hash_with_cache(&cognito_client, &username)
-        .await
-        .map_err(Error::from)?;
-
     // Setup user repository
     let table_name = get_env("TABLE_NAME", "Users");
     let user_repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
 
+    // Without an explicit username, use the one stored for the email, since older users were
+    // created in Cognito with the email as entered rather than normalized
+    let stored_user = match login_request.cognito_username {
+        Some(_) => None,
+        None => user_repository
+            .find_user_by_email(&login_request.email_lower())
+            .await
+            .map_err(|e| {
+                Error::from(LambdaError::from_repository_error(
+                    e,
+                    LambdaError::UserRetrievalFailed,
+                ))
+            })?,
+    };
+    let username = login_username(&login_request, stored_user.as_ref());
+    let hash = calculate_hash_with_cache(&cognito_client, &username).await?;
+
     match cognito_client
-        .user_login(username, login_request.email, login_request.password, hash)
+        .user_login(
+            username,
+            login_request.email_lower(),
+            login_request.password,
+            hash,
+        )
         .await
     {
         Ok(opt) => match opt.authentication_result() {
@@ -131,13 +159,18 @@ async fn login_handler(
 
                 // Parse JWT to get sub (user_id)
                 let user_id = extract_user_id_from_token(id_token)
-                    .map_err(|e| Error::from(LambdaError::InternalError(e.to_string())))?;
+                    .map_err(|e| Error::from(LambdaError::internal("read sub from id token", e)))?;
 
                 // Get user information from DynamoDB
                 let user = user_repository
-                    .get_user_by_id(user_id.clone())
+                    .get_user_by_id(user_id.clone(), false)
                     .await
                     .map_err(|_e| Error::from(LambdaError::UserNotFound))?;
+                ensure_active(&user).map_err(Error::from)?;
+                if !user.email_verified {
+                    // Login still succeeds; the response tells the client to prompt for verification
+                    info!("User {} logged in with an unverified email", user.id);
+                }
 
                 let response = LoginResponse {
                     access_token: result
@@ -153,6 +186,7 @@ async fn login_handler(
                         .to_string(),
                     user_id: user.id,
                     organization_id: user.organization_id,
+                    email_verified: user.email_verified,
                 };
                 Ok(apigw_response(
                     200,
@@ -160,12 +194,21 @@ async fn login_handler(
                     None,
                 ))
             }
-            None => {
-                debug!("Authentication result is None");
-                create_error_response(LambdaError::InternalError(
-                    "Failed to authenticate".to_string(),
-                ))
-            }
+            None => match build_challenge_response(&opt) {
+                // e.g. SMS_MFA: the client must answer the challenge with the session
+                Some(challenge) => {
+                    debug!("Authentication challenge: {}", challenge.challenge_name);
+                    Ok(apigw_response(
+                        200,
+                        Some(serde_json::to_string(&challenge)?.into()),
+                        None,
+                    ))
+                }
+                None => {
+                    debug!("Authentication result is None");
+                    Err(LambdaError::InternalError("Failed to authenticate".to_string()).into())
+                }
+            },
         },
         Err(e) => {
             let error = if e.to_string().contains("NotAuthorizedException") {
@@ -174,9 +217,11 @@ async fn login_handler(
                 LambdaError::UserNotFound
             } else {
                 debug!("Login error: {:?}", e);
-                LambdaError::InternalError(e.to_string())
+                LambdaError::from_cognito_error(e, |detail| {
+                    LambdaError::internal("authenticate user", detail)
+                })
             };
-            create_error_response(error)
+            Err(error.into())
         }
     }
 }
@@ -186,7 +231,14 @@ async fn handler(
     event: LambdaEvent<ApiGatewayProxyRequest>,
 ) -> Result<ApiGatewayProxyResponse, Error> {
     debug!("handling lambda req: {:?}", event);
-    LambdaEventRequestHandler::handle_requests(event, "/login", login_handler).await
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/login",
+        with_standard_error_handling(with_body(login_handler)),
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
 }
 
 // Custom allocator configuration
@@ -197,5 +249,102 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 async fn main() -> Result<(), Error> {
     shared::tracer::init_tracing();
     info!("Starting auth user login function");
-    lambda_runtime::run(service_fn(handler)).await
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_cognitoidentityprovider::types::ChallengeNameType;
+    use shared::entity::user::{Role, UserStatus};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_build_challenge_response_surfaces_sms_mfa() {
+        let output = InitiateAuthOutput::builder()
+            .challenge_name(ChallengeNameType::SmsMfa)
+            .session("session-token")
+            .challenge_parameters("CODE_DELIVERY_DESTINATION", "+*******1234")
+            .challenge_parameters("CODE_DELIVERY_DELIVERY_MEDIUM", "SMS")
+            .build();
+
+        assert_eq!(
+            build_challenge_response(&output),
+            Some(LoginChallengeResponse {
+                challenge_name: "SMS_MFA".to_string(),
+                session: Some("session-token".to_string()),
+                code_delivery_destination: Some("+*******1234".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_challenge_response_without_challenge() {
+        let output = InitiateAuthOutput::builder().build();
+        assert_eq!(build_challenge_response(&output), None);
+    }
+
+    fn create_test_user() -> User {
+        User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "ExampleOrg".to_string(),
+            HashSet::from([Role::Reader]),
+        )
+    }
+
+    #[test]
+    fn test_login_username_prefers_the_stored_user() {
+        let request = LoginRequest {
+            email: "Alice@Example.com".to_string(),
+            password: "Password123".to_string(),
+            cognito_username: None,
+        };
+        assert_eq!(login_username(&request, None), "alice@example.com");
+
+        // Created before usernames were normalized, so Cognito knows it by the email as entered
+        let mut user = create_test_user();
+        user.email = "Alice@Example.com".to_string();
+        assert_eq!(login_username(&request, Some(&user)), "Alice@Example.com");
+
+        let user = create_test_user().with_cognito_username(Some("alice".to_string()));
+        assert_eq!(login_username(&request, Some(&user)), "alice");
+    }
+
+    #[test]
+    fn test_active_user_can_login() {
+        assert!(ensure_active(&create_test_user()).is_ok());
+    }
+
+    #[test]
+    fn test_suspended_user_is_rejected() {
+        let mut user = create_test_user();
+        user.status = UserStatus::Suspended;
+
+        let error = ensure_active(&user).unwrap_err();
+        assert!(matches!(error, LambdaError::UserSuspended));
+        assert_eq!(error.status_code(), 403);
+    }
+
+    #[test]
+    fn test_unverified_user_can_login() {
+        let user = create_test_user();
+        assert!(!user.email_verified);
+        assert!(ensure_active(&user).is_ok());
+
+        let response = LoginResponse {
+            access_token: "access".to_string(),
+            id_token: "id".to_string(),
+            refresh_token: "refresh".to_string(),
+            user_id: user.id,
+            organization_id: user.organization_id,
+            email_verified: user.email_verified,
+        };
+        let body = serde_json::to_value(&response).unwrap();
+        assert_eq!(body["email_verified"], false);
+    }
 }
@@ -1,5 +1,6 @@
 use shared::errors::LambdaError;
-use shared::utils::regex::EMAIL_REGEX;
+use shared::utils::regex::{COGNITO_USERNAME_REGEX, EMAIL_REGEX};
+use shared::validation::{normalize_email, FieldErrorCode, Validate, ValidationErrors};
 
 use serde::{Deserialize, Serialize};
 
@@ -7,24 +8,59 @@ use serde::{Deserialize, Serialize};
 pub(super) struct LoginRequest {
     pub email: String,
     pub password: String,
+    /// Cognito username when it differs from the email
+    #[serde(default)]
+    pub cognito_username: Option<String>,
 }
 
-impl LoginRequest {
-    pub fn validate(&self) -> Result<(), LambdaError> {
+impl Validate for LoginRequest {
+    fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+
         // Email validation
         if !EMAIL_REGEX.is_match(&self.email) {
-            return Err(LambdaError::InvalidEmail);
+            errors.add("email", FieldErrorCode::EmailInvalid);
+        }
+
+        // Cognito username validation
+        if let Some(cognito_username) = &self.cognito_username {
+            if !COGNITO_USERNAME_REGEX.is_match(cognito_username) {
+                errors.add("cognito_username", FieldErrorCode::CognitoUsernameInvalid);
+            }
         }
 
         // Password validation
         if self.password.len() < 8 {
-            return Err(LambdaError::InvalidPassword);
+            errors.add("password", FieldErrorCode::PasswordTooShort);
         }
 
-        Ok(())
+        errors.into_result()
+    }
+}
+
+impl LoginRequest {
+    /// Username used for Cognito operations, defaulting to the normalized email
+    pub fn cognito_username(&self) -> String {
+        self.cognito_username
+            .clone()
+            .unwrap_or_else(|| self.email_lower())
+    }
+
+    /// Email normalized for lookups and Cognito, which compare emails case-insensitively
+    pub fn email_lower(&self) -> String {
+        normalize_email(&self.email)
     }
 }
 
+/// Returned instead of tokens when Cognito requires another authentication step
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub(super) struct LoginChallengeResponse {
+    pub challenge_name: String,
+    pub session: Option<String>,
+    /// Masked destination the MFA code was sent to (e.g. `+*******1234`)
+    pub code_delivery_destination: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub(super) struct LoginResponse {
     pub access_token: String,
@@ -32,4 +68,6 @@ pub(super) struct LoginResponse {
     pub refresh_token: String,
     pub user_id: String,
     pub organization_id: String,
+    /// False when the client should prompt the user to verify their email
+    pub email_verified: bool,
 }
@@ -2,12 +2,19 @@ mod requests;
 
 use crate::requests::{SignupRequest, SignupResponse};
 
-use shared::aws::lambda_events::{request::LambdaEventRequestHandler, response::apigw_response};
+use shared::aws::cognito::client::CognitoClient;
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, is_validate_only, preferred_language, validate_only},
+    request::LambdaEventRequestHandler,
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
 use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
 use shared::entity::user::{Role, User};
-use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
+use shared::errors::{error_chain, LambdaError, LambdaResult, ToLambdaError};
 use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
-use shared::utils::{env::get_env, uuid::generate_uuid};
+use shared::utils::{env::get_env, password::password_strength, uuid::generate_uuid};
+use shared::validation::Validate;
 
 use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
 use lambda_runtime::{service_fn, Error, LambdaEvent};
@@ -26,8 +33,11 @@ async fn generate_new_user(
     let organization_id = match repository
         .find_organization_id_by_name(&request.organization_name)
         .await
-        .map_err(|e| LambdaError::InternalError(e.to_string()))?
-    {
+        .map_err(|e| {
+            LambdaError::from_repository_error(e, |detail| {
+                LambdaError::internal("look up organization", detail)
+            })
+        })? {
         Some(existing_org_id) => {
             info!("Found existing organization: {}", existing_org_id);
             roles.insert(Role::Writer);
@@ -43,6 +53,7 @@ async fn generate_new_user(
         }
     };
 
+    let cognito_username = request.cognito_username();
     Ok(User::new(
         id,
         request.user_name,
@@ -50,15 +61,16 @@ async fn generate_new_user(
         organization_id,
         request.organization_name,
         roles,
-    ))
+    )
+    .with_cognito_username(Some(cognito_username)))
 }
 
 /// Create standardized error response
-fn create_error_response(error: LambdaError) -> Result<ApiGatewayProxyResponse, Error> {
-    let error_response = serde_json::json!({
-        "error": error.to_string(),
-        "message": error.user_message()
-    });
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
 
     Ok(apigw_response(
         error.status_code(),
@@ -71,21 +83,20 @@ fn create_error_response(error: LambdaError) -> Result<ApiGatewayProxyResponse,
 async fn signup_handler(
     event: LambdaEvent<ApiGatewayProxyRequest>,
 ) -> Result<ApiGatewayProxyResponse, Error> {
-    let client_manager = DefaultClientManager::new("ap-northeast-1".to_string());
+    if is_validate_only(&event) {
+        return validate_only::<SignupRequest>(&event);
+    }
+    let client_manager = DefaultClientManager::from_env();
 
     // Zero-copy deserialization and validation
-    let body = event
-        .payload
-        .body
-        .as_deref()
-        .ok_or_else(|| Error::from(LambdaError::MissingBody))?;
+    let body = decoded_body(&event.payload).map_err(Error::from)?;
 
     let signup_request: SignupRequest =
-        serde_json::from_slice(body.as_bytes()).map_err(|e| Error::from(e.to_lambda_error()))?;
+        serde_json::from_slice(&body).map_err(|e| Error::from(e.to_lambda_error()))?;
 
     // Validation
     if let Err(e) = signup_request.validate() {
-        return create_error_response(e);
+        return create_error_response(e, &event.payload);
     }
 
     // Get clients using abstraction with explicit trait disambiguation
@@ -99,53 +110,58 @@ async fn signup_handler(
     let table_name = get_env("TABLE_NAME", "Users");
     let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
 
+    let cognito_username = signup_request.cognito_username();
+    let strength = password_strength(&signup_request.password);
+
     // Try to create user in Cognito
     match cognito_client
-        .admin_create_user(signup_request.email.clone())
+        .admin_create_user(cognito_username.clone(), false, Vec::new())
         .await
     {
         Ok(admin_create_user_opt) => {
             debug!("admin create user output: {:?}", admin_create_user_opt);
 
             let opt = cognito_client
-                .admin_set_user_password(
-                    &signup_request.email.clone(),
-                    &signup_request.password.clone(),
-                    true,
-                )
+                .admin_set_user_password(&cognito_username, &signup_request.password.clone(), true)
                 .await
-                .map_err(|e| Error::from(LambdaError::InternalError(e.to_string())))?;
+                .map_err(|e| {
+                    Error::from(LambdaError::from_cognito_error(e, |detail| {
+                        LambdaError::internal("set password", detail)
+                    }))
+                })?;
             debug!("admin set user password output: {:?}", opt);
 
             let opt = cognito_client
-                .email_verified(signup_request.email.clone(), signup_request.email.clone())
+                .email_verified(cognito_username.clone(), signup_request.email_lower())
                 .await
-                .map_err(|e| Error::from(LambdaError::InternalError(e.to_string())))?;
+                .map_err(|e| {
+                    Error::from(LambdaError::from_cognito_error(e, |detail| {
+                        LambdaError::internal("mark email verified", detail)
+                    }))
+                })?;
             debug!("email verified user output: {:?}", opt);
 
-            let sub = admin_create_user_opt
-                .user()
-                .ok_or_else(|| Error::from(LambdaError::InternalError("user is None".to_string())))?
-                .attributes()
-                .iter()
-                .find(|attr| attr.name() == "sub")
-                .ok_or_else(|| Error::from(LambdaError::InternalError("sub is None".to_string())))?
-                .value()
-                .ok_or_else(|| {
-                    Error::from(LambdaError::InternalError("sub value is None".to_string()))
-                })?;
+            let sub = CognitoClient::extract_sub(&admin_create_user_opt).map_err(|e| {
+                Error::from(LambdaError::from_cognito_error(e, |detail| {
+                    LambdaError::internal("read Cognito sub", detail)
+                }))
+            })?;
 
-            let new_user = generate_new_user(sub.to_string(), signup_request, &repository)
+            let new_user = generate_new_user(sub, signup_request, &repository)
                 .await
-                .map_err(Error::from)?;
+                .map_err(Error::from)?
+                .with_email_verified(true);
 
-            repository
-                .create_user(new_user)
-                .await
-                .map_err(|e| Error::from(LambdaError::UserCreationFailed(e.to_string())))?;
+            repository.create_user(new_user).await.map_err(|e| {
+                Error::from(LambdaError::from_repository_error(
+                    e,
+                    LambdaError::UserCreationFailed,
+                ))
+            })?;
 
             let response = SignupResponse {
                 message: "signup successfully.".to_string(),
+                password_strength: strength,
             };
             Ok(apigw_response(
                 200,
@@ -160,9 +176,9 @@ async fn signup_handler(
                 LambdaError::InvalidPassword
             } else {
                 debug!("Signup error: {:?}", e);
-                LambdaError::InternalError(e.to_string())
+                LambdaError::internal("create Cognito user", error_chain(&e))
             };
-            create_error_response(error)
+            create_error_response(error, &event.payload)
         }
     }
 }
@@ -172,7 +188,10 @@ async fn handler(
     event: LambdaEvent<ApiGatewayProxyRequest>,
 ) -> Result<ApiGatewayProxyResponse, Error> {
     debug!("handling lambda req: {:?}", event);
-    LambdaEventRequestHandler::handle_requests(event, "/signup", signup_handler).await
+    let response =
+        LambdaEventRequestHandler::handle_requests(event, "/signup", signup_handler).await;
+    get_cache_manager().record_metrics();
+    response
 }
 
 // Custom allocator configuration
@@ -183,5 +202,7 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 async fn main() -> Result<(), Error> {
     shared::tracer::init_tracing();
     info!("Starting auth user signup function");
-    lambda_runtime::run(service_fn(handler)).await
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
 }
@@ -1,5 +1,7 @@
+use shared::config::get_config;
 use shared::errors::LambdaError;
-use shared::utils::regex::{is_valid_username, EMAIL_REGEX};
+use shared::utils::regex::{is_valid_username, COGNITO_USERNAME_REGEX, EMAIL_REGEX};
+use shared::validation::{normalize_email, FieldErrorCode, Validate, ValidationErrors};
 
 use serde::{Deserialize, Serialize};
 
@@ -9,44 +11,64 @@ pub(super) struct SignupRequest {
     pub user_name: String,
     pub email: String,
     pub password: String,
+    /// Cognito username when it differs from the email
+    #[serde(default)]
+    pub cognito_username: Option<String>,
 }
 
-impl SignupRequest {
-    pub fn validate(&self) -> Result<(), LambdaError> {
+impl Validate for SignupRequest {
+    fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+
         // Organization name validation
         if self.organization_name.len() < 2 || self.organization_name.len() > 100 {
-            return Err(LambdaError::InvalidOrganizationName);
+            errors.add("organization_name", FieldErrorCode::OrganizationNameInvalid);
         }
 
         // Username validation
         if !is_valid_username(&self.user_name) {
-            return Err(LambdaError::InvalidUsername);
+            errors.add("user_name", FieldErrorCode::UsernameInvalid);
         }
 
         // Email validation
         if !EMAIL_REGEX.is_match(&self.email) {
-            return Err(LambdaError::InvalidEmail);
+            errors.add("email", FieldErrorCode::EmailInvalid);
         }
 
-        // Password validation (apply stricter rules)
-        if self.password.len() < 8 {
-            return Err(LambdaError::InvalidPassword);
+        // Cognito username validation
+        if let Some(cognito_username) = &self.cognito_username {
+            if !COGNITO_USERNAME_REGEX.is_match(cognito_username) {
+                errors.add("cognito_username", FieldErrorCode::CognitoUsernameInvalid);
+            }
         }
 
-        // Password strength check (must contain uppercase, lowercase, and numbers)
-        let has_uppercase = self.password.chars().any(|c| c.is_uppercase());
-        let has_lowercase = self.password.chars().any(|c| c.is_lowercase());
-        let has_digit = self.password.chars().any(|c| c.is_ascii_digit());
+        // Password validation
+        errors.extend(
+            "password",
+            get_config().password_policy.check(&self.password),
+        );
 
-        if !has_uppercase || !has_lowercase || !has_digit {
-            return Err(LambdaError::InvalidPassword);
-        }
+        errors.into_result()
+    }
+}
+
+impl SignupRequest {
+    /// Username used for Cognito operations, defaulting to the normalized email
+    pub fn cognito_username(&self) -> String {
+        self.cognito_username
+            .clone()
+            .unwrap_or_else(|| self.email_lower())
+    }
 
-        Ok(())
+    /// Email normalized for lookups and Cognito, which compare emails case-insensitively
+    pub fn email_lower(&self) -> String {
+        normalize_email(&self.email)
     }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
 pub(super) struct SignupResponse {
     pub message: String,
+    /// Strength of the chosen password, from 0 to 100
+    pub password_strength: u8,
 }
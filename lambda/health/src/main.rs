@@ -0,0 +1,208 @@
+mod requests;
+
+use crate::requests::{CheckStatus, HealthResponse};
+
+use shared::aws::cognito::client::CognitoClient;
+use shared::aws::lambda_events::{request::LambdaEventRequestHandler, response::apigw_response};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{DefaultClientManager, DynamoDbClientManager};
+use shared::entity::secrets::Secrets;
+use shared::errors::{error_chain, LambdaError, LambdaResult};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{debug, error, info, instrument};
+
+/// Maximum time allowed for each dependency check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Run a dependency check, treating errors and timeouts as failures
+async fn run_check<F, T, E>(name: &str, check_timeout: Duration, check: F) -> CheckStatus
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    match tokio::time::timeout(check_timeout, check).await {
+        Ok(Ok(_)) => CheckStatus::Ok,
+        Ok(Err(e)) => {
+            error!("Health check failed for {}: {}", name, e);
+            CheckStatus::Error
+        }
+        Err(_) => {
+            error!("Health check timed out for {}", name);
+            CheckStatus::Error
+        }
+    }
+}
+
+/// Check Cognito by describing the user pool named by `COGNITO_USER_POOL_ID`, so a Secrets
+/// Manager outage is reported by its own check only
+async fn check_cognito(region: String) -> LambdaResult<()> {
+    let user_pool_id = get_env("COGNITO_USER_POOL_ID", "");
+    if user_pool_id.is_empty() {
+        return Err(LambdaError::internal(
+            "describe user pool",
+            "COGNITO_USER_POOL_ID is not set",
+        ));
+    }
+    let cognito_client = CognitoClient::for_user_pool(region, user_pool_id)
+        .await
+        .map_err(|e| LambdaError::internal("create Cognito client", e))?;
+    cognito_client
+        .describe_user_pool()
+        .await
+        .map_err(|e| LambdaError::internal("describe user pool", error_chain(&e)))?;
+    Ok(())
+}
+
+/// Check DynamoDB by scanning a single item
+async fn check_dynamodb(client_manager: &DefaultClientManager) -> LambdaResult<()> {
+    let dynamodb_client = DynamoDbClientManager::get_client(client_manager).await?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    dynamodb_client
+        .scan_table_with_limit(&table_name, 1)
+        .await
+        .map_err(|e| LambdaError::internal("scan users table", error_chain(&e)))?;
+    Ok(())
+}
+
+/// Check Secrets Manager by fetching the Cognito secrets from it on every call, never from a cache
+async fn check_secrets(region: String) -> LambdaResult<()> {
+    Secrets::get_secrets(region)
+        .await
+        .map_err(LambdaError::from_secrets_error)?;
+    Ok(())
+}
+
+/// Build health response, reporting an error if any dependency failed
+fn build_health_response(checks: BTreeMap<String, CheckStatus>) -> HealthResponse {
+    let status = if checks.values().all(|status| *status == CheckStatus::Ok) {
+        CheckStatus::Ok
+    } else {
+        CheckStatus::Error
+    };
+    HealthResponse { status, checks }
+}
+
+#[instrument(name = "lambda.health.health_handler")]
+async fn health_handler(
+    _event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+    let region = get_env("AWS_REGION", "ap-northeast-1");
+
+    // Run the checks concurrently so the total latency is bounded by the slowest one
+    let (cognito, dynamodb, secrets_manager) = tokio::join!(
+        run_check("cognito", CHECK_TIMEOUT, check_cognito(region.clone())),
+        run_check("dynamodb", CHECK_TIMEOUT, check_dynamodb(&client_manager)),
+        run_check("secrets_manager", CHECK_TIMEOUT, check_secrets(region)),
+    );
+
+    let response = build_health_response(BTreeMap::from([
+        ("cognito".to_string(), cognito),
+        ("dynamodb".to_string(), dynamodb),
+        ("secrets_manager".to_string(), secrets_manager),
+    ]));
+    debug!("health check result: {:?}", response);
+
+    let status_code = match response.status {
+        CheckStatus::Ok => 200,
+        CheckStatus::Error => LambdaError::ServiceUnavailable.status_code(),
+    };
+    Ok(apigw_response(
+        status_code,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.health.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response =
+        LambdaEventRequestHandler::handle_requests(event, "/health", health_handler).await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting health function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_check_reports_ok_and_error() {
+        let ok = run_check("ok", CHECK_TIMEOUT, async { Ok::<(), LambdaError>(()) }).await;
+        assert_eq!(ok, CheckStatus::Ok);
+
+        let failed = run_check("failed", CHECK_TIMEOUT, async {
+            Err::<(), LambdaError>(LambdaError::InternalError("boom".to_string()))
+        })
+        .await;
+        assert_eq!(failed, CheckStatus::Error);
+    }
+
+    #[tokio::test]
+    async fn test_run_check_times_out() {
+        let status = run_check(
+            "slow",
+            Duration::from_millis(10),
+            std::future::pending::<Result<(), LambdaError>>(),
+        )
+        .await;
+        assert_eq!(status, CheckStatus::Error);
+    }
+
+    #[tokio::test]
+    async fn test_cognito_check_needs_no_secrets() {
+        std::env::remove_var("COGNITO_USER_POOL_ID");
+
+        let error = check_cognito("ap-northeast-1".to_string())
+            .await
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("COGNITO_USER_POOL_ID is not set"));
+    }
+
+    #[test]
+    fn test_build_health_response() {
+        let response = build_health_response(BTreeMap::from([
+            ("cognito".to_string(), CheckStatus::Ok),
+            ("dynamodb".to_string(), CheckStatus::Ok),
+        ]));
+        assert_eq!(response.status, CheckStatus::Ok);
+
+        let response = build_health_response(BTreeMap::from([
+            ("cognito".to_string(), CheckStatus::Ok),
+            ("dynamodb".to_string(), CheckStatus::Error),
+        ]));
+        assert_eq!(response.status, CheckStatus::Error);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({
+                "status": "error",
+                "checks": { "cognito": "ok", "dynamodb": "error" }
+            })
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum CheckStatus {
+    Ok,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) struct HealthResponse {
+    pub status: CheckStatus,
+    pub checks: BTreeMap<String, CheckStatus>,
+}
@@ -0,0 +1,150 @@
+mod requests;
+
+use crate::requests::ListOrganizationsResponse;
+
+use shared::aws::lambda_events::{
+    middleware::preferred_language,
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{DefaultClientManager, DynamoDbClientManager};
+use shared::entity::user::{Permissions, User};
+use shared::errors::{LambdaError, LambdaResult};
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use tracing::{debug, info, instrument, warn};
+
+/// Only platform operators may enumerate organizations
+fn check_manage_org_permission(user: &User) -> LambdaResult<()> {
+    if user.has_permission(Permissions::MANAGE_ORG) {
+        Ok(())
+    } else {
+        warn!("User {} is not allowed to list organizations", user.id);
+        Err(LambdaError::InsufficientPermissions)
+    }
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.organizations.list.list_organizations_handler")]
+async fn list_organizations_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    // Permission check
+    let user = repository
+        .get_user_by_id(user_id.clone(), false)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+
+    if let Err(e) = check_manage_org_permission(&user) {
+        return create_error_response(e, &event.payload);
+    }
+
+    let organizations = repository.list_organizations().await.map_err(|e| {
+        Error::from(LambdaError::from_repository_error(
+            e,
+            LambdaError::InternalError,
+        ))
+    })?;
+    debug!("Listed {} organizations", organizations.len());
+
+    let response = ListOrganizationsResponse { organizations };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.organizations.list.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations",
+        list_organizations_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting organizations list function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::entity::user::Role;
+    use std::collections::HashSet;
+
+    fn create_test_user(role: Role) -> User {
+        User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Example".to_string(),
+            HashSet::from([role]),
+        )
+    }
+
+    #[test]
+    fn test_super_admin_can_list_organizations() {
+        assert!(check_manage_org_permission(&create_test_user(Role::SuperAdmin)).is_ok());
+    }
+
+    #[test]
+    fn test_other_roles_cannot_list_organizations() {
+        for role in [Role::Admin, Role::Writer, Role::Reader] {
+            assert!(matches!(
+                check_manage_org_permission(&create_test_user(role)),
+                Err(LambdaError::InsufficientPermissions)
+            ));
+        }
+    }
+}
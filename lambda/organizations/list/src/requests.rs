@@ -0,0 +1,8 @@
+use shared::entity::organization::Organization;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct ListOrganizationsResponse {
+    pub organizations: Vec<Organization>,
+}
@@ -0,0 +1,330 @@
+mod requests;
+
+use crate::requests::{OrganizationStatusRequest, OrganizationStatusResponse};
+
+use shared::audit_logger::{audit_table_name, log_audit_event};
+use shared::aws::cognito::client::CognitoClient;
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, preferred_language},
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::entity::audit_event::{AuditAction, AuditEvent, AuditOutcome};
+use shared::entity::user::{Permissions, User, UserStatus};
+use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
+use shared::repository::audit_repository::AuditRepositoryImpl;
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use futures::future::join_all;
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use tracing::{debug, error, info, instrument, warn};
+
+/// Number of Cognito users updated concurrently
+const COGNITO_BATCH_SIZE: usize = 25;
+
+/// Only platform operators may suspend or reactivate an organization, and never their own
+fn check_suspend_permission(user: &User, organization_id: &str) -> LambdaResult<()> {
+    if !user.has_permission(Permissions::SUSPEND_ORG) {
+        warn!("User {} is not allowed to suspend organizations", user.id);
+        return Err(LambdaError::InsufficientPermissions);
+    }
+    if user.organization_id == organization_id {
+        return Err(LambdaError::InvalidRequest(
+            "cannot change the status of your own organization".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Parse the optional request body, defaulting every option when it is absent
+fn parse_request(body: Option<&[u8]>) -> LambdaResult<OrganizationStatusRequest> {
+    match body.map(<[u8]>::trim_ascii) {
+        None | Some(b"") => Ok(OrganizationStatusRequest::default()),
+        Some(body) => serde_json::from_slice(body).map_err(|e| e.to_lambda_error()),
+    }
+}
+
+/// Disable or enable the users in Cognito to match `status`, returning the IDs that failed
+async fn update_cognito_users(
+    cognito_client: &CognitoClient,
+    users: &[User],
+    status: UserStatus,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    for batch in users.chunks(COGNITO_BATCH_SIZE) {
+        let results = join_all(batch.iter().map(|user| async move {
+            let username = user.cognito_username().to_string();
+            let result = match status {
+                UserStatus::Suspended => {
+                    cognito_client.admin_disable_user(username).await.map(drop)
+                }
+                UserStatus::Active => cognito_client.admin_enable_user(username).await.map(drop),
+            };
+            (user, result)
+        }))
+        .await;
+
+        for (user, result) in results {
+            if let Err(e) = result {
+                error!("Failed to update Cognito user {}: {:?}", user.id, e);
+                failures.push(user.id.clone());
+            }
+        }
+    }
+    failures
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+async fn change_organization_status(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+    status: UserStatus,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let Some(target_organization_id) = event.payload.path_parameters.get("organizationId") else {
+        return create_error_response(LambdaError::MissingOrganizationId, &event.payload);
+    };
+    let body = match decoded_body(&event.payload) {
+        Ok(body) => Some(body),
+        Err(LambdaError::MissingBody) => None,
+        Err(e) => return create_error_response(e, &event.payload),
+    };
+    let request = match parse_request(body.as_deref()) {
+        Ok(request) => request,
+        Err(e) => return create_error_response(e, &event.payload),
+    };
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+
+    let action = match status {
+        UserStatus::Suspended => AuditAction::SuspendOrganization,
+        UserStatus::Active => AuditAction::ReactivateOrganization,
+    };
+
+    // Permission check
+    let user = repository
+        .get_user_by_id(user_id.clone(), false)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+
+    if let Err(e) = check_suspend_permission(&user, target_organization_id) {
+        let audit_event = AuditEvent::new(
+            user_id,
+            action,
+            None,
+            target_organization_id.clone(),
+            AuditOutcome::PermissionDenied,
+        );
+        log_audit_event(&audit_repository, audit_event).await;
+        return create_error_response(e, &event.payload);
+    }
+
+    let changed = match status {
+        UserStatus::Suspended => {
+            repository
+                .suspend_organization(target_organization_id)
+                .await
+        }
+        UserStatus::Active => {
+            repository
+                .reactivate_organization(target_organization_id)
+                .await
+        }
+    }
+    .map_err(|e| {
+        Error::from(LambdaError::from_repository_error(
+            e,
+            LambdaError::UserUpdateFailed,
+        ))
+    })?;
+    debug!(
+        "Set status {} on {} users in organization {}",
+        status,
+        changed.len(),
+        target_organization_id
+    );
+
+    let cache_manager = get_cache_manager();
+    for changed_user in &changed {
+        cache_manager.invalidate_user(&changed_user.id).await;
+    }
+    cache_manager
+        .invalidate_org_users(target_organization_id)
+        .await;
+
+    let cognito_failures = if request.update_cognito {
+        let cognito_client = CognitoClientManager::get_client(&client_manager)
+            .await
+            .map_err(Error::from)?;
+        update_cognito_users(&cognito_client, &changed, status).await
+    } else {
+        Vec::new()
+    };
+
+    let audit_event = AuditEvent::new(
+        user_id,
+        action,
+        None,
+        target_organization_id.clone(),
+        AuditOutcome::Success,
+    );
+    log_audit_event(&audit_repository, audit_event).await;
+
+    let response = OrganizationStatusResponse {
+        organization_id: target_organization_id.clone(),
+        status,
+        users_updated: changed.len(),
+        cognito_failures,
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.organizations.suspend.suspend_organization_handler")]
+async fn suspend_organization_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    change_organization_status(event, UserStatus::Suspended).await
+}
+
+#[instrument(name = "lambda.organizations.suspend.reactivate_organization_handler")]
+async fn reactivate_organization_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    change_organization_status(event, UserStatus::Active).await
+}
+
+#[instrument(name = "lambda.organizations.suspend.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let resource = event.clone().payload.resource;
+    let response = match resource.as_deref() {
+        Some("/organizations/{organizationId}/suspend") => {
+            LambdaEventRequestHandler::handle_requests(
+                event,
+                "/organizations/{organizationId}/suspend",
+                suspend_organization_handler,
+            )
+            .await
+        }
+        Some("/organizations/{organizationId}/reactivate") => {
+            LambdaEventRequestHandler::handle_requests(
+                event,
+                "/organizations/{organizationId}/reactivate",
+                reactivate_organization_handler,
+            )
+            .await
+        }
+        Some(resource) => {
+            info!("Path not handled: {}", resource);
+            Ok(apigw_response(404, Some("Not Found".into()), None))
+        }
+        None => {
+            warn!("Request has no resource field");
+            LambdaEventRequestHandler::missing_resource_response()
+        }
+    };
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting organizations suspend function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::entity::user::Role;
+    use std::collections::HashSet;
+
+    fn create_test_user(role: Role) -> User {
+        User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Example".to_string(),
+            HashSet::from([role]),
+        )
+    }
+
+    #[test]
+    fn test_super_admin_can_suspend_other_organization() {
+        assert!(check_suspend_permission(&create_test_user(Role::SuperAdmin), "org-2").is_ok());
+    }
+
+    #[test]
+    fn test_super_admin_cannot_suspend_own_organization() {
+        assert!(matches!(
+            check_suspend_permission(&create_test_user(Role::SuperAdmin), "org-1"),
+            Err(LambdaError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_other_roles_cannot_suspend_organizations() {
+        for role in [Role::Admin, Role::Writer, Role::Reader] {
+            assert!(matches!(
+                check_suspend_permission(&create_test_user(role), "org-2"),
+                Err(LambdaError::InsufficientPermissions)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_request_defaults_without_body() {
+        assert!(!parse_request(None).unwrap().update_cognito);
+        assert!(!parse_request(Some(b"")).unwrap().update_cognito);
+        assert!(
+            parse_request(Some(br#"{"update_cognito":true}"#))
+                .unwrap()
+                .update_cognito
+        );
+        assert!(parse_request(Some(b"not json")).is_err());
+    }
+}
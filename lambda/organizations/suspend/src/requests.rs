@@ -0,0 +1,21 @@
+use shared::entity::user::UserStatus;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(super) struct OrganizationStatusRequest {
+    /// Also disable (on suspend) or enable (on reactivate) the users in Cognito
+    #[serde(default)]
+    pub update_cognito: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct OrganizationStatusResponse {
+    pub organization_id: String,
+    pub status: UserStatus,
+    /// Number of users whose status changed
+    pub users_updated: usize,
+    /// Users whose Cognito account could not be updated
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cognito_failures: Vec<String>,
+}
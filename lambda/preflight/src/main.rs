@@ -0,0 +1,208 @@
+mod requests;
+
+use crate::requests::{CheckStatus, PreflightResponse};
+
+use shared::aws::lambda_events::{request::LambdaEventRequestHandler, response::apigw_response};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{
+    DefaultClientManager, DynamoDbClientManager, SecretsManager, TokenAuthorizerManager,
+};
+use shared::config::get_config;
+use shared::errors::{error_chain, LambdaError, LambdaResult};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tracing::{debug, error, info, instrument};
+
+/// Maximum time allowed for each dependency check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Run a dependency check, logging the failure detail instead of returning it
+async fn run_check<F, T, E>(name: &str, check_timeout: Duration, check: F) -> CheckStatus
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    match tokio::time::timeout(check_timeout, check).await {
+        Ok(Ok(_)) => CheckStatus::Ok,
+        Ok(Err(e)) => {
+            error!("Preflight check failed for {}: {}", name, e);
+            CheckStatus::Error
+        }
+        Err(_) => {
+            error!("Preflight check timed out for {}", name);
+            CheckStatus::Timeout
+        }
+    }
+}
+
+/// Check that the Cognito secrets resolve
+async fn check_secrets(client_manager: &DefaultClientManager) -> LambdaResult<()> {
+    SecretsManager::get_secrets(client_manager).await?;
+    Ok(())
+}
+
+/// Check that the users table exists by scanning a single item
+async fn check_table(client_manager: &DefaultClientManager) -> LambdaResult<()> {
+    let dynamodb_client = DynamoDbClientManager::get_client(client_manager).await?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    dynamodb_client
+        .scan_table_with_limit(&table_name, 1)
+        .await
+        .map_err(|e| LambdaError::internal("scan users table", error_chain(&e)))?;
+    Ok(())
+}
+
+/// Check that the JWKS configured in the secrets is reachable
+async fn check_jwks(client_manager: &DefaultClientManager) -> LambdaResult<()> {
+    let authorizer = TokenAuthorizerManager::get_authorizer(client_manager).await?;
+    authorizer
+        .check_jwks()
+        .await
+        .map_err(|e| LambdaError::internal("fetch JWKS", e))?;
+    Ok(())
+}
+
+/// Run the dependency checks concurrently and report the status of each
+async fn run_preflight<S, T, J>(
+    check_timeout: Duration,
+    secrets: S,
+    table: T,
+    jwks: J,
+) -> PreflightResponse
+where
+    S: Future<Output = LambdaResult<()>>,
+    T: Future<Output = LambdaResult<()>>,
+    J: Future<Output = LambdaResult<()>>,
+{
+    let (secrets, table, jwks) = tokio::join!(
+        run_check("secrets", check_timeout, secrets),
+        run_check("table", check_timeout, table),
+        run_check("jwks", check_timeout, jwks),
+    );
+
+    PreflightResponse::from_checks(BTreeMap::from([
+        ("secrets".to_string(), secrets),
+        ("table".to_string(), table),
+        ("jwks".to_string(), jwks),
+    ]))
+}
+
+#[instrument(name = "lambda.preflight.preflight_handler")]
+async fn preflight_handler(
+    _event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let response = run_preflight(
+        CHECK_TIMEOUT,
+        check_secrets(&client_manager),
+        check_table(&client_manager),
+        check_jwks(&client_manager),
+    )
+    .await;
+    debug!("preflight result: {:?}", response);
+
+    let status_code = match response.status {
+        CheckStatus::Ok => 200,
+        CheckStatus::Error | CheckStatus::Timeout => LambdaError::ServiceUnavailable.status_code(),
+    };
+    Ok(apigw_response(
+        status_code,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.preflight.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    // Unauthenticated, so answer as if the route did not exist unless explicitly enabled
+    if !get_config().enable_preflight {
+        info!("Preflight is disabled");
+        return Ok(apigw_response(404, Some("Not Found".into()), None));
+    }
+    let response =
+        LambdaEventRequestHandler::handle_requests(event, "/preflight", preflight_handler).await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting preflight function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn healthy() -> LambdaResult<()> {
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_healthy_report() {
+        let response = run_preflight(CHECK_TIMEOUT, healthy(), healthy(), healthy()).await;
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({
+                "status": "ok",
+                "checks": { "jwks": "ok", "secrets": "ok", "table": "ok" }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failing_report_hides_error_details() {
+        let response = run_preflight(
+            Duration::from_millis(10),
+            async {
+                Err(LambdaError::internal(
+                    "parse secrets",
+                    "client_secret=s3cr3t-value",
+                ))
+            },
+            healthy(),
+            std::future::pending(),
+        )
+        .await;
+
+        assert_eq!(response.status, CheckStatus::Error);
+        assert_eq!(response.checks["secrets"], CheckStatus::Error);
+        assert_eq!(response.checks["table"], CheckStatus::Ok);
+        assert_eq!(response.checks["jwks"], CheckStatus::Timeout);
+        let body = serde_json::to_string(&response).unwrap();
+        assert!(!body.contains("s3cr3t-value"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_by_default() {
+        let event = LambdaEvent::new(
+            ApiGatewayProxyRequest {
+                resource: Some("/preflight".to_string()),
+                ..Default::default()
+            },
+            lambda_runtime::Context::default(),
+        );
+
+        let response = handler(event).await.unwrap();
+        assert_eq!(response.status_code, 404);
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum CheckStatus {
+    Ok,
+    Error,
+    Timeout,
+}
+
+/// Status of each dependency; error details stay in the logs so no secret can leak
+#[derive(Serialize, Deserialize, Debug)]
+pub(super) struct PreflightResponse {
+    pub status: CheckStatus,
+    pub checks: BTreeMap<String, CheckStatus>,
+}
+
+impl PreflightResponse {
+    /// Report `ok` only when every dependency check passed
+    pub fn from_checks(checks: BTreeMap<String, CheckStatus>) -> Self {
+        let status = if checks.values().all(|status| *status == CheckStatus::Ok) {
+            CheckStatus::Ok
+        } else {
+            CheckStatus::Error
+        };
+        PreflightResponse { status, checks }
+    }
+}
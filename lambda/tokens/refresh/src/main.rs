@@ -2,10 +2,18 @@ mod requests;
 
 use crate::requests::{RefreshTokenRequest, RefreshTokenResponse};
 
-use shared::aws::lambda_events::{request::LambdaEventRequestHandler, response::apigw_response};
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, preferred_language},
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
 use shared::cache_manager::get_cache_manager;
-use shared::client_manager::{CognitoClientManager, DefaultClientManager};
-use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::errors::{error_chain, LambdaError, LambdaResult, ToLambdaError};
+use shared::repository::user_repository::{
+    is_not_found, is_suspended, UserRepository, UserRepositoryImpl,
+};
+use shared::utils::env::get_env;
 
 use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
 use lambda_runtime::{service_fn, Error, LambdaEvent};
@@ -28,7 +36,7 @@ async fn calculate_hash_with_cache(
     let hash = client
         .calculate_hash(username.to_string())
         .await
-        .map_err(|e| LambdaError::InternalError(e.to_string()))?;
+        .map_err(|e| LambdaError::internal("calculate secret hash", e))?;
 
     cache_manager
         .set_hash(username.to_string(), hash.clone())
@@ -36,12 +44,23 @@ async fn calculate_hash_with_cache(
     Ok(hash)
 }
 
+/// Map a failed user lookup, rejecting users deleted or suspended since the token was issued
+fn user_lookup_error(error: anyhow::Error) -> LambdaError {
+    if is_suspended(&error) {
+        LambdaError::UserSuspended
+    } else if is_not_found(&error) {
+        LambdaError::UserNotFound
+    } else {
+        LambdaError::from_repository_error(error, LambdaError::UserRetrievalFailed)
+    }
+}
+
 /// Create standardized error response
-fn create_error_response(error: LambdaError) -> Result<ApiGatewayProxyResponse, Error> {
-    let error_response = serde_json::json!({
-        "error": error.to_string(),
-        "message": error.user_message()
-    });
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
 
     Ok(apigw_response(
         error.status_code(),
@@ -54,53 +73,59 @@ fn create_error_response(error: LambdaError) -> Result<ApiGatewayProxyResponse,
 async fn refresh_token_handler(
     event: LambdaEvent<ApiGatewayProxyRequest>,
 ) -> Result<ApiGatewayProxyResponse, Error> {
-    let client_manager = DefaultClientManager::new("ap-northeast-1".to_string());
+    let client_manager = DefaultClientManager::from_env();
 
-    let (user_id, _) =
+    let RequestContext { user_id, .. } =
         LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
 
     // Zero-copy deserialization and validation
-    let body = event
-        .payload
-        .body
-        .as_deref()
-        .ok_or_else(|| Error::from(LambdaError::MissingBody))?;
+    let body = decoded_body(&event.payload).map_err(Error::from)?;
 
     let refresh_request: RefreshTokenRequest =
-        serde_json::from_slice(body.as_bytes()).map_err(|e| Error::from(e.to_lambda_error()))?;
+        serde_json::from_slice(&body).map_err(|e| Error::from(e.to_lambda_error()))?;
 
     // Validation
     if let Err(e) = refresh_request.validate() {
-        return create_error_response(e);
+        return create_error_response(e, &event.payload);
+    }
+
+    // Refreshed tokens keep API access, so only issue them to active users
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    if let Err(e) = repository.get_user_org(&user_id).await {
+        let error = user_lookup_error(e);
+        info!("Rejecting token refresh for user {}: {}", user_id, error);
+        return create_error_response(error, &event.payload);
     }
 
     // Get client using abstraction
-    let client = client_manager.get_client().await.map_err(Error::from)?;
+    let client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
 
     let hash = calculate_hash_with_cache(&client, &user_id)
         .await
         .map_err(Error::from)?;
 
+    let previous_refresh_token = refresh_request.refresh_token.clone();
     match client
         .refresh_token(refresh_request.refresh_token, hash)
         .await
     {
         Ok(result) => match result.authentication_result() {
             Some(res) => {
-                let access_token = res
-                    .access_token
-                    .as_deref()
-                    .unwrap_or("Missing access_token")
-                    .to_string();
-                let refresh_token = res
-                    .refresh_token
-                    .as_deref()
-                    .unwrap_or("Missing refresh_token")
-                    .to_string();
-                let response = RefreshTokenResponse {
-                    access_token,
-                    refresh_token,
+                let Some(response) = RefreshTokenResponse::from_result(res, previous_refresh_token)
+                else {
+                    error!("Authentication result is missing the access or ID token");
+                    return create_error_response(
+                        LambdaError::internal("refresh tokens", "missing access or ID token"),
+                        &event.payload,
+                    );
                 };
+                debug!("refresh token rotated: {}", response.refresh_token_rotated);
                 Ok(apigw_response(
                     200,
                     Some(serde_json::to_string(&response)?.into()),
@@ -109,9 +134,10 @@ async fn refresh_token_handler(
             }
             None => {
                 error!("Authentication result is None");
-                create_error_response(LambdaError::InternalError(
-                    "Failed to refresh token".to_string(),
-                ))
+                create_error_response(
+                    LambdaError::InternalError("Failed to refresh token".to_string()),
+                    &event.payload,
+                )
             }
         },
         Err(e) => {
@@ -121,9 +147,9 @@ async fn refresh_token_handler(
                 LambdaError::TokenExpired
             } else {
                 error!("Refresh token error: {:?}", e);
-                LambdaError::InternalError(e.to_string())
+                LambdaError::internal("refresh tokens", error_chain(&e))
             };
-            create_error_response(error)
+            create_error_response(error, &event.payload)
         }
     }
 }
@@ -133,8 +159,11 @@ async fn handler(
     event: LambdaEvent<ApiGatewayProxyRequest>,
 ) -> Result<ApiGatewayProxyResponse, Error> {
     debug!("handling lambda req: {:?}", event);
-    LambdaEventRequestHandler::handle_requests(event, "/tokens/refresh", refresh_token_handler)
-        .await
+    let response =
+        LambdaEventRequestHandler::handle_requests(event, "/tokens/refresh", refresh_token_handler)
+            .await;
+    get_cache_manager().record_metrics();
+    response
 }
 
 // Custom allocator configuration
@@ -145,5 +174,91 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 async fn main() -> Result<(), Error> {
     shared::tracer::init_tracing();
     info!("Starting auth token refresh function");
-    lambda_runtime::run(service_fn(handler)).await
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_cognitoidentityprovider::types::AuthenticationResultType;
+
+    fn refresh_result(refresh_token: Option<&str>) -> AuthenticationResultType {
+        AuthenticationResultType::builder()
+            .access_token("new-access")
+            .id_token("new-id")
+            .set_refresh_token(refresh_token.map(str::to_string))
+            .build()
+    }
+
+    #[test]
+    fn test_suspended_or_missing_user_cannot_refresh() {
+        use shared::aws::dynamodb::error::DynamoDbError;
+        use shared::repository::user_repository::UserSuspendedError;
+
+        assert!(matches!(
+            user_lookup_error(UserSuspendedError.into()),
+            LambdaError::UserSuspended
+        ));
+        assert!(matches!(
+            user_lookup_error(DynamoDbError::NotFound.into()),
+            LambdaError::UserNotFound
+        ));
+        assert!(matches!(
+            user_lookup_error(anyhow::anyhow!("boom")),
+            LambdaError::UserRetrievalFailed(_)
+        ));
+    }
+
+    #[test]
+    fn test_refresh_without_rotation_keeps_previous_refresh_token() {
+        let response =
+            RefreshTokenResponse::from_result(&refresh_result(None), "old-refresh".to_string())
+                .unwrap();
+
+        assert_eq!(
+            response,
+            RefreshTokenResponse {
+                access_token: "new-access".to_string(),
+                id_token: "new-id".to_string(),
+                refresh_token: "old-refresh".to_string(),
+                refresh_token_rotated: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_refresh_with_rotation_returns_new_refresh_token() {
+        let response = RefreshTokenResponse::from_result(
+            &refresh_result(Some("new-refresh")),
+            "old-refresh".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(response.refresh_token, "new-refresh");
+        assert!(response.refresh_token_rotated);
+        assert_eq!(response.id_token, "new-id");
+    }
+
+    #[test]
+    fn test_refresh_echoing_same_token_is_not_rotation() {
+        let response = RefreshTokenResponse::from_result(
+            &refresh_result(Some("old-refresh")),
+            "old-refresh".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(response.refresh_token, "old-refresh");
+        assert!(!response.refresh_token_rotated);
+    }
+
+    #[test]
+    fn test_refresh_without_id_token_is_rejected() {
+        let result = AuthenticationResultType::builder()
+            .access_token("new-access")
+            .build();
+
+        assert!(RefreshTokenResponse::from_result(&result, "old-refresh".to_string()).is_none());
+    }
 }
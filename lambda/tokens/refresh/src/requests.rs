@@ -1,5 +1,7 @@
+use aws_sdk_cognitoidentityprovider::types::AuthenticationResultType;
 use serde::{Deserialize, Serialize};
 use shared::errors::LambdaError;
+use shared::validation::{FieldErrorCode, ValidationErrors};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub(super) struct RefreshTokenRequest {
@@ -9,20 +11,48 @@ pub(super) struct RefreshTokenRequest {
 
 impl RefreshTokenRequest {
     pub fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+
         if self.grant_type != "refresh_token" {
-            return Err(LambdaError::InvalidRefreshToken);
+            errors.add("grant_type", FieldErrorCode::GrantTypeInvalid);
         }
 
         if self.refresh_token.is_empty() {
-            return Err(LambdaError::InvalidRefreshToken);
+            errors.add("refresh_token", FieldErrorCode::RefreshTokenMissing);
         }
 
-        Ok(())
+        errors.into_result()
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub(super) struct RefreshTokenResponse {
     pub access_token: String,
+    pub id_token: String,
+    /// Refresh token to use next: the new one when Cognito rotated it, otherwise the one sent
     pub refresh_token: String,
+    /// True when Cognito issued a new refresh token
+    pub refresh_token_rotated: bool,
+}
+
+impl RefreshTokenResponse {
+    /// Build the response from Cognito's refresh result, or `None` if it lacks the access or
+    /// ID token. Without rotation Cognito returns no refresh token, so the one sent is kept.
+    pub fn from_result(
+        result: &AuthenticationResultType,
+        previous_refresh_token: String,
+    ) -> Option<Self> {
+        let rotated_refresh_token = result
+            .refresh_token()
+            .filter(|token| !token.is_empty() && *token != previous_refresh_token);
+
+        Some(Self {
+            access_token: result.access_token()?.to_string(),
+            id_token: result.id_token()?.to_string(),
+            refresh_token_rotated: rotated_refresh_token.is_some(),
+            refresh_token: rotated_refresh_token
+                .map(str::to_string)
+                .unwrap_or(previous_refresh_token),
+        })
+    }
 }
@@ -2,82 +2,251 @@ pub mod requests;
 
 use crate::requests::{TokenValidateRequest, TokenValidateResponse};
 
-use shared::aws::lambda_events::{request::LambdaEventRequestHandler, response::apigw_response};
+use shared::aws::cognito::error::CognitoError;
+use shared::aws::cognito::token_authorizer::Claims;
+use shared::aws::lambda_events::{
+    middleware::{
+        extract_bearer_token, is_validate_only, parse_body, validate_only,
+        with_standard_error_handling,
+    },
+    request::LambdaEventRequestHandler,
+    response::apigw_response,
+};
 use shared::cache_manager::get_cache_manager;
 use shared::client_manager::{DefaultClientManager, DynamoDbClientManager, TokenAuthorizerManager};
+use shared::config::{get_config, LambdaConfig};
 use shared::entity::user::User;
-use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
-use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::errors::{error_chain, LambdaError, LambdaResult};
+use shared::repository::user_repository::{
+    is_not_found, is_suspended, UserRepository, UserRepositoryImpl,
+};
 use shared::utils::env::get_env;
+use shared::validation::Validate;
 
 use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
 use aws_lambda_events::http::{HeaderMap, HeaderValue};
 use lambda_runtime::{service_fn, Error, LambdaEvent};
-use tracing::{debug, error, info, instrument};
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, instrument, warn};
 
-/// Get user info with caching
-async fn get_user_with_cache(
-    user_id: &str,
+/// User to create for a valid token without a DynamoDB row, if auto-provisioning is enabled and
+/// the token carries an email and an organization from `AUTO_PROVISION_ORGS`.
+///
+/// Only ID tokens carry `email` and `custom:*` claims, so access tokens are never provisioned.
+/// The organization claim is user-writable unless the app client excludes it from its write
+/// attributes, hence the allow-list.
+fn auto_provisioned_user(claims: &Claims, config: &LambdaConfig) -> Option<User> {
+    if !config.auto_provision_users || claims.token_use.as_deref() != Some("id") {
+        return None;
+    }
+    let organization_id = claims.claim(&config.auto_provision_org_claim)?;
+    if !config
+        .auto_provision_orgs
+        .iter()
+        .any(|allowed| allowed == organization_id)
+    {
+        warn!(
+            "Not auto-provisioning user {} into organization {} outside AUTO_PROVISION_ORGS",
+            claims.sub, organization_id
+        );
+        return None;
+    }
+    let email = claims.claim("email")?;
+    let cognito_username = claims.username.clone().filter(|username| username != email);
+
+    let user = User::new(
+        claims.sub.clone(),
+        claims.claim("name").unwrap_or(email).to_string(),
+        email.to_string(),
+        organization_id.to_string(),
+        claims
+            .claim("custom:organization_name")
+            .unwrap_or(organization_id)
+            .to_string(),
+        HashSet::from([config.auto_provision_role]),
+    )
+    .with_cognito_username(cognito_username);
+    Some(user)
+}
+
+/// Create the row of an auto-provisioned user. A user with any row, soft-deleted included, is
+/// never overwritten, so deleting a user cannot be undone by their next token; `None` then.
+async fn provision_user(
+    repository: &impl UserRepository,
+    user: User,
+) -> LambdaResult<Option<User>> {
+    match repository.get_user_by_id(user.id.clone(), true).await {
+        Ok(_) => return Ok(None),
+        Err(e) if is_not_found(&e) => {}
+        Err(e) => {
+            return Err(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        }
+    }
+
+    // The condition covers a row written since the lookup
+    repository
+        .create_user_if_absent(user)
+        .await
+        .map_err(|e| LambdaError::from_repository_error(e, LambdaError::UserCreationFailed))
+}
+
+/// `(user_id, organization_id)` straight from the verified claims when `ORG_FROM_CLAIMS` is
+/// enabled and the token carries the organization. The user's status is still checked against
+/// DynamoDB, so suspended and deleted users are rejected.
+///
+/// A user who can write `custom:organization_id` could switch tenants, so the claim is only
+/// trusted in tokens of app clients listed in `ORG_CLAIM_CLIENT_IDS`, whose write attributes
+/// must exclude it.
+fn user_org_from_claims(claims: &Claims, config: &LambdaConfig) -> Option<(String, String)> {
+    if !config.org_from_claims {
+        return None;
+    }
+    let client_id = claims.app_client_id()?;
+    if !config.org_claim_client_ids.iter().any(|id| id == client_id) {
+        debug!(
+            "Organization claim not trusted for app client: {}",
+            client_id
+        );
+        return None;
+    }
+    let organization_id = claims.organization_id()?;
+    Some((claims.sub.clone(), organization_id.to_string()))
+}
+
+/// `(user_id, organization_id)` of the token's user with caching, auto-provisioning a missing
+/// user when enabled. Only the organization is read from DynamoDB, not the whole user.
+async fn get_user_org_with_cache(
+    claims: &Claims,
     client_manager: &DefaultClientManager,
-) -> LambdaResult<User> {
+) -> LambdaResult<(String, String)> {
+    let user_id = claims.sub.as_str();
     let cache_manager = get_cache_manager();
 
     // Check cache first
     if let Some(cached_user) = cache_manager.get_user(user_id).await {
         debug!("User info cache hit for user: {}", user_id);
-        return Ok(cached_user);
+        if cached_user.is_suspended() {
+            return Err(LambdaError::UserSuspended);
+        }
+        return Ok((cached_user.id, cached_user.organization_id));
+    }
+    if let Some(organization_id) = cache_manager.get_user_org(user_id).await {
+        debug!("User organization cache hit for user: {}", user_id);
+        return Ok((user_id.to_string(), organization_id));
+    }
+
+    // Skip the database for users recently found to be missing
+    if cache_manager.get_user_negative(user_id).await.is_some() {
+        debug!("User not-found cache hit for user: {}", user_id);
+        return Err(LambdaError::UserNotFound);
     }
 
-    // Get user from database on cache miss
+    // Get user organization from database on cache miss
     let dynamodb_client = client_manager.get_client().await?;
     let table_name = get_env("TABLE_NAME", "Users");
     let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    lookup_user_org(claims, &repository).await
+}
 
-    let user = repository
-        .get_user_by_id(user_id.to_string())
-        .await
-        .map_err(|e| LambdaError::UserRetrievalFailed(e.to_string()))?;
+/// `(user_id, organization_id)` of the token's user read from DynamoDB, rejecting suspended and
+/// deleted users and caching the result
+async fn lookup_user_org(
+    claims: &Claims,
+    repository: &impl UserRepository,
+) -> LambdaResult<(String, String)> {
+    let user_id = claims.sub.as_str();
+    let cache_manager = get_cache_manager();
 
-    cache_manager
-        .set_user(user_id.to_string(), user.clone())
-        .await;
-    Ok(user)
+    match repository.get_user_org(user_id).await {
+        Ok((user_id, organization_id)) => {
+            cache_manager
+                .set_user_org(user_id.clone(), organization_id.clone())
+                .await;
+            Ok((user_id, organization_id))
+        }
+        // Suspended users are not cached, so a reactivation takes effect on the next request
+        Err(e) if is_suspended(&e) => {
+            info!("Rejecting token of suspended user: {}", user_id);
+            Err(LambdaError::UserSuspended)
+        }
+        Err(e) if is_not_found(&e) => match auto_provisioned_user(claims, get_config()) {
+            Some(user) => {
+                info!(
+                    "Auto-provisioning user {} in organization {}",
+                    user_id, user.organization_id
+                );
+                match provision_user(repository, user).await? {
+                    Some(user) => {
+                        cache_manager
+                            .set_user(user_id.to_string(), user.clone())
+                            .await;
+                        Ok((user.id, user.organization_id))
+                    }
+                    None => {
+                        info!(
+                            "Not auto-provisioning user {} over an existing row",
+                            user_id
+                        );
+                        Err(LambdaError::UserNotFound)
+                    }
+                }
+            }
+            None => {
+                cache_manager.set_user_negative(user_id.to_string()).await;
+                Err(LambdaError::UserNotFound)
+            }
+        },
+        Err(e) => Err(LambdaError::from_repository_error(
+            e,
+            LambdaError::UserRetrievalFailed,
+        )),
+    }
 }
 
-/// Create standardized error response
-fn create_error_response(error: LambdaError) -> Result<ApiGatewayProxyResponse, Error> {
-    let error_response = serde_json::json!({
-        "error": error.to_string(),
-        "message": error.user_message()
-    });
+/// Build validate response from the user's IDs and the validated token claims
+fn build_validate_response(
+    user_id: String,
+    organization_id: String,
+    claims: &Claims,
+    now: u64,
+) -> TokenValidateResponse {
+    TokenValidateResponse {
+        user_id,
+        organization_id,
+        expires_at: claims.exp,
+        expires_in_secs: claims.expires_in_secs(now),
+        groups: claims.groups.clone(),
+    }
+}
 
-    Ok(apigw_response(
-        error.status_code(),
-        Some(serde_json::to_string(&error_response)?.into()),
-        None,
-    ))
+/// Request for the `Authorization: Bearer` token if sent, otherwise for the token in the JSON body
+fn parse_validate_request(
+    event: &LambdaEvent<ApiGatewayProxyRequest>,
+) -> LambdaResult<TokenValidateRequest> {
+    match extract_bearer_token(&event.payload) {
+        Ok(token) => {
+            let request = TokenValidateRequest { token };
+            request.validate()?;
+            Ok(request)
+        }
+        Err(LambdaError::MissingToken) => parse_body(event),
+        Err(e) => Err(e),
+    }
 }
 
 #[instrument(name = "lambda.tokens.validate.token_validate_handler")]
 async fn token_validate_handler(
     event: LambdaEvent<ApiGatewayProxyRequest>,
 ) -> Result<ApiGatewayProxyResponse, Error> {
-    let client_manager = DefaultClientManager::new("ap-northeast-1".to_string());
-
-    // Zero-copy deserialization and validation
-    let body = event
-        .payload
-        .body
-        .as_deref()
-        .ok_or_else(|| Error::from(LambdaError::MissingBody))?;
-
-    let validate_request: TokenValidateRequest =
-        serde_json::from_slice(body.as_bytes()).map_err(|e| Error::from(e.to_lambda_error()))?;
-
-    // Validation
-    if let Err(e) = validate_request.validate() {
-        return create_error_response(e);
+    if is_validate_only(&event) {
+        return validate_only::<TokenValidateRequest>(&event);
     }
+    let validate_request = parse_validate_request(&event)?;
+    let client_manager = DefaultClientManager::from_env();
 
     // Get token authorizer using abstraction
     let authorizer = client_manager.get_authorizer().await.map_err(Error::from)?;
@@ -85,28 +254,40 @@ async fn token_validate_handler(
     let claims = match authorizer.validate_token(&validate_request.token).await {
         Ok(claims) => claims,
         Err(e) => {
-            let error = if e.to_string().contains("expired") {
+            let error = if matches!(e, CognitoError::HttpError(_)) {
+                // The JWKS endpoint could not be reached
+                error!("Token validation unavailable: {:?}", e);
+                LambdaError::ServiceUnavailable
+            } else if e.to_string().contains("expired") {
                 LambdaError::TokenExpired
             } else if e.to_string().contains("signature") {
                 LambdaError::InvalidSignature
             } else {
                 error!("Token validation error: {:?}", e);
-                LambdaError::InternalError(e.to_string())
+                LambdaError::internal("validate token", error_chain(&e))
             };
-            return create_error_response(error);
+            return Err(error.into());
         }
     };
 
-    // Get user info with caching
-    let user = get_user_with_cache(&claims.sub, &client_manager)
-        .await
-        .map_err(Error::from)?;
-
-    let response = TokenValidateResponse {
-        user_id: user.id.clone(),
-        organization_id: user.organization_id.clone(),
+    // Get user organization from the claims, or from DynamoDB with caching
+    let (user_id, organization_id) = match user_org_from_claims(&claims, get_config()) {
+        Some(user_org) => {
+            debug!("Organization taken from claims for user: {}", user_org.0);
+            // The row still decides whether the user may sign in; the cached lookup keeps this
+            // to one read per user per cache lifetime
+            get_user_org_with_cache(&claims, &client_manager).await?;
+            user_org
+        }
+        None => get_user_org_with_cache(&claims, &client_manager).await?,
     };
 
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::from(LambdaError::internal("read system clock", e)))?
+        .as_secs();
+    let response = build_validate_response(user_id, organization_id, &claims, now);
+
     // Set user_id and organization_id to lambda context
     let mut headers = HeaderMap::new();
     headers.insert("user_id", HeaderValue::from_str(&response.user_id)?);
@@ -127,8 +308,14 @@ async fn handler(
     event: LambdaEvent<ApiGatewayProxyRequest>,
 ) -> Result<ApiGatewayProxyResponse, Error> {
     debug!("handling lambda req: {:?}", event);
-    LambdaEventRequestHandler::handle_requests(event, "/tokens/validate", token_validate_handler)
-        .await
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/tokens/validate",
+        with_standard_error_handling(token_validate_handler),
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
 }
 
 // Custom allocator configuration
@@ -139,5 +326,325 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 async fn main() -> Result<(), Error> {
     shared::tracer::init_tracing();
     info!("Starting auth token validate function");
-    lambda_runtime::run(service_fn(handler)).await
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use shared::aws::dynamodb::in_memory::InMemoryDynamoDb;
+    use shared::config::TableConfig;
+    use shared::entity::user::{Role, UserStatus};
+    use std::collections::HashMap;
+
+    fn create_test_claims(exp: u64) -> Claims {
+        Claims {
+            sub: "user-1".to_string(),
+            iss: "https://cognito-idp.ap-northeast-1.amazonaws.com/pool".to_string(),
+            iat: exp - 3600,
+            exp,
+            ..Default::default()
+        }
+    }
+
+    fn create_sso_claims() -> Claims {
+        let mut claims = create_test_claims(1_700_003_600);
+        claims.extra = HashMap::from([
+            ("email".to_string(), json!("alice@example.com")),
+            ("custom:organization_id".to_string(), json!("org-1")),
+        ]);
+        claims.username = Some("Google_1234".to_string());
+        claims.token_use = Some("id".to_string());
+        claims.extra.insert("aud".to_string(), json!("client-1"));
+        claims
+    }
+
+    fn org_from_claims_config() -> LambdaConfig {
+        LambdaConfig {
+            org_from_claims: true,
+            org_claim_client_ids: vec!["client-1".to_string()],
+            ..LambdaConfig::default()
+        }
+    }
+
+    fn auto_provision_config() -> LambdaConfig {
+        LambdaConfig {
+            auto_provision_users: true,
+            auto_provision_orgs: vec!["org-1".to_string()],
+            ..LambdaConfig::default()
+        }
+    }
+
+    fn create_validate_event(
+        authorization: Option<&'static str>,
+        body: Option<&str>,
+    ) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let mut payload = ApiGatewayProxyRequest {
+            body: body.map(str::to_string),
+            ..Default::default()
+        };
+        if let Some(authorization) = authorization {
+            payload
+                .headers
+                .insert("Authorization", HeaderValue::from_static(authorization));
+        }
+        LambdaEvent::new(payload, lambda_runtime::Context::default())
+    }
+
+    #[test]
+    fn test_token_is_read_from_bearer_header_before_body() {
+        let event = create_validate_event(Some("Bearer h.h.h"), Some(r#"{"token":"b.b.b"}"#));
+        assert_eq!(parse_validate_request(&event).unwrap().token, "h.h.h");
+
+        let event = create_validate_event(None, Some(r#"{"token":"b.b.b"}"#));
+        assert_eq!(parse_validate_request(&event).unwrap().token, "b.b.b");
+    }
+
+    #[test]
+    fn test_malformed_bearer_header_is_rejected() {
+        let event = create_validate_event(Some("Token h.h.h"), Some(r#"{"token":"b.b.b"}"#));
+        assert!(matches!(
+            parse_validate_request(&event),
+            Err(LambdaError::InvalidToken)
+        ));
+
+        let event = create_validate_event(Some("Bearer not-a-jwt"), None);
+        assert!(matches!(
+            parse_validate_request(&event),
+            Err(LambdaError::ValidationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_org_from_claims_skips_lookup_when_claim_present() {
+        let config = org_from_claims_config();
+
+        assert_eq!(
+            user_org_from_claims(&create_sso_claims(), &config),
+            Some(("user-1".to_string(), "org-1".to_string()))
+        );
+        // Off by default, so the DynamoDB lookup is used
+        assert_eq!(
+            user_org_from_claims(&create_sso_claims(), &LambdaConfig::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_org_from_claims_only_trusts_listed_app_clients() {
+        let mut claims = create_sso_claims();
+        claims.extra.insert("aud".to_string(), json!("client-2"));
+        assert_eq!(
+            user_org_from_claims(&claims, &org_from_claims_config()),
+            None
+        );
+
+        // Access tokens name their app client in `client_id`
+        claims.extra.remove("aud");
+        claims.client_id = Some("client-1".to_string());
+        assert!(user_org_from_claims(&claims, &org_from_claims_config()).is_some());
+
+        let config = LambdaConfig {
+            org_from_claims: true,
+            ..LambdaConfig::default()
+        };
+        assert_eq!(user_org_from_claims(&create_sso_claims(), &config), None);
+    }
+
+    #[test]
+    fn test_org_from_claims_falls_back_without_claim() {
+        let config = org_from_claims_config();
+
+        assert_eq!(
+            user_org_from_claims(&create_test_claims(1_700_003_600), &config),
+            None
+        );
+        let mut claims = create_sso_claims();
+        claims
+            .extra
+            .insert("custom:organization_id".to_string(), json!(""));
+        assert_eq!(user_org_from_claims(&claims, &config), None);
+    }
+
+    #[test]
+    fn test_auto_provisioning_is_off_by_default() {
+        assert!(auto_provisioned_user(&create_sso_claims(), &LambdaConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_auto_provisioned_user_from_claims() {
+        let config = auto_provision_config();
+
+        let user = auto_provisioned_user(&create_sso_claims(), &config).unwrap();
+        assert_eq!(user.id, "user-1");
+        assert_eq!(user.email, "alice@example.com");
+        assert_eq!(user.name, "alice@example.com");
+        assert_eq!(user.organization_id, "org-1");
+        assert_eq!(user.organization_name, "org-1");
+        assert_eq!(user.cognito_username(), "Google_1234");
+        assert_eq!(user.roles(), vec![Role::Reader]);
+
+        // Without an organization claim there is nothing to provision into
+        let mut claims = create_sso_claims();
+        claims.extra.remove("custom:organization_id");
+        assert!(auto_provisioned_user(&claims, &config).is_none());
+    }
+
+    #[test]
+    fn test_auto_provisioning_requires_allowed_organization() {
+        let mut claims = create_sso_claims();
+        claims
+            .extra
+            .insert("custom:organization_id".to_string(), json!("org-2"));
+        assert!(auto_provisioned_user(&claims, &auto_provision_config()).is_none());
+
+        // Enabling provisioning without an allow-list provisions nobody
+        let config = LambdaConfig {
+            auto_provision_users: true,
+            ..LambdaConfig::default()
+        };
+        assert!(auto_provisioned_user(&create_sso_claims(), &config).is_none());
+    }
+
+    #[test]
+    fn test_auto_provisioning_requires_id_token() {
+        let mut claims = create_sso_claims();
+        claims.token_use = Some("access".to_string());
+        assert!(auto_provisioned_user(&claims, &auto_provision_config()).is_none());
+    }
+
+    fn in_memory_repository() -> UserRepositoryImpl<InMemoryDynamoDb> {
+        UserRepositoryImpl::with_table_config(
+            InMemoryDynamoDb::new(),
+            "users".to_string(),
+            TableConfig::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_provision_user_creates_missing_row() {
+        let repository = in_memory_repository();
+        let user = auto_provisioned_user(&create_sso_claims(), &auto_provision_config()).unwrap();
+
+        let provisioned = provision_user(&repository, user).await.unwrap().unwrap();
+        let stored = repository
+            .get_user_by_id(provisioned.id, false)
+            .await
+            .unwrap();
+        assert_eq!(stored.organization_id, "org-1");
+    }
+
+    #[tokio::test]
+    async fn test_provision_user_keeps_soft_deleted_row() {
+        let repository = in_memory_repository();
+        let deleted = User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Acme".to_string(),
+            HashSet::from([Role::Admin]),
+        );
+        repository.create_user(deleted).await.unwrap();
+        repository
+            .soft_delete_user("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+        let user = auto_provisioned_user(&create_sso_claims(), &auto_provision_config()).unwrap();
+
+        assert!(provision_user(&repository, user).await.unwrap().is_none());
+        let stored = repository
+            .get_user_by_id("user-1".to_string(), true)
+            .await
+            .unwrap();
+        assert!(stored.is_deleted());
+        assert_eq!(stored.roles, HashSet::from([Role::Admin]));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_rejects_suspended_and_deleted_users() {
+        let repository = in_memory_repository();
+        let mut claims = create_sso_claims();
+
+        claims.sub = "user-suspended".to_string();
+        let mut user = auto_provisioned_user(&claims, &auto_provision_config()).unwrap();
+        user.status = UserStatus::Suspended;
+        repository.create_user(user).await.unwrap();
+        assert!(matches!(
+            lookup_user_org(&claims, &repository).await,
+            Err(LambdaError::UserSuspended)
+        ));
+
+        claims.sub = "user-deleted".to_string();
+        let user = auto_provisioned_user(&claims, &auto_provision_config()).unwrap();
+        repository.create_user(user).await.unwrap();
+        repository
+            .soft_delete_user("user-deleted".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+        assert!(matches!(
+            lookup_user_org(&claims, &repository).await,
+            Err(LambdaError::UserNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_build_validate_response_reports_expiry() {
+        let claims = create_test_claims(1_700_003_600);
+        let response = build_validate_response(
+            "user-1".to_string(),
+            "org-1".to_string(),
+            &claims,
+            1_700_000_000,
+        );
+
+        assert_eq!(response.user_id, "user-1");
+        assert_eq!(response.organization_id, "org-1");
+        assert_eq!(response.expires_at, 1_700_003_600);
+        assert_eq!(response.expires_in_secs, 3600);
+    }
+
+    #[test]
+    fn test_build_validate_response_includes_groups() {
+        let mut claims = create_test_claims(1_700_003_600);
+        let response = build_validate_response(
+            "user-1".to_string(),
+            "org-1".to_string(),
+            &claims,
+            1_700_000_000,
+        );
+        assert!(serde_json::to_value(&response)
+            .unwrap()
+            .get("groups")
+            .is_none());
+
+        claims.groups = vec!["admins".to_string()];
+        let response = build_validate_response(
+            "user-1".to_string(),
+            "org-1".to_string(),
+            &claims,
+            1_700_000_000,
+        );
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["groups"],
+            json!(["admins"])
+        );
+    }
+
+    #[test]
+    fn test_build_validate_response_saturates_after_expiry() {
+        let claims = create_test_claims(1_700_000_000);
+        let response = build_validate_response(
+            "user-1".to_string(),
+            "org-1".to_string(),
+            &claims,
+            1_700_000_100,
+        );
+
+        assert_eq!(response.expires_at, 1_700_000_000);
+        assert_eq!(response.expires_in_secs, 0);
+    }
 }
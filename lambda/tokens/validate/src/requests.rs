@@ -1,24 +1,24 @@
 use serde::{Deserialize, Serialize};
 use shared::errors::LambdaError;
+use shared::validation::{FieldErrorCode, Validate, ValidationErrors};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub(super) struct TokenValidateRequest {
     pub token: String,
 }
 
-impl TokenValidateRequest {
-    pub fn validate(&self) -> Result<(), LambdaError> {
-        if self.token.is_empty() {
-            return Err(LambdaError::MissingToken);
-        }
+impl Validate for TokenValidateRequest {
+    fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
 
-        // Basic JWT format validation (3 parts separated by dots)
-        let parts: Vec<&str> = self.token.split('.').collect();
-        if parts.len() != 3 {
-            return Err(LambdaError::InvalidToken);
+        if self.token.is_empty() {
+            errors.add("token", FieldErrorCode::TokenMissing);
+        } else if self.token.split('.').count() != 3 {
+            // Basic JWT format validation (3 parts separated by dots)
+            errors.add("token", FieldErrorCode::TokenInvalid);
         }
 
-        Ok(())
+        errors.into_result()
     }
 }
 
@@ -26,4 +26,11 @@ impl TokenValidateRequest {
 pub(super) struct TokenValidateResponse {
     pub user_id: String,
     pub organization_id: String,
+    /// Token expiry as a Unix timestamp (the `exp` claim)
+    pub expires_at: u64,
+    /// Seconds remaining until the token expires
+    pub expires_in_secs: u64,
+    /// The token's `cognito:groups`, for group-based authorization downstream
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
 }
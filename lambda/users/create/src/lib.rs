@@ -0,0 +1,802 @@
+mod requests;
+
+use crate::requests::{CreateUserRequest, CreateUserResponse};
+
+use shared::audit_logger::{audit_table_name, log_audit_event};
+use shared::authz::check_permission_with_cache;
+use shared::aws::cognito::client::{is_unclaimed_user, sub_from_attributes, CognitoClient};
+use shared::aws::dynamodb::client::DynamoDbClient;
+use shared::aws::eventbridge::{
+    client::EventBridgePublisher,
+    publisher::{publish_user_event, USER_CREATED},
+};
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, is_validate_only, preferred_language, validate_only},
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::{apigw_response, org_usage_headers},
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::config::get_config;
+use shared::entity::audit_event::{AuditAction, AuditEvent, AuditOutcome};
+use shared::entity::idempotency::IdempotentResponse;
+use shared::entity::user::{Permissions, User};
+use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
+use shared::repository::audit_repository::AuditRepositoryImpl;
+use shared::repository::user_repository::{is_not_found, UserRepository, UserRepositoryImpl};
+use shared::session_store::{
+    check_rate_limit, claim_idempotent, complete_idempotent, release_idempotent, IdempotencyClaim,
+    SessionStore,
+};
+use shared::utils::{
+    env::get_env,
+    password::{generate_password_with, password_strength},
+};
+use shared::validation::Validate;
+
+use aws_lambda_events::encodings::Body;
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_lambda_events::http::{HeaderMap, HeaderValue};
+use lambda_runtime::{Error, LambdaEvent};
+use std::collections::HashSet;
+use tracing::{debug, error, info, instrument};
+
+/// Header carrying a client-chosen key that makes retried create requests safe
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Cache key for the request's `Idempotency-Key`, scoped to the caller so keys never collide
+/// across users
+fn idempotency_cache_key(headers: &HeaderMap, user_id: &str) -> Option<String> {
+    let key = headers.get(IDEMPOTENCY_KEY_HEADER)?.to_str().ok()?.trim();
+    (!key.is_empty()).then(|| format!("{user_id}:{key}"))
+}
+
+/// Replay the recorded response for a retried request, rejecting a key reused with another body
+fn replay_idempotent(
+    recorded: IdempotentResponse,
+    request_body: &[u8],
+) -> LambdaResult<ApiGatewayProxyResponse> {
+    if !recorded.matches(request_body) {
+        return Err(LambdaError::IdempotencyKeyMismatch);
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert("Idempotent-Replayed", HeaderValue::from_static("true"));
+    Ok(apigw_response(
+        recorded.status_code,
+        Some(recorded.body.into()),
+        Some(headers),
+    ))
+}
+
+/// Body of a successful create response, the only outcome recorded for an idempotency key.
+/// The temporary password is left out so no live credential sits in the session table, and a
+/// replay omits it.
+fn created_response_body(response: &ApiGatewayProxyResponse) -> Option<String> {
+    match &response.body {
+        Some(Body::Text(body)) if response.status_code == 200 => {
+            let mut body: serde_json::Value = serde_json::from_str(body).ok()?;
+            body.as_object_mut()?.remove("user_tmp_password");
+            Some(body.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Generate new user
+fn generate_new_user(id: String, request: CreateUserRequest) -> LambdaResult<User> {
+    let roles = HashSet::new();
+    let cognito_username = request.cognito_username();
+    let mut user = User::new(
+        id,
+        request.user_name,
+        request.email,
+        request.organization_id,
+        request.organization_name,
+        roles,
+    )
+    .with_cognito_username(Some(cognito_username))
+    .with_phone(request.phone)
+    .with_locale(request.locale);
+    user.set_from_roles(request.roles.clone());
+    Ok(user)
+}
+
+/// Ensure an existing Cognito user has no DynamoDB row yet, so the creation can be completed
+fn ensure_user_row_missing(lookup: anyhow::Result<User>) -> LambdaResult<()> {
+    match lookup {
+        Ok(_) => Err(LambdaError::UserAlreadyExists),
+        Err(e) if is_not_found(&e) => Ok(()),
+        Err(e) => Err(LambdaError::UserRetrievalFailed(e.to_string())),
+    }
+}
+
+/// Build create user response
+fn build_create_user_response(
+    user: &User,
+    tmp_password: String,
+) -> LambdaResult<CreateUserResponse> {
+    Ok(CreateUserResponse {
+        user_name: user.name.clone(),
+        user_email: user.email.clone(),
+        user_roles: user.roles(),
+        user_tmp_password_strength: password_strength(&tmp_password),
+        user_tmp_password: tmp_password,
+    })
+}
+
+/// Build organization quota usage headers, omitting them if quotas are disabled or counting fails
+async fn build_org_usage_headers(
+    repository: &impl UserRepository,
+    organization_id: &str,
+) -> Option<HeaderMap> {
+    let config = get_config();
+    if config.org_user_quota == 0 {
+        return None;
+    }
+
+    match repository
+        .count_users_by_organization_id(organization_id.to_string())
+        .await
+    {
+        Ok(used) => Some(org_usage_headers(
+            used,
+            config.org_user_quota,
+            config.org_user_quota_warning_percent,
+        )),
+        Err(e) => {
+            error!("Failed to count organization users: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Await the caller lookup and client construction together so their latency overlaps,
+/// reporting a failed lookup ahead of a failed client
+async fn join_caller_and_client<U, C>(
+    caller: impl std::future::Future<Output = LambdaResult<U>>,
+    client: impl std::future::Future<Output = LambdaResult<C>>,
+) -> LambdaResult<(U, C)> {
+    let (caller, client) = tokio::join!(caller, client);
+    Ok((caller?, client?))
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.create.create_user_handler")]
+async fn create_user_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    // Answered before rate limiting and idempotency so validation has no side effects
+    if is_validate_only(&event) {
+        return validate_only::<CreateUserRequest>(&event);
+    }
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let session_store = SessionStore::from_env((*dynamodb_client).clone());
+
+    // Per-user rate limit; handle_requests turns this into a 429 with Retry-After
+    let config = get_config();
+    if !check_rate_limit(
+        session_store.as_ref(),
+        &user_id,
+        config.rate_limit_max,
+        config.rate_limit_window,
+    )
+    .await
+    {
+        return Err(Error::from(LambdaError::Throttled));
+    }
+
+    // Zero-copy deserialization and validation
+    let body = decoded_body(&event.payload).map_err(Error::from)?;
+
+    // A retried request with a known key gets the original response instead of a second user,
+    // and one sent while the first is still running gets a 409
+    let idempotency_key = idempotency_cache_key(&event.payload.headers, &user_id);
+    if let Some(key) = &idempotency_key {
+        match claim_idempotent(session_store.as_ref(), key).await {
+            IdempotencyClaim::Claimed => {}
+            IdempotencyClaim::Recorded(recorded) => {
+                info!("Replaying response for idempotency key: {}", key);
+                return replay_idempotent(recorded, &body)
+                    .or_else(|e| create_error_response(e, &event.payload));
+            }
+            IdempotencyClaim::InProgress => {
+                info!("Idempotency key still in progress: {}", key);
+                return create_error_response(
+                    LambdaError::IdempotencyKeyInProgress,
+                    &event.payload,
+                );
+            }
+        }
+    }
+
+    let result = create_user(
+        &event,
+        &body,
+        user_id,
+        organization_id,
+        &dynamodb_client,
+        &client_manager,
+    )
+    .await;
+
+    // Record a created user for retries; any other outcome frees the key to be tried again
+    if let Some(key) = idempotency_key {
+        match result.as_ref().ok().and_then(created_response_body) {
+            Some(response_body) => {
+                let response = IdempotentResponse::new(&body, 200, response_body);
+                complete_idempotent(session_store.as_ref(), key, response).await;
+            }
+            None => release_idempotent(session_store.as_ref(), &key).await,
+        }
+    }
+
+    result
+}
+
+/// Create the user described by `body` on behalf of the caller
+async fn create_user(
+    event: &LambdaEvent<ApiGatewayProxyRequest>,
+    body: &[u8],
+    user_id: String,
+    organization_id: String,
+    dynamodb_client: &DynamoDbClient,
+    client_manager: &DefaultClientManager,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let create_request: CreateUserRequest =
+        serde_json::from_slice(body).map_err(|e| Error::from(e.to_lambda_error()))?;
+
+    // Validation
+    if let Err(e) = create_request.validate() {
+        return create_error_response(e, &event.payload);
+    }
+
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+
+    // Load the caller for the permission check while the Cognito client is built
+    let (user, cognito_client) = join_caller_and_client(
+        async {
+            repository
+                .get_user_by_id(user_id.clone(), false)
+                .await
+                .map_err(|e| {
+                    LambdaError::from_repository_error(e, LambdaError::UserRetrievalFailed)
+                })
+        },
+        CognitoClientManager::get_client(client_manager),
+    )
+    .await
+    .map_err(Error::from)?;
+
+    if let Err(e) = check_permission_with_cache(&user, &user_id, Permissions::CREATE).await {
+        let audit_event = AuditEvent::new(
+            user_id,
+            AuditAction::CreateUser,
+            None,
+            organization_id,
+            AuditOutcome::PermissionDenied,
+        );
+        log_audit_event(&audit_repository, audit_event).await;
+        return create_error_response(e, &event.payload);
+    }
+
+    // Reject duplicates before touching Cognito
+    let existing = repository
+        .find_user_by_email(&create_request.email_lower())
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+    if existing.is_some() {
+        debug!("user with email already exists in DynamoDB");
+        return create_error_response(LambdaError::UserAlreadyExists, &event.payload);
+    }
+
+    let password_options =
+        create_request.password_options(get_config().password_policy.generator_options());
+    let tmp_password = generate_password_with(password_options)
+        .map_err(|e| Error::from(LambdaError::internal("generate password", e)))?;
+    debug!("Password has been generated");
+
+    let cognito_username = create_request.cognito_username();
+
+    // Try to create user in Cognito
+    let sub = match cognito_client
+        .admin_create_user(cognito_username.clone(), false, Vec::new())
+        .await
+    {
+        Ok(admin_create_user_opt) => {
+            debug!("admin create user output: {:?}", admin_create_user_opt);
+            CognitoClient::extract_sub(&admin_create_user_opt).map_err(|e| {
+                Error::from(LambdaError::from_cognito_error(e, |detail| {
+                    LambdaError::internal("read Cognito sub", detail)
+                }))
+            })?
+        }
+        Err(e) => match LambdaError::from_cognito_error(e, LambdaError::UserCreationFailed) {
+            LambdaError::UserAlreadyExists => {
+                // A previous create may have stopped after Cognito, so finish it if the row is
+                // missing, but only for a user that never signed in with the requested email
+                let admin_get_user_opt = cognito_client
+                    .admin_get_user(cognito_username.clone())
+                    .await
+                    .map_err(|e| {
+                        Error::from(LambdaError::from_cognito_error(
+                            e,
+                            LambdaError::UserRetrievalFailed,
+                        ))
+                    })?;
+                debug!("admin get user output: {:?}", admin_get_user_opt);
+                if !is_unclaimed_user(&admin_get_user_opt, &create_request.email_lower()) {
+                    debug!("Cognito user already exists and is not a partial create");
+                    return create_error_response(LambdaError::UserAlreadyExists, &event.payload);
+                }
+                let sub = sub_from_attributes(admin_get_user_opt.user_attributes())
+                    .ok_or_else(|| {
+                        Error::from(LambdaError::internal(
+                            "read Cognito sub",
+                            "no sub in user attributes",
+                        ))
+                    })?
+                    .to_string();
+
+                // A soft-deleted row still counts as an existing user
+                let lookup = repository.get_user_by_id(sub.clone(), true).await;
+                if let Err(e) = ensure_user_row_missing(lookup) {
+                    return create_error_response(e, &event.payload);
+                }
+                info!("Completing partially created user: {}", sub);
+                sub
+            }
+            error => {
+                error!("Failed to create user in Cognito: {:?}", error);
+                return create_error_response(error, &event.payload);
+            }
+        },
+    };
+
+    let opt = cognito_client
+        .admin_set_user_password(&cognito_username, &tmp_password, true)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_cognito_error(e, |detail| {
+                LambdaError::internal("set temporary password", detail)
+            }))
+        })?;
+    debug!("admin set user password output: {:?}", opt);
+
+    if let Some(locale) = &create_request.locale {
+        cognito_client
+            .admin_update_user_attributes(&cognito_username, vec![("locale", locale)])
+            .await
+            .map_err(|e| {
+                Error::from(LambdaError::from_cognito_error(e, |detail| {
+                    LambdaError::internal("set Cognito locale", detail)
+                }))
+            })?;
+    }
+
+    let opt = cognito_client
+        .email_verified(cognito_username, create_request.email_lower())
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_cognito_error(e, |detail| {
+                LambdaError::internal("mark email verified", detail)
+            }))
+        })?;
+    debug!("email verified user output: {:?}", opt);
+
+    let new_user = generate_new_user(sub, create_request)
+        .map_err(Error::from)?
+        .with_email_verified(true);
+    let created_user = repository.create_user(new_user).await.map_err(|e| {
+        Error::from(LambdaError::from_repository_error(
+            e,
+            LambdaError::UserCreationFailed,
+        ))
+    })?;
+    let audit_event = AuditEvent::new(
+        user_id,
+        AuditAction::CreateUser,
+        Some(created_user.id.clone()),
+        created_user.organization_id.clone(),
+        AuditOutcome::Success,
+    );
+    log_audit_event(&audit_repository, audit_event).await;
+    publish_user_event(
+        &EventBridgePublisher::from_env(),
+        USER_CREATED,
+        &created_user.id,
+        &created_user.organization_id,
+    )
+    .await;
+    let headers = build_org_usage_headers(&repository, &created_user.organization_id).await;
+    let response = build_create_user_response(&created_user, tmp_password).map_err(Error::from)?;
+    let response_body = serde_json::to_string(&response)?;
+
+    Ok(apigw_response(200, Some(response_body.into()), headers))
+}
+
+#[instrument(name = "lambda.users.create.handler")]
+pub async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations/{organizationId}/users",
+        create_user_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::aws::dynamodb::error::DynamoDbError;
+    use shared::entity::user::Role;
+    use shared::utils::password::PasswordGeneratorOptions;
+    use shared::validation::{FieldError, FieldErrorCode};
+
+    fn create_test_user() -> User {
+        User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "ExampleOrg".to_string(),
+            [Role::Reader].into_iter().collect::<HashSet<Role>>(),
+        )
+    }
+
+    fn create_test_request(cognito_username: Option<&str>) -> CreateUserRequest {
+        CreateUserRequest {
+            user_name: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            organization_id: "org-1".to_string(),
+            organization_name: "ExampleOrg".to_string(),
+            roles: vec![Role::Reader],
+            cognito_username: cognito_username.map(str::to_string),
+            phone: None,
+            locale: None,
+            temp_password_length: None,
+            include_symbols: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_caller_and_client_runs_both_concurrently() {
+        // Each side waits for the other, so this only completes if they are awaited together
+        let barrier = tokio::sync::Barrier::new(2);
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            join_caller_and_client(
+                async {
+                    barrier.wait().await;
+                    Ok(create_test_user())
+                },
+                async {
+                    barrier.wait().await;
+                    Ok("client")
+                },
+            ),
+        )
+        .await
+        .expect("caller lookup and client construction were awaited one after the other");
+
+        let (user, client) = result.unwrap();
+        assert_eq!(user.id, "user-1");
+        assert_eq!(client, "client");
+    }
+
+    #[tokio::test]
+    async fn test_join_caller_and_client_surfaces_errors() {
+        let caller_failed = join_caller_and_client(
+            async { Err::<User, _>(LambdaError::UserRetrievalFailed("throttled".to_string())) },
+            async { Err::<(), _>(LambdaError::ServiceUnavailable) },
+        )
+        .await;
+        assert!(matches!(
+            caller_failed,
+            Err(LambdaError::UserRetrievalFailed(_))
+        ));
+
+        let client_failed = join_caller_and_client(async { Ok(create_test_user()) }, async {
+            Err::<(), _>(LambdaError::ServiceUnavailable)
+        })
+        .await;
+        assert!(matches!(
+            client_failed,
+            Err(LambdaError::ServiceUnavailable)
+        ));
+    }
+
+    #[test]
+    fn test_cognito_username_defaults_to_normalized_email() {
+        let mut request = create_test_request(None);
+        request.email = "Alice@Example.com".to_string();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.cognito_username(), "alice@example.com");
+
+        let user = generate_new_user("user-1".to_string(), request).unwrap();
+        assert_eq!(user.email, "Alice@Example.com");
+        assert_eq!(user.cognito_username.as_deref(), Some("alice@example.com"));
+        assert_eq!(user.cognito_username(), "alice@example.com");
+    }
+
+    #[test]
+    fn test_generate_new_user_with_non_email_cognito_username() {
+        let request = create_test_request(Some("alice"));
+        assert!(request.validate().is_ok());
+        assert_eq!(request.cognito_username(), "alice");
+
+        let user = generate_new_user("user-1".to_string(), request).unwrap();
+        assert_eq!(user.email, "alice@example.com");
+        assert_eq!(user.cognito_username(), "alice");
+    }
+
+    #[test]
+    fn test_invalid_cognito_username_is_rejected() {
+        let request = create_test_request(Some("alice smith"));
+        let error = request.validate().unwrap_err();
+        assert_eq!(
+            error.field_errors(),
+            [FieldError::new(
+                "cognito_username",
+                FieldErrorCode::CognitoUsernameInvalid
+            )]
+        );
+    }
+
+    #[test]
+    fn test_phone_is_validated_and_stored() {
+        let mut request = create_test_request(None);
+        request.phone = Some("+819012345678".to_string());
+        assert!(request.validate().is_ok());
+
+        let user = generate_new_user("user-1".to_string(), request.clone()).unwrap();
+        assert_eq!(user.phone.as_deref(), Some("+819012345678"));
+
+        request.phone = Some("090-1234-5678".to_string());
+        let error = request.validate().unwrap_err();
+        assert_eq!(
+            error.field_errors(),
+            [FieldError::new("phone", FieldErrorCode::PhoneInvalid)]
+        );
+    }
+
+    #[test]
+    fn test_locale_is_validated_and_stored() {
+        let mut request = create_test_request(None);
+        request.locale = Some("ja".to_string());
+        assert!(request.validate().is_ok());
+
+        let user = generate_new_user("user-1".to_string(), request.clone()).unwrap();
+        assert_eq!(user.locale.as_deref(), Some("ja"));
+
+        request.locale = Some("zz!".to_string());
+        let error = request.validate().unwrap_err();
+        assert_eq!(
+            error.field_errors(),
+            [FieldError::new("locale", FieldErrorCode::LocaleInvalid)]
+        );
+    }
+
+    #[test]
+    fn test_super_admin_role_is_not_assignable() {
+        let mut request = create_test_request(None);
+        request.roles = vec![Role::SuperAdmin];
+
+        let error = request.validate().unwrap_err();
+        assert_eq!(
+            error.field_errors(),
+            [FieldError::new("roles", FieldErrorCode::RoleNotAssignable)]
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_every_invalid_field() {
+        let mut request = create_test_request(None);
+        request.user_name = "123".to_string();
+        request.email = "not-an-email".to_string();
+        request.organization_id = String::new();
+        request.roles = Vec::new();
+
+        let error = request.validate().unwrap_err();
+        assert_eq!(error.status_code(), 400);
+        let errors: Vec<(&str, FieldErrorCode)> = error
+            .field_errors()
+            .iter()
+            .map(|e| (e.field.as_str(), e.code))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("user_name", FieldErrorCode::UsernameInvalid),
+                ("email", FieldErrorCode::EmailInvalid),
+                ("organization_id", FieldErrorCode::OrganizationIdMissing),
+                ("roles", FieldErrorCode::RolesMissing),
+            ]
+        );
+
+        let body = error.response_body();
+        assert_eq!(body["details"][1]["code"], "EMAIL_INVALID");
+        assert_eq!(body["details"][1]["field"], "email");
+    }
+
+    #[test]
+    fn test_ensure_user_row_missing_completes_partial_create() {
+        let lookup = Err(DynamoDbError::NotFound.into());
+        assert!(ensure_user_row_missing(lookup).is_ok());
+    }
+
+    #[test]
+    fn test_ensure_user_row_missing_rejects_genuine_duplicate() {
+        let result = ensure_user_row_missing(Ok(create_test_user()));
+        assert!(matches!(result, Err(LambdaError::UserAlreadyExists)));
+    }
+
+    #[test]
+    fn test_ensure_user_row_missing_propagates_lookup_failure() {
+        let result = ensure_user_row_missing(Err(anyhow::anyhow!("throttled")));
+        assert!(matches!(result, Err(LambdaError::UserRetrievalFailed(_))));
+    }
+
+    #[test]
+    fn test_idempotency_cache_key_is_scoped_to_caller() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(idempotency_cache_key(&headers, "user-1"), None);
+
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_static("  "));
+        assert_eq!(idempotency_cache_key(&headers, "user-1"), None);
+
+        headers.insert(IDEMPOTENCY_KEY_HEADER, HeaderValue::from_static("abc-123"));
+        assert_eq!(
+            idempotency_cache_key(&headers, "user-1").as_deref(),
+            Some("user-1:abc-123")
+        );
+    }
+
+    #[test]
+    fn test_replay_idempotent_returns_recorded_response() {
+        let recorded =
+            IdempotentResponse::new("{\"a\":1}", 200, "{\"user_name\":\"Alice\"}".into());
+
+        let response = replay_idempotent(recorded, b"{\"a\":1}").unwrap();
+        assert_eq!(response.status_code, 200);
+        assert_eq!(response.body, Some("{\"user_name\":\"Alice\"}".into()));
+        assert_eq!(response.headers["Idempotent-Replayed"], "true");
+    }
+
+    #[test]
+    fn test_only_created_responses_are_recorded() {
+        let response = apigw_response(200, Some("{\"user_name\":\"Alice\"}".into()), None);
+        assert_eq!(
+            created_response_body(&response).as_deref(),
+            Some("{\"user_name\":\"Alice\"}")
+        );
+
+        let response = apigw_response(409, Some("{}".into()), None);
+        assert_eq!(created_response_body(&response), None);
+        assert_eq!(
+            created_response_body(&apigw_response(200, None, None)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recorded_response_omits_temporary_password() {
+        let created = CreateUserResponse {
+            user_name: "Alice".to_string(),
+            user_email: "alice@example.com".to_string(),
+            user_roles: vec![Role::Reader],
+            user_tmp_password: "Tmp-Password-123".to_string(),
+            user_tmp_password_strength: 80,
+        };
+        let response = apigw_response(
+            200,
+            Some(serde_json::to_string(&created).unwrap().into()),
+            None,
+        );
+
+        let recorded = created_response_body(&response).unwrap();
+        assert!(!recorded.contains("Tmp-Password-123"));
+        let recorded: serde_json::Value = serde_json::from_str(&recorded).unwrap();
+        assert!(recorded.get("user_tmp_password").is_none());
+        assert_eq!(recorded["user_email"], "alice@example.com");
+    }
+
+    #[test]
+    fn test_replay_idempotent_rejects_different_body() {
+        let recorded = IdempotentResponse::new("{\"a\":1}", 200, "{}".into());
+
+        let error = replay_idempotent(recorded, b"{\"a\":2}").unwrap_err();
+        assert!(matches!(error, LambdaError::IdempotencyKeyMismatch));
+        assert_eq!(error.status_code(), 422);
+    }
+
+    #[test]
+    fn test_temp_password_length_bounds() {
+        let mut request = create_test_request(None);
+        for length in [12, 64] {
+            request.temp_password_length = Some(length);
+            assert!(request.validate().is_ok());
+        }
+        for length in [11, 65] {
+            request.temp_password_length = Some(length);
+            let error = request.validate().unwrap_err();
+            assert_eq!(
+                error.field_errors(),
+                &[FieldError::new(
+                    "temp_password_length",
+                    FieldErrorCode::TempPasswordLengthInvalid
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn test_password_options_default_when_absent() {
+        let defaults = PasswordGeneratorOptions {
+            length: 24,
+            include_symbols: true,
+            include_spaces: false,
+        };
+        assert_eq!(
+            create_test_request(None).password_options(defaults),
+            defaults
+        );
+
+        let mut request = create_test_request(None);
+        request.temp_password_length = Some(16);
+        request.include_symbols = Some(false);
+        assert_eq!(
+            request.password_options(defaults),
+            PasswordGeneratorOptions {
+                length: 16,
+                include_symbols: false,
+                include_spaces: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_user_response_includes_password_strength() {
+        let response =
+            build_create_user_response(&create_test_user(), "Passw0rd".to_string()).unwrap();
+
+        assert_eq!(response.user_tmp_password, "Passw0rd");
+        assert_eq!(
+            response.user_tmp_password_strength,
+            password_strength("Passw0rd")
+        );
+    }
+}
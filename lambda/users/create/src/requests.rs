@@ -1,9 +1,16 @@
 use shared::entity::user::Role;
 use shared::errors::LambdaError;
-use shared::utils::regex::{is_valid_username, EMAIL_REGEX};
+use shared::utils::password::PasswordGeneratorOptions;
+use shared::utils::regex::{
+    is_valid_locale, is_valid_phone, is_valid_username, COGNITO_USERNAME_REGEX, EMAIL_REGEX,
+};
+use shared::validation::{normalize_email, FieldErrorCode, Validate, ValidationErrors};
 
 use serde::{Deserialize, Serialize};
 
+/// Allowed range for a requested temporary password length
+const TEMP_PASSWORD_LENGTH_RANGE: std::ops::RangeInclusive<usize> = 12..=64;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(super) struct CreateUserRequest {
     pub user_name: String,
@@ -11,36 +18,110 @@ pub(super) struct CreateUserRequest {
     pub organization_id: String,
     pub organization_name: String,
     pub roles: Vec<Role>,
+    /// Cognito username when it differs from the email
+    #[serde(default)]
+    pub cognito_username: Option<String>,
+    /// Contact phone number in E.164 format
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Preferred locale, e.g. `en` or `ja-JP`
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Temporary password length (12-64), defaulting to the configured policy
+    #[serde(default)]
+    pub temp_password_length: Option<usize>,
+    /// Include symbols in the temporary password, defaulting to true
+    #[serde(default)]
+    pub include_symbols: Option<bool>,
 }
 
-impl CreateUserRequest {
-    pub fn validate(&self) -> Result<(), LambdaError> {
+impl Validate for CreateUserRequest {
+    fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+
         // Username validation
         if !is_valid_username(&self.user_name) {
-            return Err(LambdaError::InvalidUsername);
+            errors.add("user_name", FieldErrorCode::UsernameInvalid);
         }
 
         // Email validation
         if !EMAIL_REGEX.is_match(&self.email) {
-            return Err(LambdaError::InvalidEmail);
+            errors.add("email", FieldErrorCode::EmailInvalid);
+        }
+
+        // Cognito username validation
+        if let Some(cognito_username) = &self.cognito_username {
+            if !COGNITO_USERNAME_REGEX.is_match(cognito_username) {
+                errors.add("cognito_username", FieldErrorCode::CognitoUsernameInvalid);
+            }
+        }
+
+        // Phone validation
+        if let Some(phone) = &self.phone {
+            if !is_valid_phone(phone) {
+                errors.add("phone", FieldErrorCode::PhoneInvalid);
+            }
+        }
+
+        // Locale validation
+        if let Some(locale) = &self.locale {
+            if !is_valid_locale(locale) {
+                errors.add("locale", FieldErrorCode::LocaleInvalid);
+            }
+        }
+
+        // Temporary password validation
+        if let Some(length) = self.temp_password_length {
+            if !TEMP_PASSWORD_LENGTH_RANGE.contains(&length) {
+                errors.add(
+                    "temp_password_length",
+                    FieldErrorCode::TempPasswordLengthInvalid,
+                );
+            }
         }
 
         // Organization ID validation
         if self.organization_id.is_empty() {
-            return Err(LambdaError::MissingOrganizationId);
+            errors.add("organization_id", FieldErrorCode::OrganizationIdMissing);
         }
 
         // Organization name validation
         if self.organization_name.len() < 2 || self.organization_name.len() > 100 {
-            return Err(LambdaError::InvalidOrganizationName);
+            errors.add("organization_name", FieldErrorCode::OrganizationNameInvalid);
         }
 
         // Role validation
         if self.roles.is_empty() {
-            return Err(LambdaError::MissingRoles);
+            errors.add("roles", FieldErrorCode::RolesMissing);
         }
+        if self.roles.contains(&Role::SuperAdmin) {
+            errors.add("roles", FieldErrorCode::RoleNotAssignable);
+        }
+
+        errors.into_result()
+    }
+}
+
+impl CreateUserRequest {
+    /// Temporary password options, falling back to `defaults` for omitted fields
+    pub fn password_options(&self, defaults: PasswordGeneratorOptions) -> PasswordGeneratorOptions {
+        PasswordGeneratorOptions {
+            length: self.temp_password_length.unwrap_or(defaults.length),
+            include_symbols: self.include_symbols.unwrap_or(defaults.include_symbols),
+            ..defaults
+        }
+    }
+
+    /// Username used for Cognito operations, defaulting to the normalized email
+    pub fn cognito_username(&self) -> String {
+        self.cognito_username
+            .clone()
+            .unwrap_or_else(|| self.email_lower())
+    }
 
-        Ok(())
+    /// Email normalized for lookups and Cognito, which compare emails case-insensitively
+    pub fn email_lower(&self) -> String {
+        normalize_email(&self.email)
     }
 }
 
@@ -50,4 +131,6 @@ pub(super) struct CreateUserResponse {
     pub user_email: String,
     pub user_roles: Vec<Role>,
     pub user_tmp_password: String,
+    /// Strength of the temporary password, from 0 to 100
+    pub user_tmp_password_strength: u8,
 }
@@ -0,0 +1,167 @@
+mod requests;
+
+use crate::requests::DeleteUserResponse;
+
+use shared::audit_logger::{audit_table_name, log_audit_event};
+use shared::authz::check_permission_with_cache;
+use shared::aws::lambda_events::{
+    middleware::preferred_language,
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::config::get_config;
+use shared::entity::audit_event::{AuditAction, AuditEvent, AuditOutcome};
+use shared::entity::user::Permissions;
+use shared::errors::LambdaError;
+use shared::repository::audit_repository::AuditRepositoryImpl;
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::session_store::{check_rate_limit, SessionStore};
+use shared::user_deletion::delete_user;
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use tracing::{debug, instrument};
+
+/// Whether the caller opted into permanent deletion with `?hard=true`
+fn is_hard_delete(request: &ApiGatewayProxyRequest) -> bool {
+    request.query_string_parameters.first("hard") == Some("true")
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.delete.delete_user_handler")]
+async fn delete_user_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let session_store = SessionStore::from_env((*dynamodb_client).clone());
+
+    // Per-user rate limit; handle_requests turns this into a 429 with Retry-After
+    let config = get_config();
+    if !check_rate_limit(
+        session_store.as_ref(),
+        &user_id,
+        config.rate_limit_max,
+        config.rate_limit_window,
+    )
+    .await
+    {
+        return Err(Error::from(LambdaError::Throttled));
+    }
+
+    // Get clients using abstraction with explicit trait disambiguation
+    let cognito_client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+
+    // Permission check
+    let user = repository
+        .get_user_by_id(user_id.clone(), false)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+
+    if let Err(e) = check_permission_with_cache(&user, &user_id, Permissions::DELETE).await {
+        let audit_event = AuditEvent::new(
+            user_id.clone(),
+            AuditAction::DeleteUser,
+            Some(user_id),
+            organization_id,
+            AuditOutcome::PermissionDenied,
+        );
+        log_audit_event(&audit_repository, audit_event).await;
+        return create_error_response(e, &event.payload);
+    }
+
+    delete_user(
+        &cognito_client,
+        &repository,
+        &audit_repository,
+        &user_id,
+        &user,
+        is_hard_delete(&event.payload),
+    )
+    .await
+    .map_err(Error::from)?;
+
+    let response = DeleteUserResponse {
+        message: format!("User {user_id} has been deleted."),
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.delete.handler")]
+pub async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations/{organizationId}/users/{userId}",
+        delete_user_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lambda_events::query_map::QueryMap;
+    use std::collections::HashMap;
+
+    fn create_test_request(query: &[(&str, &str)]) -> ApiGatewayProxyRequest {
+        let params: HashMap<String, String> = query
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ApiGatewayProxyRequest {
+            query_string_parameters: QueryMap::from(params),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_hard_delete() {
+        assert!(!is_hard_delete(&create_test_request(&[])));
+        assert!(!is_hard_delete(&create_test_request(&[("hard", "false")])));
+        assert!(is_hard_delete(&create_test_request(&[("hard", "true")])));
+    }
+}
@@ -0,0 +1,462 @@
+mod requests;
+
+use crate::requests::{CognitoUserResponse, ListUsersResponse};
+
+use shared::aws::cognito::client::attributes_to_map;
+use shared::aws::lambda_events::{
+    middleware::preferred_language,
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::{apigw_response, org_usage_headers},
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::config::get_config;
+use shared::entity::user::{Permissions, User};
+use shared::entity::user_response::GetUserResponse;
+use shared::errors::{LambdaError, LambdaResult};
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_lambda_events::http::HeaderMap;
+use lambda_runtime::{Error, LambdaEvent};
+use tracing::{debug, error, info, instrument, warn};
+
+/// Build organization quota usage headers, omitting them if quotas are disabled or counting fails
+async fn build_org_usage_headers(
+    client_manager: &DefaultClientManager,
+    organization_id: &str,
+) -> Result<Option<HeaderMap>, Error> {
+    let config = get_config();
+    if config.org_user_quota == 0 {
+        return Ok(None);
+    }
+
+    let dynamodb_client = DynamoDbClientManager::get_client(client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    match repository
+        .count_users_by_organization_id(organization_id.to_string())
+        .await
+    {
+        Ok(used) => Ok(Some(org_usage_headers(
+            used,
+            config.org_user_quota,
+            config.org_user_quota_warning_percent,
+        ))),
+        Err(e) => {
+            error!("Failed to count organization users: {:?}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Parse the optional `?verified=true|false` filter
+fn parse_verified_filter(request: &ApiGatewayProxyRequest) -> LambdaResult<Option<bool>> {
+    match request.query_string_parameters.first("verified") {
+        None => Ok(None),
+        Some("true") => Ok(Some(true)),
+        Some("false") => Ok(Some(false)),
+        Some(other) => Err(LambdaError::InvalidRequest(format!(
+            "verified must be true or false, got: {other}"
+        ))),
+    }
+}
+
+/// Parse the optional `?expandPermissions=true|false` flag
+fn parse_expand_permissions(request: &ApiGatewayProxyRequest) -> LambdaResult<bool> {
+    match request.query_string_parameters.first("expandPermissions") {
+        None | Some("false") => Ok(false),
+        Some("true") => Ok(true),
+        Some(other) => Err(LambdaError::InvalidRequest(format!(
+            "expandPermissions must be true or false, got: {other}"
+        ))),
+    }
+}
+
+/// Keep only users whose email verification status matches `verified`, if given
+fn filter_by_email_verified(users: Vec<User>, verified: Option<bool>) -> Vec<User> {
+    match verified {
+        Some(verified) => users
+            .into_iter()
+            .filter(|user| user.email_verified == verified)
+            .collect(),
+        None => users,
+    }
+}
+
+/// Ensure the caller may inspect another user's Cognito state
+fn check_cognito_access(caller: &User, organization_id: &str, target: &User) -> LambdaResult<()> {
+    if !caller.has_permission(Permissions::READ)
+        || caller.organization_id != organization_id
+        || !caller.can_access(target)
+    {
+        return Err(LambdaError::InsufficientPermissions);
+    }
+    Ok(())
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+/// Load a user from the cache, falling back to DynamoDB
+async fn load_user(
+    client_manager: &DefaultClientManager,
+    user_id: &str,
+) -> Result<Option<User>, Error> {
+    let cache_manager = get_cache_manager();
+
+    if let Some(cached_user) = cache_manager.get_user(user_id).await {
+        debug!("User info cache hit for user: {}", user_id);
+        return Ok(Some(cached_user));
+    }
+
+    let dynamodb_client = DynamoDbClientManager::get_client(client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    match repository.get_user_by_id(user_id.to_string(), false).await {
+        Ok(user) => {
+            cache_manager
+                .set_user(user_id.to_string(), user.clone())
+                .await;
+            Ok(Some(user))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+#[instrument(name = "lambda.users.get.get_user_handler")]
+async fn get_user_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+    let target_user_id = event
+        .payload
+        .path_parameters
+        .get("userId")
+        .cloned()
+        .unwrap_or_else(|| user_id.clone());
+    let expand_permissions = match parse_expand_permissions(&event.payload) {
+        Ok(expand_permissions) => expand_permissions,
+        Err(e) => return create_error_response(e, &event.payload),
+    };
+
+    let Some(user) = load_user(&client_manager, &target_user_id).await? else {
+        return create_error_response(LambdaError::UserNotFound, &event.payload);
+    };
+
+    // Enforce the organization boundary unless the caller reads their own record
+    if target_user_id != user_id {
+        let Some(caller) = load_user(&client_manager, &user_id).await? else {
+            return create_error_response(LambdaError::UserNotFound, &event.payload);
+        };
+        if caller.organization_id != organization_id || !caller.can_access(&user) {
+            return create_error_response(LambdaError::InsufficientPermissions, &event.payload);
+        }
+    }
+
+    let mut response = GetUserResponse::from(user);
+    if expand_permissions {
+        response = response.with_expanded_permissions();
+    }
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.get.get_cognito_user_handler")]
+async fn get_cognito_user_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+    let target_user_id = event
+        .payload
+        .path_parameters
+        .get("userId")
+        .cloned()
+        .ok_or_else(|| Error::from(LambdaError::InvalidRequest("missing userId".to_string())))?;
+
+    let Some(caller) = load_user(&client_manager, &user_id).await? else {
+        return create_error_response(LambdaError::UserNotFound, &event.payload);
+    };
+    let Some(user) = load_user(&client_manager, &target_user_id).await? else {
+        return create_error_response(LambdaError::UserNotFound, &event.payload);
+    };
+    if let Err(e) = check_cognito_access(&caller, &organization_id, &user) {
+        return create_error_response(e, &event.payload);
+    }
+
+    let cognito_client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let username = user.cognito_username().to_string();
+    let cognito_user = match cognito_client.admin_get_user(username.clone()).await {
+        Ok(cognito_user) => cognito_user,
+        Err(e) => {
+            return create_error_response(
+                LambdaError::from_cognito_error(e, LambdaError::UserRetrievalFailed),
+                &event.payload,
+            )
+        }
+    };
+
+    let attributes = attributes_to_map(cognito_user.user_attributes());
+    let response = CognitoUserResponse {
+        user_id: user.id,
+        username,
+        email_verified: attributes.get("email_verified").map(String::as_str) == Some("true"),
+        attributes,
+        status: cognito_user
+            .user_status()
+            .map(|status| status.as_str().to_string()),
+        enabled: cognito_user.enabled(),
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.get.get_users_handler")]
+async fn get_users_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+    let cache_manager = get_cache_manager();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let verified = match parse_verified_filter(&event.payload) {
+        Ok(verified) => verified,
+        Err(e) => return create_error_response(e, &event.payload),
+    };
+
+    // Filtering by verification status is an admin lookup, so require read access
+    if verified.is_some() {
+        let Some(caller) = load_user(&client_manager, &user_id).await? else {
+            return create_error_response(LambdaError::UserNotFound, &event.payload);
+        };
+        if !caller.has_permission(Permissions::READ) {
+            return create_error_response(LambdaError::InsufficientPermissions, &event.payload);
+        }
+    }
+
+    // Get organization users list from cache
+    let users = if let Some(cached_users) = cache_manager.get_org_users(&organization_id).await {
+        debug!("Organization users cache hit for org: {}", organization_id);
+        cached_users
+    } else {
+        let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+            .await
+            .map_err(Error::from)?;
+        let table_name = get_env("TABLE_NAME", "Users");
+        let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+        match repository
+            .get_users_by_organization_id(organization_id.clone(), false)
+            .await
+        {
+            Ok(users) => {
+                cache_manager
+                    .set_org_users(organization_id.clone(), users.clone())
+                    .await;
+                users
+            }
+            Err(_) => {
+                return create_error_response(LambdaError::OrganizationNotFound, &event.payload);
+            }
+        }
+    };
+
+    let headers = build_org_usage_headers(&client_manager, &organization_id).await?;
+    let response = ListUsersResponse {
+        users: filter_by_email_verified(users, verified),
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        headers,
+    ))
+}
+
+#[instrument(name = "lambda.users.get.handler")]
+pub async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let resource = event.clone().payload.resource;
+    let response = match resource.as_deref() {
+        Some("/organizations/{organizationId}/users/{userId}") => {
+            LambdaEventRequestHandler::handle_requests(
+                event,
+                "/organizations/{organizationId}/users/{userId}",
+                get_user_handler,
+            )
+            .await
+        }
+        Some("/organizations/{organizationId}/users/{userId}/cognito") => {
+            LambdaEventRequestHandler::handle_requests(
+                event,
+                "/organizations/{organizationId}/users/{userId}/cognito",
+                get_cognito_user_handler,
+            )
+            .await
+        }
+        Some("/organizations/{organizationId}/users") => {
+            LambdaEventRequestHandler::handle_requests(
+                event,
+                "/organizations/{organizationId}/users",
+                get_users_handler,
+            )
+            .await
+        }
+        Some(resource) => {
+            info!("Path not handled: {}", resource);
+            Ok(apigw_response(404, Some("Not Found".into()), None))
+        }
+        None => {
+            warn!("Request has no resource field");
+            LambdaEventRequestHandler::missing_resource_response()
+        }
+    };
+    get_cache_manager().record_metrics();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lambda_events::query_map::QueryMap;
+    use shared::entity::user::Role;
+    use std::collections::{HashMap, HashSet};
+
+    fn create_test_request(query: &[(&str, &str)]) -> ApiGatewayProxyRequest {
+        let params: HashMap<String, String> = query
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ApiGatewayProxyRequest {
+            query_string_parameters: QueryMap::from(params),
+            ..Default::default()
+        }
+    }
+
+    fn create_test_user(id: &str, email_verified: bool) -> User {
+        User::new(
+            id.to_string(),
+            "Alice".to_string(),
+            format!("{id}@example.com"),
+            "org-1".to_string(),
+            "Example".to_string(),
+            HashSet::from([Role::Reader]),
+        )
+        .with_email_verified(email_verified)
+    }
+
+    fn user_ids(users: &[User]) -> Vec<&str> {
+        users.iter().map(|user| user.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_check_cognito_access() {
+        let caller = create_test_user("caller", true);
+        let target = create_test_user("target", true);
+        assert!(check_cognito_access(&caller, "org-1", &target).is_ok());
+
+        // The path organization must be the caller's
+        assert!(matches!(
+            check_cognito_access(&caller, "org-2", &target),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+
+        let mut other_org = create_test_user("other", true);
+        other_org.organization_id = "org-2".to_string();
+        assert!(matches!(
+            check_cognito_access(&caller, "org-1", &other_org),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+    }
+
+    #[test]
+    fn test_parse_verified_filter() {
+        assert_eq!(
+            parse_verified_filter(&create_test_request(&[])).unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_verified_filter(&create_test_request(&[("verified", "true")])).unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            parse_verified_filter(&create_test_request(&[("verified", "false")])).unwrap(),
+            Some(false)
+        );
+        assert!(matches!(
+            parse_verified_filter(&create_test_request(&[("verified", "yes")])),
+            Err(LambdaError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_expand_permissions() {
+        assert!(!parse_expand_permissions(&create_test_request(&[])).unwrap());
+        assert!(
+            parse_expand_permissions(&create_test_request(&[("expandPermissions", "true")]))
+                .unwrap()
+        );
+        assert!(matches!(
+            parse_expand_permissions(&create_test_request(&[("expandPermissions", "1")])),
+            Err(LambdaError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_filter_by_email_verified() {
+        let users = vec![
+            create_test_user("verified-1", true),
+            create_test_user("unverified-1", false),
+            create_test_user("verified-2", true),
+        ];
+
+        let verified = filter_by_email_verified(users.clone(), Some(true));
+        assert_eq!(user_ids(&verified), ["verified-1", "verified-2"]);
+
+        let unverified = filter_by_email_verified(users.clone(), Some(false));
+        assert_eq!(user_ids(&unverified), ["unverified-1"]);
+
+        assert_eq!(filter_by_email_verified(users, None).len(), 3);
+    }
+}
@@ -1,8 +1,20 @@
 use shared::entity::user::User;
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(super) struct ListUsersResponse {
     pub users: Vec<User>,
 }
+
+/// Cognito-side state of a user, for debugging drift from the DynamoDB record
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(super) struct CognitoUserResponse {
+    pub user_id: String,
+    pub username: String,
+    pub attributes: HashMap<String, String>,
+    pub email_verified: bool,
+    pub status: Option<String>,
+    pub enabled: bool,
+}
@@ -0,0 +1,504 @@
+mod requests;
+
+use crate::requests::{DeleteMeRequest, DeleteMeResponse, ExportUserResponse, MeContextResponse};
+
+use shared::audit_logger::audit_table_name;
+use shared::aws::cognito::client::attributes_to_map;
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, preferred_language},
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::entity::user::User;
+use shared::entity::user_response::GetUserResponse;
+use shared::errors::{LambdaError, ToLambdaError};
+use shared::repository::audit_repository::AuditRepositoryImpl;
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::user_deletion::delete_user;
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_lambda_events::http::{header, HeaderMap, HeaderValue, Method};
+use aws_sdk_cognitoidentityprovider::operation::admin_get_user::AdminGetUserOutput;
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use tracing::{debug, info, instrument, warn};
+
+/// Combine the DynamoDB user record with the Cognito user attributes
+fn build_export_response(user: User, cognito_user: &AdminGetUserOutput) -> ExportUserResponse {
+    let cognito_attributes = attributes_to_map(cognito_user.user_attributes())
+        .into_iter()
+        .collect();
+
+    ExportUserResponse {
+        user,
+        cognito_attributes,
+        cognito_user_status: cognito_user
+            .user_status()
+            .map(|status| status.as_str().to_string()),
+        cognito_enabled: cognito_user.enabled(),
+        cognito_user_create_date: cognito_user.user_create_date().map(|d| d.to_string()),
+        cognito_user_last_modified_date: cognito_user
+            .user_last_modified_date()
+            .map(|d| d.to_string()),
+    }
+}
+
+/// Summarize the caller's organization, roles and permissions
+fn build_me_context(user: &User, organization_user_count: u64) -> MeContextResponse {
+    MeContextResponse {
+        user_id: user.id.clone(),
+        organization_id: user.organization_id.clone(),
+        organization_name: user.organization_name.clone(),
+        roles: user.roles(),
+        permissions: user
+            .permissions()
+            .iter_names()
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        organization_user_count,
+    }
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.me.get_me_handler")]
+async fn get_me_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+    let cache_manager = get_cache_manager();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    if let Some(cached_user) = cache_manager.get_user(&user_id).await {
+        debug!("User info cache hit for user: {}", user_id);
+        return Ok(apigw_response(
+            200,
+            Some(serde_json::to_string(&GetUserResponse::from(cached_user))?.into()),
+            None,
+        ));
+    }
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    let user = match repository.get_user_by_id(user_id.clone(), false).await {
+        Ok(user) => user,
+        Err(_) => return create_error_response(LambdaError::UserNotFound, &event.payload),
+    };
+    cache_manager.set_user(user_id, user.clone()).await;
+
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&GetUserResponse::from(user))?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.me.get_me_context_handler")]
+async fn get_me_context_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+    let cache_manager = get_cache_manager();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    let user = match cache_manager.get_user(&user_id).await {
+        Some(cached_user) => {
+            debug!("User info cache hit for user: {}", user_id);
+            cached_user
+        }
+        None => match repository.get_user_by_id(user_id.clone(), false).await {
+            Ok(user) => {
+                cache_manager.set_user(user_id, user.clone()).await;
+                user
+            }
+            Err(_) => return create_error_response(LambdaError::UserNotFound, &event.payload),
+        },
+    };
+
+    let organization_user_count = repository
+        .count_users_by_organization_id(user.organization_id.clone())
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+
+    let response = build_me_context(&user, organization_user_count);
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.me.export_user_handler")]
+async fn export_user_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let cognito_client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    // Read from DynamoDB rather than the cache so the export reflects the stored record
+    let user = match repository.get_user_by_id(user_id.clone(), false).await {
+        Ok(user) => user,
+        Err(_) => return create_error_response(LambdaError::UserNotFound, &event.payload),
+    };
+
+    let cognito_user = cognito_client
+        .admin_get_user(user.cognito_username().to_string())
+        .await
+        .map_err(|e| Error::from(LambdaError::UserRetrievalFailed(e.to_string())))?;
+    debug!("admin get user output: {:?}", cognito_user);
+
+    let response = build_export_response(user, &cognito_user);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!(
+            "attachment; filename=\"user-export-{user_id}.json\""
+        ))?,
+    );
+
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        Some(headers),
+    ))
+}
+
+#[instrument(name = "lambda.users.me.delete_me_handler")]
+async fn delete_me_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    // The body carries the password confirmation required to delete the account
+    let body = match decoded_body(&event.payload) {
+        Ok(body) => body,
+        Err(e) => return create_error_response(e, &event.payload),
+    };
+    let delete_request: DeleteMeRequest =
+        serde_json::from_slice(&body).map_err(|e| Error::from(e.to_lambda_error()))?;
+
+    // Validation
+    if let Err(e) = delete_request.validate() {
+        return create_error_response(e, &event.payload);
+    }
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let cognito_client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    // Re-authenticate with the password before deleting anything
+    let user = match repository.get_user_by_id(user_id.clone(), false).await {
+        Ok(user) => user,
+        Err(_) => return create_error_response(LambdaError::UserNotFound, &event.payload),
+    };
+    let hash = cognito_client
+        .calculate_hash(user.cognito_username().to_string())
+        .await
+        .map_err(|e| Error::from(LambdaError::internal("calculate secret hash", e)))?;
+
+    if let Err(e) = cognito_client
+        .user_login(
+            user.cognito_username().to_string(),
+            user.email.clone(),
+            delete_request.password,
+            hash,
+        )
+        .await
+    {
+        let error = LambdaError::from_cognito_error(e, |detail| {
+            LambdaError::internal("re-authenticate user", detail)
+        });
+        return create_error_response(error, &event.payload);
+    }
+
+    // Soft delete through the same path as the admin endpoint, so the account is disabled,
+    // audited and announced rather than silently removed
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+    delete_user(
+        &cognito_client,
+        &repository,
+        &audit_repository,
+        &user_id,
+        &user,
+        false,
+    )
+    .await
+    .map_err(Error::from)?;
+
+    let response = DeleteMeResponse {
+        message: format!("User {user_id} has been deleted."),
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.me.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let resource = event.clone().payload.resource;
+    let method = event.payload.http_method.clone();
+    let response = match resource.as_deref() {
+        Some("/me") if method == Method::GET => {
+            LambdaEventRequestHandler::handle_requests(event, "/me", get_me_handler).await
+        }
+        Some("/me") if method == Method::DELETE => {
+            LambdaEventRequestHandler::handle_requests(event, "/me", delete_me_handler).await
+        }
+        Some("/me/context") => {
+            LambdaEventRequestHandler::handle_requests(event, "/me/context", get_me_context_handler)
+                .await
+        }
+        Some("/me/export") => {
+            LambdaEventRequestHandler::handle_requests(event, "/me/export", export_user_handler)
+                .await
+        }
+        Some(resource) => {
+            info!("Path not handled: {}", resource);
+            Ok(apigw_response(404, Some("Not Found".into()), None))
+        }
+        None => {
+            warn!("Request has no resource field");
+            LambdaEventRequestHandler::missing_resource_response()
+        }
+    };
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting auth user me function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_cognitoidentityprovider::types::{AttributeType, UserStatusType};
+    use shared::entity::user::Role;
+    use shared::validation::{FieldError, FieldErrorCode};
+    use std::collections::HashSet;
+
+    fn create_test_user(id: &str) -> User {
+        User::new(
+            id.to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "ExampleOrg".to_string(),
+            [Role::Reader].into_iter().collect::<HashSet<Role>>(),
+        )
+    }
+
+    #[test]
+    fn test_build_me_context_for_admin() {
+        let mut user = create_test_user("user-1");
+        user.roles = [Role::Admin].into_iter().collect();
+
+        let context = build_me_context(&user, 42);
+
+        assert_eq!(
+            context,
+            MeContextResponse {
+                user_id: "user-1".to_string(),
+                organization_id: "org-1".to_string(),
+                organization_name: "ExampleOrg".to_string(),
+                roles: vec![Role::Admin],
+                permissions: ["READ", "WRITE", "CREATE", "DELETE", "UPDATE"]
+                    .map(String::from)
+                    .to_vec(),
+                organization_user_count: 42,
+            }
+        );
+    }
+
+    fn create_test_event(resource: &str, method: Method) -> LambdaEvent<ApiGatewayProxyRequest> {
+        LambdaEvent::new(
+            ApiGatewayProxyRequest {
+                resource: Some(resource.to_string()),
+                http_method: method,
+                ..Default::default()
+            },
+            lambda_runtime::Context::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_handler_requires_auth_context() {
+        for (resource, method) in [
+            ("/me", Method::GET),
+            ("/me", Method::DELETE),
+            ("/me/context", Method::GET),
+            ("/me/export", Method::GET),
+        ] {
+            let response = handler(create_test_event(resource, method.clone()))
+                .await
+                .unwrap();
+            assert_eq!(response.status_code, 401, "{method} {resource}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_me_without_password_is_rejected() {
+        for body in [None, Some("{}"), Some(r#"{"password": ""}"#)] {
+            let mut event = create_test_event("/me", Method::DELETE);
+            event
+                .payload
+                .headers
+                .insert("user_id", HeaderValue::from_static("user-1"));
+            event
+                .payload
+                .headers
+                .insert("organization_id", HeaderValue::from_static("org-1"));
+            event.payload.body = body.map(str::to_string);
+
+            let response = handler(event).await.unwrap();
+            assert_eq!(response.status_code, 400, "{body:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_rejects_unknown_routes() {
+        let response = handler(create_test_event("/me/other", Method::GET))
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 404);
+
+        let mut event = create_test_event("/me", Method::GET);
+        event.payload.resource = None;
+        let response = handler(event).await.unwrap();
+        assert_eq!(response.status_code, 400);
+    }
+
+    #[test]
+    fn test_delete_me_request_requires_password() {
+        let request: DeleteMeRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            request.validate().unwrap_err().field_errors(),
+            [FieldError::new("password", FieldErrorCode::PasswordEmpty)]
+        );
+
+        let request: DeleteMeRequest =
+            serde_json::from_str(r#"{"password": "Password123"}"#).unwrap();
+        assert_eq!(request.password, "Password123");
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_delete_me_request_rejects_empty_password() {
+        let request: DeleteMeRequest = serde_json::from_str(r#"{"password": ""}"#).unwrap();
+        let error = request.validate().unwrap_err();
+        assert_eq!(
+            error.field_errors(),
+            [FieldError::new("password", FieldErrorCode::PasswordEmpty)]
+        );
+    }
+
+    #[test]
+    fn test_build_export_response_combines_user_and_cognito_data() {
+        let cognito_user = AdminGetUserOutput::builder()
+            .username("alice@example.com")
+            .user_attributes(
+                AttributeType::builder()
+                    .name("sub")
+                    .value("user-1")
+                    .build()
+                    .unwrap(),
+            )
+            .user_attributes(
+                AttributeType::builder()
+                    .name("email_verified")
+                    .value("true")
+                    .build()
+                    .unwrap(),
+            )
+            .user_status(UserStatusType::Confirmed)
+            .enabled(true)
+            .build()
+            .unwrap();
+
+        let response = build_export_response(create_test_user("user-1"), &cognito_user);
+
+        assert_eq!(response.user.id, "user-1");
+        assert_eq!(response.user.email, "alice@example.com");
+        assert_eq!(response.cognito_attributes.get("sub").unwrap(), "user-1");
+        assert_eq!(
+            response.cognito_attributes.get("email_verified").unwrap(),
+            "true"
+        );
+        assert_eq!(response.cognito_user_status.as_deref(), Some("CONFIRMED"));
+        assert!(response.cognito_enabled);
+        assert!(response.cognito_user_create_date.is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use shared::entity::user::{Role, User};
+use shared::errors::LambdaError;
+use shared::validation::{FieldErrorCode, ValidationErrors};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct ExportUserResponse {
+    pub user: User,
+    pub cognito_attributes: BTreeMap<String, String>,
+    pub cognito_user_status: Option<String>,
+    pub cognito_enabled: bool,
+    pub cognito_user_create_date: Option<String>,
+    pub cognito_user_last_modified_date: Option<String>,
+}
+
+/// The caller's organization together with their own roles and permissions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(super) struct MeContextResponse {
+    pub user_id: String,
+    pub organization_id: String,
+    pub organization_name: String,
+    pub roles: Vec<Role>,
+    /// Permission names granted by `roles`, e.g. `READ`
+    pub permissions: Vec<String>,
+    pub organization_user_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct DeleteMeRequest {
+    /// Password confirmation checked against Cognito before deleting; required
+    #[serde(default)]
+    pub password: String,
+}
+
+impl DeleteMeRequest {
+    pub fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+
+        // Password confirmation
+        if self.password.is_empty() {
+            errors.add("password", FieldErrorCode::PasswordEmpty);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct DeleteMeResponse {
+    pub message: String,
+}
@@ -0,0 +1,239 @@
+mod requests;
+
+use crate::requests::ResendInvitationResponse;
+
+use shared::authz::check_permission_with_cache;
+use shared::aws::lambda_events::{
+    middleware::preferred_language,
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::entity::user::{Permissions, User};
+use shared::errors::LambdaError;
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::utils::{env::get_env, password::generate_password};
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use tracing::{debug, info, instrument};
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+/// Check that the caller may create users and return the user to re-invite
+async fn authorize_resend(
+    repository: &impl UserRepository,
+    caller: &User,
+    caller_id: &str,
+    organization_id: &str,
+    target_user_id: &str,
+) -> Result<User, LambdaError> {
+    check_permission_with_cache(caller, caller_id, Permissions::CREATE).await?;
+
+    // Only users of the caller's organization can be re-invited
+    match repository
+        .get_user_by_id(target_user_id.to_string(), false)
+        .await
+    {
+        Ok(target_user) if target_user.organization_id == organization_id => Ok(target_user),
+        _ => Err(LambdaError::UserNotFound),
+    }
+}
+
+#[instrument(name = "lambda.users.resend.resend_invitation_handler")]
+async fn resend_invitation_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let target_user_id = match event.payload.path_parameters.get("userId") {
+        Some(target_user_id) => target_user_id.clone(),
+        None => return create_error_response(LambdaError::UserNotFound, &event.payload),
+    };
+
+    // Get clients using abstraction with explicit trait disambiguation
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let cognito_client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    // Permission check
+    let user = repository
+        .get_user_by_id(user_id.clone(), false)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+
+    let target_user = match authorize_resend(
+        &repository,
+        &user,
+        &user_id,
+        &organization_id,
+        &target_user_id,
+    )
+    .await
+    {
+        Ok(target_user) => target_user,
+        Err(e) => return create_error_response(e, &event.payload),
+    };
+
+    let tmp_password = generate_password()
+        .map_err(|e| Error::from(LambdaError::internal("generate password", e)))?;
+    debug!("Password has been generated");
+
+    let opt = cognito_client
+        .admin_set_user_password(target_user.cognito_username(), &tmp_password, true)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_cognito_error(e, |detail| {
+                LambdaError::internal("set temporary password", detail)
+            }))
+        })?;
+    debug!("admin set user password output: {:?}", opt);
+
+    let response = ResendInvitationResponse {
+        user_id: target_user.id,
+        user_email: target_user.email,
+        user_tmp_password: tmp_password,
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.resend.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations/{organizationId}/users/{userId}/resend",
+        resend_invitation_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting auth user resend function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::aws::dynamodb::in_memory::InMemoryDynamoDb;
+    use shared::config::TableConfig;
+    use shared::entity::user::Role;
+
+    fn in_memory_repository() -> UserRepositoryImpl<InMemoryDynamoDb> {
+        UserRepositoryImpl::with_table_config(
+            InMemoryDynamoDb::new(),
+            "users".to_string(),
+            TableConfig::default(),
+        )
+    }
+
+    fn create_test_user(id: &str, organization_id: &str, role: Role) -> User {
+        User::new(
+            id.to_string(),
+            format!("User {id}"),
+            format!("{id}@example.com"),
+            organization_id.to_string(),
+            "Example".to_string(),
+            [role].into_iter().collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_resend_returns_target_in_callers_organization() {
+        let repository = in_memory_repository();
+        let caller = create_test_user("resend-admin", "org-1", Role::Admin);
+        let target = create_test_user("resend-target", "org-1", Role::Reader);
+        repository.create_user(target.clone()).await.unwrap();
+
+        let resolved = authorize_resend(&repository, &caller, &caller.id, "org-1", &target.id)
+            .await
+            .unwrap();
+        assert_eq!(resolved.id, target.id);
+        assert_eq!(resolved.email, target.email);
+    }
+
+    #[tokio::test]
+    async fn test_resend_without_create_permission_is_rejected() {
+        let repository = in_memory_repository();
+        let caller = create_test_user("resend-reader", "org-1", Role::Reader);
+        let target = create_test_user("resend-target", "org-1", Role::Reader);
+        repository.create_user(target.clone()).await.unwrap();
+
+        let error = authorize_resend(&repository, &caller, &caller.id, "org-1", &target.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LambdaError::InsufficientPermissions));
+        assert_eq!(error.status_code(), 403);
+    }
+
+    #[tokio::test]
+    async fn test_resend_to_missing_user_is_not_found() {
+        let repository = in_memory_repository();
+        let caller = create_test_user("resend-missing-admin", "org-1", Role::Admin);
+
+        let error = authorize_resend(&repository, &caller, &caller.id, "org-1", "missing")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LambdaError::UserNotFound));
+        assert_eq!(error.status_code(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_resend_to_another_organization_is_not_found() {
+        let repository = in_memory_repository();
+        let caller = create_test_user("resend-cross-org-admin", "org-1", Role::Admin);
+        let target = create_test_user("resend-outsider", "org-2", Role::Reader);
+        repository.create_user(target.clone()).await.unwrap();
+
+        let error = authorize_resend(&repository, &caller, &caller.id, "org-1", &target.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, LambdaError::UserNotFound));
+    }
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct ResendInvitationResponse {
+    pub user_id: String,
+    pub user_email: String,
+    pub user_tmp_password: String,
+}
@@ -0,0 +1,549 @@
+mod requests;
+
+use crate::requests::{
+    AssignRolesRequest, AssignRolesResponse, BulkAssignRolesRequest, BulkAssignRolesResponse,
+    RoleAssignment,
+};
+
+use shared::audit_logger::{audit_table_name, log_audit_event};
+use shared::authz::check_permission_with_cache;
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, preferred_language},
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::config::get_config;
+use shared::entity::audit_event::{AuditAction, AuditEvent, AuditOutcome};
+use shared::entity::idempotency::BatchItemOutcome;
+use shared::entity::user::{Permissions, Role, User};
+use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
+use shared::repository::audit_repository::AuditRepositoryImpl;
+use shared::repository::user_repository::{is_not_found, UserRepository, UserRepositoryImpl};
+use shared::session_store::{idempotent_batch, SessionStore};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_lambda_events::http::HeaderMap;
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use std::collections::HashSet;
+use tracing::{debug, info, instrument, warn};
+
+/// Resource of the bulk role assignment endpoint
+const BULK_ROLES_RESOURCE: &str = "/organizations/{organizationId}/users/bulk-roles";
+/// Header carrying a client-chosen key that makes retried bulk requests safe
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Only admins may grant the Admin role
+fn check_role_assignment(caller: &User, roles: &[Role]) -> LambdaResult<()> {
+    let caller_is_admin = caller.has_role(Role::Admin) || caller.has_role(Role::SuperAdmin);
+    if roles.contains(&Role::Admin) && !caller_is_admin {
+        return Err(LambdaError::InsufficientPermissions);
+    }
+    Ok(())
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+/// Caller of the request, loaded for the permission check
+async fn load_caller(repository: &UserRepositoryImpl, user_id: &str) -> LambdaResult<User> {
+    repository
+        .get_user_by_id(user_id.to_string(), false)
+        .await
+        .map_err(|e| LambdaError::from_repository_error(e, LambdaError::UserRetrievalFailed))
+}
+
+/// Reject a role change when `IMMUTABLE_FIELDS` lists `roles`
+fn check_roles_mutable(roles_changed: bool, immutable_fields: &[String]) -> LambdaResult<()> {
+    if roles_changed && immutable_fields.iter().any(|field| field == "roles") {
+        return Err(LambdaError::ImmutableField("roles".to_string()));
+    }
+    Ok(())
+}
+
+/// Replace the roles of a user in the caller's organization and mirror them into Cognito groups
+async fn assign_roles(
+    repository: &UserRepositoryImpl,
+    client_manager: &DefaultClientManager,
+    organization_id: &str,
+    target_user_id: &str,
+    roles: Vec<Role>,
+) -> LambdaResult<AssignRolesResponse> {
+    // Target user must belong to the caller's organization
+    let target_user = match repository
+        .get_user_by_id(target_user_id.to_string(), false)
+        .await
+    {
+        Ok(user) if user.organization_id == organization_id => user,
+        Ok(_) => return Err(LambdaError::UserNotFound),
+        Err(e) if is_not_found(&e) => return Err(LambdaError::UserNotFound),
+        Err(e) => {
+            return Err(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        }
+    };
+
+    // Only the roles attribute is written
+    let roles: HashSet<Role> = roles.into_iter().collect();
+    let roles_changed = roles != target_user.roles;
+    check_roles_mutable(roles_changed, &get_config().immutable_fields)?;
+    let updated_user = repository
+        .update_user_roles(target_user, roles)
+        .await
+        .map_err(|e| LambdaError::from_repository_error(e, LambdaError::UserUpdateFailed))?;
+
+    // Cached user and permission entries are stale now
+    get_cache_manager().invalidate_user(target_user_id).await;
+
+    // Mirror roles into Cognito groups so tokens minted afterward carry `cognito:groups`
+    if roles_changed {
+        let cognito_client = CognitoClientManager::get_client(client_manager).await?;
+        if let Err(e) = cognito_client
+            .sync_user_groups(updated_user.cognito_username(), &updated_user.roles)
+            .await
+        {
+            // DynamoDB roles stay authoritative; the groups catch up on the next role change
+            warn!(
+                "Failed to sync Cognito groups for user {}: {}",
+                updated_user.id, e
+            );
+        }
+    }
+
+    Ok(AssignRolesResponse {
+        user_id: target_user_id.to_string(),
+        roles: updated_user.roles(),
+    })
+}
+
+#[instrument(name = "lambda.users.roles.assign_roles_handler")]
+async fn assign_roles_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let target_user_id = event
+        .payload
+        .path_parameters
+        .get("userId")
+        .cloned()
+        .ok_or_else(|| Error::from(LambdaError::InvalidRequest("missing userId".to_string())))?;
+
+    // Zero-copy deserialization and validation
+    let body = decoded_body(&event.payload).map_err(Error::from)?;
+
+    let assign_roles_request: AssignRolesRequest =
+        serde_json::from_slice(&body).map_err(|e| Error::from(e.to_lambda_error()))?;
+
+    // Validation
+    if let Err(e) = assign_roles_request.validate() {
+        return create_error_response(e, &event.payload);
+    }
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+
+    // Permission check
+    let caller = load_caller(&repository, &user_id)
+        .await
+        .map_err(Error::from)?;
+
+    let permission = match check_permission_with_cache(&caller, &user_id, Permissions::UPDATE).await
+    {
+        Ok(()) => check_role_assignment(&caller, &assign_roles_request.roles),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = permission {
+        let audit_event = AuditEvent::new(
+            user_id,
+            AuditAction::AssignRoles,
+            Some(target_user_id),
+            organization_id,
+            AuditOutcome::PermissionDenied,
+        );
+        log_audit_event(&audit_repository, audit_event).await;
+        return create_error_response(e, &event.payload);
+    }
+
+    let response = match assign_roles(
+        &repository,
+        &client_manager,
+        &organization_id,
+        &target_user_id,
+        assign_roles_request.roles,
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e @ LambdaError::UserNotFound) => return create_error_response(e, &event.payload),
+        Err(e) => return Err(Error::from(e)),
+    };
+
+    let audit_event = AuditEvent::new(
+        user_id,
+        AuditAction::AssignRoles,
+        Some(target_user_id),
+        organization_id,
+        AuditOutcome::Success,
+    );
+    log_audit_event(&audit_repository, audit_event).await;
+
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+/// What every assignment of a bulk request shares
+struct BulkContext<'a> {
+    repository: &'a UserRepositoryImpl,
+    audit_repository: &'a AuditRepositoryImpl,
+    client_manager: &'a DefaultClientManager,
+    caller: &'a User,
+    user_id: &'a str,
+    organization_id: &'a str,
+}
+
+/// Apply one assignment of a bulk request, reporting its result as the single-user endpoint would
+async fn apply_assignment(
+    context: &BulkContext<'_>,
+    assignment: RoleAssignment,
+) -> BatchItemOutcome {
+    let RoleAssignment {
+        user_id: target_user_id,
+        roles,
+    } = assignment;
+    let request = AssignRolesRequest { roles };
+    if let Err(e) = request.validate() {
+        return BatchItemOutcome::failed(target_user_id, &e);
+    }
+
+    let audit_event = |outcome| {
+        AuditEvent::new(
+            context.user_id.to_string(),
+            AuditAction::AssignRoles,
+            Some(target_user_id.clone()),
+            context.organization_id.to_string(),
+            outcome,
+        )
+    };
+    if let Err(e) = check_role_assignment(context.caller, &request.roles) {
+        log_audit_event(
+            context.audit_repository,
+            audit_event(AuditOutcome::PermissionDenied),
+        )
+        .await;
+        return BatchItemOutcome::failed(target_user_id, &e);
+    }
+
+    let result = assign_roles(
+        context.repository,
+        context.client_manager,
+        context.organization_id,
+        &target_user_id,
+        request.roles,
+    )
+    .await;
+    let body = result
+        .and_then(|response| serde_json::to_string(&response).map_err(|e| e.to_lambda_error()));
+    match body {
+        Ok(body) => {
+            log_audit_event(context.audit_repository, audit_event(AuditOutcome::Success)).await;
+            BatchItemOutcome {
+                item_id: target_user_id,
+                status_code: 200,
+                body,
+                replayed: false,
+            }
+        }
+        Err(e) => BatchItemOutcome::failed(target_user_id, &e),
+    }
+}
+
+#[instrument(name = "lambda.users.roles.bulk_assign_roles_handler")]
+async fn bulk_assign_roles_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let body = decoded_body(&event.payload).map_err(Error::from)?;
+    let bulk_request: BulkAssignRolesRequest =
+        serde_json::from_slice(&body).map_err(|e| Error::from(e.to_lambda_error()))?;
+
+    // Validation
+    if let Err(e) = bulk_request.validate() {
+        return create_error_response(e, &event.payload);
+    }
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+    let session_store = SessionStore::from_env((*dynamodb_client).clone());
+
+    // Permission check
+    let caller = load_caller(&repository, &user_id)
+        .await
+        .map_err(Error::from)?;
+    if let Err(e) = check_permission_with_cache(&caller, &user_id, Permissions::UPDATE).await {
+        let audit_event = AuditEvent::new(
+            user_id,
+            AuditAction::AssignRoles,
+            None,
+            organization_id,
+            AuditOutcome::PermissionDenied,
+        );
+        log_audit_event(&audit_repository, audit_event).await;
+        return create_error_response(e, &event.payload);
+    }
+
+    let context = BulkContext {
+        repository: &repository,
+        audit_repository: &audit_repository,
+        client_manager: &client_manager,
+        caller: &caller,
+        user_id: &user_id,
+        organization_id: &organization_id,
+    };
+    let context = &context;
+
+    // A retried batch replays the assignments already applied and applies only the rest
+    let results = match idempotency_key(&event.payload.headers) {
+        Some(key) => {
+            let items = bulk_request
+                .assignments
+                .into_iter()
+                .map(|assignment| (assignment.user_id.clone(), assignment))
+                .collect();
+            match idempotent_batch(
+                session_store.as_ref(),
+                &organization_id,
+                &key,
+                items,
+                |assignment| apply_assignment(context, assignment),
+            )
+            .await
+            {
+                Ok(results) => results,
+                Err(e) => return create_error_response(e, &event.payload),
+            }
+        }
+        None => {
+            let mut results = Vec::with_capacity(bulk_request.assignments.len());
+            for assignment in bulk_request.assignments {
+                results.push(apply_assignment(context, assignment).await);
+            }
+            results
+        }
+    };
+
+    let response = BulkAssignRolesResponse { results };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+/// The request's `Idempotency-Key`, if it has a non-empty one
+fn idempotency_key(headers: &HeaderMap) -> Option<String> {
+    let key = headers.get(IDEMPOTENCY_KEY_HEADER)?.to_str().ok()?.trim();
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+#[instrument(name = "lambda.users.roles.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = match event.payload.resource.as_deref() {
+        Some(BULK_ROLES_RESOURCE) => {
+            LambdaEventRequestHandler::handle_requests(
+                event,
+                BULK_ROLES_RESOURCE,
+                bulk_assign_roles_handler,
+            )
+            .await
+        }
+        _ => {
+            LambdaEventRequestHandler::handle_requests(
+                event,
+                "/organizations/{organizationId}/users/{userId}/roles",
+                assign_roles_handler,
+            )
+            .await
+        }
+    };
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting auth user roles function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::validation::{FieldError, FieldErrorCode};
+
+    fn create_test_user(roles: &[Role]) -> User {
+        User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Example".to_string(),
+            roles.iter().copied().collect(),
+        )
+    }
+
+    #[test]
+    fn test_only_admins_can_assign_admin() {
+        let admin = create_test_user(&[Role::Admin]);
+        let writer = create_test_user(&[Role::Writer]);
+
+        assert!(check_role_assignment(&admin, &[Role::Admin]).is_ok());
+        assert!(check_role_assignment(&writer, &[Role::Reader, Role::Writer]).is_ok());
+        assert!(matches!(
+            check_role_assignment(&writer, &[Role::Admin]),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+    }
+
+    #[test]
+    fn test_roles_listed_as_immutable_cannot_change() {
+        let immutable = vec!["roles".to_string()];
+
+        assert!(matches!(
+            check_roles_mutable(true, &immutable),
+            Err(LambdaError::ImmutableField(field)) if field == "roles"
+        ));
+        assert!(check_roles_mutable(false, &immutable).is_ok());
+        assert!(check_roles_mutable(true, &["locale".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_and_super_admin_roles() {
+        let request = AssignRolesRequest { roles: Vec::new() };
+        let error = request.validate().unwrap_err();
+        assert_eq!(error.status_code(), 400);
+        assert_eq!(
+            error.response_body()["details"],
+            serde_json::json!([{
+                "code": "ROLES_MISSING",
+                "field": "roles",
+                "message": "At least one role must be specified"
+            }])
+        );
+
+        let request = AssignRolesRequest {
+            roles: vec![Role::SuperAdmin],
+        };
+        assert!(matches!(
+            request.validate(),
+            Err(LambdaError::ValidationFailed(_))
+        ));
+
+        let request = AssignRolesRequest {
+            roles: vec![Role::Reader],
+        };
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_bulk_request_requires_distinct_assignments() {
+        let assignment = |user_id: &str| RoleAssignment {
+            user_id: user_id.to_string(),
+            roles: vec![Role::Reader],
+        };
+
+        let request = BulkAssignRolesRequest {
+            assignments: vec![assignment("user-1"), assignment("user-2")],
+        };
+        assert!(request.validate().is_ok());
+
+        for assignments in [
+            Vec::new(),
+            vec![assignment("user-1"), assignment("user-1")],
+            (0..101).map(|i| assignment(&format!("user-{i}"))).collect(),
+        ] {
+            let error = BulkAssignRolesRequest { assignments }
+                .validate()
+                .unwrap_err();
+            assert_eq!(
+                error.field_errors(),
+                [FieldError::new(
+                    "assignments",
+                    FieldErrorCode::AssignmentsInvalid
+                )]
+            );
+        }
+    }
+
+    #[test]
+    fn test_idempotency_key_is_trimmed() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(idempotency_key(&headers), None);
+        headers.insert(IDEMPOTENCY_KEY_HEADER, " ".parse().unwrap());
+        assert_eq!(idempotency_key(&headers), None);
+        headers.insert(IDEMPOTENCY_KEY_HEADER, " key-1 ".parse().unwrap());
+        assert_eq!(idempotency_key(&headers).as_deref(), Some("key-1"));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_route_is_dispatched() {
+        let event = LambdaEvent::new(
+            ApiGatewayProxyRequest {
+                resource: Some(BULK_ROLES_RESOURCE.to_string()),
+                ..Default::default()
+            },
+            lambda_runtime::Context::default(),
+        );
+
+        // Reaches the bulk handler, which rejects a request without the authorizer context
+        let response = handler(event).await.unwrap();
+        assert_eq!(response.status_code, 401);
+    }
+}
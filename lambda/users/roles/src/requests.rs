@@ -0,0 +1,72 @@
+use shared::entity::idempotency::BatchItemOutcome;
+use shared::entity::user::Role;
+use shared::errors::LambdaError;
+use shared::validation::{FieldErrorCode, ValidationErrors};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Most assignments a bulk request may carry, keeping it within the function timeout
+const MAX_BULK_ASSIGNMENTS: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct AssignRolesRequest {
+    pub roles: Vec<Role>,
+}
+
+impl AssignRolesRequest {
+    pub fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+        if self.roles.is_empty() {
+            errors.add("roles", FieldErrorCode::RolesMissing);
+        }
+        if self.roles.contains(&Role::SuperAdmin) {
+            errors.add("roles", FieldErrorCode::RoleNotAssignable);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct AssignRolesResponse {
+    pub user_id: String,
+    pub roles: Vec<Role>,
+}
+
+/// Roles to assign to one user of a bulk request
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct RoleAssignment {
+    pub user_id: String,
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct BulkAssignRolesRequest {
+    pub assignments: Vec<RoleAssignment>,
+}
+
+impl BulkAssignRolesRequest {
+    /// Check the batch itself; each assignment's roles are validated as it is applied
+    pub fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+        let user_ids: HashSet<&str> = self
+            .assignments
+            .iter()
+            .map(|assignment| assignment.user_id.as_str())
+            .collect();
+        if self.assignments.is_empty()
+            || self.assignments.len() > MAX_BULK_ASSIGNMENTS
+            || user_ids.len() != self.assignments.len()
+        {
+            errors.add("assignments", FieldErrorCode::AssignmentsInvalid);
+        }
+
+        errors.into_result()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct BulkAssignRolesResponse {
+    pub results: Vec<BatchItemOutcome>,
+}
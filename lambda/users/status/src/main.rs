@@ -0,0 +1,273 @@
+mod requests;
+
+use crate::requests::{UserStatusRequest, UserStatusResponse};
+
+use shared::audit_logger::{audit_table_name, log_audit_event};
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, preferred_language},
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::entity::audit_event::{AuditAction, AuditEvent, AuditOutcome};
+use shared::entity::user::{Permissions, User};
+use shared::errors::{LambdaError, LambdaResult, ToLambdaError};
+use shared::repository::audit_repository::AuditRepositoryImpl;
+use shared::repository::user_repository::{is_not_found, UserRepository, UserRepositoryImpl};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use tracing::{debug, info, instrument, warn};
+
+/// Callers need `UPDATE` within the organization, and may not disable or enable themselves
+fn check_status_permission(
+    caller: &User,
+    organization_id: &str,
+    target: &User,
+) -> LambdaResult<()> {
+    if !caller.has_permission(Permissions::UPDATE)
+        || caller.organization_id != organization_id
+        || target.organization_id != organization_id
+    {
+        warn!(
+            "User {} is not allowed to change the status of user {}",
+            caller.id, target.id
+        );
+        return Err(LambdaError::InsufficientPermissions);
+    }
+    if caller.id == target.id {
+        return Err(LambdaError::InvalidRequest(
+            "cannot change your own status".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.status.update_user_status_handler")]
+async fn update_user_status_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+    let target_user_id = event
+        .payload
+        .path_parameters
+        .get("userId")
+        .cloned()
+        .ok_or_else(|| Error::from(LambdaError::InvalidRequest("missing userId".to_string())))?;
+
+    let body = decoded_body(&event.payload).map_err(Error::from)?;
+    let status_request: UserStatusRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => return create_error_response(e.to_lambda_error(), &event.payload),
+    };
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+
+    let action = if status_request.enabled {
+        AuditAction::EnableUser
+    } else {
+        AuditAction::DisableUser
+    };
+
+    // Permission check
+    let caller = repository
+        .get_user_by_id(user_id.clone(), false)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+    let target = match repository
+        .get_user_by_id(target_user_id.clone(), false)
+        .await
+    {
+        Ok(user) => user,
+        Err(e) if is_not_found(&e) => {
+            return create_error_response(LambdaError::UserNotFound, &event.payload)
+        }
+        Err(e) => {
+            return create_error_response(
+                LambdaError::from_repository_error(e, LambdaError::UserRetrievalFailed),
+                &event.payload,
+            )
+        }
+    };
+
+    if let Err(e) = check_status_permission(&caller, &organization_id, &target) {
+        let audit_event = AuditEvent::new(
+            user_id,
+            action,
+            Some(target_user_id),
+            organization_id,
+            AuditOutcome::PermissionDenied,
+        );
+        log_audit_event(&audit_repository, audit_event).await;
+        return create_error_response(e, &event.payload);
+    }
+
+    let cognito_client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let username = target.cognito_username().to_string();
+    let result = if status_request.enabled {
+        cognito_client
+            .admin_enable_user(username.clone())
+            .await
+            .map(drop)
+    } else {
+        cognito_client
+            .admin_disable_user(username.clone())
+            .await
+            .map(drop)
+    };
+    if let Err(e) = result {
+        return create_error_response(
+            LambdaError::from_cognito_error(e, LambdaError::UserUpdateFailed),
+            &event.payload,
+        );
+    }
+    debug!(
+        "Set Cognito user {} enabled={}",
+        target_user_id, status_request.enabled
+    );
+
+    // Drop cached permission and hash entries so in-flight sessions are re-evaluated
+    let cache_manager = get_cache_manager();
+    cache_manager.invalidate_user(&target_user_id).await;
+    cache_manager.invalidate_hash(&username).await;
+
+    let audit_event = AuditEvent::new(
+        user_id,
+        action,
+        Some(target_user_id.clone()),
+        organization_id,
+        AuditOutcome::Success,
+    );
+    log_audit_event(&audit_repository, audit_event).await;
+
+    let response = UserStatusResponse {
+        user_id: target_user_id,
+        enabled: status_request.enabled,
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.status.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations/{organizationId}/users/{userId}/status",
+        update_user_status_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting users status function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::entity::user::Role;
+    use std::collections::HashSet;
+
+    fn create_test_user(id: &str, organization_id: &str, role: Role) -> User {
+        User::new(
+            id.to_string(),
+            "Alice".to_string(),
+            format!("{id}@example.com"),
+            organization_id.to_string(),
+            "Example".to_string(),
+            HashSet::from([role]),
+        )
+    }
+
+    #[test]
+    fn test_admin_can_change_status_in_own_organization() {
+        let caller = create_test_user("admin", "org-1", Role::Admin);
+        let target = create_test_user("user", "org-1", Role::Reader);
+        assert!(check_status_permission(&caller, "org-1", &target).is_ok());
+    }
+
+    #[test]
+    fn test_status_change_requires_update_permission() {
+        let target = create_test_user("user", "org-1", Role::Reader);
+        for role in [Role::Reader, Role::Writer] {
+            let caller = create_test_user("caller", "org-1", role);
+            assert!(matches!(
+                check_status_permission(&caller, "org-1", &target),
+                Err(LambdaError::InsufficientPermissions)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_status_change_respects_organization_boundary() {
+        let caller = create_test_user("admin", "org-1", Role::Admin);
+        let other_org = create_test_user("user", "org-2", Role::Reader);
+        assert!(matches!(
+            check_status_permission(&caller, "org-1", &other_org),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+        assert!(matches!(
+            check_status_permission(&caller, "org-2", &other_org),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+    }
+
+    #[test]
+    fn test_cannot_change_own_status() {
+        let caller = create_test_user("admin", "org-1", Role::Admin);
+        assert!(matches!(
+            check_status_permission(&caller, "org-1", &caller.clone()),
+            Err(LambdaError::InvalidRequest(_))
+        ));
+    }
+}
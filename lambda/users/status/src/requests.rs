@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct UserStatusRequest {
+    /// `false` disables the user's Cognito account, `true` enables it again
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(super) struct UserStatusResponse {
+    pub user_id: String,
+    pub enabled: bool,
+}
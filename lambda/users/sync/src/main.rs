@@ -0,0 +1,283 @@
+mod requests;
+
+use crate::requests::{AttributeDiff, SyncUserResponse};
+
+use shared::aws::lambda_events::{
+    middleware::preferred_language,
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::entity::user::{Permissions, User};
+use shared::errors::{LambdaError, LambdaResult};
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::utils::env::get_env;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{service_fn, Error, LambdaEvent};
+use tracing::{debug, info, instrument, warn};
+
+/// Ensure the caller may repair another user's Cognito attributes
+fn check_sync_access(caller: &User, organization_id: &str, target: &User) -> LambdaResult<()> {
+    if !caller.has_permission(Permissions::UPDATE)
+        || caller.organization_id != organization_id
+        || !caller.can_access(target)
+    {
+        return Err(LambdaError::InsufficientPermissions);
+    }
+    Ok(())
+}
+
+/// Cognito attribute updates that bring it in line with the DynamoDB user
+fn attribute_updates<'a>(user: &'a User, diff: &'a [AttributeDiff]) -> Vec<(&'a str, &'a str)> {
+    let mut updates: Vec<(&str, &str)> = diff
+        .iter()
+        .map(|diff| (diff.attribute.as_str(), diff.dynamodb.as_str()))
+        .collect();
+    // Cognito resets `email_verified` on an email change, so carry over the DynamoDB state
+    if diff.iter().any(|diff| diff.attribute == "email") {
+        updates.push((
+            "email_verified",
+            if user.email_verified { "true" } else { "false" },
+        ));
+    }
+    updates
+}
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.sync.sync_user_handler")]
+async fn sync_user_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let client_manager = DefaultClientManager::from_env();
+
+    let RequestContext {
+        user_id,
+        organization_id,
+    } = LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    let target_user_id = match event.payload.path_parameters.get("userId") {
+        Some(target_user_id) => target_user_id.clone(),
+        None => return create_error_response(LambdaError::UserNotFound, &event.payload),
+    };
+
+    // Get clients using abstraction with explicit trait disambiguation
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let cognito_client = CognitoClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+
+    // DynamoDB is the source of truth, so read both users from it rather than the cache
+    let caller = repository
+        .get_user_by_id(user_id.clone(), false)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserRetrievalFailed,
+            ))
+        })?;
+    let target_user = match repository
+        .get_user_by_id(target_user_id.clone(), false)
+        .await
+    {
+        Ok(target_user) => target_user,
+        Err(_) => return create_error_response(LambdaError::UserNotFound, &event.payload),
+    };
+    if let Err(e) = check_sync_access(&caller, &organization_id, &target_user) {
+        return create_error_response(e, &event.payload);
+    }
+
+    let username = target_user.cognito_username().to_string();
+    let cognito_attributes = match cognito_client
+        .get_cognito_user_attributes(username.clone())
+        .await
+    {
+        Ok(attributes) => attributes,
+        Err(e) => {
+            return create_error_response(
+                LambdaError::from_cognito_error(e, LambdaError::UserRetrievalFailed),
+                &event.payload,
+            )
+        }
+    };
+
+    let diff = AttributeDiff::between(&target_user, &cognito_attributes);
+    let in_sync = if diff.is_empty() {
+        true
+    } else {
+        debug!("Cognito attributes out of sync: {:?}", diff);
+        if let Err(e) = cognito_client
+            .admin_update_user_attributes(&username, attribute_updates(&target_user, &diff))
+            .await
+        {
+            return create_error_response(
+                LambdaError::from_cognito_error(e, LambdaError::UserUpdateFailed),
+                &event.payload,
+            );
+        }
+
+        // Read the attributes back to confirm Cognito accepted every change
+        match cognito_client
+            .get_cognito_user_attributes(username.clone())
+            .await
+        {
+            Ok(attributes) => AttributeDiff::between(&target_user, &attributes).is_empty(),
+            Err(e) => {
+                warn!("Failed to verify Cognito attributes of {}: {}", username, e);
+                false
+            }
+        }
+    };
+
+    let response = SyncUserResponse {
+        user_id: target_user.id,
+        username,
+        diff,
+        in_sync,
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.sync.handler")]
+async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations/{organizationId}/users/{userId}/sync",
+        sync_user_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+// Custom allocator configuration
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    shared::tracer::init_tracing();
+    info!("Starting auth user sync function");
+    let result = lambda_runtime::run(service_fn(handler)).await;
+    shared::tracer::shutdown_tracing();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::entity::user::Role;
+    use std::collections::HashMap;
+
+    fn create_test_user(id: &str, organization_id: &str, role: Role) -> User {
+        User::new(
+            id.to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            organization_id.to_string(),
+            "Example".to_string(),
+            [role].into_iter().collect(),
+        )
+    }
+
+    fn cognito_attributes(attributes: &[(&str, &str)]) -> HashMap<String, String> {
+        attributes
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_sync_access_requires_update_in_same_organization() {
+        let target = create_test_user("user-2", "org-1", Role::Reader);
+
+        let admin = create_test_user("user-1", "org-1", Role::Admin);
+        assert!(check_sync_access(&admin, "org-1", &target).is_ok());
+
+        let reader = create_test_user("user-1", "org-1", Role::Reader);
+        assert!(matches!(
+            check_sync_access(&reader, "org-1", &target),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+
+        let other_org = create_test_user("user-1", "org-2", Role::Admin);
+        assert!(matches!(
+            check_sync_access(&other_org, "org-2", &target),
+            Err(LambdaError::InsufficientPermissions)
+        ));
+    }
+
+    #[test]
+    fn test_diff_is_empty_when_both_sides_agree() {
+        let user = create_test_user("user-1", "org-1", Role::Reader);
+        let attributes = cognito_attributes(&[("name", "Alice"), ("email", "alice@example.com")]);
+
+        assert!(AttributeDiff::between(&user, &attributes).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_changed_and_missing_attributes() {
+        let user = create_test_user("user-1", "org-1", Role::Reader);
+        let attributes = cognito_attributes(&[("email", "old@example.com")]);
+
+        assert_eq!(
+            AttributeDiff::between(&user, &attributes),
+            vec![
+                AttributeDiff {
+                    attribute: "name".to_string(),
+                    dynamodb: "Alice".to_string(),
+                    cognito: None,
+                },
+                AttributeDiff {
+                    attribute: "email".to_string(),
+                    dynamodb: "alice@example.com".to_string(),
+                    cognito: Some("old@example.com".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_email_update_carries_over_verified_state() {
+        let mut user = create_test_user("user-1", "org-1", Role::Reader);
+        user.email_verified = true;
+        let attributes = cognito_attributes(&[("name", "Alice"), ("email", "old@example.com")]);
+        let diff = AttributeDiff::between(&user, &attributes);
+
+        assert_eq!(
+            attribute_updates(&user, &diff),
+            vec![("email", "alice@example.com"), ("email_verified", "true")]
+        );
+
+        let attributes = cognito_attributes(&[("email", "alice@example.com")]);
+        let diff = AttributeDiff::between(&user, &attributes);
+        assert_eq!(attribute_updates(&user, &diff), vec![("name", "Alice")]);
+    }
+}
@@ -0,0 +1,40 @@
+use shared::entity::user::User;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// An attribute whose DynamoDB and Cognito values disagreed before the sync
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(super) struct AttributeDiff {
+    pub attribute: String,
+    pub dynamodb: String,
+    /// Cognito value before the sync, `None` when the attribute was unset
+    pub cognito: Option<String>,
+}
+
+impl AttributeDiff {
+    /// Compare the attributes DynamoDB is authoritative for against the Cognito attributes
+    pub fn between(user: &User, cognito_attributes: &HashMap<String, String>) -> Vec<Self> {
+        [("name", user.name.as_str()), ("email", user.email.as_str())]
+            .into_iter()
+            .filter(|(attribute, value)| {
+                cognito_attributes.get(*attribute).map(String::as_str) != Some(*value)
+            })
+            .map(|(attribute, value)| AttributeDiff {
+                attribute: attribute.to_string(),
+                dynamodb: value.to_string(),
+                cognito: cognito_attributes.get(attribute).cloned(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(super) struct SyncUserResponse {
+    pub user_id: String,
+    pub username: String,
+    /// Attributes that differed and were pushed to Cognito
+    pub diff: Vec<AttributeDiff>,
+    /// Whether both sides agree once the sync is done
+    pub in_sync: bool,
+}
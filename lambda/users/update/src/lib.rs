@@ -0,0 +1,391 @@
+mod requests;
+
+use crate::requests::{UpdateUserRequest, UpdateUserResponse};
+
+use shared::audit_logger::{audit_table_name, log_audit_event};
+use shared::authz::check_permission_with_cache;
+use shared::aws::eventbridge::{
+    client::EventBridgePublisher,
+    publisher::{publish_user_event, USER_UPDATED},
+};
+use shared::aws::lambda_events::{
+    middleware::{decoded_body, is_validate_only, preferred_language, validate_only},
+    request::{LambdaEventRequestHandler, RequestContext},
+    response::apigw_response,
+};
+use shared::cache_manager::get_cache_manager;
+use shared::client_manager::{CognitoClientManager, DefaultClientManager, DynamoDbClientManager};
+use shared::config::get_config;
+use shared::entity::audit_event::{AuditAction, AuditEvent, AuditOutcome};
+use shared::entity::user::{Permissions, User};
+use shared::errors::{LambdaError, ToLambdaError};
+use shared::repository::audit_repository::AuditRepositoryImpl;
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use shared::utils::env::get_env;
+use shared::validation::Validate;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use lambda_runtime::{Error, LambdaEvent};
+use tracing::{debug, instrument, warn};
+
+/// Create standardized error response
+fn create_error_response(
+    error: LambdaError,
+    request: &ApiGatewayProxyRequest,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    let error_response = error.response_body_localized(preferred_language(request));
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error_response)?.into()),
+        None,
+    ))
+}
+
+/// Reject an update that changes a field listed in `IMMUTABLE_FIELDS`
+fn check_immutable_fields(
+    current: &User,
+    updated: &User,
+    immutable_fields: &[String],
+) -> Result<(), LambdaError> {
+    let changes = [
+        ("user_name", current.name != updated.name),
+        (
+            "organization_name",
+            current.organization_name != updated.organization_name,
+        ),
+        ("roles", current.roles != updated.roles),
+        ("phone", current.phone != updated.phone),
+        ("locale", current.locale != updated.locale),
+    ];
+
+    match changes.into_iter().find(|(field, changed)| {
+        *changed && immutable_fields.iter().any(|immutable| immutable == field)
+    }) {
+        Some((field, _)) => Err(LambdaError::ImmutableField(field.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Persist `updated`, routing roles-only changes through the conditional write that skips no-ops
+async fn save_user(
+    repository: &impl UserRepository,
+    current: &User,
+    updated: User,
+) -> anyhow::Result<User> {
+    if updated.name == current.name
+        && updated.organization_name == current.organization_name
+        && updated.phone == current.phone
+        && updated.locale == current.locale
+    {
+        repository
+            .update_user_roles(current.clone(), updated.get_roles())
+            .await
+    } else {
+        repository.update_user(updated).await
+    }
+}
+
+#[instrument(name = "lambda.users.update.update_user_handler")]
+async fn update_user_handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    if is_validate_only(&event) {
+        return validate_only::<UpdateUserRequest>(&event);
+    }
+    let client_manager = DefaultClientManager::from_env();
+    let cache_manager = get_cache_manager();
+
+    let RequestContext { user_id, .. } =
+        LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await?;
+
+    // Zero-copy deserialization and validation
+    let body = decoded_body(&event.payload).map_err(Error::from)?;
+
+    let update_user_request: UpdateUserRequest =
+        serde_json::from_slice(&body).map_err(|e| Error::from(e.to_lambda_error()))?;
+
+    // Validation
+    if let Err(e) = update_user_request.validate() {
+        return create_error_response(e, &event.payload);
+    }
+
+    let dynamodb_client = DynamoDbClientManager::get_client(&client_manager)
+        .await
+        .map_err(Error::from)?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name);
+    let audit_repository = AuditRepositoryImpl::new((*dynamodb_client).clone(), audit_table_name());
+
+    // Get user info from cache
+    let user = if let Some(cached_user) = cache_manager.get_user(&user_id).await {
+        debug!("User info cache hit for user: {}", user_id);
+        cached_user
+    } else {
+        let user = repository
+            .get_user_by_id(user_id.clone(), false)
+            .await
+            .map_err(|e| {
+                Error::from(LambdaError::from_repository_error(
+                    e,
+                    LambdaError::UserRetrievalFailed,
+                ))
+            })?;
+        cache_manager.set_user(user_id.clone(), user.clone()).await;
+        user
+    };
+
+    // Permission check
+    if let Err(e) = check_permission_with_cache(&user, &user_id, Permissions::UPDATE).await {
+        let audit_event = AuditEvent::new(
+            user_id.clone(),
+            AuditAction::UpdateUser,
+            Some(user_id),
+            user.organization_id,
+            AuditOutcome::PermissionDenied,
+        );
+        log_audit_event(&audit_repository, audit_event).await;
+        return create_error_response(e, &event.payload);
+    }
+
+    // Update user information
+    let mut updated_user = user.clone();
+    updated_user.name = update_user_request.user_name.clone();
+    updated_user.organization_name = update_user_request.organization_name.clone();
+    if let Some(phone) = update_user_request.phone.clone() {
+        updated_user.phone = Some(phone);
+    }
+    if let Some(locale) = update_user_request.locale.clone() {
+        updated_user.locale = Some(locale);
+    }
+
+    let new_roles = update_user_request.roles.clone();
+    if !new_roles.is_empty() {
+        updated_user.set_from_roles(new_roles);
+    }
+    let roles_changed = updated_user.roles != user.roles;
+
+    if let Err(e) = check_immutable_fields(&user, &updated_user, &get_config().immutable_fields) {
+        return create_error_response(e, &event.payload);
+    }
+
+    // Update DynamoDB
+    let updated_user = save_user(&repository, &user, updated_user)
+        .await
+        .map_err(|e| {
+            Error::from(LambdaError::from_repository_error(
+                e,
+                LambdaError::UserUpdateFailed,
+            ))
+        })?;
+
+    // Cached permission decisions were made for the old roles
+    if roles_changed {
+        cache_manager.invalidate_user(&user_id).await;
+    }
+
+    // Mirror the locale into Cognito so its messages to the user follow it
+    if updated_user.locale != user.locale {
+        if let Some(locale) = &updated_user.locale {
+            let cognito_client = CognitoClientManager::get_client(&client_manager)
+                .await
+                .map_err(Error::from)?;
+            if let Err(e) = cognito_client
+                .admin_update_user_attributes(
+                    updated_user.cognito_username(),
+                    vec![("locale", locale)],
+                )
+                .await
+            {
+                // DynamoDB stays authoritative; Cognito catches up on the next locale change
+                warn!(
+                    "Failed to set Cognito locale for user {}: {}",
+                    updated_user.id, e
+                );
+            }
+        }
+    }
+
+    // Mirror roles into Cognito groups so tokens minted afterward carry `cognito:groups`
+    if roles_changed {
+        let cognito_client = CognitoClientManager::get_client(&client_manager)
+            .await
+            .map_err(Error::from)?;
+        if let Err(e) = cognito_client
+            .sync_user_groups(updated_user.cognito_username(), &updated_user.roles)
+            .await
+        {
+            // DynamoDB roles stay authoritative; the groups catch up on the next role change
+            warn!(
+                "Failed to sync Cognito groups for user {}: {}",
+                updated_user.id, e
+            );
+        }
+    }
+
+    let audit_event = AuditEvent::new(
+        user_id.clone(),
+        AuditAction::UpdateUser,
+        Some(updated_user.id.clone()),
+        updated_user.organization_id.clone(),
+        AuditOutcome::Success,
+    );
+    log_audit_event(&audit_repository, audit_event).await;
+    publish_user_event(
+        &EventBridgePublisher::from_env(),
+        USER_UPDATED,
+        &updated_user.id,
+        &updated_user.organization_id,
+    )
+    .await;
+
+    // Update cache
+    cache_manager
+        .set_user(user_id.clone(), updated_user.clone())
+        .await;
+
+    let response = UpdateUserResponse {
+        message: format!("User {user_id} has been updated."),
+    };
+    Ok(apigw_response(
+        200,
+        Some(serde_json::to_string(&response)?.into()),
+        None,
+    ))
+}
+
+#[instrument(name = "lambda.users.update.handler")]
+pub async fn handler(
+    event: LambdaEvent<ApiGatewayProxyRequest>,
+) -> Result<ApiGatewayProxyResponse, Error> {
+    debug!("handling lambda req: {:?}", event);
+    let response = LambdaEventRequestHandler::handle_requests(
+        event,
+        "/organizations/{organizationId}/users/{userId}",
+        update_user_handler,
+    )
+    .await;
+    get_cache_manager().record_metrics();
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::aws::dynamodb::in_memory::InMemoryDynamoDb;
+    use shared::config::TableConfig;
+    use shared::entity::user::Role;
+
+    fn create_test_user() -> User {
+        User::new(
+            "user-1".to_string(),
+            "alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Example".to_string(),
+            [Role::Writer].into_iter().collect(),
+        )
+    }
+
+    fn in_memory_repository() -> UserRepositoryImpl<InMemoryDynamoDb> {
+        UserRepositoryImpl::with_table_config(
+            InMemoryDynamoDb::new(),
+            "users".to_string(),
+            TableConfig::default(),
+        )
+    }
+
+    fn immutable(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    #[test]
+    fn test_update_to_immutable_field_is_rejected() {
+        let user = create_test_user();
+        let mut updated = user.clone();
+        updated.organization_name = "Renamed".to_string();
+
+        let error = check_immutable_fields(&user, &updated, &immutable(&["organization_name"]))
+            .unwrap_err();
+        assert!(
+            matches!(&error, LambdaError::ImmutableField(field) if field == "organization_name")
+        );
+        assert_eq!(error.status_code(), 409);
+    }
+
+    #[test]
+    fn test_update_to_mutable_field_succeeds() {
+        let user = create_test_user();
+        let mut updated = user.clone();
+        updated.locale = Some("ja-JP".to_string());
+
+        assert!(
+            check_immutable_fields(&user, &updated, &immutable(&["organization_name"])).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_resending_unchanged_immutable_field_succeeds() {
+        let user = create_test_user();
+        let updated = user.clone();
+
+        assert!(check_immutable_fields(
+            &user,
+            &updated,
+            &immutable(&["user_name", "organization_name", "roles"])
+        )
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_roles_only_update_writes_roles() {
+        let repository = in_memory_repository();
+        let user = create_test_user();
+        repository.create_user(user.clone()).await.unwrap();
+        let mut updated = user.clone();
+        updated.set_from_roles(vec![Role::Admin]);
+
+        let saved = save_user(&repository, &user, updated).await.unwrap();
+        assert!(saved.has_role(Role::Admin));
+
+        let stored = repository
+            .get_user_by_id(user.id.clone(), false)
+            .await
+            .unwrap();
+        assert_eq!(stored.roles, saved.roles);
+        assert_eq!(stored.name, user.name);
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_update_skips_the_write() {
+        let repository = in_memory_repository();
+        let user = create_test_user();
+
+        // Nothing is stored, so any write would show up as a new item
+        let saved = save_user(&repository, &user, user.clone()).await.unwrap();
+        assert_eq!(saved.roles, user.roles);
+        assert!(repository
+            .get_user_by_id(user.id.clone(), false)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_profile_update_writes_every_field() {
+        let repository = in_memory_repository();
+        let user = create_test_user();
+        repository.create_user(user.clone()).await.unwrap();
+        let mut updated = user.clone();
+        updated.name = "alicia".to_string();
+        updated.set_from_roles(vec![Role::Reader]);
+
+        save_user(&repository, &user, updated).await.unwrap();
+
+        let stored = repository
+            .get_user_by_id(user.id.clone(), false)
+            .await
+            .unwrap();
+        assert_eq!(stored.name, "alicia");
+        assert!(stored.has_role(Role::Reader));
+    }
+}
@@ -1,6 +1,7 @@
 use shared::entity::user::Role;
 use shared::errors::LambdaError;
-use shared::utils::regex::is_valid_username;
+use shared::utils::regex::{is_valid_locale, is_valid_phone, is_valid_username};
+use shared::validation::{FieldErrorCode, Validate, ValidationErrors};
 
 use serde::{Deserialize, Serialize};
 
@@ -9,21 +10,48 @@ pub(super) struct UpdateUserRequest {
     pub user_name: String,
     pub organization_name: String,
     pub roles: Vec<Role>,
+    /// Contact phone number in E.164 format, left unchanged when omitted
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Preferred locale, e.g. `en` or `ja-JP`, left unchanged when omitted
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
-impl UpdateUserRequest {
-    pub fn validate(&self) -> Result<(), LambdaError> {
+impl Validate for UpdateUserRequest {
+    fn validate(&self) -> Result<(), LambdaError> {
+        let mut errors = ValidationErrors::new();
+
         // Username validation
         if !is_valid_username(&self.user_name) {
-            return Err(LambdaError::InvalidUsername);
+            errors.add("user_name", FieldErrorCode::UsernameInvalid);
+        }
+
+        // Phone validation
+        if let Some(phone) = &self.phone {
+            if !is_valid_phone(phone) {
+                errors.add("phone", FieldErrorCode::PhoneInvalid);
+            }
+        }
+
+        // Locale validation
+        if let Some(locale) = &self.locale {
+            if !is_valid_locale(locale) {
+                errors.add("locale", FieldErrorCode::LocaleInvalid);
+            }
         }
 
         // Organization name validation
         if self.organization_name.len() < 2 || self.organization_name.len() > 100 {
-            return Err(LambdaError::InvalidOrganizationName);
+            errors.add("organization_name", FieldErrorCode::OrganizationNameInvalid);
+        }
+
+        // Role validation
+        if self.roles.contains(&Role::SuperAdmin) {
+            errors.add("roles", FieldErrorCode::RoleNotAssignable);
         }
 
-        Ok(())
+        errors.into_result()
     }
 }
 
@@ -0,0 +1,111 @@
+use crate::entity::audit_event::AuditEvent;
+use crate::repository::audit_repository::AuditRepository;
+use crate::utils::env::get_env;
+
+use tracing::{error, info};
+
+/// Audit table name, configurable via `AUDIT_TABLE_NAME`
+pub fn audit_table_name() -> String {
+    get_env("AUDIT_TABLE_NAME", "AuditLogs")
+}
+
+/// Record an audit event. Failures are logged and swallowed so auditing never fails the caller.
+pub async fn log_audit_event(repository: &impl AuditRepository, event: AuditEvent) {
+    info!(
+        audit.actor_user_id = %event.actor_user_id,
+        audit.action = %event.action,
+        audit.target_user_id = ?event.target_user_id,
+        audit.organization_id = %event.organization_id,
+        audit.outcome = %event.outcome,
+        "audit event"
+    );
+
+    if let Err(e) = repository.put_audit_event(&event).await {
+        error!("Failed to write audit event {}: {:?}", event.id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::audit_event::{AuditAction, AuditOutcome};
+    use crate::repository::audit_repository::AuditPage;
+
+    use anyhow::{anyhow, Error as AnyhowError};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct MockAuditRepository {
+        fail: bool,
+        events: Mutex<Vec<AuditEvent>>,
+    }
+
+    impl MockAuditRepository {
+        fn new(fail: bool) -> Self {
+            Self {
+                fail,
+                events: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AuditRepository for MockAuditRepository {
+        async fn put_audit_event(&self, event: &AuditEvent) -> Result<(), AnyhowError> {
+            if self.fail {
+                return Err(anyhow!("audit table unavailable"));
+            }
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+
+        async fn query(
+            &self,
+            _organization_id: &str,
+            _from_ts: u64,
+            _to_ts: u64,
+            _action: Option<String>,
+            _limit: i32,
+            _next_token: Option<String>,
+        ) -> Result<AuditPage, AnyhowError> {
+            Ok(AuditPage {
+                events: vec![],
+                next_token: None,
+            })
+        }
+    }
+
+    fn create_test_event() -> AuditEvent {
+        AuditEvent::new(
+            "admin-1".to_string(),
+            AuditAction::UpdateUser,
+            Some("user-1".to_string()),
+            "org-1".to_string(),
+            AuditOutcome::Success,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_log_audit_event_writes_event() {
+        let repository = MockAuditRepository::new(false);
+        log_audit_event(&repository, create_test_event()).await;
+
+        let events = repository.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, AuditAction::UpdateUser);
+    }
+
+    #[tokio::test]
+    async fn test_log_audit_event_swallows_errors() {
+        let repository = MockAuditRepository::new(true);
+        log_audit_event(&repository, create_test_event()).await;
+
+        assert!(repository.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_audit_table_name_default() {
+        std::env::remove_var("AUDIT_TABLE_NAME");
+        assert_eq!(audit_table_name(), "AuditLogs");
+    }
+}
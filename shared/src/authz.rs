@@ -0,0 +1,111 @@
+use crate::cache_manager::get_cache_manager;
+use crate::entity::user::{Permissions, User};
+use crate::errors::{LambdaError, LambdaResult};
+
+use tracing::debug;
+
+/// Check that `user` holds `permission`, caching the decision per user and permission
+pub async fn check_permission_with_cache(
+    user: &User,
+    user_id: &str,
+    permission: Permissions,
+) -> LambdaResult<()> {
+    let cache_manager = get_cache_manager();
+
+    // Check cache first
+    let has_permission = match cache_manager.get_permission_for(user_id, &permission).await {
+        Some(has_permission) => {
+            debug!("Permission cache hit for user: {}", user_id);
+            has_permission
+        }
+        None => {
+            let has_permission = user.has_permission(permission.clone());
+            cache_manager
+                .set_permission_for(user_id, &permission, has_permission)
+                .await;
+            has_permission
+        }
+    };
+
+    if has_permission {
+        Ok(())
+    } else {
+        Err(LambdaError::InsufficientPermissions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::user::Role;
+    use std::collections::HashSet;
+
+    fn create_test_user(id: &str, role: Role) -> User {
+        User::new(
+            id.to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Example".to_string(),
+            HashSet::from([role]),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_cache_miss_granted_is_cached() {
+        let user = create_test_user("authz-granted", Role::Admin);
+        assert!(
+            check_permission_with_cache(&user, &user.id, Permissions::DELETE)
+                .await
+                .is_ok()
+        );
+
+        let cached = get_cache_manager()
+            .get_permission_for(&user.id, &Permissions::DELETE)
+            .await;
+        assert_eq!(cached, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_cache_miss_denied_is_cached() {
+        let user = create_test_user("authz-denied", Role::Reader);
+        assert!(matches!(
+            check_permission_with_cache(&user, &user.id, Permissions::CREATE).await,
+            Err(LambdaError::InsufficientPermissions)
+        ));
+
+        let cached = get_cache_manager()
+            .get_permission_for(&user.id, &Permissions::CREATE)
+            .await;
+        assert_eq!(cached, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_skips_role_check() {
+        let user = create_test_user("authz-hit", Role::Reader);
+        get_cache_manager()
+            .set_permission_for(&user.id, &Permissions::UPDATE, true)
+            .await;
+
+        assert!(
+            check_permission_with_cache(&user, &user.id, Permissions::UPDATE)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decisions_are_cached_per_permission() {
+        let user = create_test_user("authz-distinct", Role::Writer);
+        assert!(
+            check_permission_with_cache(&user, &user.id, Permissions::CREATE)
+                .await
+                .is_ok()
+        );
+        // A cached CREATE grant must not be reused for DELETE
+        assert!(matches!(
+            check_permission_with_cache(&user, &user.id, Permissions::DELETE).await,
+            Err(LambdaError::InsufficientPermissions)
+        ));
+    }
+}
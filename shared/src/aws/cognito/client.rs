@@ -1,23 +1,37 @@
 use crate::aws::cognito::error::CognitoError;
+use crate::entity::user::Role;
 
 use aws_config::{meta::region::RegionProviderChain, Region};
 use aws_sdk_cognitoidentityprovider::{
     operation::{
-        admin_create_user::AdminCreateUserOutput, admin_delete_user::AdminDeleteUserOutput,
-        admin_get_user::AdminGetUserOutput, admin_set_user_password::AdminSetUserPasswordOutput,
+        admin_add_user_to_group::AdminAddUserToGroupOutput,
+        admin_create_user::{builders::AdminCreateUserFluentBuilder, AdminCreateUserOutput},
+        admin_delete_user::AdminDeleteUserOutput,
+        admin_disable_user::AdminDisableUserOutput,
+        admin_enable_user::AdminEnableUserOutput,
+        admin_get_user::AdminGetUserOutput,
+        admin_remove_user_from_group::AdminRemoveUserFromGroupOutput,
+        admin_set_user_password::AdminSetUserPasswordOutput,
         admin_update_user_attributes::AdminUpdateUserAttributesOutput,
+        admin_user_global_sign_out::AdminUserGlobalSignOutOutput,
+        describe_user_pool::DescribeUserPoolOutput,
         initiate_auth::InitiateAuthOutput,
+        respond_to_auth_challenge::RespondToAuthChallengeOutput,
+    },
+    types::{
+        AttributeType, AuthFlowType, ChallengeNameType, DeliveryMediumType, MessageActionType,
+        UserStatusType,
     },
-    types::{AttributeType, AuthFlowType, DeliveryMediumType, MessageActionType},
     Client,
 };
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
-use hmac::{Hmac, Mac};
+use hmac::{digest::InvalidLength, Hmac, Mac};
 use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use tracing::instrument;
+use tracing::{error, instrument};
 
 #[derive(Clone)]
 pub struct CognitoClient {
@@ -46,6 +60,14 @@ impl CognitoClient {
         })
     }
 
+    /// Create a client for user pool calls that need no app client, e.g. `describe_user_pool`
+    pub async fn for_user_pool(
+        region_string: String,
+        user_pool_id: String,
+    ) -> Result<Self, CognitoError> {
+        Self::new(region_string, user_pool_id, String::new(), String::new()).await
+    }
+
     #[instrument(
         skip(self),
         fields(user_pool_id = %self.user_pool_id, username = %username),
@@ -54,14 +76,57 @@ impl CognitoClient {
     pub async fn admin_create_user(
         &self,
         username: String,
+        force_alias_creation: bool,
+        validation_data: Vec<(String, String)>,
     ) -> Result<AdminCreateUserOutput, CognitoError> {
-        let result = self
+        let builder = self
             .client
             .admin_create_user()
             .user_pool_id(&self.user_pool_id)
             .username(&username)
             .message_action(MessageActionType::Suppress)
-            .desired_delivery_mediums(DeliveryMediumType::Email)
+            .desired_delivery_mediums(DeliveryMediumType::Email);
+        let result = with_create_user_options(builder, force_alias_creation, validation_data)
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id, username = %username),
+        name = "aws.cognito.admin_disable_user"
+    )]
+    pub async fn admin_disable_user(
+        &self,
+        username: String,
+    ) -> Result<AdminDisableUserOutput, CognitoError> {
+        let result = self
+            .client
+            .admin_disable_user()
+            .user_pool_id(&self.user_pool_id)
+            .username(&username)
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id, username = %username),
+        name = "aws.cognito.admin_enable_user"
+    )]
+    pub async fn admin_enable_user(
+        &self,
+        username: String,
+    ) -> Result<AdminEnableUserOutput, CognitoError> {
+        let result = self
+            .client
+            .admin_enable_user()
+            .user_pool_id(&self.user_pool_id)
+            .username(&username)
             .send()
             .await?;
 
@@ -108,6 +173,138 @@ impl CognitoClient {
         Ok(result)
     }
 
+    /// Cognito `sub` of the user created by `admin_create_user`
+    // Shares the client-wide error type; clippy only flags its size on non-async functions
+    #[allow(clippy::result_large_err)]
+    pub fn extract_sub(output: &AdminCreateUserOutput) -> Result<String, CognitoError> {
+        let user = output
+            .user()
+            .ok_or_else(|| CognitoError::MissingSub("no user in AdminCreateUser output".into()))?;
+        sub_from_attributes(user.attributes())
+            .map(str::to_string)
+            .ok_or_else(|| CognitoError::MissingSub("no sub in user attributes".into()))
+    }
+
+    /// User attributes of `username` as a name-to-value map
+    pub async fn get_cognito_user_attributes(
+        &self,
+        username: String,
+    ) -> Result<HashMap<String, String>, CognitoError> {
+        let user = self.admin_get_user(username).await?;
+        Ok(attributes_to_map(user.user_attributes()))
+    }
+
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id, username = %username, group = %group),
+        name = "aws.cognito.admin_add_user_to_group"
+    )]
+    pub async fn admin_add_user_to_group(
+        &self,
+        username: &str,
+        group: &str,
+    ) -> Result<AdminAddUserToGroupOutput, CognitoError> {
+        let result = self
+            .client
+            .admin_add_user_to_group()
+            .user_pool_id(&self.user_pool_id)
+            .username(username)
+            .group_name(group)
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id, username = %username, group = %group),
+        name = "aws.cognito.admin_remove_user_from_group"
+    )]
+    pub async fn admin_remove_user_from_group(
+        &self,
+        username: &str,
+        group: &str,
+    ) -> Result<AdminRemoveUserFromGroupOutput, CognitoError> {
+        let result = self
+            .client
+            .admin_remove_user_from_group()
+            .user_pool_id(&self.user_pool_id)
+            .username(username)
+            .group_name(group)
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Names of every group `username` belongs to, following pagination
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id, username = %username),
+        name = "aws.cognito.admin_list_groups_for_user"
+    )]
+    pub async fn admin_list_groups_for_user(
+        &self,
+        username: &str,
+    ) -> Result<Vec<String>, CognitoError> {
+        let mut groups = Vec::new();
+        let mut next_token = None;
+        loop {
+            let result = self
+                .client
+                .admin_list_groups_for_user()
+                .user_pool_id(&self.user_pool_id)
+                .username(username)
+                .set_next_token(next_token)
+                .send()
+                .await?;
+            groups.extend(
+                result
+                    .groups()
+                    .iter()
+                    .filter_map(|group| group.group_name().map(str::to_string)),
+            );
+            next_token = result.next_token;
+            if next_token.is_none() {
+                return Ok(groups);
+            }
+        }
+    }
+
+    /// Reconcile the role groups of `username` with `roles`; groups unrelated to roles are kept
+    pub async fn sync_user_groups(
+        &self,
+        username: &str,
+        roles: &HashSet<Role>,
+    ) -> Result<(), CognitoError> {
+        let current = self.admin_list_groups_for_user(username).await?;
+        let (to_add, to_remove) = role_group_changes(&current, roles);
+        for group in to_add {
+            self.admin_add_user_to_group(username, group).await?;
+        }
+        for group in to_remove {
+            self.admin_remove_user_from_group(username, &group).await?;
+        }
+        Ok(())
+    }
+
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id),
+        name = "aws.cognito.describe_user_pool"
+    )]
+    pub async fn describe_user_pool(&self) -> Result<DescribeUserPoolOutput, CognitoError> {
+        let result = self
+            .client
+            .describe_user_pool()
+            .user_pool_id(&self.user_pool_id)
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
     #[instrument(
         skip(self, password),
         fields(user_pool_id = %self.user_pool_id, username = %username),
@@ -132,6 +329,52 @@ impl CognitoClient {
         Ok(result)
     }
 
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id, username = %username),
+        name = "aws.cognito.admin_user_global_sign_out"
+    )]
+    pub async fn admin_user_global_sign_out(
+        &self,
+        username: &str,
+    ) -> Result<AdminUserGlobalSignOutOutput, CognitoError> {
+        let result = self
+            .client
+            .admin_user_global_sign_out()
+            .user_pool_id(&self.user_pool_id)
+            .username(username)
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Force a password reset: set a temporary password, then revoke every refresh token of the
+    /// user. Fails before signing out if the password could not be set; a sign-out failure is
+    /// returned as is, with the new password already in place, so the caller may retry it.
+    #[instrument(
+        skip(self, temp_password),
+        fields(user_pool_id = %self.user_pool_id, username = %username),
+        name = "aws.cognito.reset_and_revoke"
+    )]
+    pub async fn reset_and_revoke(
+        &self,
+        username: &str,
+        temp_password: &str,
+    ) -> Result<(), CognitoError> {
+        self.admin_set_user_password(username, temp_password, false)
+            .await?;
+        self.admin_user_global_sign_out(username)
+            .await
+            .inspect_err(|e| {
+                error!(
+                    "Temporary password set for {} but sessions were not revoked: {}",
+                    username, e
+                )
+            })?;
+        Ok(())
+    }
+
     #[instrument(
         skip(self),
         fields(user_pool_id = %self.user_pool_id, username = %username, email = %email),
@@ -165,12 +408,37 @@ impl CognitoClient {
         Ok(result)
     }
 
+    /// Set standard or custom attributes (e.g. `locale`) of `username`
+    #[instrument(
+        skip(self, attributes),
+        fields(user_pool_id = %self.user_pool_id, username = %username),
+        name = "aws.cognito.admin_update_user_attributes"
+    )]
+    pub async fn admin_update_user_attributes(
+        &self,
+        username: &str,
+        attributes: Vec<(&str, &str)>,
+    ) -> Result<AdminUpdateUserAttributesOutput, CognitoError> {
+        let user_attributes = attributes
+            .into_iter()
+            .map(|(name, value)| AttributeType::builder().name(name).value(value).build())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let result = self
+            .client
+            .admin_update_user_attributes()
+            .user_pool_id(&self.user_pool_id)
+            .username(username)
+            .set_user_attributes(Some(user_attributes))
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
     pub async fn calculate_hash(&self, username: String) -> Result<String, CognitoError> {
-        let mut mac = Hmac::<Sha256>::new_from_slice(self.client_secret.as_bytes())
-            .map_err(|e| CognitoError::Unknown(e.to_string()))?;
-        let message = format!("{}{}", username, self.client_id);
-        mac.update(message.as_bytes());
-        Ok(STANDARD.encode(mac.finalize().into_bytes()))
+        secret_hash(&username, &self.client_id, &self.client_secret)
+            .map_err(|e| CognitoError::Unknown(e.to_string()))
     }
 
     #[instrument(
@@ -200,6 +468,31 @@ impl CognitoClient {
         Ok(result)
     }
 
+    #[instrument(
+        skip(self, code, session, hash),
+        fields(user_pool_id = %self.user_pool_id, username = %username),
+        name = "aws.cognito.respond_to_sms_mfa_challenge"
+    )]
+    pub async fn respond_to_sms_mfa_challenge(
+        &self,
+        username: String,
+        code: String,
+        session: String,
+        hash: String,
+    ) -> Result<RespondToAuthChallengeOutput, CognitoError> {
+        let result = self
+            .client
+            .respond_to_auth_challenge()
+            .client_id(&self.client_id)
+            .challenge_name(ChallengeNameType::SmsMfa)
+            .session(session)
+            .set_challenge_responses(Some(sms_mfa_challenge_responses(&username, &code, &hash)))
+            .send()
+            .await?;
+
+        Ok(result)
+    }
+
     #[instrument(
         skip(self, hash),
         fields(user_pool_id = %self.user_pool_id, refresh_token = %refresh_token),
@@ -223,3 +516,424 @@ impl CognitoClient {
         Ok(result)
     }
 }
+
+/// Build the `ChallengeResponses` answering an `SMS_MFA` challenge
+/// Flatten Cognito attributes into a map, skipping attributes without a value
+/// Role groups to join and leave so that membership matches `roles`
+fn role_group_changes(
+    current: &[String],
+    roles: &HashSet<Role>,
+) -> (Vec<&'static str>, Vec<String>) {
+    let mut to_add: Vec<&'static str> = roles
+        .iter()
+        .map(Role::to_cognito_group)
+        .filter(|group| !current.iter().any(|c| c == group))
+        .collect();
+    to_add.sort_unstable();
+    let to_remove = current
+        .iter()
+        .filter(|group| Role::from_cognito_group(group).is_some_and(|role| !roles.contains(&role)))
+        .cloned()
+        .collect();
+    (to_add, to_remove)
+}
+
+pub fn attributes_to_map(attributes: &[AttributeType]) -> HashMap<String, String> {
+    attributes
+        .iter()
+        .filter_map(|attr| {
+            attr.value()
+                .map(|value| (attr.name().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Value of the `sub` attribute, which Cognito sets on every user
+pub fn sub_from_attributes(attributes: &[AttributeType]) -> Option<&str> {
+    attributes
+        .iter()
+        .find(|attr| attr.name() == "sub")
+        .and_then(|attr| attr.value())
+}
+
+/// Whether an existing Cognito user was left behind by an interrupted create of `email_lower`:
+/// it has never signed in (`FORCE_CHANGE_PASSWORD`) and holds that email, or, if the create
+/// stopped before the email attribute was set, is named by it. Completing a create resets the
+/// password, so no other user may be adopted.
+pub fn is_unclaimed_user(user: &AdminGetUserOutput, email_lower: &str) -> bool {
+    if user.user_status() != Some(&UserStatusType::ForceChangePassword) {
+        return false;
+    }
+    match user
+        .user_attributes()
+        .iter()
+        .find(|attr| attr.name() == "email")
+    {
+        Some(email) => email
+            .value()
+            .is_some_and(|email| email.eq_ignore_ascii_case(email_lower)),
+        None => user.username().eq_ignore_ascii_case(email_lower),
+    }
+}
+
+fn sms_mfa_challenge_responses(username: &str, code: &str, hash: &str) -> HashMap<String, String> {
+    HashMap::from([
+        ("USERNAME".to_string(), username.to_string()),
+        ("SMS_MFA_CODE".to_string(), code.to_string()),
+        ("SECRET_HASH".to_string(), hash.to_string()),
+    ])
+}
+
+/// Apply the alias and pre-signup validation options, leaving them unset when not requested
+fn with_create_user_options(
+    builder: AdminCreateUserFluentBuilder,
+    force_alias_creation: bool,
+    validation_data: Vec<(String, String)>,
+) -> AdminCreateUserFluentBuilder {
+    let validation_data = (!validation_data.is_empty()).then(|| {
+        validation_data
+            .into_iter()
+            .map(|(name, value)| AttributeType::builder().name(name).value(value).build())
+            .collect::<Result<Vec<_>, _>>()
+    });
+
+    builder
+        .set_force_alias_creation(force_alias_creation.then_some(true))
+        // AttributeType only fails to build without a name, which a tuple always has
+        .set_validation_data(validation_data.and_then(Result::ok))
+}
+
+/// Compute the Cognito `SECRET_HASH` for a username
+fn secret_hash(
+    username: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<String, InvalidLength> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(client_secret.as_bytes())?;
+    let message = format!("{username}{client_id}");
+    mac.update(message.as_bytes());
+    Ok(STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_cognitoidentityprovider::types::UserType;
+    use wiremock::matchers::{header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn admin_get_user_output(status: UserStatusType, email: Option<&str>) -> AdminGetUserOutput {
+        let attributes = email
+            .map(|email| {
+                AttributeType::builder()
+                    .name("email")
+                    .value(email)
+                    .build()
+                    .unwrap()
+            })
+            .into_iter()
+            .collect();
+        AdminGetUserOutput::builder()
+            .username("alice@example.com")
+            .user_status(status)
+            .set_user_attributes(Some(attributes))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unclaimed_user_has_never_signed_in_and_holds_the_email() {
+        let user = admin_get_user_output(
+            UserStatusType::ForceChangePassword,
+            Some("Alice@Example.com"),
+        );
+        assert!(is_unclaimed_user(&user, "alice@example.com"));
+        assert!(!is_unclaimed_user(&user, "mallory@example.com"));
+
+        // A create that stopped before the email was set is matched by the username
+        let user = admin_get_user_output(UserStatusType::ForceChangePassword, None);
+        assert!(is_unclaimed_user(&user, "alice@example.com"));
+    }
+
+    #[test]
+    fn test_signed_in_or_federated_users_are_not_unclaimed() {
+        for status in [
+            UserStatusType::Confirmed,
+            UserStatusType::ExternalProvider,
+            UserStatusType::ResetRequired,
+        ] {
+            let user = admin_get_user_output(status, Some("alice@example.com"));
+            assert!(!is_unclaimed_user(&user, "alice@example.com"));
+        }
+    }
+
+    #[test]
+    fn test_role_group_changes() {
+        let current = vec!["Reader".to_string(), "beta-testers".to_string()];
+        let roles = HashSet::from([Role::Admin, Role::Writer]);
+        let (to_add, to_remove) = role_group_changes(&current, &roles);
+        assert_eq!(to_add, vec!["Admin", "Writer"]);
+        // Groups that do not mirror a role are left alone
+        assert_eq!(to_remove, vec!["Reader".to_string()]);
+
+        let (to_add, to_remove) =
+            role_group_changes(&["Admin".to_string()], &HashSet::from([Role::Admin]));
+        assert!(to_add.is_empty() && to_remove.is_empty());
+    }
+
+    fn attribute(name: &str, value: &str) -> AttributeType {
+        AttributeType::builder()
+            .name(name)
+            .value(value)
+            .build()
+            .unwrap()
+    }
+
+    fn create_user_output(attributes: Vec<AttributeType>) -> AdminCreateUserOutput {
+        AdminCreateUserOutput::builder()
+            .user(UserType::builder().set_attributes(Some(attributes)).build())
+            .build()
+    }
+
+    #[test]
+    fn test_extract_sub() {
+        let output = create_user_output(vec![
+            attribute("email", "alice@example.com"),
+            attribute("sub", "user-1"),
+        ]);
+        assert_eq!(CognitoClient::extract_sub(&output).unwrap(), "user-1");
+    }
+
+    #[test]
+    fn test_extract_sub_without_sub_attribute() {
+        let output = create_user_output(vec![attribute("email", "alice@example.com")]);
+        assert!(matches!(
+            CognitoClient::extract_sub(&output),
+            Err(CognitoError::MissingSub(_))
+        ));
+        assert!(matches!(
+            CognitoClient::extract_sub(&AdminCreateUserOutput::builder().build()),
+            Err(CognitoError::MissingSub(_))
+        ));
+    }
+
+    #[test]
+    fn test_sms_mfa_challenge_responses() {
+        let responses = sms_mfa_challenge_responses("alice", "123456", "hash");
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses["USERNAME"], "alice");
+        assert_eq!(responses["SMS_MFA_CODE"], "123456");
+        assert_eq!(responses["SECRET_HASH"], "hash");
+    }
+
+    #[test]
+    fn test_attributes_to_map() {
+        let attributes = vec![
+            AttributeType::builder()
+                .name("email")
+                .value("alice@example.com")
+                .build()
+                .unwrap(),
+            AttributeType::builder()
+                .name("email_verified")
+                .value("true")
+                .build()
+                .unwrap(),
+            AttributeType::builder()
+                .name("phone_number")
+                .build()
+                .unwrap(),
+        ];
+
+        let map = attributes_to_map(&attributes);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["email"], "alice@example.com");
+        assert_eq!(map["email_verified"], "true");
+    }
+
+    fn test_client() -> Client {
+        let config = aws_sdk_cognitoidentityprovider::Config::builder()
+            .behavior_version(aws_config::BehaviorVersion::latest())
+            .region(Region::new("ap-northeast-1"))
+            .build();
+        Client::from_conf(config)
+    }
+
+    /// Client sending its requests to `server`
+    fn mock_cognito_client(server: &MockServer) -> CognitoClient {
+        let config = aws_sdk_cognitoidentityprovider::Config::builder()
+            .behavior_version(aws_config::BehaviorVersion::latest())
+            .region(Region::new("ap-northeast-1"))
+            .endpoint_url(server.uri())
+            .credentials_provider(aws_sdk_cognitoidentityprovider::config::Credentials::new(
+                "test", "test", None, None, "test",
+            ))
+            .build();
+        CognitoClient {
+            client: Arc::new(Client::from_conf(config)),
+            user_pool_id: "pool-1".to_string(),
+            client_id: "client-1".to_string(),
+            client_secret: "secret".to_string(),
+        }
+    }
+
+    async fn mount_operation(server: &MockServer, operation: &str, response: ResponseTemplate) {
+        Mock::given(method("POST"))
+            .and(header(
+                "x-amz-target",
+                format!("AWSCognitoIdentityProviderService.{operation}").as_str(),
+            ))
+            .respond_with(response)
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    /// Cognito operations received by `server`, in order
+    async fn received_operations(server: &MockServer) -> Vec<String> {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter_map(|request| request.headers.get("x-amz-target"))
+            .map(|target| {
+                target
+                    .to_str()
+                    .unwrap()
+                    .rsplit('.')
+                    .next()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn json_response(status: u16, body: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(status)
+            .insert_header("content-type", "application/x-amz-json-1.1")
+            .set_body_json(body)
+    }
+
+    #[tokio::test]
+    async fn test_reset_and_revoke_sets_password_then_signs_out() {
+        let server = MockServer::start().await;
+        let ok = json_response(200, serde_json::json!({}));
+        mount_operation(&server, "AdminSetUserPassword", ok.clone()).await;
+        mount_operation(&server, "AdminUserGlobalSignOut", ok).await;
+
+        let client = mock_cognito_client(&server);
+        client
+            .reset_and_revoke("alice", "Temp-pass-1")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            received_operations(&server).await,
+            vec!["AdminSetUserPassword", "AdminUserGlobalSignOut"]
+        );
+        let set_password: serde_json::Value =
+            serde_json::from_slice(&server.received_requests().await.unwrap()[0].body).unwrap();
+        assert_eq!(set_password["Permanent"], false);
+        assert_eq!(set_password["Username"], "alice");
+    }
+
+    #[tokio::test]
+    async fn test_reset_and_revoke_reports_sign_out_failure() {
+        let server = MockServer::start().await;
+        mount_operation(
+            &server,
+            "AdminSetUserPassword",
+            json_response(200, serde_json::json!({})),
+        )
+        .await;
+        mount_operation(
+            &server,
+            "AdminUserGlobalSignOut",
+            json_response(
+                400,
+                serde_json::json!({
+                    "__type": "UserNotFoundException",
+                    "message": "User does not exist."
+                }),
+            ),
+        )
+        .await;
+
+        let client = mock_cognito_client(&server);
+        let error = client
+            .reset_and_revoke("alice", "Temp-pass-1")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            CognitoError::AdminUserGlobalSignOutError(_)
+        ));
+        assert!(error.is_user_not_found());
+        assert_eq!(
+            received_operations(&server).await,
+            vec!["AdminSetUserPassword", "AdminUserGlobalSignOut"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reset_and_revoke_skips_sign_out_when_reset_fails() {
+        let server = MockServer::start().await;
+        mount_operation(
+            &server,
+            "AdminSetUserPassword",
+            json_response(
+                400,
+                serde_json::json!({
+                    "__type": "InvalidPasswordException",
+                    "message": "Password does not conform to policy"
+                }),
+            ),
+        )
+        .await;
+
+        let client = mock_cognito_client(&server);
+        let error = client.reset_and_revoke("alice", "weak").await.unwrap_err();
+        assert!(matches!(error, CognitoError::AdminSetUserPasswordError(_)));
+        assert_eq!(
+            received_operations(&server).await,
+            vec!["AdminSetUserPassword"]
+        );
+    }
+
+    #[test]
+    fn test_create_user_options_are_passed_to_builder() {
+        let builder = with_create_user_options(
+            test_client().admin_create_user(),
+            true,
+            vec![("invite_code".to_string(), "abc123".to_string())],
+        );
+
+        assert_eq!(builder.get_force_alias_creation(), &Some(true));
+        let validation_data = builder.get_validation_data().as_ref().unwrap();
+        assert_eq!(validation_data.len(), 1);
+        assert_eq!(validation_data[0].name(), "invite_code");
+        assert_eq!(validation_data[0].value(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_create_user_options_default_to_unset() {
+        let builder =
+            with_create_user_options(test_client().admin_create_user(), false, Vec::new());
+
+        assert_eq!(builder.get_force_alias_creation(), &None);
+        assert_eq!(builder.get_validation_data(), &None);
+    }
+
+    #[test]
+    fn test_secret_hash_uses_cognito_username() {
+        let email_hash = secret_hash("alice@example.com", "client-id", "client-secret").unwrap();
+        let username_hash = secret_hash("alice", "client-id", "client-secret").unwrap();
+
+        assert_ne!(email_hash, username_hash);
+        assert_eq!(
+            username_hash,
+            secret_hash("alice", "client-id", "client-secret").unwrap()
+        );
+    }
+}
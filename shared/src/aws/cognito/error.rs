@@ -1,8 +1,15 @@
 use aws_sdk_cognitoidentityprovider::error::{BuildError, SdkError};
 use aws_sdk_cognitoidentityprovider::operation::{
-    admin_create_user::AdminCreateUserError, admin_delete_user::AdminDeleteUserError,
-    admin_get_user::AdminGetUserError, admin_set_user_password::AdminSetUserPasswordError,
-    admin_update_user_attributes::AdminUpdateUserAttributesError, initiate_auth::InitiateAuthError,
+    admin_add_user_to_group::AdminAddUserToGroupError, admin_create_user::AdminCreateUserError,
+    admin_delete_user::AdminDeleteUserError, admin_disable_user::AdminDisableUserError,
+    admin_enable_user::AdminEnableUserError, admin_get_user::AdminGetUserError,
+    admin_list_groups_for_user::AdminListGroupsForUserError,
+    admin_remove_user_from_group::AdminRemoveUserFromGroupError,
+    admin_set_user_password::AdminSetUserPasswordError,
+    admin_update_user_attributes::AdminUpdateUserAttributesError,
+    admin_user_global_sign_out::AdminUserGlobalSignOutError,
+    describe_user_pool::DescribeUserPoolError, initiate_auth::InitiateAuthError,
+    respond_to_auth_challenge::RespondToAuthChallengeError,
 };
 use hmac::digest::InvalidLength as HmacInvalidLength;
 use jsonwebtoken::errors::Error as JwtError;
@@ -14,24 +21,48 @@ pub enum CognitoError {
     #[error("BuildError: {0}")]
     BuildError(#[from] BuildError),
 
+    #[error("AdminAddUserToGroupError: {0}")]
+    AdminAddUserToGroupError(#[from] SdkError<AdminAddUserToGroupError>),
+
     #[error("AdminCreateUserError: {0}")]
     AdminCreateUserError(#[from] SdkError<AdminCreateUserError>),
 
     #[error("AdminDeleteUserError: {0}")]
     AdminDeleteUserError(#[from] SdkError<AdminDeleteUserError>),
 
+    #[error("AdminDisableUserError: {0}")]
+    AdminDisableUserError(#[from] SdkError<AdminDisableUserError>),
+
+    #[error("AdminEnableUserError: {0}")]
+    AdminEnableUserError(#[from] SdkError<AdminEnableUserError>),
+
     #[error("AdminGetUserError: {0}")]
     AdminGetUserError(#[from] SdkError<AdminGetUserError>),
 
+    #[error("AdminListGroupsForUserError: {0}")]
+    AdminListGroupsForUserError(#[from] SdkError<AdminListGroupsForUserError>),
+
+    #[error("AdminRemoveUserFromGroupError: {0}")]
+    AdminRemoveUserFromGroupError(#[from] SdkError<AdminRemoveUserFromGroupError>),
+
     #[error("AdminSetUserPasswordError: {0}")]
     AdminSetUserPasswordError(#[from] SdkError<AdminSetUserPasswordError>),
 
     #[error("AdminUpdateUserAttributesError: {0}")]
     AdminUpdateUserAttributesError(#[from] SdkError<AdminUpdateUserAttributesError>),
 
+    #[error("AdminUserGlobalSignOutError: {0}")]
+    AdminUserGlobalSignOutError(#[from] SdkError<AdminUserGlobalSignOutError>),
+
+    #[error("DescribeUserPoolError: {0}")]
+    DescribeUserPoolError(#[from] SdkError<DescribeUserPoolError>),
+
     #[error("InitiateAuthError: {0}")]
     InitiateAuthError(#[from] SdkError<InitiateAuthError>),
 
+    #[error("RespondToAuthChallengeError: {0}")]
+    RespondToAuthChallengeError(#[from] SdkError<RespondToAuthChallengeError>),
+
     #[error("JWT Error: {0}")]
     JwtError(#[from] JwtError),
 
@@ -44,9 +75,91 @@ pub enum CognitoError {
     #[error("Http Error: {0}")]
     HttpError(String),
 
+    #[error("Missing sub attribute: {0}")]
+    MissingSub(String),
+
     #[error("Invalid Token Error: {0}")]
     InvalidTokenError(String),
 
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
+
+impl CognitoError {
+    /// Check whether an attribute update collided with another account's email or phone alias
+    pub fn is_alias_exists(&self) -> bool {
+        match self {
+            CognitoError::AdminUpdateUserAttributesError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_alias_exists_exception()),
+            _ => false,
+        }
+    }
+
+    /// Check whether a user could not be created because its username is taken
+    pub fn is_username_exists(&self) -> bool {
+        match self {
+            CognitoError::AdminCreateUserError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_username_exists_exception()),
+            _ => false,
+        }
+    }
+
+    /// Check whether a sign-in was refused for a wrong password or a disabled user
+    pub fn is_not_authorized(&self) -> bool {
+        match self {
+            CognitoError::InitiateAuthError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_not_authorized_exception()),
+            _ => false,
+        }
+    }
+
+    /// Check whether a sign-in was refused because the user has not confirmed their sign-up
+    pub fn is_user_not_confirmed(&self) -> bool {
+        match self {
+            CognitoError::InitiateAuthError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_confirmed_exception()),
+            _ => false,
+        }
+    }
+
+    /// Check whether an admin operation targeted a user that does not exist in the pool
+    pub fn is_user_not_found(&self) -> bool {
+        match self {
+            CognitoError::AdminAddUserToGroupError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminDeleteUserError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminDisableUserError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminEnableUserError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminGetUserError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminListGroupsForUserError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminRemoveUserFromGroupError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminSetUserPasswordError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminUpdateUserAttributesError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            CognitoError::AdminUserGlobalSignOutError(e) => e
+                .as_service_error()
+                .is_some_and(|e| e.is_user_not_found_exception()),
+            _ => false,
+        }
+    }
+}
@@ -1,19 +1,80 @@
 use crate::aws::cognito::error::CognitoError;
+use crate::config::get_config;
 
 use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Number of attempts to fetch the JWKS before giving up
+const JWKS_FETCH_ATTEMPTS: u32 = 2;
+/// Backoff before the first retry, doubled on each subsequent retry
+const JWKS_BASE_BACKOFF: Duration = Duration::from_millis(100);
+/// Upper bound for establishing the connection to the JWKS endpoint
+const JWKS_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long a fetched JWKS is reused before it is fetched again
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Custom attribute claim carrying the user's organization ID
+pub const ORGANIZATION_ID_CLAIM: &str = "custom:organization_id";
+
+/// Claims of a Cognito ID or access token; claims only one kind carries are optional
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub iss: String,
     pub iat: u64,
     pub exp: u64,
+    /// `id` or `access`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_use: Option<String>,
+    /// App client ID, only in access tokens (ID tokens carry it as `aud`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Cognito username: `username` in access tokens, `cognito:username` in ID tokens
+    #[serde(
+        default,
+        alias = "cognito:username",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub username: Option<String>,
+    #[serde(
+        default,
+        rename = "cognito:groups",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub groups: Vec<String>,
+    /// Any other claims, e.g. `email` or `custom:*` attributes
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Claims {
+    /// String value of an additional claim
+    pub fn claim(&self, name: &str) -> Option<&str> {
+        self.extra.get(name).and_then(Value::as_str)
+    }
+
+    /// Organization ID from the `custom:organization_id` claim, if the token carries it
+    pub fn organization_id(&self) -> Option<&str> {
+        self.claim(ORGANIZATION_ID_CLAIM)
+            .filter(|id| !id.is_empty())
+    }
+
+    /// App client that the token was issued to: `client_id` of access tokens, `aud` of ID tokens
+    pub fn app_client_id(&self) -> Option<&str> {
+        self.client_id.as_deref().or_else(|| self.claim("aud"))
+    }
+
+    /// Seconds remaining until the token expires, saturating at zero
+    pub fn expires_in_secs(&self, now: u64) -> u64 {
+        self.exp.saturating_sub(now)
+    }
 }
 
 #[derive(Clone)]
@@ -21,49 +82,90 @@ pub struct CognitoTokenAuthorizer {
     user_pool_id: String,
     jwks_url: String,
     region: String,
-    jwks_cache: Arc<RwLock<(Value, Instant)>>,
+    jwks_cache: Arc<RwLock<Option<(Value, Instant)>>>,
+    http_client: reqwest::Client,
+    leeway: Duration,
+    reject_future_iat: bool,
 }
 
 impl CognitoTokenAuthorizer {
-    pub async fn new(user_pool_id: String, jwks_url: String, region: String) -> Self {
+    /// Create an authorizer, fetching the JWKS with `http_client` if given (e.g. one pointed at a
+    /// mock server in tests) or with a client configured from `JWKS_TIMEOUT_SECS` otherwise.
+    /// Clock skew leeway defaults to `JWT_LEEWAY_SECS`, future `iat` rejection to `REJECT_FUTURE_IAT`.
+    pub async fn new(
+        user_pool_id: String,
+        jwks_url: String,
+        region: String,
+        http_client: Option<reqwest::Client>,
+    ) -> Self {
         CognitoTokenAuthorizer {
             user_pool_id,
             jwks_url,
             region,
-            jwks_cache: Arc::new(RwLock::new((serde_json::json!({}), Instant::now()))),
+            jwks_cache: Arc::new(RwLock::new(None)),
+            http_client: http_client
+                .unwrap_or_else(|| build_http_client(get_config().jwks_timeout)),
+            leeway: get_config().jwt_leeway,
+            reject_future_iat: get_config().reject_future_iat,
         }
     }
 
+    /// Tolerate this much clock skew between the token issuer and this Lambda
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Reject tokens issued more than the leeway in the future, e.g. forged or from a skewed clock
+    pub fn with_reject_future_iat(mut self, reject_future_iat: bool) -> Self {
+        self.reject_future_iat = reject_future_iat;
+        self
+    }
+
     async fn get_jwks(&self) -> Result<Value, CognitoError> {
         let mut cache = self.jwks_cache.write().await;
         let now = Instant::now();
-        if now.duration_since(cache.1) > Duration::from_secs(3600) {
-            info!("Fetching new JWKS from {}", self.jwks_url);
-            let client = reqwest::Client::new();
-            let response = client.get(&self.jwks_url).send().await.map_err(|e| {
-                error!("Failed to fetch JWKS: {:?}", e);
-                CognitoError::ReqwestError(e)
-            })?;
+        match cache.as_ref() {
+            Some((jwks, fetched_at)) if now.duration_since(*fetched_at) <= JWKS_CACHE_TTL => {
+                info!("Using cached JWKS");
+                Ok(jwks.clone())
+            }
+            _ => {
+                info!("Fetching new JWKS from {}", self.jwks_url);
+                let jwks = fetch_jwks(&self.http_client, &self.jwks_url).await?;
 
-            if !response.status().is_success() {
-                error!("Failed to fetch JWKS: HTTP {}", response.status());
-                CognitoError::HttpError(format!(
-                    "Failed to fetch JWKS: HTTP {}",
-                    response.status()
-                ));
+                *cache = Some((jwks.clone(), now));
+                Ok(jwks)
             }
+        }
+    }
 
-            let jwks: Value = response.json().await.map_err(|e| {
-                error!("Failed to parse JWKS JSON: {:?}", e);
-                CognitoError::ReqwestError(e)
-            })?;
+    /// Check that the JWKS is reachable and holds at least one key, without validating a token
+    #[instrument(
+        skip(self),
+        fields(user_pool_id = %self.user_pool_id),
+        name = "aws.cognito.token_authorizer.check_jwks"
+    )]
+    pub async fn check_jwks(&self) -> Result<(), CognitoError> {
+        let jwks = self.get_jwks().await?;
+        self.jwks_keys(&jwks).map(|_| ())
+    }
 
-            *cache = (jwks, now);
-            Ok(cache.0.clone())
-        } else {
-            info!("Using cached JWKS");
-            Ok(cache.0.clone())
+    /// Keys of a fetched JWKS. An empty key set means a misconfigured endpoint, not a rotated
+    /// key, so it is reported like a fetch failure.
+    #[allow(clippy::result_large_err)]
+    fn jwks_keys<'a>(&self, jwks: &'a Value) -> Result<&'a [Value], CognitoError> {
+        let keys = jwks["keys"].as_array().ok_or_else(|| {
+            error!("JWKS does not contain 'keys' array");
+            CognitoError::InvalidTokenError("Missing keys".to_string())
+        })?;
+        if keys.is_empty() {
+            error!("JWKS from {} contained no keys", self.jwks_url);
+            return Err(CognitoError::HttpError(
+                "JWKS contained no keys".to_string(),
+            ));
         }
+        Ok(keys)
     }
 
     #[instrument(
@@ -86,10 +188,7 @@ impl CognitoTokenAuthorizer {
 
         info!("Token 'kid' extracted: {}", kid);
 
-        let keys = jwks["keys"].as_array().ok_or_else(|| {
-            error!("JWKS does not contain 'keys' array");
-            CognitoError::InvalidTokenError("Missing keys".to_string())
-        })?;
+        let keys = self.jwks_keys(&jwks)?;
 
         let jwk = keys
             .iter()
@@ -121,7 +220,9 @@ impl CognitoTokenAuthorizer {
             self.region, self.user_pool_id
         );
         let mut validation = Validation::new(Algorithm::RS256);
+        #[allow(clippy::cloned_ref_to_slice_refs)]
         validation.set_issuer(&[issuer.clone()]);
+        validation.leeway = self.leeway.as_secs();
 
         info!("Validation configured with issuer: {}", issuer);
 
@@ -130,8 +231,342 @@ impl CognitoTokenAuthorizer {
             CognitoError::JwtError(e)
         })?;
 
+        if self.reject_future_iat {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            if is_issued_in_future(token_data.claims.iat, now, self.leeway) {
+                warn!(
+                    "Token issued in the future: iat {} is after now {}",
+                    token_data.claims.iat, now
+                );
+                return Err(CognitoError::InvalidTokenError(
+                    "Token issued in the future".to_string(),
+                ));
+            }
+        }
+
         info!("Token successfully decoded and validated");
 
         Ok(token_data.claims)
     }
 }
+
+/// Check whether `iat` lies more than `leeway` after `now`
+fn is_issued_in_future(iat: u64, now: u64, leeway: Duration) -> bool {
+    iat > now.saturating_add(leeway.as_secs())
+}
+
+/// Build the JWKS HTTP client with bounded request and connect timeouts
+fn build_http_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .connect_timeout(JWKS_CONNECT_TIMEOUT.min(timeout))
+        .build()
+        .unwrap_or_else(|e| {
+            warn!("Failed to build JWKS HTTP client, using defaults: {:?}", e);
+            reqwest::Client::new()
+        })
+}
+
+/// Check whether a failed JWKS request is worth retrying
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_timeout()
+        || error.is_connect()
+        || error
+            .status()
+            .is_some_and(|status| status.is_server_error())
+}
+
+/// Exponential backoff with full jitter for the given retry (starting at 1)
+fn backoff_delay(retry: u32) -> Duration {
+    let max_delay = JWKS_BASE_BACKOFF * 2u32.pow(retry.saturating_sub(1));
+    rand::thread_rng().gen_range(Duration::ZERO..=max_delay)
+}
+
+async fn fetch_jwks_once(client: &reqwest::Client, url: &str) -> Result<Value, reqwest::Error> {
+    client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+}
+
+/// Fetch the JWKS, retrying transient network failures
+async fn fetch_jwks(client: &reqwest::Client, url: &str) -> Result<Value, CognitoError> {
+    let mut attempt = 1;
+    loop {
+        match fetch_jwks_once(client, url).await {
+            Ok(jwks) => return Ok(jwks),
+            Err(e) if attempt < JWKS_FETCH_ATTEMPTS && is_transient(&e) => {
+                let delay = backoff_delay(attempt);
+                warn!(
+                    "Failed to fetch JWKS, retrying in {:?} ({}/{}): {:?}",
+                    delay, attempt, JWKS_FETCH_ATTEMPTS, e
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                error!("Failed to fetch JWKS: {:?}", e);
+                return Err(CognitoError::HttpError(format!(
+                    "Failed to fetch JWKS: {e}"
+                )));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TEST_KID: &str = "test-key";
+    const TEST_PRIVATE_KEY: &str = include_str!("testdata/jwks_test_key.pem");
+    /// Base64url RSA modulus of `TEST_PRIVATE_KEY`
+    const TEST_MODULUS: &str = "qr0tmN4l__njbhseogUx_IuDnE7RENrUPPWMLVIxVap893S-Zwqh_3RSr2oMHpmB4mNpRXTtah7FfyKMpvceRrOxVpb5s5shVAn75hQDUA4bOHLcVtXHvOl1sEW15nxYgzDGqH4tWSrfL6oMMKOuuXNdExi_7rKeKjPGEpXfk7yowdWMY-lCQeWACZDK-XrdTESd5Y8SFQPD6lmUraQIZR0V7dB15yEhDEga4XheF4g0PvYqeKLP_EUQBZAiXNsyr0LjepvP2Bi0uZuqubv2VO28fuGM8dyMhEBzFF9Xis6_1angd1VSeBHh9Ho1dlPDhkdFQ6wI6fleXTnxbdWILw";
+
+    const TEST_ISSUER: &str = "https://cognito-idp.ap-northeast-1.amazonaws.com/pool-1";
+
+    fn now_secs() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn sign_test_token(issuer: &str) -> String {
+        sign_test_token_expiring_at(issuer, now_secs() + 300)
+    }
+
+    fn sign_test_token_expiring_at(issuer: &str, exp: u64) -> String {
+        sign_test_token_with_times(issuer, exp.saturating_sub(300), exp)
+    }
+
+    fn sign_test_token_with_times(issuer: &str, iat: u64, exp: u64) -> String {
+        let claims = Claims {
+            sub: "user-1".to_string(),
+            iss: issuer.to_string(),
+            iat,
+            exp,
+            ..Default::default()
+        };
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(TEST_KID.to_string());
+        let key = EncodingKey::from_rsa_pem(TEST_PRIVATE_KEY.as_bytes()).unwrap();
+        encode(&header, &claims, &key).unwrap()
+    }
+
+    async fn mount_jwks(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/.well-known/jwks.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "keys": [{
+                    "kty": "RSA",
+                    "alg": "RS256",
+                    "use": "sig",
+                    "kid": TEST_KID,
+                    "n": TEST_MODULUS,
+                    "e": "AQAB"
+                }]
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    async fn test_authorizer(server: &MockServer) -> CognitoTokenAuthorizer {
+        CognitoTokenAuthorizer::new(
+            "pool-1".to_string(),
+            format!("{}/.well-known/jwks.json", server.uri()),
+            "ap-northeast-1".to_string(),
+            Some(reqwest::Client::new()),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_check_jwks() {
+        let server = MockServer::start().await;
+        mount_jwks(&server).await;
+        assert!(test_authorizer(&server).await.check_jwks().await.is_ok());
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/jwks.json"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "keys": [] })),
+            )
+            .mount(&server)
+            .await;
+        assert!(matches!(
+            test_authorizer(&server).await.check_jwks().await,
+            Err(CognitoError::HttpError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_with_injected_client() {
+        let server = MockServer::start().await;
+        mount_jwks(&server).await;
+
+        let authorizer = test_authorizer(&server).await;
+        let token = sign_test_token(TEST_ISSUER);
+
+        let claims = authorizer.validate_token(&token).await.unwrap();
+        assert_eq!(claims.sub, "user-1");
+        // The second validation is served from the JWKS cache
+        authorizer.validate_token(&token).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_empty_jwks_is_distinct_from_unknown_kid() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/jwks.json"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "keys": [] })),
+            )
+            .mount(&server)
+            .await;
+
+        let authorizer = test_authorizer(&server).await;
+        let result = authorizer
+            .validate_token(&sign_test_token(TEST_ISSUER))
+            .await;
+        assert!(matches!(
+            result,
+            Err(CognitoError::HttpError(message)) if message == "JWKS contained no keys"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_recently_expired_token_validates_within_leeway() {
+        let server = MockServer::start().await;
+        mount_jwks(&server).await;
+        let token = sign_test_token_expiring_at(TEST_ISSUER, now_secs() - 30);
+
+        let lenient = test_authorizer(&server)
+            .await
+            .with_leeway(Duration::from_secs(60));
+        assert_eq!(lenient.validate_token(&token).await.unwrap().sub, "user-1");
+
+        let strict = lenient.with_leeway(Duration::ZERO);
+        let result = strict.validate_token(&token).await;
+        assert!(matches!(
+            result,
+            Err(CognitoError::JwtError(e))
+                if *e.kind() == jsonwebtoken::errors::ErrorKind::ExpiredSignature
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_future_iat_rejected_beyond_leeway() {
+        let server = MockServer::start().await;
+        mount_jwks(&server).await;
+        let iat = now_secs() + 300;
+        let token = sign_test_token_with_times(TEST_ISSUER, iat, iat + 3600);
+
+        let authorizer = test_authorizer(&server)
+            .await
+            .with_leeway(Duration::from_secs(60));
+        // Off by default
+        assert!(authorizer.validate_token(&token).await.is_ok());
+
+        let authorizer = authorizer.with_reject_future_iat(true);
+        assert!(matches!(
+            authorizer.validate_token(&token).await,
+            Err(CognitoError::InvalidTokenError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_future_iat_accepted_within_leeway() {
+        let server = MockServer::start().await;
+        mount_jwks(&server).await;
+        let iat = now_secs() + 30;
+        let token = sign_test_token_with_times(TEST_ISSUER, iat, iat + 3600);
+
+        let authorizer = test_authorizer(&server)
+            .await
+            .with_leeway(Duration::from_secs(60))
+            .with_reject_future_iat(true);
+        assert_eq!(
+            authorizer.validate_token(&token).await.unwrap().sub,
+            "user-1"
+        );
+    }
+
+    #[test]
+    fn test_is_issued_in_future_bounds() {
+        let leeway = Duration::from_secs(60);
+        assert!(!is_issued_in_future(1_000, 1_000, leeway));
+        assert!(!is_issued_in_future(1_060, 1_000, leeway));
+        assert!(is_issued_in_future(1_061, 1_000, leeway));
+    }
+
+    #[test]
+    fn test_claims_parse_access_token() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "user-1",
+            "iss": TEST_ISSUER,
+            "iat": 1_700_000_000,
+            "exp": 1_700_003_600,
+            "token_use": "access",
+            "client_id": "client-1",
+            "username": "alice",
+            "cognito:groups": ["admins", "billing"],
+            "scope": "aws.cognito.signin.user.admin"
+        }))
+        .unwrap();
+
+        assert_eq!(claims.token_use.as_deref(), Some("access"));
+        assert_eq!(claims.client_id.as_deref(), Some("client-1"));
+        assert_eq!(claims.username.as_deref(), Some("alice"));
+        assert_eq!(claims.groups, vec!["admins", "billing"]);
+        assert_eq!(claims.claim("scope"), Some("aws.cognito.signin.user.admin"));
+    }
+
+    #[test]
+    fn test_claims_parse_id_token() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "user-1",
+            "iss": TEST_ISSUER,
+            "iat": 1_700_000_000,
+            "exp": 1_700_003_600,
+            "token_use": "id",
+            "aud": "client-1",
+            "cognito:username": "alice",
+            "email": "alice@example.com"
+        }))
+        .unwrap();
+
+        assert_eq!(claims.token_use.as_deref(), Some("id"));
+        assert_eq!(claims.client_id, None);
+        assert_eq!(claims.username.as_deref(), Some("alice"));
+        assert!(claims.groups.is_empty());
+        assert_eq!(claims.claim("email"), Some("alice@example.com"));
+    }
+
+    #[test]
+    fn test_backoff_delay_is_bounded() {
+        for retry in 1..=JWKS_FETCH_ATTEMPTS {
+            assert!(backoff_delay(retry) <= JWKS_BASE_BACKOFF * 2u32.pow(retry - 1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_jwks_unreachable_endpoint_returns_http_error() {
+        let client = build_http_client(Duration::from_millis(500));
+        // Nothing listens on port 1, so every attempt fails to connect
+        let result = fetch_jwks(&client, "http://127.0.0.1:1/jwks.json").await;
+
+        assert!(matches!(result, Err(CognitoError::HttpError(_))));
+    }
+}
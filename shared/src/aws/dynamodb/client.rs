@@ -1,17 +1,181 @@
 use crate::aws::dynamodb::error::DynamoDbError;
+use crate::config::{get_config, TableConfig};
 
 use aws_config::{meta::region::RegionProviderChain, Region};
 use aws_sdk_dynamodb::{
+    error::{BuildError, ProvideErrorMetadata},
     operation::{
         delete_item::DeleteItemOutput, get_item::GetItemOutput, put_item::PutItemOutput,
         query::QueryOutput, scan::ScanOutput, update_item::UpdateItemOutput,
     },
-    types::AttributeValue,
+    types::{
+        AttributeDefinition, AttributeValue, BillingMode, GlobalSecondaryIndex, KeySchemaElement,
+        KeyType, Projection, ProjectionType, ReturnValue, ScalarAttributeType, Select,
+    },
     Client,
 };
+use rand::Rng;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use tracing::instrument;
+use std::time::Duration;
+use tracing::{info, instrument, warn};
+
+/// Maximum number of retries for throttled requests
+const MAX_THROTTLE_RETRIES: u32 = 3;
+/// Backoff before the first retry, doubled on each subsequent retry
+const BASE_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Check whether an error means DynamoDB throttled the request
+pub fn is_throttling_error<E: ProvideErrorMetadata>(error: &E) -> bool {
+    matches!(
+        error.code(),
+        Some(
+            "ProvisionedThroughputExceededException"
+                | "RequestLimitExceeded"
+                | "ThrottlingException"
+        )
+    )
+}
+
+/// Check whether an error means the condition expression of a write was not met
+pub fn is_conditional_check_failed<E: ProvideErrorMetadata>(error: &E) -> bool {
+    error.code() == Some("ConditionalCheckFailedException")
+}
+
+/// Outcome of a conditional write: `Ok(true)` if written, `Ok(false)` if the condition was not met
+fn conditional_write_outcome<T, E: ProvideErrorMetadata>(result: Result<T, E>) -> Result<bool, E> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(e) if is_conditional_check_failed(&e) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Outcome of a table creation: `Ok(true)` if created, `Ok(false)` if the table already existed
+fn table_creation_outcome<T, E: ProvideErrorMetadata>(result: Result<T, E>) -> Result<bool, E> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(e) if e.code() == Some("ResourceInUseException") => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn key_element(name: &str, key_type: KeyType) -> Result<KeySchemaElement, BuildError> {
+    KeySchemaElement::builder()
+        .attribute_name(name)
+        .key_type(key_type)
+        .build()
+}
+
+fn string_attribute(name: &str) -> Result<AttributeDefinition, BuildError> {
+    AttributeDefinition::builder()
+        .attribute_name(name)
+        .attribute_type(ScalarAttributeType::S)
+        .build()
+}
+
+/// Parameters of `DynamoDbClient::query_index_page`
+pub struct IndexPageQuery<'a> {
+    pub index_name: &'a str,
+    pub key_condition_expression: &'a str,
+    pub filter_expression: Option<&'a str>,
+    pub expression_attribute_names: HashMap<String, String>,
+    pub expression_attribute_values: HashMap<String, AttributeValue>,
+    pub limit: i32,
+    /// `LastEvaluatedKey` of the previous page
+    pub exclusive_start_key: Option<HashMap<String, AttributeValue>>,
+}
+
+/// Exponential backoff with full jitter for the given retry (starting at 1)
+fn backoff_delay(retry: u32) -> Duration {
+    let max_delay = BASE_BACKOFF * 2u32.pow(retry.saturating_sub(1));
+    rand::thread_rng().gen_range(Duration::ZERO..=max_delay)
+}
+
+/// Send a request, retrying throttled attempts with jittered exponential backoff
+async fn retry_throttled<T, E, F, Fut>(mut send: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: ProvideErrorMetadata,
+{
+    let mut retry = 0;
+    loop {
+        match send().await {
+            Err(e) if retry < MAX_THROTTLE_RETRIES && is_throttling_error(&e) => {
+                retry += 1;
+                let delay = backoff_delay(retry);
+                warn!(
+                    "DynamoDB request throttled, retrying in {:?} ({}/{})",
+                    delay, retry, MAX_THROTTLE_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// A single DynamoDB item
+type Item = HashMap<String, AttributeValue>;
+
+/// One page of a paginated Query or Scan
+trait Page {
+    /// Items of the page and its `LastEvaluatedKey`
+    fn into_parts(self) -> (Vec<Item>, Option<Item>);
+}
+
+impl Page for QueryOutput {
+    fn into_parts(self) -> (Vec<Item>, Option<Item>) {
+        (self.items.unwrap_or_default(), self.last_evaluated_key)
+    }
+}
+
+impl Page for ScanOutput {
+    fn into_parts(self) -> (Vec<Item>, Option<Item>) {
+        (self.items.unwrap_or_default(), self.last_evaluated_key)
+    }
+}
+
+/// Collect the items of every page, passing each page's `LastEvaluatedKey` as the next start key
+async fn collect_pages<P, E, F, Fut>(
+    mut fetch_page: F,
+) -> Result<Vec<HashMap<String, AttributeValue>>, E>
+where
+    P: Page,
+    F: FnMut(Option<HashMap<String, AttributeValue>>) -> Fut,
+    Fut: Future<Output = Result<P, E>>,
+{
+    let mut items = Vec::new();
+    let mut start_key = None;
+    loop {
+        let (page_items, last_evaluated_key) = fetch_page(start_key).await?.into_parts();
+        items.extend(page_items);
+        match last_evaluated_key {
+            Some(key) if !key.is_empty() => start_key = Some(key),
+            _ => return Ok(items),
+        }
+    }
+}
+
+/// Sum the `Count` of every query page, passing each page's `LastEvaluatedKey` as the next start key
+async fn count_pages<E, F, Fut>(mut fetch_page: F) -> Result<i32, E>
+where
+    F: FnMut(Option<HashMap<String, AttributeValue>>) -> Fut,
+    Fut: Future<Output = Result<QueryOutput, E>>,
+{
+    let mut count = 0;
+    let mut start_key = None;
+    loop {
+        let page = fetch_page(start_key).await?;
+        count += page.count();
+        match page.last_evaluated_key {
+            Some(key) if !key.is_empty() => start_key = Some(key),
+            _ => return Ok(count),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct DynamoDbClient {
@@ -27,44 +191,20 @@ impl DynamoDbClient {
         Ok(DynamoDbClient { client })
     }
 
-    pub async fn generate_attribute_names<K: AsRef<str>, V: AsRef<str>>(
-        &self,
-        items: &[(K, V)],
-    ) -> HashMap<String, String> {
-        items
-            .iter()
-            .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
-            .collect()
-    }
-
-    pub async fn generate_attribute_values<K: AsRef<str>, V: AsRef<str>>(
-        &self,
-        items: &[(K, V)],
-    ) -> HashMap<String, AttributeValue> {
-        items
-            .iter()
-            .map(|(k, v)| {
-                (
-                    k.as_ref().to_string(),
-                    AttributeValue::S(v.as_ref().to_string()),
-                )
-            })
-            .collect()
-    }
-
     #[instrument(skip(self, key), fields(table = %table_name), name = "aws.dynamodb.get_item")]
     pub async fn get_item(
         &self,
         table_name: &str,
         key: &HashMap<String, AttributeValue>,
     ) -> Result<Option<HashMap<String, AttributeValue>>, DynamoDbError> {
-        let result: GetItemOutput = self
-            .client
-            .get_item()
-            .table_name(table_name)
-            .set_key(Some(key.clone()))
-            .send()
-            .await?;
+        let result: GetItemOutput = retry_throttled(|| {
+            self.client
+                .get_item()
+                .table_name(table_name)
+                .set_key(Some(key.clone()))
+                .send()
+        })
+        .await?;
 
         Ok(result.item)
     }
@@ -75,17 +215,48 @@ impl DynamoDbClient {
         table_name: &str,
         item: HashMap<String, AttributeValue>,
     ) -> Result<PutItemOutput, DynamoDbError> {
-        let result: PutItemOutput = self
-            .client
-            .put_item()
-            .table_name(table_name)
-            .set_item(Some(item.clone()))
-            .send()
-            .await?;
+        let result: PutItemOutput = retry_throttled(|| {
+            self.client
+                .put_item()
+                .table_name(table_name)
+                .set_item(Some(item.clone()))
+                .send()
+        })
+        .await?;
 
         Ok(result)
     }
 
+    /// Put an item only if `condition_expression` holds; `Ok(false)` when it does not
+    #[instrument(
+        skip(self, item, expression_attribute_names),
+        fields(table = %table_name),
+        name = "aws.dynamodb.put_item_with_condition"
+    )]
+    pub async fn put_item_with_condition(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+    ) -> Result<bool, DynamoDbError> {
+        let written = conditional_write_outcome(
+            retry_throttled(|| {
+                self.client
+                    .put_item()
+                    .table_name(table_name)
+                    .set_item(Some(item.clone()))
+                    .condition_expression(condition_expression)
+                    .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                    .send()
+            })
+            .await,
+        )?;
+
+        Ok(written)
+    }
+
+    /// Update an item, returning its attributes as they are after the update
     #[instrument(
         skip(self, key, expression_attribute_values),
         fields(table = %table_name),
@@ -99,40 +270,173 @@ impl DynamoDbClient {
         expression_attribute_names: &HashMap<String, String>,
         expression_attribute_values: &HashMap<String, AttributeValue>,
     ) -> Result<UpdateItemOutput, DynamoDbError> {
-        let result: UpdateItemOutput = self
-            .client
-            .update_item()
-            .table_name(table_name)
-            .set_key(Some(key.clone()))
-            .update_expression(update_expression)
-            .set_expression_attribute_names(Some(expression_attribute_names.clone()))
-            .set_expression_attribute_values(Some(expression_attribute_values.clone()))
-            .send()
-            .await?;
+        let result: UpdateItemOutput = retry_throttled(|| {
+            self.client
+                .update_item()
+                .table_name(table_name)
+                .set_key(Some(key.clone()))
+                .update_expression(update_expression)
+                .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                .set_expression_attribute_values(Some(expression_attribute_values.clone()))
+                .return_values(ReturnValue::AllNew)
+                .send()
+        })
+        .await?;
 
         Ok(result)
     }
 
+    #[instrument(
+        skip(self, key, expression_attribute_values),
+        fields(table = %table_name),
+        name = "aws.dynamodb.update_item_with_condition"
+    )]
+    /// Update an item only if `condition_expression` holds; `Ok(false)` when it does not
+    pub async fn update_item_with_condition(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+        update_expression: &str,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<bool, DynamoDbError> {
+        let written = conditional_write_outcome(
+            retry_throttled(|| {
+                self.client
+                    .update_item()
+                    .table_name(table_name)
+                    .set_key(Some(key.clone()))
+                    .update_expression(update_expression)
+                    .condition_expression(condition_expression)
+                    .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                    // DynamoDB rejects an empty map, e.g. for REMOVE-only updates
+                    .set_expression_attribute_values(
+                        (!expression_attribute_values.is_empty())
+                            .then(|| expression_attribute_values.clone()),
+                    )
+                    .send()
+            })
+            .await,
+        )?;
+
+        Ok(written)
+    }
+
     #[instrument(skip(self, key), fields(table = %table_name), name = "aws.dynamodb.delete_item")]
     pub async fn delete_item(
         &self,
         table_name: &str,
         key: &HashMap<String, AttributeValue>,
     ) -> Result<DeleteItemOutput, DynamoDbError> {
-        let result: DeleteItemOutput = self
-            .client
-            .delete_item()
-            .table_name(table_name)
-            .set_key(Some(key.clone()))
-            .send()
-            .await?;
+        let result: DeleteItemOutput = retry_throttled(|| {
+            self.client
+                .delete_item()
+                .table_name(table_name)
+                .set_key(Some(key.clone()))
+                .send()
+        })
+        .await?;
 
         Ok(result)
     }
 
     #[instrument(skip(self), fields(table = %table_name), name = "aws.dynamodb.scan_table")]
     pub async fn scan_table(&self, table_name: &str) -> Result<ScanOutput, DynamoDbError> {
-        let result: ScanOutput = self.client.scan().table_name(table_name).send().await?;
+        let result: ScanOutput =
+            retry_throttled(|| self.client.scan().table_name(table_name).send()).await?;
+
+        Ok(result)
+    }
+
+    /// Scan every page of the table, following `LastEvaluatedKey` until it is exhausted.
+    /// `filter_expression` is applied server-side, so only matching items are transferred.
+    #[instrument(
+        skip(self, expression_attribute_names, expression_attribute_values),
+        fields(table = %table_name),
+        name = "aws.dynamodb.scan_all"
+    )]
+    pub async fn scan_all(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        // DynamoDB rejects empty expression maps
+        let names =
+            (!expression_attribute_names.is_empty()).then(|| expression_attribute_names.clone());
+        let values =
+            (!expression_attribute_values.is_empty()).then(|| expression_attribute_values.clone());
+        let (names, values) = (&names, &values);
+        let items = collect_pages(|start_key| {
+            retry_throttled(move || {
+                self.client
+                    .scan()
+                    .table_name(table_name)
+                    .set_filter_expression(filter_expression.map(str::to_string))
+                    .set_expression_attribute_names(names.clone())
+                    .set_expression_attribute_values(values.clone())
+                    .set_exclusive_start_key(start_key.clone())
+                    .send()
+            })
+        })
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Scan every page like `scan_all`, returning only the attributes in `projection_expression`
+    #[instrument(
+        skip(self, expression_attribute_names, expression_attribute_values),
+        fields(table = %table_name),
+        name = "aws.dynamodb.scan_all_projected"
+    )]
+    pub async fn scan_all_projected(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        let names =
+            (!expression_attribute_names.is_empty()).then(|| expression_attribute_names.clone());
+        let values =
+            (!expression_attribute_values.is_empty()).then(|| expression_attribute_values.clone());
+        let (names, values) = (&names, &values);
+        let items = collect_pages(|start_key| {
+            retry_throttled(move || {
+                self.client
+                    .scan()
+                    .table_name(table_name)
+                    .set_filter_expression(filter_expression.map(str::to_string))
+                    .projection_expression(projection_expression)
+                    .set_expression_attribute_names(names.clone())
+                    .set_expression_attribute_values(values.clone())
+                    .set_exclusive_start_key(start_key.clone())
+                    .send()
+            })
+        })
+        .await?;
+
+        Ok(items)
+    }
+
+    #[instrument(skip(self), fields(table = %table_name), name = "aws.dynamodb.scan_table_with_limit")]
+    pub async fn scan_table_with_limit(
+        &self,
+        table_name: &str,
+        limit: i32,
+    ) -> Result<ScanOutput, DynamoDbError> {
+        let result: ScanOutput = retry_throttled(|| {
+            self.client
+                .scan()
+                .table_name(table_name)
+                .limit(limit)
+                .send()
+        })
+        .await?;
 
         Ok(result)
     }
@@ -149,16 +453,464 @@ impl DynamoDbClient {
         expression_attribute_names: &HashMap<String, String>,
         expression_attribute_values: &HashMap<String, AttributeValue>,
     ) -> Result<QueryOutput, DynamoDbError> {
-        let result: QueryOutput = self
+        let result: QueryOutput = retry_throttled(|| {
+            self.client
+                .query()
+                .table_name(table_name)
+                .key_condition_expression(key_condition_expression)
+                .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                .set_expression_attribute_values(Some(expression_attribute_values.clone()))
+                .send()
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Same as `query_table`, reading only the attributes named in `projection_expression`
+    #[instrument(
+        skip(self, expression_attribute_names, expression_attribute_values),
+        fields(table = %table_name),
+        name = "aws.dynamodb.query_table_projected"
+    )]
+    pub async fn query_table_projected(
+        &self,
+        table_name: &str,
+        key_condition_expression: &str,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        let result: QueryOutput = retry_throttled(|| {
+            self.client
+                .query()
+                .table_name(table_name)
+                .key_condition_expression(key_condition_expression)
+                .projection_expression(projection_expression)
+                .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                .set_expression_attribute_values(Some(expression_attribute_values.clone()))
+                .send()
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Create the users table with the given key schema, an `email_lower` email index and an
+    /// `organization_id` organization index, unless it already exists. Only acts when `CREATE_TABLES=true`, so production tables
+    /// are never created from application code; returns whether a table was created.
+    #[instrument(skip(self, key_schema), fields(table = %table_name), name = "aws.dynamodb.ensure_table_exists")]
+    pub async fn ensure_table_exists(
+        &self,
+        table_name: &str,
+        key_schema: &TableConfig,
+    ) -> Result<bool, DynamoDbError> {
+        if !get_config().create_tables {
+            warn!(
+                "CREATE_TABLES is not enabled, not creating table {}",
+                table_name
+            );
+            return Ok(false);
+        }
+
+        let email_index = GlobalSecondaryIndex::builder()
+            .index_name(&key_schema.email_index)
+            .key_schema(
+                key_element("email_lower", KeyType::Hash).map_err(DynamoDbError::BuildError)?,
+            )
+            .projection(
+                Projection::builder()
+                    .projection_type(ProjectionType::All)
+                    .build(),
+            )
+            .build()
+            .map_err(DynamoDbError::BuildError)?;
+        let organization_index = GlobalSecondaryIndex::builder()
+            .index_name(&key_schema.organization_index)
+            .key_schema(
+                key_element("organization_id", KeyType::Hash).map_err(DynamoDbError::BuildError)?,
+            )
+            .projection(
+                Projection::builder()
+                    .projection_type(ProjectionType::All)
+                    .build(),
+            )
+            .build()
+            .map_err(DynamoDbError::BuildError)?;
+
+        let mut request = self
             .client
-            .query()
+            .create_table()
             .table_name(table_name)
-            .key_condition_expression(key_condition_expression)
-            .set_expression_attribute_names(Some(expression_attribute_names.clone()))
-            .set_expression_attribute_values(Some(expression_attribute_values.clone()))
-            .send()
-            .await?;
+            .attribute_definitions(
+                string_attribute(&key_schema.partition_key).map_err(DynamoDbError::BuildError)?,
+            )
+            .attribute_definitions(
+                string_attribute("email_lower").map_err(DynamoDbError::BuildError)?,
+            )
+            .key_schema(
+                key_element(&key_schema.partition_key, KeyType::Hash)
+                    .map_err(DynamoDbError::BuildError)?,
+            );
+        if let Some(sort_key) = &key_schema.sort_key {
+            request = request
+                .attribute_definitions(
+                    string_attribute(sort_key).map_err(DynamoDbError::BuildError)?,
+                )
+                .key_schema(
+                    key_element(sort_key, KeyType::Range).map_err(DynamoDbError::BuildError)?,
+                );
+        }
+        // Each attribute may only be defined once, and the sort key usually is `organization_id`
+        if !key_schema.key_attributes().contains(&"organization_id") {
+            request = request.attribute_definitions(
+                string_attribute("organization_id").map_err(DynamoDbError::BuildError)?,
+            );
+        }
+
+        let created = table_creation_outcome(
+            request
+                .global_secondary_indexes(email_index)
+                .global_secondary_indexes(organization_index)
+                .billing_mode(BillingMode::PayPerRequest)
+                .send()
+                .await,
+        )?;
+        if created {
+            info!("Created table {}", table_name);
+        }
+
+        Ok(created)
+    }
+
+    /// Query every page of an index, following `LastEvaluatedKey` until it is exhausted
+    #[instrument(
+        skip(self, expression_attribute_names, expression_attribute_values),
+        fields(table = %table_name, index = %index_name),
+        name = "aws.dynamodb.query_index_all"
+    )]
+    pub async fn query_index_all(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        let items = collect_pages(|start_key| {
+            retry_throttled(move || {
+                self.client
+                    .query()
+                    .table_name(table_name)
+                    .index_name(index_name)
+                    .key_condition_expression(key_condition_expression)
+                    .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                    .set_expression_attribute_values(Some(expression_attribute_values.clone()))
+                    .set_exclusive_start_key(start_key.clone())
+                    .send()
+            })
+        })
+        .await?;
+
+        Ok(items)
+    }
+
+    #[instrument(
+        skip(self, expression_attribute_names, expression_attribute_values),
+        fields(table = %table_name, index = %index_name),
+        name = "aws.dynamodb.query_index"
+    )]
+    pub async fn query_index(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        let result: QueryOutput = retry_throttled(|| {
+            self.client
+                .query()
+                .table_name(table_name)
+                .index_name(index_name)
+                .key_condition_expression(key_condition_expression)
+                .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                .set_expression_attribute_values(Some(expression_attribute_values.clone()))
+                .send()
+        })
+        .await?;
 
         Ok(result)
     }
+
+    /// Query a single page of an index, newest sort key first. `Limit` applies before the
+    /// filter, so a page may hold fewer items and still have a `LastEvaluatedKey`.
+    #[instrument(
+        skip(self, query),
+        fields(table = %table_name, index = %query.index_name),
+        name = "aws.dynamodb.query_index_page"
+    )]
+    pub async fn query_index_page(
+        &self,
+        table_name: &str,
+        query: &IndexPageQuery<'_>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        let result: QueryOutput = retry_throttled(|| {
+            self.client
+                .query()
+                .table_name(table_name)
+                .index_name(query.index_name)
+                .key_condition_expression(query.key_condition_expression)
+                .set_filter_expression(query.filter_expression.map(str::to_string))
+                .set_expression_attribute_names(Some(query.expression_attribute_names.clone()))
+                .set_expression_attribute_values(Some(query.expression_attribute_values.clone()))
+                .limit(query.limit)
+                .scan_index_forward(false)
+                .set_exclusive_start_key(query.exclusive_start_key.clone())
+                .send()
+        })
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Count the items of an index matching the key condition and `filter_expression` across
+    /// every page, following `LastEvaluatedKey`
+    #[instrument(
+        skip(self, expression_attribute_names, expression_attribute_values),
+        fields(table = %table_name, index = %index_name),
+        name = "aws.dynamodb.count_index_query"
+    )]
+    pub async fn count_index_query(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<i32, DynamoDbError> {
+        let count = count_pages(|start_key| {
+            retry_throttled(move || {
+                self.client
+                    .query()
+                    .table_name(table_name)
+                    .index_name(index_name)
+                    .select(Select::Count)
+                    .key_condition_expression(key_condition_expression)
+                    .set_filter_expression(filter_expression.map(str::to_string))
+                    .set_expression_attribute_names(Some(expression_attribute_names.clone()))
+                    .set_expression_attribute_values(Some(expression_attribute_values.clone()))
+                    .set_exclusive_start_key(start_key.clone())
+                    .send()
+            })
+        })
+        .await?;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_dynamodb::error::ErrorMetadata;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn error_with_code(code: &str) -> ErrorMetadata {
+        ErrorMetadata::builder().code(code).build()
+    }
+
+    #[test]
+    fn test_is_throttling_error() {
+        assert!(is_throttling_error(&error_with_code(
+            "ProvisionedThroughputExceededException"
+        )));
+        assert!(is_throttling_error(&error_with_code(
+            "RequestLimitExceeded"
+        )));
+        assert!(!is_throttling_error(&error_with_code(
+            "ConditionalCheckFailedException"
+        )));
+        assert!(!is_throttling_error(&ErrorMetadata::builder().build()));
+    }
+
+    #[test]
+    fn test_conditional_write_outcome_condition_met() {
+        let result: Result<&str, ErrorMetadata> = Ok("output");
+        assert!(conditional_write_outcome(result).unwrap());
+    }
+
+    #[test]
+    fn test_conditional_write_outcome_condition_failed() {
+        let result: Result<(), _> = Err(error_with_code("ConditionalCheckFailedException"));
+        assert!(!conditional_write_outcome(result).unwrap());
+    }
+
+    #[test]
+    fn test_conditional_write_outcome_service_error() {
+        let result: Result<(), _> = Err(error_with_code("InternalServerError"));
+        let error = conditional_write_outcome(result).unwrap_err();
+        assert_eq!(error.code(), Some("InternalServerError"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_follows_last_evaluated_key() {
+        let item =
+            |id: &str| HashMap::from([("id".to_string(), AttributeValue::S(id.to_string()))]);
+        let pages = [
+            QueryOutput::builder()
+                .items(item("user-1"))
+                .items(item("user-2"))
+                .set_last_evaluated_key(Some(item("user-2")))
+                .build(),
+            QueryOutput::builder().items(item("user-3")).build(),
+        ];
+        let mut start_keys = Vec::new();
+
+        let items = collect_pages(|start_key| {
+            let page = pages[start_keys.len()].clone();
+            start_keys.push(start_key);
+            async move { Ok::<_, ErrorMetadata>(page) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(items, vec![item("user-1"), item("user-2"), item("user-3")]);
+        assert_eq!(start_keys, vec![None, Some(item("user-2"))]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_spans_scan_pages() {
+        let item =
+            |id: &str| HashMap::from([("id".to_string(), AttributeValue::S(id.to_string()))]);
+        // A filtered scan may return an empty page that still has a `LastEvaluatedKey`
+        let pages = [
+            ScanOutput::builder()
+                .set_last_evaluated_key(Some(item("user-2")))
+                .build(),
+            ScanOutput::builder()
+                .items(item("user-3"))
+                .set_last_evaluated_key(Some(HashMap::new()))
+                .build(),
+        ];
+        let mut start_keys = Vec::new();
+
+        let items = collect_pages(|start_key| {
+            let page = pages[start_keys.len()].clone();
+            start_keys.push(start_key);
+            async move { Ok::<_, ErrorMetadata>(page) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(items, vec![item("user-3")]);
+        assert_eq!(start_keys, vec![None, Some(item("user-2"))]);
+    }
+
+    #[tokio::test]
+    async fn test_count_pages_sums_every_page() {
+        let key = HashMap::from([("id".to_string(), AttributeValue::S("user-2".to_string()))]);
+        // A filtered page may count nothing and still have a `LastEvaluatedKey`
+        let pages = [
+            QueryOutput::builder()
+                .count(2)
+                .set_last_evaluated_key(Some(key.clone()))
+                .build(),
+            QueryOutput::builder()
+                .count(0)
+                .set_last_evaluated_key(Some(key.clone()))
+                .build(),
+            QueryOutput::builder().count(1).build(),
+        ];
+        let mut start_keys = Vec::new();
+
+        let count = count_pages(|start_key| {
+            let page = pages[start_keys.len()].clone();
+            start_keys.push(start_key);
+            async move { Ok::<_, ErrorMetadata>(page) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(start_keys, vec![None, Some(key.clone()), Some(key)]);
+    }
+
+    #[test]
+    fn test_table_creation_outcome_is_idempotent() {
+        let created: Result<(), ErrorMetadata> = Ok(());
+        assert!(table_creation_outcome(created).unwrap());
+
+        let exists: Result<(), _> = Err(error_with_code("ResourceInUseException"));
+        assert!(!table_creation_outcome(exists).unwrap());
+
+        let failed: Result<(), _> = Err(error_with_code("LimitExceededException"));
+        assert_eq!(
+            table_creation_outcome(failed).unwrap_err().code(),
+            Some("LimitExceededException")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ensure_table_exists_is_disabled_by_default() {
+        let client = DynamoDbClient::new("ap-northeast-1".to_string())
+            .await
+            .unwrap();
+        // With CREATE_TABLES unset this returns before any request is sent
+        assert!(!client
+            .ensure_table_exists("Users", &TableConfig::default())
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    fn test_backoff_delay_is_bounded() {
+        for retry in 1..=MAX_THROTTLE_RETRIES {
+            let max_delay = BASE_BACKOFF * 2u32.pow(retry - 1);
+            assert!(backoff_delay(retry) <= max_delay);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_throttled_succeeds_after_two_throttles() {
+        let attempts = AtomicU32::new(0);
+        let result = retry_throttled(|| async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(error_with_code("ProvisionedThroughputExceededException"))
+            } else {
+                Ok("item")
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "item");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_throttled_gives_up_after_max_retries() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), _> = retry_throttled(|| async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(error_with_code("RequestLimitExceeded"))
+        })
+        .await;
+
+        assert!(is_throttling_error(&result.unwrap_err()));
+        assert_eq!(attempts.load(Ordering::SeqCst), MAX_THROTTLE_RETRIES + 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_throttled_does_not_retry_other_errors() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), _> = retry_throttled(|| async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(error_with_code("ValidationException"))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
 }
@@ -1,8 +1,10 @@
+use crate::aws::dynamodb::client::is_throttling_error;
+
 use aws_sdk_dynamodb::{
     error::{BuildError, SdkError},
     operation::{
-        delete_item::DeleteItemError, get_item::GetItemError, put_item::PutItemError,
-        query::QueryError, scan::ScanError, update_item::UpdateItemError,
+        create_table::CreateTableError, delete_item::DeleteItemError, get_item::GetItemError,
+        put_item::PutItemError, query::QueryError, scan::ScanError, update_item::UpdateItemError,
     },
 };
 use thiserror::Error;
@@ -30,6 +32,9 @@ pub enum DynamoDbError {
     #[error("QueryError: {0}")]
     QueryError(#[from] SdkError<QueryError>),
 
+    #[error("CreateTableError: {0}")]
+    CreateTableError(#[from] SdkError<CreateTableError>),
+
     #[error("Not found")]
     NotFound,
 
@@ -42,3 +47,18 @@ pub enum DynamoDbError {
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
+
+impl DynamoDbError {
+    /// Check whether DynamoDB throttled the request (after retries were exhausted)
+    pub fn is_throttling(&self) -> bool {
+        match self {
+            DynamoDbError::GetItemError(e) => is_throttling_error(e),
+            DynamoDbError::PutItemError(e) => is_throttling_error(e),
+            DynamoDbError::UpdateItemError(e) => is_throttling_error(e),
+            DynamoDbError::DeleteItemError(e) => is_throttling_error(e),
+            DynamoDbError::ScanError(e) => is_throttling_error(e),
+            DynamoDbError::QueryError(e) => is_throttling_error(e),
+            _ => false,
+        }
+    }
+}
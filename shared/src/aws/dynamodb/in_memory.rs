@@ -0,0 +1,656 @@
+use crate::aws::dynamodb::error::DynamoDbError;
+use crate::aws::dynamodb::ops::DynamoDbOps;
+use crate::config::TableConfig;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::{
+    operation::{
+        delete_item::DeleteItemOutput, put_item::PutItemOutput, query::QueryOutput,
+        update_item::UpdateItemOutput,
+    },
+    types::AttributeValue,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+type Item = HashMap<String, AttributeValue>;
+
+/// `DynamoDbOps` backed by a `HashMap`, for tests that exercise the repositories without AWS.
+///
+/// Items are keyed by the partition and sort key of `TableConfig`. Expressions are evaluated
+/// for the subset the repositories use: `AND`-joined `=`, `<>`, `attribute_exists` and
+/// `attribute_not_exists` conditions, and `SET a = :a, ...` or `REMOVE a, ...` updates.
+/// Anything else fails with `DynamoDbError::Unknown`. As in DynamoDB, a key condition may only
+/// name the key attributes of the table, or the key of its email or organization index, and must
+/// include the partition key; it is then applied as a filter over the whole table.
+#[derive(Default)]
+pub struct InMemoryDynamoDb {
+    table_config: TableConfig,
+    tables: Mutex<HashMap<String, Vec<Item>>>,
+}
+
+impl InMemoryDynamoDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_table_config(table_config: TableConfig) -> Self {
+        Self {
+            table_config,
+            tables: Mutex::default(),
+        }
+    }
+
+    /// Every item currently stored in a table
+    pub fn items(&self, table_name: &str) -> Vec<Item> {
+        self.tables
+            .lock()
+            .unwrap()
+            .get(table_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn has_key(&self, item: &Item, key: &Item) -> bool {
+        self.table_config
+            .key_attributes()
+            .into_iter()
+            .all(|attribute| item.get(attribute) == key.get(attribute))
+    }
+
+    /// Check that a key condition names only key attributes of the table, or of `index_name`,
+    /// and includes the partition key, so queries DynamoDB would reject fail here too
+    fn check_key_condition(
+        &self,
+        index_name: Option<&str>,
+        key_condition_expression: &str,
+        names: &HashMap<String, String>,
+    ) -> Result<(), ExpressionError> {
+        let key_attributes = match index_name {
+            None => self.table_config.key_attributes(),
+            Some(index) if index == self.table_config.email_index => vec!["email_lower"],
+            Some(index) if index == self.table_config.organization_index => {
+                vec!["organization_id"]
+            }
+            Some(index) => return Err(format!("Unknown index: {index}")),
+        };
+
+        let mut attributes = HashSet::new();
+        for clause in key_condition_expression.split(" AND ") {
+            let (path, _) = clause.split_once('=').ok_or_else(|| unsupported(clause))?;
+            let attribute = attribute_name(path, names)?;
+            if !key_attributes.contains(&attribute) {
+                return Err(format!(
+                    "Query key condition names non-key attribute: {attribute}"
+                ));
+            }
+            attributes.insert(attribute);
+        }
+        if !attributes.contains(key_attributes[0]) {
+            return Err(format!(
+                "Query key condition is missing the partition key: {}",
+                key_attributes[0]
+            ));
+        }
+        Ok(())
+    }
+
+    /// Items of a table or index matching a key condition
+    fn query(
+        &self,
+        table_name: &str,
+        index_name: Option<&str>,
+        key_condition_expression: &str,
+        names: &HashMap<String, String>,
+        values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<Item>, ExpressionError> {
+        self.check_key_condition(index_name, key_condition_expression, names)?;
+        self.matching(table_name, Some(key_condition_expression), names, values)
+    }
+
+    /// Items of a table matching a condition, in insertion order
+    fn matching(
+        &self,
+        table_name: &str,
+        condition: Option<&str>,
+        names: &HashMap<String, String>,
+        values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<Item>, ExpressionError> {
+        let tables = self.tables.lock().unwrap();
+        let mut items = Vec::new();
+        for item in tables.get(table_name).into_iter().flatten() {
+            if condition.map_or(Ok(true), |c| evaluate_condition(c, item, names, values))? {
+                items.push(item.clone());
+            }
+        }
+        Ok(items)
+    }
+
+    /// Apply an update to the item at `key`, creating it as DynamoDB does when it is missing.
+    /// Returns `Ok(None)` without writing when `condition` does not hold.
+    fn apply_update(
+        &self,
+        table_name: &str,
+        key: &Item,
+        update_expression: &str,
+        condition: Option<&str>,
+        names: &HashMap<String, String>,
+        values: &HashMap<String, AttributeValue>,
+    ) -> Result<Option<Item>, ExpressionError> {
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables.entry(table_name.to_string()).or_default();
+        let position = table.iter().position(|item| self.has_key(item, key));
+        let mut item = position.map_or_else(|| key.clone(), |i| table[i].clone());
+
+        if let Some(condition) = condition {
+            let current = position.map_or_else(Item::new, |i| table[i].clone());
+            if !evaluate_condition(condition, &current, names, values)? {
+                return Ok(None);
+            }
+        }
+        apply_update_expression(update_expression, &mut item, names, values)?;
+
+        match position {
+            Some(i) => table[i] = item.clone(),
+            None => table.push(item.clone()),
+        }
+        Ok(Some(item))
+    }
+}
+
+/// Expression evaluation error, surfaced as `DynamoDbError::Unknown`
+type ExpressionError = String;
+
+fn unsupported(expression: &str) -> ExpressionError {
+    format!("Unsupported expression: {expression}")
+}
+
+/// Attribute name for a path, resolving `#placeholders`
+fn attribute_name<'a>(
+    path: &'a str,
+    names: &'a HashMap<String, String>,
+) -> Result<&'a str, ExpressionError> {
+    let path = path.trim();
+    if path.starts_with('#') {
+        names
+            .get(path)
+            .map(String::as_str)
+            .ok_or_else(|| format!("Undefined attribute name: {path}"))
+    } else {
+        Ok(path)
+    }
+}
+
+/// Value bound to a `:placeholder`
+fn attribute_value<'a>(
+    placeholder: &str,
+    values: &'a HashMap<String, AttributeValue>,
+) -> Result<&'a AttributeValue, ExpressionError> {
+    let placeholder = placeholder.trim();
+    values
+        .get(placeholder)
+        .ok_or_else(|| format!("Undefined attribute value: {placeholder}"))
+}
+
+/// Compare attribute values, treating string sets as unordered
+fn same_value(a: &AttributeValue, b: &AttributeValue) -> bool {
+    match (a, b) {
+        (AttributeValue::Ss(a), AttributeValue::Ss(b)) => {
+            a.iter().collect::<HashSet<_>>() == b.iter().collect::<HashSet<_>>()
+        }
+        _ => a == b,
+    }
+}
+
+fn evaluate_condition(
+    expression: &str,
+    item: &Item,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> Result<bool, ExpressionError> {
+    for clause in expression.split(" AND ") {
+        let clause = clause.trim();
+        let holds = if let Some(path) = function_argument(clause, "attribute_exists") {
+            item.contains_key(attribute_name(path, names)?)
+        } else if let Some(path) = function_argument(clause, "attribute_not_exists") {
+            !item.contains_key(attribute_name(path, names)?)
+        } else if let Some((path, placeholder)) = clause.split_once("<>") {
+            let expected = attribute_value(placeholder, values)?;
+            item.get(attribute_name(path, names)?)
+                .is_none_or(|actual| !same_value(actual, expected))
+        } else if let Some((path, placeholder)) = clause.split_once('=') {
+            let expected = attribute_value(placeholder, values)?;
+            item.get(attribute_name(path, names)?)
+                .is_some_and(|actual| same_value(actual, expected))
+        } else {
+            return Err(unsupported(clause));
+        };
+        if !holds {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// The argument of `function(argument)`, if the clause is a call to `function`
+fn function_argument<'a>(clause: &'a str, function: &str) -> Option<&'a str> {
+    clause
+        .strip_prefix(function)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+fn apply_update_expression(
+    expression: &str,
+    item: &mut Item,
+    names: &HashMap<String, String>,
+    values: &HashMap<String, AttributeValue>,
+) -> Result<(), ExpressionError> {
+    let expression = expression.trim();
+    if let Some(assignments) = expression.strip_prefix("SET ") {
+        for assignment in assignments.split(',') {
+            let (path, placeholder) = assignment
+                .split_once('=')
+                .ok_or_else(|| unsupported(expression))?;
+            let name = attribute_name(path, names)?.to_string();
+            item.insert(name, attribute_value(placeholder, values)?.clone());
+        }
+        Ok(())
+    } else if let Some(paths) = expression.strip_prefix("REMOVE ") {
+        for path in paths.split(',') {
+            item.remove(attribute_name(path, names)?);
+        }
+        Ok(())
+    } else {
+        Err(unsupported(expression))
+    }
+}
+
+/// Keep only the attributes named in a projection expression
+fn project(
+    item: Item,
+    projection_expression: &str,
+    names: &HashMap<String, String>,
+) -> Result<Item, ExpressionError> {
+    let attributes = projection_expression
+        .split(',')
+        .map(|path| attribute_name(path, names))
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(item
+        .into_iter()
+        .filter(|(name, _)| attributes.contains(name.as_str()))
+        .collect())
+}
+
+fn query_output(items: Vec<Item>) -> QueryOutput {
+    QueryOutput::builder()
+        .count(items.len() as i32)
+        .set_items(Some(items))
+        .build()
+}
+
+#[async_trait]
+impl DynamoDbOps for InMemoryDynamoDb {
+    async fn get_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+    ) -> Result<Option<HashMap<String, AttributeValue>>, DynamoDbError> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .get(table_name)
+            .and_then(|table| table.iter().find(|item| self.has_key(item, key)))
+            .cloned())
+    }
+
+    async fn put_item(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+    ) -> Result<PutItemOutput, DynamoDbError> {
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables.entry(table_name.to_string()).or_default();
+        match table.iter().position(|stored| self.has_key(stored, &item)) {
+            Some(i) => table[i] = item,
+            None => table.push(item),
+        }
+        Ok(PutItemOutput::builder().build())
+    }
+
+    async fn put_item_with_condition(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+    ) -> Result<bool, DynamoDbError> {
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables.entry(table_name.to_string()).or_default();
+        let position = table.iter().position(|stored| self.has_key(stored, &item));
+        let current = position.map_or_else(Item::new, |i| table[i].clone());
+        if !evaluate_condition(
+            condition_expression,
+            &current,
+            expression_attribute_names,
+            &HashMap::new(),
+        )
+        .map_err(DynamoDbError::Unknown)?
+        {
+            return Ok(false);
+        }
+
+        match position {
+            Some(i) => table[i] = item,
+            None => table.push(item),
+        }
+        Ok(true)
+    }
+
+    async fn update_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+        update_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<UpdateItemOutput, DynamoDbError> {
+        let item = self
+            .apply_update(
+                table_name,
+                key,
+                update_expression,
+                None,
+                expression_attribute_names,
+                expression_attribute_values,
+            )
+            .map_err(DynamoDbError::Unknown)?;
+        Ok(UpdateItemOutput::builder().set_attributes(item).build())
+    }
+
+    async fn update_item_with_condition(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+        update_expression: &str,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<bool, DynamoDbError> {
+        let item = self
+            .apply_update(
+                table_name,
+                key,
+                update_expression,
+                Some(condition_expression),
+                expression_attribute_names,
+                expression_attribute_values,
+            )
+            .map_err(DynamoDbError::Unknown)?;
+        Ok(item.is_some())
+    }
+
+    async fn delete_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+    ) -> Result<DeleteItemOutput, DynamoDbError> {
+        let mut tables = self.tables.lock().unwrap();
+        if let Some(table) = tables.get_mut(table_name) {
+            table.retain(|item| !self.has_key(item, key));
+        }
+        Ok(DeleteItemOutput::builder().build())
+    }
+
+    async fn query_table(
+        &self,
+        table_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        let items = self
+            .query(
+                table_name,
+                None,
+                key_condition_expression,
+                expression_attribute_names,
+                expression_attribute_values,
+            )
+            .map_err(DynamoDbError::Unknown)?;
+        Ok(query_output(items))
+    }
+
+    async fn query_table_projected(
+        &self,
+        table_name: &str,
+        key_condition_expression: &str,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        let items = self
+            .query(
+                table_name,
+                None,
+                key_condition_expression,
+                expression_attribute_names,
+                expression_attribute_values,
+            )
+            .and_then(|items| {
+                items
+                    .into_iter()
+                    .map(|item| project(item, projection_expression, expression_attribute_names))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(DynamoDbError::Unknown)?;
+        Ok(query_output(items))
+    }
+
+    async fn query_index_all(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        self.query(
+            table_name,
+            Some(index_name),
+            key_condition_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .map_err(DynamoDbError::Unknown)
+    }
+
+    async fn query_index(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        let items = self
+            .query(
+                table_name,
+                Some(index_name),
+                key_condition_expression,
+                expression_attribute_names,
+                expression_attribute_values,
+            )
+            .map_err(DynamoDbError::Unknown)?;
+        Ok(query_output(items))
+    }
+
+    async fn count_index_query(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<i32, DynamoDbError> {
+        let condition = match filter_expression {
+            Some(filter) => format!("{key_condition_expression} AND {filter}"),
+            None => key_condition_expression.to_string(),
+        };
+        let items = self
+            .check_key_condition(
+                Some(index_name),
+                key_condition_expression,
+                expression_attribute_names,
+            )
+            .and_then(|()| {
+                self.matching(
+                    table_name,
+                    Some(&condition),
+                    expression_attribute_names,
+                    expression_attribute_values,
+                )
+            })
+            .map_err(DynamoDbError::Unknown)?;
+        Ok(items.len() as i32)
+    }
+
+    async fn scan_all(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        self.matching(
+            table_name,
+            filter_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .map_err(DynamoDbError::Unknown)
+    }
+
+    async fn scan_all_projected(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        self.matching(
+            table_name,
+            filter_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .and_then(|items| {
+            items
+                .into_iter()
+                .map(|item| project(item, projection_expression, expression_attribute_names))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(DynamoDbError::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(value: &str) -> AttributeValue {
+        AttributeValue::S(value.to_string())
+    }
+
+    fn names(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_condition_clauses() {
+        let item = HashMap::from([
+            ("id".to_string(), s("user-1")),
+            (
+                "roles".to_string(),
+                AttributeValue::Ss(vec!["Admin".to_string(), "Reader".to_string()]),
+            ),
+        ]);
+        let names = names(&[("#id", "id"), ("#roles", "roles"), ("#gone", "deleted_at")]);
+        let values = HashMap::from([
+            (":id".to_string(), s("user-1")),
+            (
+                ":roles".to_string(),
+                AttributeValue::Ss(vec!["Reader".to_string(), "Admin".to_string()]),
+            ),
+        ]);
+        let holds = |expression| evaluate_condition(expression, &item, &names, &values).unwrap();
+
+        assert!(holds("#id = :id AND attribute_exists(#id)"));
+        assert!(holds("attribute_not_exists(#gone)"));
+        assert!(!holds("#roles <> :roles"), "string sets are unordered");
+        assert!(!holds("#gone = :id"));
+        assert!(evaluate_condition("begins_with(#id, :id)", &item, &names, &values).is_err());
+    }
+
+    #[test]
+    fn test_update_expressions() {
+        let mut item = HashMap::from([("deleted_at".to_string(), s("1"))]);
+        let names = names(&[("#name", "user_name"), ("#deleted_at", "deleted_at")]);
+        let values = HashMap::from([(":name".to_string(), s("Alice"))]);
+
+        apply_update_expression("SET #name = :name", &mut item, &names, &values).unwrap();
+        apply_update_expression("REMOVE #deleted_at", &mut item, &names, &values).unwrap();
+
+        assert_eq!(item, HashMap::from([("user_name".to_string(), s("Alice"))]));
+        assert!(apply_update_expression("ADD #name :name", &mut item, &names, &values).is_err());
+    }
+
+    #[test]
+    fn test_key_condition_must_name_table_or_index_keys() {
+        let db = InMemoryDynamoDb::new();
+        let names = names(&[
+            ("#id", "id"),
+            ("#organization_id", "organization_id"),
+            ("#email_lower", "email_lower"),
+        ]);
+        let check = |index, condition| db.check_key_condition(index, condition, &names);
+
+        assert!(check(None, "#id = :id").is_ok());
+        assert!(check(None, "#id = :id AND #organization_id = :organization_id").is_ok());
+        assert!(
+            check(None, "#organization_id = :organization_id").is_err(),
+            "the base table is not keyed by organization alone"
+        );
+        assert!(check(None, "#email_lower = :email_lower").is_err());
+        assert!(check(
+            Some("OrganizationIndex"),
+            "#organization_id = :organization_id"
+        )
+        .is_ok());
+        assert!(check(Some("EmailIndex"), "#email_lower = :email_lower").is_ok());
+        assert!(check(Some("EmailIndex"), "#id = :id").is_err());
+        assert!(check(Some("NoSuchIndex"), "#id = :id").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_put_replaces_item_with_same_key() {
+        let db = InMemoryDynamoDb::new();
+        let key = HashMap::from([
+            ("id".to_string(), s("user-1")),
+            ("organization_id".to_string(), s("org-1")),
+        ]);
+        let mut item = key.clone();
+        item.insert("user_name".to_string(), s("Alice"));
+        db.put_item("users", item.clone()).await.unwrap();
+        item.insert("user_name".to_string(), s("Alicia"));
+        db.put_item("users", item.clone()).await.unwrap();
+
+        assert_eq!(db.items("users"), vec![item.clone()]);
+        assert_eq!(db.get_item("users", &key).await.unwrap(), Some(item));
+    }
+}
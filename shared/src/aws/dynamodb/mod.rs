@@ -1,2 +1,5 @@
 pub mod client;
 pub mod error;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod in_memory;
+pub mod ops;
@@ -0,0 +1,377 @@
+use crate::aws::dynamodb::client::DynamoDbClient;
+use crate::aws::dynamodb::error::DynamoDbError;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::{
+    operation::{
+        delete_item::DeleteItemOutput, put_item::PutItemOutput, query::QueryOutput,
+        update_item::UpdateItemOutput,
+    },
+    types::AttributeValue,
+};
+use std::collections::HashMap;
+
+/// DynamoDB operations used by the repositories, implemented by `DynamoDbClient` and, for
+/// tests, by the in-memory fake
+#[async_trait]
+pub trait DynamoDbOps: Send + Sync {
+    async fn get_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+    ) -> Result<Option<HashMap<String, AttributeValue>>, DynamoDbError>;
+
+    async fn put_item(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+    ) -> Result<PutItemOutput, DynamoDbError>;
+
+    /// Put an item only if `condition_expression` holds; `Ok(false)` when it does not
+    async fn put_item_with_condition(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+    ) -> Result<bool, DynamoDbError>;
+
+    /// Update an item, returning its attributes as they are after the update
+    async fn update_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+        update_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<UpdateItemOutput, DynamoDbError>;
+
+    /// Update an item only if `condition_expression` holds; `Ok(false)` when it does not
+    async fn update_item_with_condition(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+        update_expression: &str,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<bool, DynamoDbError>;
+
+    async fn delete_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+    ) -> Result<DeleteItemOutput, DynamoDbError>;
+
+    async fn query_table(
+        &self,
+        table_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError>;
+
+    /// Same as `query_table`, reading only the attributes named in `projection_expression`
+    async fn query_table_projected(
+        &self,
+        table_name: &str,
+        key_condition_expression: &str,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError>;
+
+    /// Query every page of an index, following `LastEvaluatedKey`
+    async fn query_index_all(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError>;
+
+    async fn query_index(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError>;
+
+    /// Count every page of an index query, applying `filter_expression` if given
+    async fn count_index_query(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<i32, DynamoDbError>;
+
+    /// Scan every page, applying `filter_expression` if given
+    async fn scan_all(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError>;
+
+    /// Scan every page like `scan_all`, returning only the attributes in `projection_expression`
+    async fn scan_all_projected(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError>;
+
+    async fn generate_attribute_names<K, V>(&self, items: &[(K, V)]) -> HashMap<String, String>
+    where
+        K: AsRef<str> + Sync,
+        V: AsRef<str> + Sync,
+    {
+        items
+            .iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+            .collect()
+    }
+
+    async fn generate_attribute_values<K, V>(
+        &self,
+        items: &[(K, V)],
+    ) -> HashMap<String, AttributeValue>
+    where
+        K: AsRef<str> + Sync,
+        V: AsRef<str> + Sync,
+    {
+        items
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.as_ref().to_string(),
+                    AttributeValue::S(v.as_ref().to_string()),
+                )
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DynamoDbOps for DynamoDbClient {
+    async fn get_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+    ) -> Result<Option<HashMap<String, AttributeValue>>, DynamoDbError> {
+        DynamoDbClient::get_item(self, table_name, key).await
+    }
+
+    async fn put_item(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+    ) -> Result<PutItemOutput, DynamoDbError> {
+        DynamoDbClient::put_item(self, table_name, item).await
+    }
+
+    async fn put_item_with_condition(
+        &self,
+        table_name: &str,
+        item: HashMap<String, AttributeValue>,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+    ) -> Result<bool, DynamoDbError> {
+        DynamoDbClient::put_item_with_condition(
+            self,
+            table_name,
+            item,
+            condition_expression,
+            expression_attribute_names,
+        )
+        .await
+    }
+
+    async fn update_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+        update_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<UpdateItemOutput, DynamoDbError> {
+        DynamoDbClient::update_item(
+            self,
+            table_name,
+            key,
+            update_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn update_item_with_condition(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+        update_expression: &str,
+        condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<bool, DynamoDbError> {
+        DynamoDbClient::update_item_with_condition(
+            self,
+            table_name,
+            key,
+            update_expression,
+            condition_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn delete_item(
+        &self,
+        table_name: &str,
+        key: &HashMap<String, AttributeValue>,
+    ) -> Result<DeleteItemOutput, DynamoDbError> {
+        DynamoDbClient::delete_item(self, table_name, key).await
+    }
+
+    async fn query_table(
+        &self,
+        table_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        DynamoDbClient::query_table(
+            self,
+            table_name,
+            key_condition_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn query_table_projected(
+        &self,
+        table_name: &str,
+        key_condition_expression: &str,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        DynamoDbClient::query_table_projected(
+            self,
+            table_name,
+            key_condition_expression,
+            projection_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn query_index_all(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        DynamoDbClient::query_index_all(
+            self,
+            table_name,
+            index_name,
+            key_condition_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn query_index(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<QueryOutput, DynamoDbError> {
+        DynamoDbClient::query_index(
+            self,
+            table_name,
+            index_name,
+            key_condition_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn count_index_query(
+        &self,
+        table_name: &str,
+        index_name: &str,
+        key_condition_expression: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<i32, DynamoDbError> {
+        DynamoDbClient::count_index_query(
+            self,
+            table_name,
+            index_name,
+            key_condition_expression,
+            filter_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn scan_all(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        DynamoDbClient::scan_all(
+            self,
+            table_name,
+            filter_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+
+    async fn scan_all_projected(
+        &self,
+        table_name: &str,
+        filter_expression: Option<&str>,
+        projection_expression: &str,
+        expression_attribute_names: &HashMap<String, String>,
+        expression_attribute_values: &HashMap<String, AttributeValue>,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, DynamoDbError> {
+        DynamoDbClient::scan_all_projected(
+            self,
+            table_name,
+            filter_expression,
+            projection_expression,
+            expression_attribute_names,
+            expression_attribute_values,
+        )
+        .await
+    }
+}
@@ -0,0 +1,88 @@
+use crate::aws::eventbridge::error::EventBridgeError;
+use crate::aws::eventbridge::publisher::EventPublisher;
+use crate::utils::env::get_env;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use aws_config::{meta::region::RegionProviderChain, Region};
+use aws_sdk_eventbridge::{types::PutEventsRequestEntry, Client};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+use tracing::instrument;
+
+/// `source` of every event published by this service
+const EVENT_SOURCE: &str = "sls-uma.users";
+
+/// Publishes events to an EventBridge bus with `PutEvents`
+pub struct EventBridgePublisher {
+    client: OnceCell<Client>,
+    region: String,
+    event_bus_name: String,
+}
+
+impl EventBridgePublisher {
+    /// Create a publisher for `event_bus_name`; the client is built on first use
+    pub fn new(region: String, event_bus_name: String) -> Self {
+        Self {
+            client: OnceCell::new(),
+            region,
+            event_bus_name,
+        }
+    }
+
+    /// Create a publisher for the bus named by `EVENT_BUS_NAME` (defaults to `default`)
+    pub fn from_env() -> Self {
+        Self::new(
+            get_env("AWS_REGION", "ap-northeast-1"),
+            get_env("EVENT_BUS_NAME", "default"),
+        )
+    }
+
+    async fn client(&self) -> &Client {
+        self.client
+            .get_or_init(|| async {
+                let region = Region::new(self.region.clone());
+                let region_provider = RegionProviderChain::default_provider().or_else(region);
+                let config = aws_config::from_env().region(region_provider).load().await;
+                Client::new(&config)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl EventPublisher for EventBridgePublisher {
+    #[instrument(
+        skip(self, detail),
+        fields(event_bus_name = %self.event_bus_name),
+        name = "aws.eventbridge.publish"
+    )]
+    async fn publish(&self, detail_type: &str, detail: Value) -> Result<(), Error> {
+        let entry = PutEventsRequestEntry::builder()
+            .event_bus_name(&self.event_bus_name)
+            .source(EVENT_SOURCE)
+            .detail_type(detail_type)
+            .detail(detail.to_string())
+            .build();
+
+        let output = self
+            .client()
+            .await
+            .put_events()
+            .entries(entry)
+            .send()
+            .await
+            .map_err(|e| EventBridgeError::PutEventsError(Box::new(e)))?;
+
+        // PutEvents reports per-entry failures in the response rather than as an error
+        if output.failed_entry_count() > 0 {
+            let message = output
+                .entries()
+                .iter()
+                .find_map(|entry| entry.error_message())
+                .unwrap_or("unknown error");
+            return Err(EventBridgeError::FailedEntry(message.to_string()).into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+use aws_sdk_eventbridge::{error::SdkError, operation::put_events::PutEventsError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EventBridgeError {
+    #[error("PutEventsError: {0}")]
+    PutEventsError(#[from] Box<SdkError<PutEventsError>>),
+
+    #[error("FailedEntry: {0}")]
+    FailedEntry(String),
+}
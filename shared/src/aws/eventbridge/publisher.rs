@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tracing::{error, info};
+
+/// Detail type of the event emitted after a user is created
+pub const USER_CREATED: &str = "UserCreated";
+/// Detail type of the event emitted after a user is updated
+pub const USER_UPDATED: &str = "UserUpdated";
+/// Detail type of the event emitted after a user is deleted
+pub const USER_DELETED: &str = "UserDeleted";
+
+/// Publishing of domain events for other services to react to
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, detail_type: &str, detail: Value) -> Result<(), Error>;
+}
+
+/// Publish a user lifecycle event. Failures are logged and swallowed so publishing never fails
+/// the user operation.
+pub async fn publish_user_event(
+    publisher: &dyn EventPublisher,
+    detail_type: &str,
+    user_id: &str,
+    organization_id: &str,
+) {
+    let detail = json!({
+        "user_id": user_id,
+        "organization_id": organization_id,
+    });
+
+    match publisher.publish(detail_type, detail).await {
+        Ok(()) => info!("Published {} event for user {}", detail_type, user_id),
+        Err(e) => error!(
+            "Failed to publish {} event for user {}: {:?}",
+            detail_type, user_id, e
+        ),
+    }
+}
+
+/// In-memory publisher for tests, recording every published event
+#[derive(Default)]
+pub struct MockEventPublisher {
+    fail: bool,
+    events: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockEventPublisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A publisher whose every `publish` fails
+    pub fn failing() -> Self {
+        Self {
+            fail: true,
+            ..Self::default()
+        }
+    }
+
+    /// Events published so far, as `(detail_type, detail)` pairs
+    pub fn events(&self) -> Vec<(String, Value)> {
+        self.events
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for MockEventPublisher {
+    async fn publish(&self, detail_type: &str, detail: Value) -> Result<(), Error> {
+        if self.fail {
+            return Err(anyhow!("event bus unavailable"));
+        }
+        self.events
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push((detail_type.to_string(), detail));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_user_event_includes_ids() {
+        let publisher = MockEventPublisher::new();
+        publish_user_event(&publisher, USER_CREATED, "user-1", "org-1").await;
+
+        assert_eq!(
+            publisher.events(),
+            vec![(
+                USER_CREATED.to_string(),
+                json!({"user_id": "user-1", "organization_id": "org-1"})
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_user_event_swallows_errors() {
+        let publisher = MockEventPublisher::failing();
+        publish_user_event(&publisher, USER_DELETED, "user-1", "org-1").await;
+
+        assert!(publisher.events().is_empty());
+    }
+}
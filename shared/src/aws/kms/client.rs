@@ -0,0 +1,188 @@
+use crate::aws::kms::error::KmsError;
+use crate::utils::crypto::Crypto;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::Error;
+use async_trait::async_trait;
+use aws_config::{meta::region::RegionProviderChain, Region};
+use aws_sdk_kms::{
+    primitives::Blob,
+    types::{DataKeySpec, MacAlgorithmSpec},
+    Client,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use tokio::sync::OnceCell;
+use tracing::instrument;
+
+/// Length of the AES-GCM nonce in bytes
+const NONCE_LEN: usize = 12;
+
+/// Envelope encryption with data keys generated by a KMS key
+///
+/// Each value is encrypted locally with AES-256-GCM under a fresh data key; the
+/// KMS-encrypted data key is stored alongside the ciphertext. Blind indexes are HMAC-SHA256
+/// digests computed by a separate `HMAC_256` KMS key, so the index key never leaves KMS.
+pub struct KmsCrypto {
+    client: OnceCell<Client>,
+    region: String,
+    key_id: String,
+    index_key_id: String,
+}
+
+impl KmsCrypto {
+    /// Create a KMS crypto for `key_id` and the HMAC key `index_key_id`; the client is built on
+    /// first use so repositories can stay synchronous to construct
+    pub fn new(region: String, key_id: String, index_key_id: String) -> Self {
+        Self {
+            client: OnceCell::new(),
+            region,
+            key_id,
+            index_key_id,
+        }
+    }
+
+    async fn client(&self) -> &Client {
+        self.client
+            .get_or_init(|| async {
+                let region = Region::new(self.region.clone());
+                let region_provider = RegionProviderChain::default_provider().or_else(region);
+                let config = aws_config::from_env().region(region_provider).load().await;
+                Client::new(&config)
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Crypto for KmsCrypto {
+    #[instrument(skip(self, plaintext), name = "aws.kms.encrypt")]
+    async fn encrypt(&self, plaintext: &str) -> Result<String, Error> {
+        let data_key = self
+            .client()
+            .await
+            .generate_data_key()
+            .key_id(&self.key_id)
+            .key_spec(DataKeySpec::Aes256)
+            .send()
+            .await
+            .map_err(|e| KmsError::GenerateDataKeyError(Box::new(e)))?;
+
+        let key = data_key
+            .plaintext()
+            .ok_or_else(|| KmsError::MissingAttribute("plaintext data key".to_string()))?;
+        let encrypted_key = data_key
+            .ciphertext_blob()
+            .ok_or_else(|| KmsError::MissingAttribute("encrypted data key".to_string()))?;
+
+        let envelope = seal(key.as_ref(), encrypted_key.as_ref(), plaintext.as_bytes())?;
+        Ok(STANDARD.encode(envelope))
+    }
+
+    #[instrument(skip(self, ciphertext), name = "aws.kms.decrypt")]
+    async fn decrypt(&self, ciphertext: &str) -> Result<String, Error> {
+        let envelope = STANDARD
+            .decode(ciphertext)
+            .map_err(|e| KmsError::InvalidCiphertext(e.to_string()))?;
+        let (encrypted_key, _) = split_envelope(&envelope)?;
+
+        let data_key = self
+            .client()
+            .await
+            .decrypt()
+            .key_id(&self.key_id)
+            .ciphertext_blob(Blob::new(encrypted_key))
+            .send()
+            .await
+            .map_err(|e| KmsError::DecryptError(Box::new(e)))?;
+        let key = data_key
+            .plaintext()
+            .ok_or_else(|| KmsError::MissingAttribute("plaintext data key".to_string()))?;
+
+        let plaintext = open(key.as_ref(), &envelope)?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    #[instrument(skip(self, plaintext), name = "aws.kms.generate_mac")]
+    async fn blind_index(&self, plaintext: &str) -> Result<String, Error> {
+        let output = self
+            .client()
+            .await
+            .generate_mac()
+            .key_id(&self.index_key_id)
+            .mac_algorithm(MacAlgorithmSpec::HmacSha256)
+            .message(Blob::new(plaintext.as_bytes()))
+            .send()
+            .await
+            .map_err(|e| KmsError::GenerateMacError(Box::new(e)))?;
+
+        let mac = output
+            .mac()
+            .ok_or_else(|| KmsError::MissingAttribute("mac".to_string()))?;
+        Ok(STANDARD.encode(mac.as_ref()))
+    }
+}
+
+/// Encrypt `plaintext` with `key` into `len(encrypted_key) | encrypted_key | nonce | ciphertext`
+fn seal(key: &[u8], encrypted_key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, KmsError> {
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|e| KmsError::InvalidCiphertext(e.to_string()))?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| KmsError::InvalidCiphertext(e.to_string()))?;
+    let key_len = u16::try_from(encrypted_key.len())
+        .map_err(|_| KmsError::InvalidCiphertext("encrypted data key too long".to_string()))?;
+
+    let mut envelope = Vec::with_capacity(2 + encrypted_key.len() + NONCE_LEN + ciphertext.len());
+    envelope.extend_from_slice(&key_len.to_be_bytes());
+    envelope.extend_from_slice(encrypted_key);
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&ciphertext);
+    Ok(envelope)
+}
+
+/// Split an envelope into the encrypted data key and the nonce plus ciphertext
+fn split_envelope(envelope: &[u8]) -> Result<(&[u8], &[u8]), KmsError> {
+    let invalid = || KmsError::InvalidCiphertext("truncated envelope".to_string());
+    let (key_len, rest) = envelope.split_first_chunk::<2>().ok_or_else(invalid)?;
+    let key_len = u16::from_be_bytes(*key_len) as usize;
+    if rest.len() < key_len + NONCE_LEN {
+        return Err(invalid());
+    }
+    Ok(rest.split_at(key_len))
+}
+
+/// Decrypt an envelope produced by `seal` with the plaintext data key
+fn open(key: &[u8], envelope: &[u8]) -> Result<Vec<u8>, KmsError> {
+    let (_, sealed) = split_envelope(envelope)?;
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|e| KmsError::InvalidCiphertext(e.to_string()))?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|e| KmsError::InvalidCiphertext(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open_round_trip() {
+        let key = [7u8; 32];
+        let envelope = seal(&key, b"encrypted-key", b"alice@example.com").unwrap();
+
+        let (encrypted_key, _) = split_envelope(&envelope).unwrap();
+        assert_eq!(encrypted_key, b"encrypted-key");
+        assert_eq!(open(&key, &envelope).unwrap(), b"alice@example.com");
+        assert!(open(&[8u8; 32], &envelope).is_err());
+    }
+
+    #[test]
+    fn test_split_envelope_rejects_truncated_input() {
+        assert!(split_envelope(&[0]).is_err());
+        assert!(split_envelope(&[0, 4, 1, 2]).is_err());
+    }
+}
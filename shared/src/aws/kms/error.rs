@@ -0,0 +1,26 @@
+use aws_sdk_kms::{
+    error::SdkError,
+    operation::{
+        decrypt::DecryptError, generate_data_key::GenerateDataKeyError,
+        generate_mac::GenerateMacError,
+    },
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum KmsError {
+    #[error("GenerateDataKeyError: {0}")]
+    GenerateDataKeyError(#[from] Box<SdkError<GenerateDataKeyError>>),
+
+    #[error("DecryptError: {0}")]
+    DecryptError(#[from] Box<SdkError<DecryptError>>),
+
+    #[error("GenerateMacError: {0}")]
+    GenerateMacError(#[from] Box<SdkError<GenerateMacError>>),
+
+    #[error("MissingAttribute: {0}")]
+    MissingAttribute(String),
+
+    #[error("InvalidCiphertext: {0}")]
+    InvalidCiphertext(String),
+}
@@ -0,0 +1,465 @@
+use crate::aws::lambda_events::request::LambdaEventRequestHandler;
+use crate::aws::lambda_events::response::apigw_response;
+use crate::errors::{LambdaError, ToLambdaError, DEFAULT_LANGUAGE};
+use crate::validation::Validate;
+
+use aws_lambda_events::event::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_lambda_events::http::header;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use lambda_runtime::{Error, LambdaEvent};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::pin::Pin;
+use tracing::warn;
+
+/// Query parameter asking a handler to only validate the request body
+pub const VALIDATE_ONLY_PARAM: &str = "validateOnly";
+
+/// Result returned by API Gateway handlers
+pub type HandlerResult = Result<ApiGatewayProxyResponse, Error>;
+
+/// Boxed future returned by wrapped handlers
+pub type HandlerFuture = Pin<Box<dyn Future<Output = HandlerResult> + Send>>;
+
+/// Build the standard JSON response for a `LambdaError`
+pub fn error_response(error: &LambdaError) -> HandlerResult {
+    localized_error_response(error, DEFAULT_LANGUAGE)
+}
+
+/// Build the standard JSON response for a `LambdaError`, with the message in `lang`
+pub fn localized_error_response(error: &LambdaError, lang: &str) -> HandlerResult {
+    if error.retry_after_secs().is_some() {
+        return LambdaEventRequestHandler::retry_after_response(error);
+    }
+
+    Ok(apigw_response(
+        error.status_code(),
+        Some(serde_json::to_string(&error.response_body_localized(lang))?.into()),
+        None,
+    ))
+}
+
+/// Most preferred language of the `Accept-Language` header (e.g. `ja` for `ja;q=0.9, en;q=0.8`),
+/// or `DEFAULT_LANGUAGE` without one
+pub fn preferred_language(request: &ApiGatewayProxyRequest) -> &str {
+    let mut best: Option<(&str, f32)> = None;
+    let values = request
+        .headers
+        .get_all(header::ACCEPT_LANGUAGE)
+        .iter()
+        .filter_map(|value| value.to_str().ok());
+    for range in values.flat_map(|value| value.split(',')) {
+        let mut parts = range.split(';').map(str::trim);
+        let tag = parts.next().unwrap_or_default();
+        let quality = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .map_or(Some(1.0), |q| q.parse::<f32>().ok())
+            .unwrap_or(0.0);
+        if tag.is_empty() || tag == "*" || quality <= 0.0 {
+            continue;
+        }
+        if best.is_none_or(|(_, best_quality)| quality > best_quality) {
+            best = Some((tag, quality));
+        }
+    }
+    best.map_or(DEFAULT_LANGUAGE, |(tag, _)| tag)
+}
+
+/// Request body bytes, base64-decoded when API Gateway delivered it encoded (binary media types)
+pub fn decoded_body(request: &ApiGatewayProxyRequest) -> Result<Vec<u8>, LambdaError> {
+    let body = request.body.as_deref().ok_or(LambdaError::MissingBody)?;
+    if !request.is_base64_encoded {
+        return Ok(body.as_bytes().to_vec());
+    }
+    STANDARD.decode(body).map_err(|e| {
+        warn!("Failed to decode base64 request body: {}", e);
+        LambdaError::InvalidBodyEncoding
+    })
+}
+
+/// Token of an `Authorization: Bearer <token>` header, matching the scheme case-insensitively.
+/// Fails with `MissingToken` without the header and `InvalidToken` if it is malformed or repeated
+/// with different values.
+pub fn extract_bearer_token(request: &ApiGatewayProxyRequest) -> Result<String, LambdaError> {
+    let mut values = request
+        .headers
+        .get_all(header::AUTHORIZATION)
+        .iter()
+        .chain(request.multi_value_headers.get_all(header::AUTHORIZATION));
+    let value = values.next().ok_or(LambdaError::MissingToken)?;
+    if values.any(|other| other != value) {
+        warn!("Request carries conflicting Authorization headers");
+        return Err(LambdaError::InvalidToken);
+    }
+
+    let (scheme, token) = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.trim().split_once(' '))
+        .ok_or(LambdaError::InvalidToken)?;
+    let token = token.trim();
+    if !scheme.eq_ignore_ascii_case("bearer") || token.is_empty() {
+        return Err(LambdaError::InvalidToken);
+    }
+    Ok(token.to_string())
+}
+
+/// Deserialize and validate the JSON body of a request
+pub fn parse_body<T>(event: &LambdaEvent<ApiGatewayProxyRequest>) -> Result<T, LambdaError>
+where
+    T: DeserializeOwned + Validate,
+{
+    let body = decoded_body(&event.payload)?;
+
+    let request: T = serde_json::from_slice(&body).map_err(|e| e.to_lambda_error())?;
+    request.validate()?;
+    Ok(request)
+}
+
+/// Check whether the request only asks for validation (`?validateOnly=true`)
+pub fn is_validate_only(event: &LambdaEvent<ApiGatewayProxyRequest>) -> bool {
+    event
+        .payload
+        .query_string_parameters
+        .first(VALIDATE_ONLY_PARAM)
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+}
+
+/// Validate the request body without performing the operation: `200 {"valid": true}`, or the
+/// standard error response with field-level `errors`
+pub fn validate_only<T>(event: &LambdaEvent<ApiGatewayProxyRequest>) -> HandlerResult
+where
+    T: DeserializeOwned + Validate,
+{
+    match parse_body::<T>(event) {
+        Ok(_) => Ok(apigw_response(
+            200,
+            Some(serde_json::json!({ "valid": true }).to_string().into()),
+            None,
+        )),
+        Err(error) => localized_error_response(&error, preferred_language(&event.payload)),
+    }
+}
+
+/// Wrap a handler so any `LambdaError` it returns becomes the standard JSON error response
+pub fn with_standard_error_handling<F, Fut>(
+    handler: F,
+) -> impl Fn(LambdaEvent<ApiGatewayProxyRequest>) -> HandlerFuture + Clone + Send + Sync + 'static
+where
+    F: Fn(LambdaEvent<ApiGatewayProxyRequest>) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = HandlerResult> + Send + 'static,
+{
+    move |event| {
+        let handler = handler.clone();
+        Box::pin(async move {
+            let lang = preferred_language(&event.payload).to_string();
+            match handler(event).await {
+                Err(e) => match e.downcast_ref::<LambdaError>() {
+                    Some(error) => {
+                        warn!("Request failed: {}", error);
+                        localized_error_response(error, &lang)
+                    }
+                    None => Err(e),
+                },
+                result => result,
+            }
+        })
+    }
+}
+
+/// Wrap a handler so it receives the request body already deserialized and validated.
+/// Validate-only requests are answered here without calling the handler.
+pub fn with_body<T, F, Fut>(
+    handler: F,
+) -> impl Fn(LambdaEvent<ApiGatewayProxyRequest>) -> HandlerFuture + Clone + Send + Sync + 'static
+where
+    T: DeserializeOwned + Validate + Send + 'static,
+    F: Fn(LambdaEvent<ApiGatewayProxyRequest>, T) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = HandlerResult> + Send + 'static,
+{
+    move |event| {
+        let handler = handler.clone();
+        Box::pin(async move {
+            if is_validate_only(&event) {
+                return validate_only::<T>(&event);
+            }
+            let request = parse_body::<T>(&event)?;
+            handler(event, request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_lambda_events::http::HeaderValue;
+    use aws_lambda_events::query_map::QueryMap;
+    use lambda_runtime::Context;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Deserialize, Debug)]
+    struct EchoRequest {
+        name: String,
+    }
+
+    impl Validate for EchoRequest {
+        fn validate(&self) -> Result<(), LambdaError> {
+            if self.name.is_empty() {
+                return Err(LambdaError::InvalidRequest("name is empty".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    fn create_test_event(body: Option<&str>) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let payload = ApiGatewayProxyRequest {
+            body: body.map(str::to_string),
+            ..Default::default()
+        };
+        LambdaEvent::new(payload, Context::default())
+    }
+
+    fn body_json(response: &ApiGatewayProxyResponse) -> serde_json::Value {
+        match response.body.as_ref() {
+            Some(aws_lambda_events::encodings::Body::Text(text)) => {
+                serde_json::from_str(text).unwrap()
+            }
+            other => panic!("unexpected body: {:?}", other),
+        }
+    }
+
+    fn create_authorized_request(values: &[&'static str]) -> ApiGatewayProxyRequest {
+        let mut request = ApiGatewayProxyRequest::default();
+        for value in values {
+            request
+                .multi_value_headers
+                .append(header::AUTHORIZATION, HeaderValue::from_static(value));
+        }
+        request
+    }
+
+    #[test]
+    fn test_extract_bearer_token() {
+        let mut request = ApiGatewayProxyRequest::default();
+        request.headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer a.b.c"),
+        );
+        assert_eq!(extract_bearer_token(&request).unwrap(), "a.b.c");
+
+        // The scheme is case-insensitive and repeated identical values are accepted
+        let request = create_authorized_request(&["bearer a.b.c", "bearer a.b.c"]);
+        assert_eq!(extract_bearer_token(&request).unwrap(), "a.b.c");
+    }
+
+    #[test]
+    fn test_extract_bearer_token_errors() {
+        assert!(matches!(
+            extract_bearer_token(&ApiGatewayProxyRequest::default()),
+            Err(LambdaError::MissingToken)
+        ));
+        for values in [
+            vec!["Basic dXNlcjpwYXNz"],
+            vec!["Bearer"],
+            vec!["Bearer   "],
+            vec!["Bearer a.b.c", "Bearer x.y.z"],
+        ] {
+            assert!(matches!(
+                extract_bearer_token(&create_authorized_request(&values)),
+                Err(LambdaError::InvalidToken)
+            ));
+        }
+    }
+
+    fn create_encoded_event(body: &str) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let mut event = create_test_event(Some(body));
+        event.payload.is_base64_encoded = true;
+        event
+    }
+
+    #[test]
+    fn test_decoded_body_passes_plain_body_through() {
+        let event = create_test_event(Some(r#"{"name":"Alice"}"#));
+        assert_eq!(
+            decoded_body(&event.payload).unwrap(),
+            br#"{"name":"Alice"}"#
+        );
+    }
+
+    #[test]
+    fn test_decoded_body_decodes_base64_body() {
+        let event = create_encoded_event(&STANDARD.encode(r#"{"name":"Alice"}"#));
+        assert_eq!(
+            decoded_body(&event.payload).unwrap(),
+            br#"{"name":"Alice"}"#
+        );
+
+        let request: EchoRequest = parse_body(&event).unwrap();
+        assert_eq!(request.name, "Alice");
+    }
+
+    #[test]
+    fn test_decoded_body_errors() {
+        assert!(matches!(
+            decoded_body(&create_test_event(None).payload),
+            Err(LambdaError::MissingBody)
+        ));
+        assert!(matches!(
+            decoded_body(&create_encoded_event("not base64!").payload),
+            Err(LambdaError::InvalidBodyEncoding)
+        ));
+    }
+
+    fn create_validate_only_event(body: &str) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let mut event = create_test_event(Some(body));
+        event.payload.query_string_parameters = QueryMap::from(HashMap::from([(
+            VALIDATE_ONLY_PARAM.to_string(),
+            "true".to_string(),
+        )]));
+        event
+    }
+
+    async fn failing_handler(_event: LambdaEvent<ApiGatewayProxyRequest>) -> HandlerResult {
+        Err(LambdaError::UserNotFound.into())
+    }
+
+    async fn echo_handler(
+        _event: LambdaEvent<ApiGatewayProxyRequest>,
+        request: EchoRequest,
+    ) -> HandlerResult {
+        Ok(apigw_response(200, Some(request.name.into()), None))
+    }
+
+    #[tokio::test]
+    async fn test_lambda_error_becomes_standard_response() {
+        let handler = with_standard_error_handling(failing_handler);
+        let response = handler(create_test_event(None)).await.unwrap();
+
+        assert_eq!(response.status_code, 404);
+        assert_eq!(
+            body_json(&response),
+            LambdaError::UserNotFound.response_body()
+        );
+    }
+
+    fn request_with_language(value: &'static str) -> ApiGatewayProxyRequest {
+        let mut request = ApiGatewayProxyRequest::default();
+        request
+            .headers
+            .insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static(value));
+        request
+    }
+
+    #[test]
+    fn test_preferred_language() {
+        assert_eq!(
+            preferred_language(&ApiGatewayProxyRequest::default()),
+            DEFAULT_LANGUAGE
+        );
+        assert_eq!(preferred_language(&request_with_language("ja-JP")), "ja-JP");
+        assert_eq!(
+            preferred_language(&request_with_language("en;q=0.5, ja;q=0.9, *")),
+            "ja"
+        );
+        assert_eq!(
+            preferred_language(&request_with_language("fr, ja;q=0.9")),
+            "fr"
+        );
+        assert_eq!(
+            preferred_language(&request_with_language("ja;q=0, *")),
+            DEFAULT_LANGUAGE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lambda_error_message_follows_accept_language() {
+        let handler = with_standard_error_handling(failing_handler);
+        let event = LambdaEvent::new(request_with_language("ja,en;q=0.8"), Context::default());
+        let response = handler(event).await.unwrap();
+
+        assert_eq!(response.status_code, 404);
+        assert_eq!(body_json(&response)["message"], "ユーザーが見つかりません");
+    }
+
+    #[tokio::test]
+    async fn test_throttled_error_keeps_retry_after_header() {
+        let handler = with_standard_error_handling(|_event| async {
+            Err::<ApiGatewayProxyResponse, Error>(LambdaError::Throttled.into())
+        });
+        let response = handler(create_test_event(None)).await.unwrap();
+
+        assert_eq!(response.status_code, 429);
+        assert!(response.headers.contains_key(header::RETRY_AFTER));
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_propagated() {
+        let handler = with_standard_error_handling(|_event| async {
+            Err::<ApiGatewayProxyResponse, Error>("boom".into())
+        });
+        assert!(handler(create_test_event(None)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_body_passes_parsed_request() {
+        let handler = with_standard_error_handling(with_body(echo_handler));
+        let response = handler(create_test_event(Some(r#"{"name":"alice"}"#)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(
+            response.body,
+            Some(aws_lambda_events::encodings::Body::Text(
+                "alice".to_string()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_body_rejects_missing_and_invalid_bodies() {
+        let handler = with_standard_error_handling(with_body(echo_handler));
+
+        let response = handler(create_test_event(None)).await.unwrap();
+        assert_eq!(response.status_code, 400);
+        assert_eq!(
+            body_json(&response),
+            LambdaError::MissingBody.response_body()
+        );
+
+        let response = handler(create_test_event(Some(r#"{"name":""}"#)))
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 400);
+    }
+
+    #[tokio::test]
+    async fn test_validate_only_skips_handler_for_valid_body() {
+        let handler =
+            with_standard_error_handling(with_body(|_event, _request: EchoRequest| async {
+                panic!("validate-only requests must not reach the handler")
+            }));
+        let response = handler(create_validate_only_event(r#"{"name":"alice"}"#))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(body_json(&response), serde_json::json!({ "valid": true }));
+    }
+
+    #[tokio::test]
+    async fn test_validate_only_returns_field_errors() {
+        let event = create_validate_only_event(r#"{"name":""}"#);
+        assert!(is_validate_only(&event));
+
+        let response = validate_only::<EchoRequest>(&event).unwrap();
+        assert_eq!(response.status_code, 400);
+        assert_eq!(
+            body_json(&response),
+            LambdaError::InvalidRequest("name is empty".to_string()).response_body()
+        );
+        assert!(!is_validate_only(&create_test_event(Some("{}"))));
+    }
+}
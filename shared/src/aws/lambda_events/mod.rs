@@ -1,2 +1,3 @@
+pub mod middleware;
 pub mod request;
 pub mod response;
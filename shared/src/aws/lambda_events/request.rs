@@ -1,9 +1,58 @@
-use super::response::apigw_response;
+use super::response::{
+    allowed_origin, apigw_response, cold_start_metric, cors_response, with_cold_start_header,
+    COLD_START,
+};
+use crate::config::get_config;
+use crate::errors::LambdaError;
+use crate::tracer::current_xray_trace_id;
+use crate::utils::env::get_env;
+
+use aws_lambda_events::http::{header, HeaderMap, HeaderValue, Method};
 
 use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
 use lambda_runtime::{Error, LambdaEvent};
 use std::future::Future;
-use tracing::{info, instrument};
+use std::time::SystemTime;
+use tracing::{info, instrument, warn};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Strip a trailing slash, keeping the root resource `/` intact
+fn normalize_resource(resource: &str) -> &str {
+    match resource.strip_suffix('/') {
+        Some(stripped) if !stripped.is_empty() => stripped,
+        _ => resource,
+    }
+}
+
+/// Check whether a request resource matches a handler target, ignoring a trailing slash and,
+/// if `case_insensitive` is set, case
+fn resource_matches(resource: &str, target: &str, case_insensitive: bool) -> bool {
+    let (resource, target) = (normalize_resource(resource), normalize_resource(target));
+    if case_insensitive {
+        resource.eq_ignore_ascii_case(target)
+    } else {
+        resource == target
+    }
+}
+
+/// Caller identity injected into the request headers by the token authorizer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    pub user_id: String,
+    pub organization_id: String,
+}
+
+/// Value of an authorizer-injected header, or `MissingAuthContext` if absent or not ASCII
+fn context_header(headers: &HeaderMap, name: &str) -> Result<String, LambdaError> {
+    let value = headers.get(name).and_then(|value| value.to_str().ok());
+    match value {
+        Some(value) => Ok(value.to_string()),
+        None => {
+            warn!("Request is missing the {} authorizer header", name);
+            Err(LambdaError::MissingAuthContext)
+        }
+    }
+}
 
 pub struct LambdaEventRequestHandler {}
 
@@ -14,40 +63,318 @@ impl LambdaEventRequestHandler {
     )]
     pub async fn get_ids_from_request_context(
         event: LambdaEvent<ApiGatewayProxyRequest>,
-    ) -> Result<(String, String), Error> {
-        let headers = event.clone().payload.headers;
-        let user_id = headers.get("user_id").expect("missing user id").to_str()?;
-        let organization_id = headers
-            .get("organization_id")
-            .expect("missing organization id")
-            .to_str()?;
-        Ok((user_id.to_string(), organization_id.to_string()))
+    ) -> Result<RequestContext, LambdaError> {
+        let headers = &event.payload.headers;
+        Ok(RequestContext {
+            user_id: context_header(headers, "user_id")?,
+            organization_id: context_header(headers, "organization_id")?,
+        })
+    }
+
+    /// Build the 400 response for a request without a `resource` field (e.g. a direct invoke)
+    pub fn missing_resource_response() -> Result<ApiGatewayProxyResponse, Error> {
+        let error = LambdaError::InvalidRequest("missing resource field".to_string());
+        let error_response = error.response_body();
+
+        Ok(apigw_response(
+            error.status_code(),
+            Some(serde_json::to_string(&error_response)?.into()),
+            None,
+        ))
+    }
+
+    /// Build the response for a retryable error (429/503), with a `Retry-After` header
+    pub fn retry_after_response(error: &LambdaError) -> Result<ApiGatewayProxyResponse, Error> {
+        let error_response = error.response_body();
+
+        let mut headers = HeaderMap::new();
+        if let Some(retry_after_secs) = error.retry_after_secs() {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+
+        Ok(apigw_response(
+            error.status_code(),
+            Some(serde_json::to_string(&error_response)?.into()),
+            Some(headers),
+        ))
     }
 
+    /// Route the request to `handler`, reporting whether this is the container's cold start
+    /// in the `X-Cold-Start` header and the `faas.coldstart` span attribute, and counting cold
+    /// starts in the `ColdStart` CloudWatch metric
     #[instrument(
         skip(event, handler),
+        fields(trace_id = tracing::field::Empty),
         name = "aws.lambda_events.request.handle_requests"
     )]
-
     pub async fn handle_requests<F, Fut>(
         event: LambdaEvent<ApiGatewayProxyRequest>,
         target: &str,
         handler: F,
     ) -> Result<ApiGatewayProxyResponse, Error>
+    where
+        F: Fn(LambdaEvent<ApiGatewayProxyRequest>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ApiGatewayProxyResponse, Error>> + Send,
+    {
+        let span = tracing::Span::current();
+        // Lets JSON logs be correlated with the X-Ray trace
+        if let Some(trace_id) = current_xray_trace_id(&span) {
+            span.record("trace_id", trace_id);
+        }
+        let cold_start = COLD_START.take();
+        span.set_attribute("faas.coldstart", cold_start);
+        if cold_start {
+            info!("Cold start invocation");
+            // Printed bare so CloudWatch Logs sees the EMF document rather than a log record
+            println!(
+                "{}",
+                cold_start_metric(
+                    &get_env("SERVICE_NAME", "local"),
+                    &get_env("AWS_LAMBDA_FUNCTION_NAME", "local"),
+                    SystemTime::now(),
+                )
+            );
+        }
+
+        Self::route_request(event, target, handler)
+            .await
+            .map(|response| with_cold_start_header(response, cold_start))
+    }
+
+    async fn route_request<F, Fut>(
+        event: LambdaEvent<ApiGatewayProxyRequest>,
+        target: &str,
+        handler: F,
+    ) -> Result<ApiGatewayProxyResponse, Error>
     where
         F: Fn(LambdaEvent<ApiGatewayProxyRequest>) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = Result<ApiGatewayProxyResponse, Error>> + Send,
     {
         let path = event.clone().payload.path.unwrap_or_default();
+
+        // Answer CORS preflight requests without invoking the handler
+        if event.payload.http_method == Method::OPTIONS {
+            info!("Received CORS preflight for {}", path);
+            return Ok(cors_response(204, None, &allowed_origin()));
+        }
+
         match event.clone().payload.resource.as_deref() {
-            Some(p) if p == target => {
+            Some(p) if resource_matches(p, target, get_config().case_insensitive_routes) => {
                 info!("Received request for {}", p);
-                handler(event).await
+                match handler(event).await {
+                    Err(e) => match e.downcast_ref::<LambdaError>() {
+                        Some(error) if error.retry_after_secs().is_some() => {
+                            warn!("Request failed with retryable error: {}", error);
+                            Self::retry_after_response(error)
+                        }
+                        // Reached without the authorizer, so answer 401 instead of failing the invocation
+                        Some(error @ LambdaError::MissingAuthContext) => Ok(apigw_response(
+                            error.status_code(),
+                            Some(serde_json::to_string(&error.response_body())?.into()),
+                            None,
+                        )),
+                        _ => Err(e),
+                    },
+                    result => result,
+                }
             }
-            _ => {
+            Some(_) => {
                 info!("Invalid path: {}", path);
                 Ok(apigw_response(404, Some("Not Found".into()), None))
             }
+            None => {
+                warn!("Request has no resource field (path: {})", path);
+                Self::missing_resource_response()
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::lambda_events::response::COLD_START_HEADER;
+    use lambda_runtime::Context;
+
+    fn create_test_event(resource: Option<&str>) -> LambdaEvent<ApiGatewayProxyRequest> {
+        let payload = ApiGatewayProxyRequest {
+            resource: resource.map(str::to_string),
+            ..Default::default()
+        };
+        LambdaEvent::new(payload, Context::default())
+    }
+
+    async fn ok_handler(
+        _event: LambdaEvent<ApiGatewayProxyRequest>,
+    ) -> Result<ApiGatewayProxyResponse, Error> {
+        Ok(apigw_response(200, None, None))
+    }
+
+    async fn context_handler(
+        event: LambdaEvent<ApiGatewayProxyRequest>,
+    ) -> Result<ApiGatewayProxyResponse, Error> {
+        LambdaEventRequestHandler::get_ids_from_request_context(event).await?;
+        Ok(apigw_response(200, None, None))
+    }
+
+    #[tokio::test]
+    async fn test_get_ids_from_request_context() {
+        let mut event = create_test_event(Some("/users"));
+        event
+            .payload
+            .headers
+            .insert("user_id", HeaderValue::from_static("user-1"));
+        event
+            .payload
+            .headers
+            .insert("organization_id", HeaderValue::from_static("org-1"));
+
+        let context = LambdaEventRequestHandler::get_ids_from_request_context(event)
+            .await
+            .unwrap();
+        assert_eq!(
+            context,
+            RequestContext {
+                user_id: "user-1".to_string(),
+                organization_id: "org-1".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_auth_context_is_unauthorized() {
+        let mut event = create_test_event(Some("/users"));
+        event
+            .payload
+            .headers
+            .insert("user_id", HeaderValue::from_static("user-1"));
+        assert!(matches!(
+            LambdaEventRequestHandler::get_ids_from_request_context(event.clone()).await,
+            Err(LambdaError::MissingAuthContext)
+        ));
+
+        let response = LambdaEventRequestHandler::handle_requests(
+            create_test_event(Some("/users")),
+            "/users",
+            context_handler,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 401);
+    }
+
+    #[tokio::test]
+    async fn test_handle_requests_routes_matching_resource() {
+        let response = LambdaEventRequestHandler::handle_requests(
+            create_test_event(Some("/health")),
+            "/health",
+            ok_handler,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 200);
+        // Other tests may have taken the cold start, so only the header's presence is fixed
+        assert!(response.headers.contains_key(COLD_START_HEADER));
+    }
+
+    #[tokio::test]
+    async fn test_handle_requests_routes_trailing_slash_resource() {
+        let response = LambdaEventRequestHandler::handle_requests(
+            create_test_event(Some("/login/")),
+            "/login",
+            ok_handler,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 200);
+    }
+
+    #[test]
+    fn test_resource_matches_ignores_trailing_slash() {
+        assert!(resource_matches("/login/", "/login", false));
+        assert!(resource_matches("/login", "/login/", false));
+        assert!(resource_matches("/", "/", false));
+        assert!(!resource_matches("/login/", "/logout", false));
+    }
+
+    #[test]
+    fn test_resource_matches_case_variants() {
+        let target = "/organizations/{organizationId}/users";
+        assert!(!resource_matches(
+            "/Organizations/{organizationId}/Users",
+            target,
+            false
+        ));
+        assert!(resource_matches(
+            "/Organizations/{organizationId}/Users/",
+            target,
+            true
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_handle_requests_unknown_resource_is_not_found() {
+        let response = LambdaEventRequestHandler::handle_requests(
+            create_test_event(Some("/unknown")),
+            "/health",
+            ok_handler,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 404);
+    }
+
+    #[tokio::test]
+    async fn test_handle_requests_options_is_preflight() {
+        let mut event = create_test_event(Some("/health"));
+        event.payload.http_method = Method::OPTIONS;
+
+        let response = LambdaEventRequestHandler::handle_requests(event, "/health", ok_handler)
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 204);
+        assert!(response.body.is_none());
+        assert!(response
+            .headers
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_handle_requests_throttled_returns_retry_after() {
+        async fn throttled_handler(
+            _event: LambdaEvent<ApiGatewayProxyRequest>,
+        ) -> Result<ApiGatewayProxyResponse, Error> {
+            Err(Error::from(LambdaError::Throttled))
+        }
+
+        let response = LambdaEventRequestHandler::handle_requests(
+            create_test_event(Some("/health")),
+            "/health",
+            throttled_handler,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 429);
+        assert_eq!(response.headers.get(header::RETRY_AFTER).unwrap(), "1");
+    }
+
+    #[tokio::test]
+    async fn test_handle_requests_missing_resource_is_bad_request() {
+        let response = LambdaEventRequestHandler::handle_requests(
+            create_test_event(None),
+            "/health",
+            ok_handler,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status_code, 400);
+
+        let body = match response.body {
+            Some(aws_lambda_events::encodings::Body::Text(body)) => body,
+            other => panic!("unexpected body: {other:?}"),
+        };
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["error"], "Invalid request: missing resource field");
+    }
+}
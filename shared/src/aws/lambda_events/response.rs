@@ -1,16 +1,391 @@
 use aws_lambda_events::encodings::Body;
 use aws_lambda_events::event::apigw::ApiGatewayProxyResponse;
-use aws_lambda_events::http::HeaderMap;
+use aws_lambda_events::http::{header, HeaderMap, HeaderValue};
 
+use crate::config::get_config;
+use crate::utils::env::get_env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Methods advertised to browsers in CORS responses
+const CORS_ALLOW_METHODS: &str = "GET,POST,PUT,PATCH,DELETE,OPTIONS";
+/// Request headers browsers may send in CORS requests
+const CORS_ALLOW_HEADERS: &str = "Content-Type,Authorization,Idempotency-Key";
+/// Response header reporting whether the invocation was the container's first
+pub const COLD_START_HEADER: &str = "X-Cold-Start";
+/// Response header naming the deployed version that served the request
+pub const SERVICE_VERSION_HEADER: &str = "X-Service-Version";
+
+/// Process-global cold-start flag, cleared by the first invocation in the container
+pub static COLD_START: ColdStart = ColdStart::new();
+
+/// Tracks whether a container has served its first invocation yet
+pub struct ColdStart(AtomicBool);
+
+impl ColdStart {
+    pub const fn new() -> Self {
+        Self(AtomicBool::new(true))
+    }
+
+    /// `true` on the first call only, `false` on every call after it
+    pub fn take(&self) -> bool {
+        self.0.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Default for ColdStart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Headers hardening browser handling of API responses
+pub fn security_headers(hsts_max_age: Duration) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+    headers.insert(
+        header::STRICT_TRANSPORT_SECURITY,
+        HeaderValue::from_str(&format!(
+            "max-age={}; includeSubDomains",
+            hsts_max_age.as_secs()
+        ))
+        .expect("HSTS header is valid ASCII"),
+    );
+    headers
+}
+
+/// Add the security headers to `headers`, keeping any value the caller already set
+fn with_security_headers(mut headers: HeaderMap, hsts_max_age: Duration) -> HeaderMap {
+    for (name, value) in security_headers(hsts_max_age).iter() {
+        headers.entry(name).or_insert_with(|| value.clone());
+    }
+    headers
+}
+
+/// Add the `X-Service-Version` header, keeping a value the caller already set
+fn with_service_version(mut headers: HeaderMap, service_version: &str) -> HeaderMap {
+    match HeaderValue::from_str(service_version) {
+        Ok(value) => {
+            headers.entry(SERVICE_VERSION_HEADER).or_insert(value);
+        }
+        Err(_) => warn!("Ignoring invalid service version: {}", service_version),
+    }
+    headers
+}
+
+/// Add the `Access-Control-Allow-Origin` header, keeping a value the caller already set
+fn with_allowed_origin(mut headers: HeaderMap, origin: &str) -> HeaderMap {
+    match HeaderValue::from_str(origin) {
+        Ok(value) => {
+            headers
+                .entry(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .or_insert(value);
+        }
+        Err(_) => warn!("Ignoring invalid CORS origin: {}", origin),
+    }
+    headers
+}
+
+/// Build a response tagged with the service version and allowed to be read by `ALLOWED_ORIGIN`;
+/// security headers are added unless disabled with `SECURITY_HEADERS=false`
 pub fn apigw_response(
     status_code: i64,
     body: Option<Body>,
     headers: Option<HeaderMap>,
 ) -> ApiGatewayProxyResponse {
+    let config = get_config();
+    let headers = with_service_version(headers.unwrap_or_default(), &config.service_version);
+    let headers = with_allowed_origin(headers, &allowed_origin());
     ApiGatewayProxyResponse {
         status_code,
         body,
-        headers: headers.unwrap_or_default(),
+        headers: if config.security_headers {
+            with_security_headers(headers, config.hsts_max_age)
+        } else {
+            headers
+        },
         ..Default::default()
     }
 }
+
+/// CloudWatch embedded metric format document counting one cold start of `function_name`.
+/// Lambda forwards stdout to CloudWatch Logs, which turns each such line into a metric.
+pub fn cold_start_metric(
+    namespace: &str,
+    function_name: &str,
+    timestamp: SystemTime,
+) -> serde_json::Value {
+    let timestamp_ms = timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    serde_json::json!({
+        "_aws": {
+            "Timestamp": timestamp_ms,
+            "CloudWatchMetrics": [{
+                "Namespace": namespace,
+                "Dimensions": [["FunctionName"]],
+                "Metrics": [{ "Name": "ColdStart", "Unit": "Count" }],
+            }],
+        },
+        "FunctionName": function_name,
+        "ColdStart": 1,
+    })
+}
+
+/// Add the `X-Cold-Start` header to a response
+pub fn with_cold_start_header(
+    mut response: ApiGatewayProxyResponse,
+    cold_start: bool,
+) -> ApiGatewayProxyResponse {
+    response.headers.insert(
+        COLD_START_HEADER,
+        HeaderValue::from_static(if cold_start { "true" } else { "false" }),
+    );
+    response
+}
+
+/// Allowed CORS origin, configured via `ALLOWED_ORIGIN` (defaults to `*`)
+pub fn allowed_origin() -> String {
+    get_env("ALLOWED_ORIGIN", "*")
+}
+
+/// Build the `Access-Control-Allow-*` headers for the given origin
+pub fn cors_headers(origin: &str) -> HeaderMap {
+    let mut headers = with_allowed_origin(HeaderMap::new(), origin);
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        HeaderValue::from_static(CORS_ALLOW_METHODS),
+    );
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static(CORS_ALLOW_HEADERS),
+    );
+    headers
+}
+
+/// Same as `apigw_response`, with CORS headers for browser clients
+pub fn cors_response(
+    status_code: i64,
+    body: Option<Body>,
+    origin: &str,
+) -> ApiGatewayProxyResponse {
+    apigw_response(status_code, body, Some(cors_headers(origin)))
+}
+
+/// Build organization quota usage headers (`X-Org-Usage` and, above the warning threshold, `Warning`)
+pub fn org_usage_headers(used: u64, quota: u64, warning_percent: u64) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if quota == 0 {
+        return headers;
+    }
+
+    headers.insert(
+        "X-Org-Usage",
+        HeaderValue::from_str(&format!("{used}/{quota}")).expect("usage header is valid ASCII"),
+    );
+
+    let usage_percent = used.saturating_mul(100) / quota;
+    if usage_percent >= warning_percent {
+        headers.insert(
+            "Warning",
+            HeaderValue::from_str(&format!(
+                "299 - \"Organization has used {usage_percent}% of its user quota\""
+            ))
+            .expect("warning header is valid ASCII"),
+        );
+    }
+
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_security_headers(response: &ApiGatewayProxyResponse) {
+        assert_eq!(
+            response
+                .headers
+                .get(header::X_CONTENT_TYPE_OPTIONS)
+                .unwrap(),
+            "nosniff"
+        );
+        assert_eq!(
+            response.headers.get(header::X_FRAME_OPTIONS).unwrap(),
+            "DENY"
+        );
+        assert_eq!(
+            response
+                .headers
+                .get(header::STRICT_TRANSPORT_SECURITY)
+                .unwrap(),
+            "max-age=31536000; includeSubDomains"
+        );
+    }
+
+    #[test]
+    fn test_security_headers_on_success_and_error_responses() {
+        assert_security_headers(&apigw_response(200, Some("{}".into()), None));
+        assert_security_headers(&cors_response(204, None, "https://app.example.com"));
+
+        let error = crate::errors::LambdaError::UserNotFound;
+        let response = crate::aws::lambda_events::middleware::error_response(&error).unwrap();
+        assert_eq!(response.status_code, 404);
+        assert_security_headers(&response);
+    }
+
+    #[test]
+    fn test_security_headers_keep_caller_values() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::X_FRAME_OPTIONS,
+            HeaderValue::from_static("SAMEORIGIN"),
+        );
+        let headers = with_security_headers(headers, Duration::from_secs(60));
+        assert_eq!(headers.get(header::X_FRAME_OPTIONS).unwrap(), "SAMEORIGIN");
+        assert_eq!(
+            headers.get(header::STRICT_TRANSPORT_SECURITY).unwrap(),
+            "max-age=60; includeSubDomains"
+        );
+    }
+
+    #[test]
+    fn test_service_version_header_matches_config() {
+        let response = apigw_response(200, None, None);
+        assert_eq!(
+            response.headers.get(SERVICE_VERSION_HEADER).unwrap(),
+            get_config().service_version.as_str()
+        );
+
+        let headers = with_service_version(HeaderMap::new(), "1.2.3");
+        assert_eq!(headers.get(SERVICE_VERSION_HEADER).unwrap(), "1.2.3");
+        let headers = with_service_version(HeaderMap::new(), "bad\nversion");
+        assert!(headers.get(SERVICE_VERSION_HEADER).is_none());
+    }
+
+    #[test]
+    fn test_cold_start_is_reported_on_first_invocation_only() {
+        let cold_start = ColdStart::new();
+        assert!(cold_start.take());
+        assert!(!cold_start.take());
+        assert!(!cold_start.take());
+    }
+
+    #[test]
+    fn test_cold_start_metric_is_embedded_metric_format() {
+        let timestamp = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let metric = cold_start_metric("sls-uma", "users-get", timestamp);
+
+        assert_eq!(metric["_aws"]["Timestamp"], 1_700_000_000_123u64);
+        let directive = &metric["_aws"]["CloudWatchMetrics"][0];
+        assert_eq!(directive["Namespace"], "sls-uma");
+        assert_eq!(
+            directive["Dimensions"],
+            serde_json::json!([["FunctionName"]])
+        );
+        assert_eq!(directive["Metrics"][0]["Name"], "ColdStart");
+        assert_eq!(directive["Metrics"][0]["Unit"], "Count");
+        assert_eq!(metric["FunctionName"], "users-get");
+        assert_eq!(metric["ColdStart"], 1);
+    }
+
+    #[test]
+    fn test_with_cold_start_header() {
+        let response = with_cold_start_header(apigw_response(200, None, None), true);
+        assert_eq!(response.headers.get(COLD_START_HEADER).unwrap(), "true");
+
+        let response = with_cold_start_header(apigw_response(200, None, None), false);
+        assert_eq!(response.headers.get(COLD_START_HEADER).unwrap(), "false");
+    }
+
+    #[test]
+    fn test_cors_response_sets_allow_headers() {
+        let response = cors_response(200, None, "https://app.example.com");
+        assert_eq!(response.status_code, 200);
+        assert_eq!(
+            response
+                .headers
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "https://app.example.com"
+        );
+        assert_eq!(
+            response
+                .headers
+                .get(header::ACCESS_CONTROL_ALLOW_METHODS)
+                .unwrap(),
+            CORS_ALLOW_METHODS
+        );
+        assert_eq!(
+            response
+                .headers
+                .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+                .unwrap(),
+            CORS_ALLOW_HEADERS
+        );
+    }
+
+    #[test]
+    fn test_every_response_carries_allowed_origin() {
+        let response = apigw_response(500, Some("{}".into()), None);
+        assert_eq!(
+            response
+                .headers
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            allowed_origin().as_str()
+        );
+
+        let headers = with_allowed_origin(cors_headers("https://app.example.com"), "*");
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://app.example.com"
+        );
+        assert!(with_allowed_origin(HeaderMap::new(), "bad\norigin").is_empty());
+    }
+
+    #[test]
+    fn test_cors_headers_skips_invalid_origin() {
+        let headers = cors_headers("bad\norigin");
+        assert!(headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+        assert!(headers.get(header::ACCESS_CONTROL_ALLOW_METHODS).is_some());
+    }
+
+    #[test]
+    fn test_org_usage_headers_below_threshold() {
+        let headers = org_usage_headers(50, 100, 90);
+        assert_eq!(headers.get("X-Org-Usage").unwrap(), "50/100");
+        assert!(headers.get("Warning").is_none());
+    }
+
+    #[test]
+    fn test_org_usage_headers_at_and_above_threshold() {
+        let headers = org_usage_headers(90, 100, 90);
+        assert_eq!(headers.get("X-Org-Usage").unwrap(), "90/100");
+        assert_eq!(
+            headers.get("Warning").unwrap(),
+            "299 - \"Organization has used 90% of its user quota\""
+        );
+
+        let headers = org_usage_headers(95, 100, 90);
+        assert_eq!(headers.get("X-Org-Usage").unwrap(), "95/100");
+        assert!(headers.get("Warning").is_some());
+
+        let headers = org_usage_headers(120, 100, 90);
+        assert_eq!(headers.get("X-Org-Usage").unwrap(), "120/100");
+        assert!(headers.get("Warning").is_some());
+    }
+
+    #[test]
+    fn test_org_usage_headers_disabled_without_quota() {
+        let headers = org_usage_headers(95, 0, 90);
+        assert!(headers.is_empty());
+    }
+}
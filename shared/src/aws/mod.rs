@@ -1,4 +1,6 @@
 pub mod cognito;
 pub mod dynamodb;
+pub mod eventbridge;
+pub mod kms;
 pub mod lambda_events;
 pub mod secret_manager;
@@ -1,5 +1,5 @@
 use aws_sdk_secretsmanager::{
-    error::{BuildError, SdkError},
+    error::{BuildError, ProvideErrorMetadata, SdkError},
     operation::get_secret_value::GetSecretValueError,
 };
 use thiserror::Error;
@@ -27,3 +27,23 @@ pub enum SecretManagerError {
     #[error("Other: {0}")]
     Other(String),
 }
+
+impl SecretManagerError {
+    /// Check whether Secrets Manager was unreachable or overloaded rather than misconfigured
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            SecretManagerError::GetSecretValueError(e) => match e.as_ref() {
+                SdkError::DispatchFailure(_)
+                | SdkError::TimeoutError(_)
+                | SdkError::ResponseError(_) => true,
+                SdkError::ServiceError(_) => {
+                    e.as_service_error()
+                        .is_some_and(|e| e.is_internal_service_error())
+                        || e.code() == Some("ThrottlingException")
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
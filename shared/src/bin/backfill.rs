@@ -0,0 +1,20 @@
+//! Rewrite the lookup attributes (`email_lower`, `organization_name_normalized`) of every user, e.g.
+//! after enabling `ENCRYPT_PII`.
+//!
+//! Run with AWS credentials and the environment of the deployed functions:
+//! `TABLE_NAME=Users ENCRYPT_PII=true PII_KMS_KEY_ID=... PII_INDEX_KMS_KEY_ID=... cargo run -p shared --bin backfill`
+
+use shared::client_manager::{DefaultClientManager, DynamoDbClientManager};
+use shared::repository::user_repository::UserRepositoryImpl;
+use shared::utils::env::get_env;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let dynamodb_client = DefaultClientManager::from_env().get_client().await?;
+    let table_name = get_env("TABLE_NAME", "Users");
+    let repository = UserRepositoryImpl::new((*dynamodb_client).clone(), table_name.clone());
+
+    let updated = repository.backfill_lookup_attributes().await?;
+    println!("Backfilled {updated} users in {table_name}");
+    Ok(())
+}
@@ -1,64 +1,317 @@
 use crate::config::get_config;
+use crate::entity::idempotency::IdempotentResponse;
 use crate::entity::secrets::Secrets;
-use crate::entity::user::User;
+use crate::entity::user::{Permissions, User};
 
 use moka::future::Cache;
 use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::debug;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Hit and miss counters for a single cache
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
 
-/// Unified cache manager for all Lambda functions
-pub struct CacheManager {
-    user_cache: Cache<String, User>,
-    permission_cache: Cache<String, bool>,
-    hash_cache: Cache<String, String>,
-    secrets_cache: Cache<String, Secrets>,
-    org_users_cache: Cache<String, Vec<User>>,
+impl CacheCounters {
+    /// Count a lookup result as a hit or a miss and pass it through
+    fn record<T>(&self, value: Option<T>) -> Option<T> {
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
 }
 
-impl CacheManager {
-    pub fn new() -> Self {
-        let config = get_config();
+/// Hash a cache key to a fixed-size value
+fn hash_key(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Value stored under a hashed key, with the original key to detect collisions
+#[derive(Clone)]
+struct HashedEntry<V> {
+    key: Box<str>,
+    value: V,
+}
+
+/// Cache keyed by strings, optionally storing keys as fixed-size hashes
+enum KeyedCache<V> {
+    Raw(Cache<String, V>),
+    Hashed {
+        cache: Cache<u64, HashedEntry<V>>,
+        hasher: fn(&str) -> u64,
+    },
+}
+
+impl<V: Clone + Send + Sync + 'static> KeyedCache<V> {
+    fn new(max_capacity: u64, time_to_live: Duration, hash_keys: bool) -> Self {
+        if hash_keys {
+            KeyedCache::Hashed {
+                cache: Cache::builder()
+                    .max_capacity(max_capacity)
+                    .time_to_live(time_to_live)
+                    .build(),
+                hasher: hash_key,
+            }
+        } else {
+            KeyedCache::Raw(
+                Cache::builder()
+                    .max_capacity(max_capacity)
+                    .time_to_live(time_to_live)
+                    .build(),
+            )
+        }
+    }
+
+    async fn get(&self, key: &str) -> Option<V> {
+        match self {
+            KeyedCache::Raw(cache) => cache.get(key).await,
+            KeyedCache::Hashed { cache, hasher } => {
+                let entry = cache.get(&hasher(key)).await?;
+                if *entry.key == *key {
+                    Some(entry.value)
+                } else {
+                    // Another key with the same hash owns the slot
+                    debug!("Cache key hash collision for key: {}", key);
+                    None
+                }
+            }
+        }
+    }
+
+    async fn insert(&self, key: String, value: V) {
+        match self {
+            KeyedCache::Raw(cache) => cache.insert(key, value).await,
+            KeyedCache::Hashed { cache, hasher } => {
+                let entry = HashedEntry {
+                    key: key.into_boxed_str(),
+                    value,
+                };
+                cache.insert(hasher(&entry.key), entry).await;
+            }
+        }
+    }
+
+    async fn invalidate(&self, key: &str) {
+        match self {
+            KeyedCache::Raw(cache) => cache.invalidate(key).await,
+            KeyedCache::Hashed { cache, hasher } => cache.invalidate(&hasher(key)).await,
+        }
+    }
+
+    fn invalidate_all(&self) {
+        match self {
+            KeyedCache::Raw(cache) => cache.invalidate_all(),
+            KeyedCache::Hashed { cache, .. } => cache.invalidate_all(),
+        }
+    }
+
+    fn entry_count(&self) -> u64 {
+        match self {
+            KeyedCache::Raw(cache) => cache.entry_count(),
+            KeyedCache::Hashed { cache, .. } => cache.entry_count(),
+        }
+    }
+}
+
+/// Permission cache key for one permission of a user, so decisions for different permissions never mix
+fn permission_cache_key(user_id: &str, permission: &Permissions) -> String {
+    format!("{user_id}:{:x}", permission.bits())
+}
 
+/// Sliding-window request counter per key
+struct RateLimiter {
+    windows: Cache<String, Arc<Mutex<VecDeque<Instant>>>>,
+}
+
+impl RateLimiter {
+    fn new(max_capacity: u64, time_to_idle: Duration) -> Self {
         Self {
-            user_cache: Cache::builder()
-                .max_capacity(config.cache_max_capacity)
-                .time_to_live(config.cache_ttl)
+            windows: Cache::builder()
+                .max_capacity(max_capacity)
+                .time_to_idle(time_to_idle)
                 .build(),
+        }
+    }
 
-            permission_cache: Cache::builder()
-                .max_capacity(config.cache_max_capacity)
-                .time_to_live(config.cache_ttl)
-                .build(),
+    /// Record a request for `key`, returning `false` if `max` requests already fell within `window`
+    async fn check(&self, key: &str, max: u32, window: Duration) -> bool {
+        let requests = self
+            .windows
+            .get_with(key.to_string(), async {
+                Arc::new(Mutex::new(VecDeque::new()))
+            })
+            .await;
+        let mut requests = requests.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let now = Instant::now();
+        while requests
+            .front()
+            .is_some_and(|&at| now.duration_since(at) >= window)
+        {
+            requests.pop_front();
+        }
 
-            hash_cache: Cache::builder()
-                .max_capacity(config.cache_max_capacity)
-                .time_to_live(config.hash_cache_ttl)
-                .build(),
+        if requests.len() >= max as usize {
+            return false;
+        }
+        requests.push_back(now);
+        true
+    }
 
-            secrets_cache: Cache::builder()
-                .max_capacity(config.secrets_cache_max_capacity)
-                .time_to_live(config.secrets_cache_ttl)
-                .build(),
+    fn invalidate_all(&self) {
+        self.windows.invalidate_all();
+    }
+}
 
-            org_users_cache: Cache::builder()
-                .max_capacity(config.org_users_cache_max_capacity)
-                .time_to_live(config.cache_ttl)
-                .build(),
+/// Unified cache manager for all Lambda functions
+pub struct CacheManager {
+    user_cache: KeyedCache<User>,
+    user_negative_cache: KeyedCache<bool>,
+    permission_cache: KeyedCache<bool>,
+    hash_cache: KeyedCache<String>,
+    secrets_cache: KeyedCache<Secrets>,
+    org_users_cache: KeyedCache<Vec<User>>,
+    user_org_cache: KeyedCache<String>,
+    idempotency_cache: KeyedCache<IdempotentResponse>,
+    rate_limiter: RateLimiter,
+    user_counters: CacheCounters,
+    user_negative_counters: CacheCounters,
+    permission_counters: CacheCounters,
+    hash_counters: CacheCounters,
+    secrets_counters: CacheCounters,
+    org_users_counters: CacheCounters,
+}
+
+impl CacheManager {
+    pub fn new() -> Self {
+        Self::with_key_hashing(get_config().hash_cache_keys)
+    }
+
+    /// Create a cache manager, storing keys as fixed-size hashes when `hash_keys` is set
+    pub fn with_key_hashing(hash_keys: bool) -> Self {
+        let config = get_config();
+
+        Self {
+            user_cache: KeyedCache::new(config.cache_max_capacity, config.cache_ttl, hash_keys),
+            user_negative_cache: KeyedCache::new(
+                config.cache_max_capacity,
+                config.negative_cache_ttl,
+                hash_keys,
+            ),
+            permission_cache: KeyedCache::new(
+                config.cache_max_capacity,
+                config.cache_ttl,
+                hash_keys,
+            ),
+            hash_cache: KeyedCache::new(
+                config.cache_max_capacity,
+                config.hash_cache_ttl,
+                hash_keys,
+            ),
+            secrets_cache: KeyedCache::new(
+                config.secrets_cache_max_capacity,
+                config.secrets_cache_ttl,
+                hash_keys,
+            ),
+            org_users_cache: KeyedCache::new(
+                config.org_users_cache_max_capacity,
+                config.cache_ttl,
+                hash_keys,
+            ),
+            user_org_cache: KeyedCache::new(config.cache_max_capacity, config.cache_ttl, hash_keys),
+            idempotency_cache: KeyedCache::new(
+                config.cache_max_capacity,
+                config.idempotency_ttl,
+                hash_keys,
+            ),
+            rate_limiter: RateLimiter::new(config.cache_max_capacity, config.rate_limit_window),
+
+            user_counters: CacheCounters::default(),
+            user_negative_counters: CacheCounters::default(),
+            permission_counters: CacheCounters::default(),
+            hash_counters: CacheCounters::default(),
+            secrets_counters: CacheCounters::default(),
+            org_users_counters: CacheCounters::default(),
         }
     }
 
     /// Get user from cache
     pub async fn get_user(&self, user_id: &str) -> Option<User> {
-        self.user_cache.get(user_id).await
+        self.user_counters
+            .record(self.user_cache.get(user_id).await)
     }
 
     /// Set user in cache
     pub async fn set_user(&self, user_id: String, user: User) {
+        self.user_negative_cache.invalidate(&user_id).await;
         self.user_cache.insert(user_id, user).await;
     }
 
+    /// Remove user from cache
+    pub async fn invalidate_user(&self, user_id: &str) {
+        self.user_cache.invalidate(user_id).await;
+        self.user_org_cache.invalidate(user_id).await;
+        self.permission_cache.invalidate(user_id).await;
+        for permission in Permissions::all().iter() {
+            self.permission_cache
+                .invalidate(&permission_cache_key(user_id, &permission))
+                .await;
+        }
+    }
+
+    /// Get the organization ID of a user from cache
+    pub async fn get_user_org(&self, user_id: &str) -> Option<String> {
+        self.user_org_cache.get(user_id).await
+    }
+
+    /// Set the organization ID of a user in cache
+    pub async fn set_user_org(&self, user_id: String, organization_id: String) {
+        self.user_negative_cache.invalidate(&user_id).await;
+        self.user_org_cache.insert(user_id, organization_id).await;
+    }
+
+    /// Check whether a user was recently looked up and not found
+    pub async fn get_user_negative(&self, user_id: &str) -> Option<bool> {
+        self.user_negative_counters
+            .record(self.user_negative_cache.get(user_id).await)
+    }
+
+    /// Remember that a user was not found
+    pub async fn set_user_negative(&self, user_id: String) {
+        self.user_negative_cache.insert(user_id, true).await;
+    }
+
     /// Get permission from cache
     pub async fn get_permission(&self, user_id: &str) -> Option<bool> {
-        self.permission_cache.get(user_id).await
+        self.permission_counters
+            .record(self.permission_cache.get(user_id).await)
     }
 
     /// Set permission in cache
@@ -66,9 +319,30 @@ impl CacheManager {
         self.permission_cache.insert(user_id, has_permission).await;
     }
 
+    /// Get the cached decision on whether a user holds `permission`
+    pub async fn get_permission_for(
+        &self,
+        user_id: &str,
+        permission: &Permissions,
+    ) -> Option<bool> {
+        self.get_permission(&permission_cache_key(user_id, permission))
+            .await
+    }
+
+    /// Cache the decision on whether a user holds `permission`
+    pub async fn set_permission_for(
+        &self,
+        user_id: &str,
+        permission: &Permissions,
+        has_permission: bool,
+    ) {
+        self.set_permission(permission_cache_key(user_id, permission), has_permission)
+            .await;
+    }
+
     /// Get hash from cache
     pub async fn get_hash(&self, key: &str) -> Option<String> {
-        self.hash_cache.get(key).await
+        self.hash_counters.record(self.hash_cache.get(key).await)
     }
 
     /// Set hash in cache
@@ -76,9 +350,15 @@ impl CacheManager {
         self.hash_cache.insert(key, hash).await;
     }
 
+    /// Remove hash from cache
+    pub async fn invalidate_hash(&self, key: &str) {
+        self.hash_cache.invalidate(key).await;
+    }
+
     /// Get secrets from cache
     pub async fn get_secrets(&self, region: &str) -> Option<Secrets> {
-        self.secrets_cache.get(region).await
+        self.secrets_counters
+            .record(self.secrets_cache.get(region).await)
     }
 
     /// Set secrets in cache
@@ -88,7 +368,8 @@ impl CacheManager {
 
     /// Get organization users from cache
     pub async fn get_org_users(&self, org_id: &str) -> Option<Vec<User>> {
-        self.org_users_cache.get(org_id).await
+        self.org_users_counters
+            .record(self.org_users_cache.get(org_id).await)
     }
 
     /// Set organization users in cache
@@ -96,25 +377,127 @@ impl CacheManager {
         self.org_users_cache.insert(org_id, users).await;
     }
 
+    /// Get the response recorded for an idempotency key
+    pub async fn get_idempotent(&self, key: &str) -> Option<IdempotentResponse> {
+        self.idempotency_cache.get(key).await
+    }
+
+    /// Record the response for an idempotency key
+    pub async fn set_idempotent(&self, key: String, response: IdempotentResponse) {
+        self.idempotency_cache.insert(key, response).await;
+    }
+
+    /// Remove organization users from cache
+    pub async fn invalidate_org_users(&self, org_id: &str) {
+        self.org_users_cache.invalidate(org_id).await;
+    }
+
+    /// Count a request by `user_id`, returning `false` once more than `max` fall within `window`.
+    /// A `max` of 0 disables the limit.
+    pub async fn check_rate_limit(&self, user_id: &str, max: u32, window: Duration) -> bool {
+        if max == 0 {
+            return true;
+        }
+        self.rate_limiter.check(user_id, max, window).await
+    }
+
     /// Clear all caches (useful for testing)
     pub async fn clear_all(&self) {
         self.user_cache.invalidate_all();
+        self.user_negative_cache.invalidate_all();
         self.permission_cache.invalidate_all();
         self.hash_cache.invalidate_all();
         self.secrets_cache.invalidate_all();
         self.org_users_cache.invalidate_all();
+        self.user_org_cache.invalidate_all();
+        self.idempotency_cache.invalidate_all();
+        self.rate_limiter.invalidate_all();
+        self.user_counters.reset();
+        self.user_negative_counters.reset();
+        self.permission_counters.reset();
+        self.hash_counters.reset();
+        self.secrets_counters.reset();
+        self.org_users_counters.reset();
     }
 
     /// Get cache statistics
     pub fn get_stats(&self) -> CacheStats {
         CacheStats {
             user_cache_size: self.user_cache.entry_count(),
+            user_negative_cache_size: self.user_negative_cache.entry_count(),
             permission_cache_size: self.permission_cache.entry_count(),
             hash_cache_size: self.hash_cache.entry_count(),
             secrets_cache_size: self.secrets_cache.entry_count(),
             org_users_cache_size: self.org_users_cache.entry_count(),
+            user_cache_hits: self.user_counters.hits(),
+            user_cache_misses: self.user_counters.misses(),
+            user_negative_cache_hits: self.user_negative_counters.hits(),
+            user_negative_cache_misses: self.user_negative_counters.misses(),
+            permission_cache_hits: self.permission_counters.hits(),
+            permission_cache_misses: self.permission_counters.misses(),
+            hash_cache_hits: self.hash_counters.hits(),
+            hash_cache_misses: self.hash_counters.misses(),
+            secrets_cache_hits: self.secrets_counters.hits(),
+            secrets_cache_misses: self.secrets_counters.misses(),
+            org_users_cache_hits: self.org_users_counters.hits(),
+            org_users_cache_misses: self.org_users_counters.misses(),
         }
     }
+
+    /// Record cache statistics as attributes on the current span
+    pub fn record_metrics(&self) {
+        let stats = self.get_stats();
+        let span = tracing::Span::current();
+
+        for (cache, size, hits, misses) in [
+            (
+                "user",
+                stats.user_cache_size,
+                stats.user_cache_hits,
+                stats.user_cache_misses,
+            ),
+            (
+                "user_negative",
+                stats.user_negative_cache_size,
+                stats.user_negative_cache_hits,
+                stats.user_negative_cache_misses,
+            ),
+            (
+                "permission",
+                stats.permission_cache_size,
+                stats.permission_cache_hits,
+                stats.permission_cache_misses,
+            ),
+            (
+                "hash",
+                stats.hash_cache_size,
+                stats.hash_cache_hits,
+                stats.hash_cache_misses,
+            ),
+            (
+                "secrets",
+                stats.secrets_cache_size,
+                stats.secrets_cache_hits,
+                stats.secrets_cache_misses,
+            ),
+            (
+                "org_users",
+                stats.org_users_cache_size,
+                stats.org_users_cache_hits,
+                stats.org_users_cache_misses,
+            ),
+        ] {
+            span.set_attribute(format!("cache.{cache}.size"), size as i64);
+            span.set_attribute(format!("cache.{cache}.hits"), hits as i64);
+            span.set_attribute(format!("cache.{cache}.misses"), misses as i64);
+            span.set_attribute(
+                format!("cache.{cache}.hit_ratio"),
+                CacheStats::hit_ratio(hits, misses),
+            );
+        }
+
+        debug!("Cache metrics: {:?}", stats);
+    }
 }
 
 impl Default for CacheManager {
@@ -127,10 +510,35 @@ impl Default for CacheManager {
 #[derive(Debug, Clone)]
 pub struct CacheStats {
     pub user_cache_size: u64,
+    pub user_negative_cache_size: u64,
     pub permission_cache_size: u64,
     pub hash_cache_size: u64,
     pub secrets_cache_size: u64,
     pub org_users_cache_size: u64,
+    pub user_cache_hits: u64,
+    pub user_cache_misses: u64,
+    pub user_negative_cache_hits: u64,
+    pub user_negative_cache_misses: u64,
+    pub permission_cache_hits: u64,
+    pub permission_cache_misses: u64,
+    pub hash_cache_hits: u64,
+    pub hash_cache_misses: u64,
+    pub secrets_cache_hits: u64,
+    pub secrets_cache_misses: u64,
+    pub org_users_cache_hits: u64,
+    pub org_users_cache_misses: u64,
+}
+
+impl CacheStats {
+    /// Ratio of hits to total lookups (0.0 when the cache has not been used)
+    pub fn hit_ratio(hits: u64, misses: u64) -> f64 {
+        let total = hits + misses;
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
 }
 
 /// Global cache manager instance
@@ -209,7 +617,7 @@ impl Cacheable<Vec<User>> for CacheManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::entity::user::{Permissions, Role};
+    use crate::entity::user::Role;
     use std::collections::HashSet;
 
     /// Test utilities for cache manager
@@ -295,6 +703,87 @@ mod tests {
         assert!(cleared_user.is_none());
     }
 
+    #[tokio::test]
+    async fn test_cache_manager_invalidate_user() {
+        let utils = CacheTestUtils::new();
+
+        let user = CacheTestUtils::create_test_user(
+            "invalidate-1",
+            "Test User",
+            "test@example.com",
+            "org-1",
+            "Test Org",
+            vec![Role::Admin],
+        );
+        utils
+            .cache_manager
+            .set_user("invalidate-1".to_string(), user)
+            .await;
+        utils
+            .cache_manager
+            .set_permission("invalidate-1".to_string(), true)
+            .await;
+
+        let delete_key = permission_cache_key("invalidate-1", &Permissions::DELETE);
+        utils
+            .cache_manager
+            .set_permission(delete_key.clone(), true)
+            .await;
+
+        utils.cache_manager.invalidate_user("invalidate-1").await;
+
+        assert!(utils
+            .cache_manager
+            .get_permission(&delete_key)
+            .await
+            .is_none());
+        assert!(utils.cache_manager.get_user("invalidate-1").await.is_none());
+        assert!(utils
+            .cache_manager
+            .get_permission("invalidate-1")
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_manager_user_negative_operations() {
+        let utils = CacheTestUtils::new();
+
+        assert!(utils
+            .cache_manager
+            .get_user_negative("missing-user")
+            .await
+            .is_none());
+
+        utils
+            .cache_manager
+            .set_user_negative("missing-user".to_string())
+            .await;
+        assert_eq!(
+            utils.cache_manager.get_user_negative("missing-user").await,
+            Some(true)
+        );
+
+        // Caching the user clears the tombstone
+        let user = CacheTestUtils::create_test_user(
+            "missing-user",
+            "Late User",
+            "late@example.com",
+            "org-1",
+            "Test Org",
+            vec![Role::Reader],
+        );
+        utils
+            .cache_manager
+            .set_user("missing-user".to_string(), user)
+            .await;
+        assert!(utils
+            .cache_manager
+            .get_user_negative("missing-user")
+            .await
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_cache_manager_permission_operations() {
         let utils = CacheTestUtils::new();
@@ -319,6 +808,38 @@ mod tests {
         assert!(!cached_permission.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_permission_decisions_do_not_leak_across_permissions() {
+        let cache_manager = CacheManager::new();
+
+        // A user granted CREATE but not DELETE
+        cache_manager
+            .set_permission_for("leak-1", &Permissions::CREATE, true)
+            .await;
+        assert_eq!(
+            cache_manager
+                .get_permission_for("leak-1", &Permissions::DELETE)
+                .await,
+            None
+        );
+
+        cache_manager
+            .set_permission_for("leak-1", &Permissions::DELETE, false)
+            .await;
+        assert_eq!(
+            cache_manager
+                .get_permission_for("leak-1", &Permissions::CREATE)
+                .await,
+            Some(true)
+        );
+        assert_eq!(
+            cache_manager
+                .get_permission_for("leak-1", &Permissions::DELETE)
+                .await,
+            Some(false)
+        );
+    }
+
     #[tokio::test]
     async fn test_cache_manager_hash_operations() {
         let utils = CacheTestUtils::new();
@@ -430,6 +951,38 @@ mod tests {
         assert_eq!(stats.org_users_cache_size, 0);
     }
 
+    #[tokio::test]
+    async fn test_cache_hit_miss_counters() {
+        let utils = CacheTestUtils::new();
+
+        // Miss before the entry exists
+        assert!(utils.cache_manager.get_hash("counter-key").await.is_none());
+
+        utils
+            .cache_manager
+            .set_hash("counter-key".to_string(), "hash-value".to_string())
+            .await;
+
+        // Two hits after insertion
+        assert!(utils.cache_manager.get_hash("counter-key").await.is_some());
+        assert!(utils.cache_manager.get_hash("counter-key").await.is_some());
+        assert!(utils.cache_manager.get_user("missing-user").await.is_none());
+
+        let stats = utils.get_cache_stats();
+        assert_eq!(stats.hash_cache_hits, 2);
+        assert_eq!(stats.hash_cache_misses, 1);
+        assert_eq!(stats.user_cache_hits, 0);
+        assert_eq!(stats.user_cache_misses, 1);
+        assert!((CacheStats::hit_ratio(2, 1) - 2.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(CacheStats::hit_ratio(0, 0), 0.0);
+
+        // Clearing caches also resets counters
+        utils.clear_caches().await;
+        let stats = utils.get_cache_stats();
+        assert_eq!(stats.hash_cache_hits, 0);
+        assert_eq!(stats.hash_cache_misses, 0);
+    }
+
     #[tokio::test]
     async fn test_cacheable_trait_user() {
         let cache_manager = CacheManager::new();
@@ -534,4 +1087,99 @@ mod tests {
         let stats = utils.get_cache_stats();
         assert!(stats.user_cache_size <= 3);
     }
+
+    #[tokio::test]
+    async fn test_hashed_keys_hit_and_miss() {
+        let cache_manager = CacheManager::with_key_hashing(true);
+
+        cache_manager
+            .set_hash("alice@example.com".to_string(), "hash-a".to_string())
+            .await;
+        assert_eq!(
+            cache_manager.get_hash("alice@example.com").await.as_deref(),
+            Some("hash-a")
+        );
+        assert_eq!(cache_manager.get_hash("bob@example.com").await, None);
+
+        cache_manager
+            .set_permission("user-1".to_string(), true)
+            .await;
+        cache_manager.invalidate_user("user-1").await;
+        assert_eq!(cache_manager.get_permission("user-1").await, None);
+
+        let stats = cache_manager.get_stats();
+        assert_eq!(stats.hash_cache_hits, 1);
+        assert_eq!(stats.hash_cache_misses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_hashed_key_collision_is_a_miss() {
+        // Force every key onto the same hash
+        let cache: KeyedCache<String> = KeyedCache::Hashed {
+            cache: Cache::builder().max_capacity(10).build(),
+            hasher: |_| 42,
+        };
+
+        cache.insert("first".to_string(), "1".to_string()).await;
+        assert_eq!(cache.get("first").await.as_deref(), Some("1"));
+        assert_eq!(cache.get("second").await, None);
+
+        // The last write owns the slot
+        cache.insert("second".to_string(), "2".to_string()).await;
+        assert_eq!(cache.get("second").await.as_deref(), Some("2"));
+        assert_eq!(cache.get("first").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_rejects_request_over_max() {
+        let cache_manager = CacheManager::new();
+        let window = Duration::from_secs(60);
+
+        for _ in 0..3 {
+            assert!(cache_manager.check_rate_limit("user-1", 3, window).await);
+        }
+        assert!(!cache_manager.check_rate_limit("user-1", 3, window).await);
+        // Other users have their own window
+        assert!(cache_manager.check_rate_limit("user-2", 3, window).await);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_window_slides() {
+        let cache_manager = CacheManager::new();
+        let window = Duration::from_millis(50);
+
+        assert!(cache_manager.check_rate_limit("user-1", 1, window).await);
+        assert!(!cache_manager.check_rate_limit("user-1", 1, window).await);
+
+        tokio::time::sleep(window).await;
+        assert!(cache_manager.check_rate_limit("user-1", 1, window).await);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_zero_max_is_disabled() {
+        let cache_manager = CacheManager::new();
+        for _ in 0..5 {
+            assert!(
+                cache_manager
+                    .check_rate_limit("user-1", 0, Duration::from_secs(60))
+                    .await
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_response_round_trip() {
+        let cache_manager = CacheManager::new();
+        assert_eq!(cache_manager.get_idempotent("user-1:key-1").await, None);
+
+        let response = IdempotentResponse::new("{}", 200, r#"{"id":"user-2"}"#.to_string());
+        cache_manager
+            .set_idempotent("user-1:key-1".to_string(), response.clone())
+            .await;
+        assert_eq!(
+            cache_manager.get_idempotent("user-1:key-1").await,
+            Some(response)
+        );
+        assert_eq!(cache_manager.get_idempotent("user-2:key-1").await, None);
+    }
 }
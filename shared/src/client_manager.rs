@@ -3,9 +3,14 @@ use crate::aws::cognito::token_authorizer::CognitoTokenAuthorizer;
 use crate::aws::dynamodb::client::DynamoDbClient;
 use crate::entity::secrets::Secrets;
 use crate::errors::LambdaResult;
+use crate::utils::env::get_env;
 
 use async_trait::async_trait;
 use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Authorizer shared across invocations so its JWKS cache survives warm starts
+static TOKEN_AUTHORIZER: OnceCell<CognitoTokenAuthorizer> = OnceCell::const_new();
 
 /// Trait for managing Cognito client instances
 #[async_trait]
@@ -40,6 +45,11 @@ impl DefaultClientManager {
     pub fn new(region: String) -> Self {
         Self { region }
     }
+
+    /// Create a manager for the region in `AWS_REGION`, defaulting to ap-northeast-1
+    pub fn from_env() -> Self {
+        Self::new(get_env("AWS_REGION", "ap-northeast-1"))
+    }
 }
 
 #[async_trait]
@@ -49,7 +59,7 @@ impl CognitoClientManager for DefaultClientManager {
         // but with better error handling and abstraction
         let secrets = Secrets::get_secrets(self.region.clone())
             .await
-            .map_err(|e| crate::errors::LambdaError::InternalError(e.to_string()))?;
+            .map_err(crate::errors::LambdaError::from_secrets_error)?;
 
         CognitoClient::new(
             self.region.clone(),
@@ -58,7 +68,7 @@ impl CognitoClientManager for DefaultClientManager {
             secrets.client_secret,
         )
         .await
-        .map_err(|e| crate::errors::LambdaError::InternalError(e.to_string()))
+        .map_err(|e| crate::errors::LambdaError::internal("create Cognito client", e))
     }
 }
 
@@ -68,25 +78,29 @@ impl DynamoDbClientManager for DefaultClientManager {
         DynamoDbClient::new(self.region.clone())
             .await
             .map(Arc::new)
-            .map_err(|e| crate::errors::LambdaError::InternalError(e.to_string()))
+            .map_err(|e| crate::errors::LambdaError::internal("create DynamoDB client", e))
     }
 }
 
 #[async_trait]
 impl TokenAuthorizerManager for DefaultClientManager {
     async fn get_authorizer(&self) -> LambdaResult<CognitoTokenAuthorizer> {
-        let secrets = Secrets::get_secrets(self.region.clone())
+        TOKEN_AUTHORIZER
+            .get_or_try_init(|| async {
+                let secrets = Secrets::get_secrets(self.region.clone())
+                    .await
+                    .map_err(crate::errors::LambdaError::from_secrets_error)?;
+
+                Ok(CognitoTokenAuthorizer::new(
+                    secrets.user_pool_id,
+                    secrets.jwks_url,
+                    self.region.clone(),
+                    None,
+                )
+                .await)
+            })
             .await
-            .map_err(|e| crate::errors::LambdaError::InternalError(e.to_string()))?;
-
-        Ok(
-            CognitoTokenAuthorizer::new(
-                secrets.user_pool_id,
-                secrets.jwks_url,
-                self.region.clone(),
-            )
-            .await,
-        )
+            .cloned()
     }
 }
 
@@ -95,7 +109,7 @@ impl SecretsManager for DefaultClientManager {
     async fn get_secrets(&self) -> LambdaResult<Secrets> {
         Secrets::get_secrets(self.region.clone())
             .await
-            .map_err(|e| crate::errors::LambdaError::InternalError(e.to_string()))
+            .map_err(crate::errors::LambdaError::from_secrets_error)
     }
 }
 
@@ -261,6 +275,17 @@ mod tests {
         assert_eq!(manager.region, region);
     }
 
+    #[test]
+    fn test_default_client_manager_from_env() {
+        std::env::set_var("AWS_REGION", "us-west-2");
+        let manager = DefaultClientManager::from_env();
+        assert_eq!(manager.region, "us-west-2");
+
+        std::env::remove_var("AWS_REGION");
+        let manager = DefaultClientManager::from_env();
+        assert_eq!(manager.region, "ap-northeast-1");
+    }
+
     #[test]
     fn test_mock_client_manager_creation() {
         let mock_manager = MockClientManager {
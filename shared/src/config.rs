@@ -1,4 +1,68 @@
+use crate::entity::user::Role;
+use crate::utils::password::PasswordPolicy;
+
+use std::str::FromStr;
 use std::time::Duration;
+use tracing::warn;
+
+/// Field names `IMMUTABLE_FIELDS` may list
+pub const IMMUTABLE_FIELD_NAMES: [&str; 5] =
+    ["user_name", "organization_name", "roles", "phone", "locale"];
+
+/// Shortest cache TTL accepted by `LambdaConfig::validate`
+const MIN_CACHE_TTL: Duration = Duration::from_secs(1);
+/// Longest cache TTL accepted by `LambdaConfig::validate` (7 days)
+const MAX_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Key schema of the users table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableConfig {
+    /// Partition key attribute name (holds the user ID)
+    pub partition_key: String,
+    /// Sort key attribute name (holds the organization ID), `None` for a table keyed by user ID
+    /// alone; the organization ID is then stored as a plain attribute
+    pub sort_key: Option<String>,
+    /// Global secondary index keyed by email
+    pub email_index: String,
+    /// Global secondary index keyed by organization ID
+    pub organization_index: String,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self {
+            partition_key: "id".to_string(),
+            sort_key: Some("organization_id".to_string()),
+            email_index: "EmailIndex".to_string(),
+            organization_index: "OrganizationIndex".to_string(),
+        }
+    }
+}
+
+impl TableConfig {
+    /// Attribute names making up the primary key
+    pub fn key_attributes(&self) -> Vec<&str> {
+        std::iter::once(self.partition_key.as_str())
+            .chain(self.sort_key.as_deref())
+            .collect()
+    }
+
+    /// Get the key schema from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            partition_key: std::env::var("PK_ATTR").unwrap_or_else(|_| "id".to_string()),
+            // An empty `SK_ATTR` selects a table without a sort key
+            sort_key: match std::env::var("SK_ATTR") {
+                Ok(sort_key) if sort_key.is_empty() => None,
+                Ok(sort_key) => Some(sort_key),
+                Err(_) => Some("organization_id".to_string()),
+            },
+            email_index: std::env::var("EMAIL_INDEX").unwrap_or_else(|_| "EmailIndex".to_string()),
+            organization_index: std::env::var("ORGANIZATION_INDEX")
+                .unwrap_or_else(|_| "OrganizationIndex".to_string()),
+        }
+    }
+}
 
 /// Centralized configuration for all Lambda functions
 pub struct LambdaConfig {
@@ -8,12 +72,81 @@ pub struct LambdaConfig {
     pub hash_cache_ttl: Duration,
     /// Cache TTL for secrets (longer due to AWS API calls)
     pub secrets_cache_ttl: Duration,
+    /// Cache TTL for user-not-found tombstones (short so new users become visible quickly)
+    pub negative_cache_ttl: Duration,
     /// Maximum capacity for all caches
     pub cache_max_capacity: u64,
     /// Maximum capacity for organization users cache (smaller due to list size)
     pub org_users_cache_max_capacity: u64,
     /// Maximum capacity for secrets cache (smaller due to limited secrets)
     pub secrets_cache_max_capacity: u64,
+    /// Maximum number of users per organization (0 disables quota reporting)
+    pub org_user_quota: u64,
+    /// Usage percentage of the organization quota at which a warning is reported
+    pub org_user_quota_warning_percent: u64,
+    /// Password rules for generated passwords and password validation
+    pub password_policy: PasswordPolicy,
+    /// Key schema of the users table
+    pub table: TableConfig,
+    /// Store cache keys as fixed-size hashes to bound per-entry key memory
+    pub hash_cache_keys: bool,
+    /// Timeout for a single JWKS HTTP request
+    pub jwks_timeout: Duration,
+    /// Clock skew tolerated when checking token `exp`/`iat`
+    pub jwt_leeway: Duration,
+    /// Reject tokens whose `iat` is more than `jwt_leeway` in the future
+    pub reject_future_iat: bool,
+    /// Add `X-Content-Type-Options`, `X-Frame-Options` and `Strict-Transport-Security` to every response
+    pub security_headers: bool,
+    /// `max-age` of the `Strict-Transport-Security` header
+    pub hsts_max_age: Duration,
+    /// Deployed version reported in the `X-Service-Version` response header
+    pub service_version: String,
+    /// Maximum write requests per user within `rate_limit_window` (0 disables rate limiting)
+    pub rate_limit_max: u32,
+    /// Sliding window for per-user rate limiting
+    pub rate_limit_window: Duration,
+    /// How long a response is kept for replay under its `Idempotency-Key`
+    pub idempotency_ttl: Duration,
+    /// DynamoDB table sharing idempotency keys and rate limits across instances (unset keeps them per-instance)
+    pub session_table_name: Option<String>,
+    /// Match request resources against handler targets ignoring case
+    pub case_insensitive_routes: bool,
+    /// Create the DynamoDB row for a validly signed token whose user has none (e.g. SSO users)
+    pub auto_provision_users: bool,
+    /// Role granted to auto-provisioned users; only `Reader` or `Writer` are accepted
+    pub auto_provision_role: Role,
+    /// Token claim holding the organization ID of an auto-provisioned user
+    pub auto_provision_org_claim: String,
+    /// Organizations users may be auto-provisioned into; none are allowed when empty, since
+    /// `custom:*` attributes are user-writable unless the app client restricts them
+    pub auto_provision_orgs: Vec<String>,
+    /// Take the organization from the token's `custom:organization_id` claim in token validation;
+    /// the user's row is still read, through the cache, to reject deleted or suspended users
+    pub org_from_claims: bool,
+    /// App clients whose write attributes exclude `custom:organization_id`; `ORG_FROM_CLAIMS` only
+    /// trusts the claim in tokens issued to these
+    pub org_claim_client_ids: Vec<String>,
+    /// Allow `DynamoDbClient::ensure_table_exists` to create missing tables (local/dev only)
+    pub create_tables: bool,
+    /// Encrypt PII attributes (email) with KMS envelope encryption
+    pub encrypt_pii: bool,
+    /// KMS key used for PII envelope encryption
+    pub pii_kms_key_id: String,
+    /// `HMAC_256` KMS key computing the blind index stored in `email_lower` for email lookups
+    pub pii_index_kms_key_id: String,
+    /// Request fields the user update endpoint refuses to change, e.g. `user_name,organization_name`
+    pub immutable_fields: Vec<String>,
+    /// Panic at cold start on an invalid configuration instead of clamping it
+    pub config_strict: bool,
+    /// Serve the unauthenticated `/preflight` dependency check (non-production stages only)
+    pub enable_preflight: bool,
+    /// Deployment stage from `SERVICE_ENVIRONMENT`, e.g. `dev` or `prod`
+    pub service_environment: String,
+    /// HMAC key signing pagination tokens so clients cannot forge a start key
+    pub pagination_token_secret: String,
+    /// Environment variables that failed to parse and fell back to their defaults
+    pub parse_errors: Vec<String>,
 }
 
 impl Default for LambdaConfig {
@@ -22,9 +155,42 @@ impl Default for LambdaConfig {
             cache_ttl: Duration::from_secs(1800),         // 30 minutes
             hash_cache_ttl: Duration::from_secs(3600),    // 1 hour
             secrets_cache_ttl: Duration::from_secs(3600), // 1 hour
+            negative_cache_ttl: Duration::from_secs(60),  // 1 minute
             cache_max_capacity: 1000,
             org_users_cache_max_capacity: 100,
             secrets_cache_max_capacity: 10,
+            org_user_quota: 0,
+            org_user_quota_warning_percent: 90,
+            password_policy: PasswordPolicy::default(),
+            table: TableConfig::default(),
+            hash_cache_keys: false,
+            jwks_timeout: Duration::from_secs(5),
+            jwt_leeway: Duration::from_secs(60),
+            reject_future_iat: false,
+            security_headers: true,
+            hsts_max_age: Duration::from_secs(31_536_000), // 1 year
+            service_version: env!("CARGO_PKG_VERSION").to_string(),
+            rate_limit_max: 30,
+            rate_limit_window: Duration::from_secs(60),
+            idempotency_ttl: Duration::from_secs(3600),
+            session_table_name: None,
+            case_insensitive_routes: false,
+            auto_provision_users: false,
+            auto_provision_role: Role::Reader,
+            auto_provision_org_claim: "custom:organization_id".to_string(),
+            auto_provision_orgs: Vec::new(),
+            org_from_claims: false,
+            org_claim_client_ids: Vec::new(),
+            create_tables: false,
+            encrypt_pii: false,
+            pii_kms_key_id: String::new(),
+            pii_index_kms_key_id: String::new(),
+            immutable_fields: Vec::new(),
+            config_strict: false,
+            enable_preflight: false,
+            service_environment: "local".to_string(),
+            pagination_token_secret: String::new(),
+            parse_errors: Vec::new(),
         }
     }
 }
@@ -35,6 +201,7 @@ impl LambdaConfig {
         cache_ttl: Duration,
         hash_cache_ttl: Duration,
         secrets_cache_ttl: Duration,
+        negative_cache_ttl: Duration,
         cache_max_capacity: u64,
         org_users_cache_max_capacity: u64,
         secrets_cache_max_capacity: u64,
@@ -43,53 +210,303 @@ impl LambdaConfig {
             cache_ttl,
             hash_cache_ttl,
             secrets_cache_ttl,
+            negative_cache_ttl,
             cache_max_capacity,
             org_users_cache_max_capacity,
             secrets_cache_max_capacity,
+            ..Self::default()
         }
     }
 
     /// Get configuration from environment variables
     pub fn from_env() -> Self {
-        let cache_ttl_secs = std::env::var("CACHE_TTL_SECS")
-            .unwrap_or_else(|_| "1800".to_string())
-            .parse::<u64>()
-            .unwrap_or(1800);
+        let mut parse_errors = Vec::new();
+        let cache_ttl_secs = parsed_var("CACHE_TTL_SECS", 1800, &mut parse_errors);
+
+        let hash_cache_ttl_secs = parsed_var("HASH_CACHE_TTL_SECS", 3600, &mut parse_errors);
 
-        let hash_cache_ttl_secs = std::env::var("HASH_CACHE_TTL_SECS")
-            .unwrap_or_else(|_| "3600".to_string())
-            .parse::<u64>()
-            .unwrap_or(3600);
+        let secrets_cache_ttl_secs = parsed_var("SECRETS_CACHE_TTL_SECS", 3600, &mut parse_errors);
 
-        let secrets_cache_ttl_secs = std::env::var("SECRETS_CACHE_TTL_SECS")
-            .unwrap_or_else(|_| "3600".to_string())
-            .parse::<u64>()
-            .unwrap_or(3600);
+        let negative_cache_ttl_secs = parsed_var("NEGATIVE_CACHE_TTL_SECS", 60, &mut parse_errors);
 
         Self {
             cache_ttl: Duration::from_secs(cache_ttl_secs),
             hash_cache_ttl: Duration::from_secs(hash_cache_ttl_secs),
             secrets_cache_ttl: Duration::from_secs(secrets_cache_ttl_secs),
-            cache_max_capacity: std::env::var("CACHE_MAX_CAPACITY")
-                .unwrap_or_else(|_| "1000".to_string())
-                .parse::<u64>()
-                .unwrap_or(1000),
-            org_users_cache_max_capacity: std::env::var("ORG_USERS_CACHE_MAX_CAPACITY")
-                .unwrap_or_else(|_| "100".to_string())
-                .parse::<u64>()
-                .unwrap_or(100),
-            secrets_cache_max_capacity: std::env::var("SECRETS_CACHE_MAX_CAPACITY")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse::<u64>()
-                .unwrap_or(10),
+            negative_cache_ttl: Duration::from_secs(negative_cache_ttl_secs),
+            cache_max_capacity: parsed_var("CACHE_MAX_CAPACITY", 1000, &mut parse_errors),
+            org_users_cache_max_capacity: parsed_var(
+                "ORG_USERS_CACHE_MAX_CAPACITY",
+                100,
+                &mut parse_errors,
+            ),
+            secrets_cache_max_capacity: parsed_var(
+                "SECRETS_CACHE_MAX_CAPACITY",
+                10,
+                &mut parse_errors,
+            ),
+            org_user_quota: parsed_var("ORG_USER_QUOTA", 0, &mut parse_errors),
+            org_user_quota_warning_percent: parsed_var(
+                "ORG_USER_QUOTA_WARNING_PERCENT",
+                90,
+                &mut parse_errors,
+            ),
+            password_policy: PasswordPolicy::from_env(),
+            table: TableConfig::from_env(),
+            hash_cache_keys: std::env::var("HASH_CACHE_KEYS")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            jwks_timeout: Duration::from_secs(parsed_var(
+                "JWKS_TIMEOUT_SECS",
+                5,
+                &mut parse_errors,
+            )),
+            jwt_leeway: Duration::from_secs(parsed_var("JWT_LEEWAY_SECS", 60, &mut parse_errors)),
+            reject_future_iat: std::env::var("REJECT_FUTURE_IAT")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            security_headers: !std::env::var("SECURITY_HEADERS")
+                .map(|v| v.eq_ignore_ascii_case("false"))
+                .unwrap_or(false),
+            hsts_max_age: Duration::from_secs(parsed_var(
+                "HSTS_MAX_AGE_SECS",
+                31_536_000,
+                &mut parse_errors,
+            )),
+            service_version: std::env::var("SERVICE_VERSION")
+                .ok()
+                .filter(|version| !version.is_empty())
+                .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string()),
+            rate_limit_max: parsed_var("RATE_LIMIT_MAX", 30, &mut parse_errors),
+            rate_limit_window: Duration::from_secs(parsed_var(
+                "RATE_LIMIT_WINDOW_SECS",
+                60,
+                &mut parse_errors,
+            )),
+            idempotency_ttl: Duration::from_secs(parsed_var(
+                "IDEMPOTENCY_TTL_SECS",
+                3600,
+                &mut parse_errors,
+            )),
+            session_table_name: std::env::var("SESSION_TABLE_NAME")
+                .ok()
+                .filter(|name| !name.is_empty()),
+            case_insensitive_routes: std::env::var("CASE_INSENSITIVE_ROUTES")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            auto_provision_users: std::env::var("AUTO_PROVISION_USERS")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            auto_provision_role: match std::env::var("AUTO_PROVISION_ROLE").as_deref() {
+                Ok("Writer") => Role::Writer,
+                _ => Role::Reader,
+            },
+            auto_provision_org_claim: std::env::var("AUTO_PROVISION_ORG_CLAIM")
+                .unwrap_or_else(|_| "custom:organization_id".to_string()),
+            auto_provision_orgs: list_var("AUTO_PROVISION_ORGS"),
+            org_from_claims: std::env::var("ORG_FROM_CLAIMS")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            org_claim_client_ids: list_var("ORG_CLAIM_CLIENT_IDS"),
+            create_tables: std::env::var("CREATE_TABLES")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            encrypt_pii: std::env::var("ENCRYPT_PII")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            pii_kms_key_id: std::env::var("PII_KMS_KEY_ID").unwrap_or_default(),
+            pii_index_kms_key_id: std::env::var("PII_INDEX_KMS_KEY_ID").unwrap_or_default(),
+            immutable_fields: list_var("IMMUTABLE_FIELDS"),
+            config_strict: std::env::var("CONFIG_STRICT")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            enable_preflight: std::env::var("ENABLE_PREFLIGHT")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            service_environment: std::env::var("SERVICE_ENVIRONMENT")
+                .unwrap_or_else(|_| "local".to_string()),
+            pagination_token_secret: std::env::var("PAGINATION_TOKEN_SECRET").unwrap_or_default(),
+            parse_errors,
+        }
+    }
+
+    /// Whether this is the production stage, where 500 responses carry no error detail
+    pub fn is_production(&self) -> bool {
+        self.service_environment.eq_ignore_ascii_case("prod")
+    }
+
+    fn cache_ttls(&self) -> [(&'static str, Duration); 4] {
+        [
+            ("cache_ttl", self.cache_ttl),
+            ("hash_cache_ttl", self.hash_cache_ttl),
+            ("secrets_cache_ttl", self.secrets_cache_ttl),
+            ("negative_cache_ttl", self.negative_cache_ttl),
+        ]
+    }
+
+    fn cache_capacities(&self) -> [(&'static str, u64); 3] {
+        [
+            ("cache_max_capacity", self.cache_max_capacity),
+            (
+                "org_users_cache_max_capacity",
+                self.org_users_cache_max_capacity,
+            ),
+            (
+                "secrets_cache_max_capacity",
+                self.secrets_cache_max_capacity,
+            ),
+        ]
+    }
+
+    /// Check the invariants the caches rely on, describing every violation
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = self.parse_errors.clone();
+        for (name, ttl) in self.cache_ttls() {
+            if !(MIN_CACHE_TTL..=MAX_CACHE_TTL).contains(&ttl) {
+                problems.push(format!(
+                    "{name} of {}s is outside {}s..={}s",
+                    ttl.as_secs(),
+                    MIN_CACHE_TTL.as_secs(),
+                    MAX_CACHE_TTL.as_secs()
+                ));
+            }
+        }
+        for (name, capacity) in self.cache_capacities() {
+            if capacity == 0 {
+                problems.push(format!("{name} must be greater than 0"));
+            }
+        }
+        if self.hash_cache_ttl < self.cache_ttl {
+            problems.push(format!(
+                "hash_cache_ttl of {}s is shorter than cache_ttl of {}s",
+                self.hash_cache_ttl.as_secs(),
+                self.cache_ttl.as_secs()
+            ));
+        }
+        for field in self.unknown_immutable_fields() {
+            problems.push(format!("immutable_fields names unknown field {field:?}"));
+        }
+        if self.is_production() && self.pagination_token_secret.is_empty() {
+            problems.push("pagination_token_secret must be set in production".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; "))
+        }
+    }
+
+    /// Replace invalid values with the nearest valid ones, logging a warning for each
+    fn clamp(&mut self) {
+        for (name, ttl) in [
+            ("cache_ttl", &mut self.cache_ttl),
+            ("hash_cache_ttl", &mut self.hash_cache_ttl),
+            ("secrets_cache_ttl", &mut self.secrets_cache_ttl),
+            ("negative_cache_ttl", &mut self.negative_cache_ttl),
+        ] {
+            let clamped = (*ttl).clamp(MIN_CACHE_TTL, MAX_CACHE_TTL);
+            if clamped != *ttl {
+                warn!(
+                    "Clamped {name} from {}s to {}s",
+                    ttl.as_secs(),
+                    clamped.as_secs()
+                );
+                *ttl = clamped;
+            }
+        }
+
+        let defaults = Self::default();
+        for (name, capacity, default) in [
+            (
+                "cache_max_capacity",
+                &mut self.cache_max_capacity,
+                defaults.cache_max_capacity,
+            ),
+            (
+                "org_users_cache_max_capacity",
+                &mut self.org_users_cache_max_capacity,
+                defaults.org_users_cache_max_capacity,
+            ),
+            (
+                "secrets_cache_max_capacity",
+                &mut self.secrets_cache_max_capacity,
+                defaults.secrets_cache_max_capacity,
+            ),
+        ] {
+            if *capacity == 0 {
+                warn!("Clamped {name} from 0 to {default}");
+                *capacity = default;
+            }
+        }
+
+        if self.hash_cache_ttl < self.cache_ttl {
+            warn!(
+                "Clamped hash_cache_ttl from {}s to cache_ttl of {}s",
+                self.hash_cache_ttl.as_secs(),
+                self.cache_ttl.as_secs()
+            );
+            self.hash_cache_ttl = self.cache_ttl;
+        }
+
+        let unknown = self.unknown_immutable_fields();
+        if !unknown.is_empty() {
+            warn!("Ignoring unknown immutable_fields {unknown:?}");
+            self.immutable_fields
+                .retain(|field| IMMUTABLE_FIELD_NAMES.contains(&field.as_str()));
+        }
+    }
+
+    fn unknown_immutable_fields(&self) -> Vec<String> {
+        self.immutable_fields
+            .iter()
+            .filter(|field| !IMMUTABLE_FIELD_NAMES.contains(&field.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Validate the configuration, panicking on a violation in strict mode and clamping otherwise
+    fn checked(mut self) -> Self {
+        if let Err(problems) = self.validate() {
+            if self.config_strict {
+                panic!("Invalid configuration: {problems}");
+            }
+            warn!("Invalid configuration: {problems}");
+            self.clamp();
         }
+        self
+    }
+}
+
+/// Numeric environment variable, recording a value that does not parse and using `default`
+fn parsed_var<T: FromStr>(name: &str, default: T, parse_errors: &mut Vec<String>) -> T {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => value.trim().parse().unwrap_or_else(|_| {
+            parse_errors.push(format!("{name} of {value:?} is not a valid number"));
+            default
+        }),
+        _ => default,
     }
 }
 
+/// Comma-separated environment variable as a list, skipping empty entries
+fn list_var(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .map(|values| {
+            values
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Global configuration instance
 pub fn get_config() -> &'static LambdaConfig {
     static CONFIG: once_cell::sync::Lazy<LambdaConfig> =
-        once_cell::sync::Lazy::new(LambdaConfig::from_env);
+        once_cell::sync::Lazy::new(|| LambdaConfig::from_env().checked());
     &CONFIG
 }
 
@@ -105,9 +522,155 @@ mod tests {
         assert_eq!(config.cache_ttl, Duration::from_secs(1800));
         assert_eq!(config.hash_cache_ttl, Duration::from_secs(3600));
         assert_eq!(config.secrets_cache_ttl, Duration::from_secs(3600));
+        assert_eq!(config.negative_cache_ttl, Duration::from_secs(60));
         assert_eq!(config.cache_max_capacity, 1000);
         assert_eq!(config.org_users_cache_max_capacity, 100);
         assert_eq!(config.secrets_cache_max_capacity, 10);
+        assert_eq!(config.org_user_quota, 0);
+        assert_eq!(config.org_user_quota_warning_percent, 90);
+        assert_eq!(config.password_policy, PasswordPolicy::default());
+        assert_eq!(config.table, TableConfig::default());
+        assert!(!config.hash_cache_keys);
+        assert_eq!(config.jwks_timeout, Duration::from_secs(5));
+        assert_eq!(config.jwt_leeway, Duration::from_secs(60));
+        assert!(!config.reject_future_iat);
+        assert!(config.security_headers);
+        assert_eq!(config.hsts_max_age, Duration::from_secs(31_536_000));
+        assert_eq!(config.service_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(config.rate_limit_max, 30);
+        assert_eq!(config.rate_limit_window, Duration::from_secs(60));
+        assert!(!config.encrypt_pii);
+        assert!(!config.auto_provision_users);
+        assert_eq!(config.auto_provision_role, Role::Reader);
+        assert!(!config.org_from_claims);
+        assert!(!config.create_tables);
+        assert!(config.immutable_fields.is_empty());
+        assert!(!config.config_strict);
+        assert!(!config.enable_preflight);
+        assert!(!config.is_production());
+        assert!(config.pagination_token_secret.is_empty());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_is_production() {
+        let config = LambdaConfig {
+            service_environment: "prod".to_string(),
+            ..LambdaConfig::default()
+        };
+        assert!(config.is_production());
+
+        let config = LambdaConfig {
+            service_environment: "dev".to_string(),
+            ..LambdaConfig::default()
+        };
+        assert!(!config.is_production());
+    }
+
+    #[test]
+    fn test_production_requires_pagination_token_secret() {
+        let config = LambdaConfig {
+            service_environment: "prod".to_string(),
+            ..LambdaConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err(),
+            "pagination_token_secret must be set in production"
+        );
+
+        let config = LambdaConfig {
+            pagination_token_secret: "secret".to_string(),
+            ..config
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    fn invalid_config() -> LambdaConfig {
+        LambdaConfig {
+            cache_ttl: Duration::from_secs(1800),
+            hash_cache_ttl: Duration::from_secs(600),
+            secrets_cache_ttl: Duration::from_secs(30 * 24 * 60 * 60),
+            cache_max_capacity: 0,
+            ..LambdaConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_every_violation() {
+        let problems = invalid_config().validate().unwrap_err();
+
+        assert_eq!(
+            problems,
+            "secrets_cache_ttl of 2592000s is outside 1s..=604800s; \
+             cache_max_capacity must be greater than 0; \
+             hash_cache_ttl of 600s is shorter than cache_ttl of 1800s"
+        );
+    }
+
+    #[test]
+    fn test_checked_clamps_invalid_values() {
+        let config = invalid_config().checked();
+
+        assert_eq!(config.hash_cache_ttl, Duration::from_secs(1800));
+        assert_eq!(config.secrets_cache_ttl, MAX_CACHE_TTL);
+        assert_eq!(config.cache_max_capacity, 1000);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid configuration: cache_max_capacity must be greater than 0")]
+    fn test_checked_panics_in_strict_mode() {
+        LambdaConfig {
+            cache_max_capacity: 0,
+            config_strict: true,
+            ..LambdaConfig::default()
+        }
+        .checked();
+    }
+
+    #[test]
+    fn test_unknown_immutable_fields_are_reported_and_dropped() {
+        let config = LambdaConfig {
+            immutable_fields: vec!["roles".to_string(), "name".to_string()],
+            ..LambdaConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err(),
+            "immutable_fields names unknown field \"name\""
+        );
+
+        let config = config.checked();
+        assert_eq!(config.immutable_fields, vec!["roles"]);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_table_config_from_env() {
+        env::set_var("PK_ATTR", "PK");
+        env::set_var("SK_ATTR", "SK");
+        env::set_var("EMAIL_INDEX", "GSI1");
+        env::set_var("ORGANIZATION_INDEX", "GSI2");
+
+        let table = TableConfig::from_env();
+        assert_eq!(table.partition_key, "PK");
+        assert_eq!(table.sort_key.as_deref(), Some("SK"));
+        assert_eq!(table.email_index, "GSI1");
+        assert_eq!(table.organization_index, "GSI2");
+
+        assert_eq!(table.key_attributes(), vec!["PK", "SK"]);
+
+        env::set_var("SK_ATTR", "");
+        let table = TableConfig::from_env();
+        assert_eq!(table.sort_key, None);
+        assert_eq!(table.key_attributes(), vec!["PK"]);
+
+        env::remove_var("PK_ATTR");
+        env::remove_var("SK_ATTR");
+        env::remove_var("EMAIL_INDEX");
+        env::remove_var("ORGANIZATION_INDEX");
+
+        let table = TableConfig::from_env();
+        assert_eq!(table, TableConfig::default());
     }
 
     #[test]
@@ -116,6 +679,7 @@ mod tests {
             Duration::from_secs(900),
             Duration::from_secs(1800),
             Duration::from_secs(2700),
+            Duration::from_secs(30),
             500,
             50,
             5,
@@ -124,6 +688,7 @@ mod tests {
         assert_eq!(config.cache_ttl, Duration::from_secs(900));
         assert_eq!(config.hash_cache_ttl, Duration::from_secs(1800));
         assert_eq!(config.secrets_cache_ttl, Duration::from_secs(2700));
+        assert_eq!(config.negative_cache_ttl, Duration::from_secs(30));
         assert_eq!(config.cache_max_capacity, 500);
         assert_eq!(config.org_users_cache_max_capacity, 50);
         assert_eq!(config.secrets_cache_max_capacity, 5);
@@ -136,9 +701,12 @@ mod tests {
             "CACHE_TTL_SECS",
             "HASH_CACHE_TTL_SECS",
             "SECRETS_CACHE_TTL_SECS",
+            "NEGATIVE_CACHE_TTL_SECS",
             "CACHE_MAX_CAPACITY",
             "ORG_USERS_CACHE_MAX_CAPACITY",
             "SECRETS_CACHE_MAX_CAPACITY",
+            "ORG_USER_QUOTA",
+            "ORG_USER_QUOTA_WARNING_PERCENT",
         ];
 
         for var in &env_vars {
@@ -150,6 +718,7 @@ mod tests {
         assert_eq!(config.cache_ttl, Duration::from_secs(1800));
         assert_eq!(config.hash_cache_ttl, Duration::from_secs(3600));
         assert_eq!(config.secrets_cache_ttl, Duration::from_secs(3600));
+        assert_eq!(config.negative_cache_ttl, Duration::from_secs(60));
         assert_eq!(config.cache_max_capacity, 1000);
         assert_eq!(config.org_users_cache_max_capacity, 100);
         assert_eq!(config.secrets_cache_max_capacity, 10);
@@ -161,26 +730,44 @@ mod tests {
         env::set_var("CACHE_TTL_SECS", "900");
         env::set_var("HASH_CACHE_TTL_SECS", "1800");
         env::set_var("SECRETS_CACHE_TTL_SECS", "2700");
+        env::set_var("NEGATIVE_CACHE_TTL_SECS", "30");
         env::set_var("CACHE_MAX_CAPACITY", "500");
         env::set_var("ORG_USERS_CACHE_MAX_CAPACITY", "50");
         env::set_var("SECRETS_CACHE_MAX_CAPACITY", "5");
+        env::set_var("ORG_USER_QUOTA", "100");
+        env::set_var("ORG_USER_QUOTA_WARNING_PERCENT", "80");
+        env::set_var("AUTO_PROVISION_USERS", "true");
+        env::set_var("AUTO_PROVISION_ROLE", "Writer");
+        env::set_var("AUTO_PROVISION_ORGS", "org-1, ,org-2");
 
         let config = LambdaConfig::from_env();
 
         assert_eq!(config.cache_ttl, Duration::from_secs(900));
         assert_eq!(config.hash_cache_ttl, Duration::from_secs(1800));
         assert_eq!(config.secrets_cache_ttl, Duration::from_secs(2700));
+        assert_eq!(config.negative_cache_ttl, Duration::from_secs(30));
         assert_eq!(config.cache_max_capacity, 500);
         assert_eq!(config.org_users_cache_max_capacity, 50);
         assert_eq!(config.secrets_cache_max_capacity, 5);
+        assert_eq!(config.org_user_quota, 100);
+        assert_eq!(config.org_user_quota_warning_percent, 80);
+        assert!(config.auto_provision_users);
+        assert_eq!(config.auto_provision_role, Role::Writer);
+        assert_eq!(config.auto_provision_orgs, vec!["org-1", "org-2"]);
 
         // Clean up environment variables
         env::remove_var("CACHE_TTL_SECS");
         env::remove_var("HASH_CACHE_TTL_SECS");
         env::remove_var("SECRETS_CACHE_TTL_SECS");
+        env::remove_var("NEGATIVE_CACHE_TTL_SECS");
         env::remove_var("CACHE_MAX_CAPACITY");
         env::remove_var("ORG_USERS_CACHE_MAX_CAPACITY");
         env::remove_var("SECRETS_CACHE_MAX_CAPACITY");
+        env::remove_var("ORG_USER_QUOTA");
+        env::remove_var("ORG_USER_QUOTA_WARNING_PERCENT");
+        env::remove_var("AUTO_PROVISION_USERS");
+        env::remove_var("AUTO_PROVISION_ROLE");
+        env::remove_var("AUTO_PROVISION_ORGS");
     }
 
     #[test]
@@ -197,6 +784,14 @@ mod tests {
         assert_eq!(config.hash_cache_ttl, Duration::from_secs(3600));
         assert_eq!(config.cache_max_capacity, 1000);
 
+        // ...but report them so validation fails instead of silently using the defaults
+        assert!(config
+            .parse_errors
+            .contains(&"CACHE_TTL_SECS of \"invalid\" is not a valid number".to_string()));
+        let problems = config.validate().unwrap_err();
+        assert!(problems.contains("HASH_CACHE_TTL_SECS of \"not_a_number\" is not a valid number"));
+        assert!(problems.contains("CACHE_MAX_CAPACITY of \"not_numeric\" is not a valid number"));
+
         // Clean up environment variables
         env::remove_var("CACHE_TTL_SECS");
         env::remove_var("HASH_CACHE_TTL_SECS");
@@ -229,6 +824,9 @@ mod tests {
         // Secrets cache should typically have longer TTL than regular cache
         assert!(config.secrets_cache_ttl >= config.cache_ttl);
 
+        // Negative cache should expire sooner than regular cache
+        assert!(config.negative_cache_ttl <= config.cache_ttl);
+
         // Organization users cache should be smaller than main cache
         assert!(config.org_users_cache_max_capacity <= config.cache_max_capacity);
 
@@ -0,0 +1,249 @@
+use crate::utils::uuid::generate_uuid;
+
+use anyhow::{anyhow, Error};
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    CreateUser,
+    UpdateUser,
+    AssignRoles,
+    DeleteUser,
+    SuspendOrganization,
+    ReactivateOrganization,
+    DisableUser,
+    EnableUser,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action_str = match self {
+            AuditAction::CreateUser => "CreateUser",
+            AuditAction::UpdateUser => "UpdateUser",
+            AuditAction::AssignRoles => "AssignRoles",
+            AuditAction::DeleteUser => "DeleteUser",
+            AuditAction::SuspendOrganization => "SuspendOrganization",
+            AuditAction::ReactivateOrganization => "ReactivateOrganization",
+            AuditAction::DisableUser => "DisableUser",
+            AuditAction::EnableUser => "EnableUser",
+        };
+        write!(f, "{action_str}")
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditOutcome {
+    Success,
+    PermissionDenied,
+}
+
+impl std::fmt::Display for AuditOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let outcome_str = match self {
+            AuditOutcome::Success => "Success",
+            AuditOutcome::PermissionDenied => "PermissionDenied",
+        };
+        write!(f, "{outcome_str}")
+    }
+}
+
+/// Parse a unit enum from its variant name, as written by `Display`
+fn parse_variant<T: DeserializeOwned>(name: &str) -> Result<T, Error> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| anyhow!("Unknown value '{}'", name))
+}
+
+fn string_attribute<'a>(
+    item: &'a HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<&'a str, Error> {
+    item.get(name)
+        .and_then(|v| v.as_s().ok())
+        .map(String::as_str)
+        .ok_or_else(|| anyhow!("Missing or invalid '{}' attribute", name))
+}
+
+/// Record of who performed which user mutation and how it ended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub actor_user_id: String,
+    pub action: AuditAction,
+    /// Affected user, if known (a denied create has no user yet)
+    pub target_user_id: Option<String>,
+    pub organization_id: String,
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub outcome: AuditOutcome,
+}
+
+impl AuditEvent {
+    pub fn new(
+        actor_user_id: String,
+        action: AuditAction,
+        target_user_id: Option<String>,
+        organization_id: String,
+        outcome: AuditOutcome,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+
+        AuditEvent {
+            id: generate_uuid(),
+            actor_user_id,
+            action,
+            target_user_id,
+            organization_id,
+            timestamp,
+            outcome,
+        }
+    }
+
+    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::from([
+            ("id".to_string(), AttributeValue::S(self.id.clone())),
+            (
+                "actor_user_id".to_string(),
+                AttributeValue::S(self.actor_user_id.clone()),
+            ),
+            (
+                "action".to_string(),
+                AttributeValue::S(self.action.to_string()),
+            ),
+            (
+                "organization_id".to_string(),
+                AttributeValue::S(self.organization_id.clone()),
+            ),
+            (
+                "timestamp".to_string(),
+                AttributeValue::N(self.timestamp.to_string()),
+            ),
+            (
+                "outcome".to_string(),
+                AttributeValue::S(self.outcome.to_string()),
+            ),
+        ]);
+
+        if let Some(target_user_id) = &self.target_user_id {
+            item.insert(
+                "target_user_id".to_string(),
+                AttributeValue::S(target_user_id.clone()),
+            );
+        }
+
+        item
+    }
+
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Result<AuditEvent, Error> {
+        let timestamp = item
+            .get("timestamp")
+            .and_then(|v| v.as_n().ok())
+            .and_then(|n| n.parse::<u64>().ok())
+            .ok_or_else(|| anyhow!("Missing or invalid 'timestamp' attribute"))?;
+
+        Ok(AuditEvent {
+            id: string_attribute(item, "id")?.to_string(),
+            actor_user_id: string_attribute(item, "actor_user_id")?.to_string(),
+            action: parse_variant(string_attribute(item, "action")?)?,
+            target_user_id: item
+                .get("target_user_id")
+                .and_then(|v| v.as_s().ok())
+                .cloned(),
+            organization_id: string_attribute(item, "organization_id")?.to_string(),
+            timestamp,
+            outcome: parse_variant(string_attribute(item, "outcome")?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_item() {
+        let event = AuditEvent::new(
+            "admin-1".to_string(),
+            AuditAction::DeleteUser,
+            Some("user-1".to_string()),
+            "org-1".to_string(),
+            AuditOutcome::Success,
+        );
+        let item = event.to_item();
+
+        assert_eq!(item["id"].as_s().unwrap(), &event.id);
+        assert_eq!(item["actor_user_id"].as_s().unwrap(), "admin-1");
+        assert_eq!(item["action"].as_s().unwrap(), "DeleteUser");
+        assert_eq!(item["target_user_id"].as_s().unwrap(), "user-1");
+        assert_eq!(item["organization_id"].as_s().unwrap(), "org-1");
+        assert_eq!(
+            item["timestamp"].as_n().unwrap(),
+            &event.timestamp.to_string()
+        );
+        assert_eq!(item["outcome"].as_s().unwrap(), "Success");
+    }
+
+    #[test]
+    fn test_to_item_without_target() {
+        let event = AuditEvent::new(
+            "writer-1".to_string(),
+            AuditAction::CreateUser,
+            None,
+            "org-1".to_string(),
+            AuditOutcome::PermissionDenied,
+        );
+        let item = event.to_item();
+
+        assert!(!item.contains_key("target_user_id"));
+        assert_eq!(item["outcome"].as_s().unwrap(), "PermissionDenied");
+    }
+
+    #[test]
+    fn test_from_item_round_trip() {
+        let event = AuditEvent::new(
+            "admin-1".to_string(),
+            AuditAction::EnableUser,
+            Some("user-1".to_string()),
+            "org-1".to_string(),
+            AuditOutcome::Success,
+        );
+        let parsed = AuditEvent::from_item(&event.to_item()).unwrap();
+
+        assert_eq!(parsed.id, event.id);
+        assert_eq!(parsed.action, AuditAction::EnableUser);
+        assert_eq!(parsed.target_user_id.as_deref(), Some("user-1"));
+        assert_eq!(parsed.timestamp, event.timestamp);
+        assert_eq!(parsed.outcome, AuditOutcome::Success);
+    }
+
+    #[test]
+    fn test_from_item_rejects_unknown_action() {
+        let mut item = AuditEvent::new(
+            "admin-1".to_string(),
+            AuditAction::DeleteUser,
+            None,
+            "org-1".to_string(),
+            AuditOutcome::Success,
+        )
+        .to_item();
+        item.insert(
+            "action".to_string(),
+            AttributeValue::S("Reboot".to_string()),
+        );
+        assert!(AuditEvent::from_item(&item).is_err());
+    }
+}
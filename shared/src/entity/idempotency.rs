@@ -0,0 +1,105 @@
+use crate::errors::LambdaError;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Response recorded for an `Idempotency-Key`, replayed when the same request is retried
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdempotentResponse {
+    /// SHA-256 of the original request body, used to detect key reuse with a different body
+    pub request_hash: String,
+    pub status_code: i64,
+    pub body: String,
+}
+
+impl IdempotentResponse {
+    pub fn new(request_body: impl AsRef<[u8]>, status_code: i64, body: String) -> Self {
+        Self {
+            request_hash: Self::hash_request(request_body),
+            status_code,
+            body,
+        }
+    }
+
+    /// Hex-encoded SHA-256 of a request body
+    pub fn hash_request(request_body: impl AsRef<[u8]>) -> String {
+        format!("{:x}", Sha256::digest(request_body.as_ref()))
+    }
+
+    /// Check whether `request_body` is the request this response was recorded for
+    pub fn matches(&self, request_body: impl AsRef<[u8]>) -> bool {
+        self.request_hash == Self::hash_request(request_body)
+    }
+}
+
+/// Outcome of one item of a batch operation, recorded or replayed under the batch's key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchItemOutcome {
+    pub item_id: String,
+    pub status_code: i64,
+    pub body: String,
+    /// True when the outcome was recorded by an earlier attempt and not applied again
+    pub replayed: bool,
+}
+
+impl BatchItemOutcome {
+    /// Outcome replayed from the response recorded for the item
+    pub fn replayed(item_id: String, recorded: IdempotentResponse) -> Self {
+        Self {
+            item_id,
+            status_code: recorded.status_code,
+            body: recorded.body,
+            replayed: true,
+        }
+    }
+
+    /// Outcome of an item rejected with `error` without being applied
+    pub fn failed(item_id: String, error: &LambdaError) -> Self {
+        Self {
+            item_id,
+            status_code: error.status_code(),
+            body: error.response_body().to_string(),
+            replayed: false,
+        }
+    }
+
+    /// Whether a retry should replay this outcome; server errors, throttling, timeouts and
+    /// conflicts with a request still in progress are retried instead
+    pub fn is_final(&self) -> bool {
+        self.status_code < 500 && !matches!(self.status_code, 408 | 409 | 429)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_same_body() {
+        let response = IdempotentResponse::new(r#"{"email":"a@example.com"}"#, 200, "{}".into());
+        assert!(response.matches(r#"{"email":"a@example.com"}"#));
+    }
+
+    #[test]
+    fn test_does_not_match_different_body() {
+        let response = IdempotentResponse::new(r#"{"email":"a@example.com"}"#, 200, "{}".into());
+        assert!(!response.matches(r#"{"email":"b@example.com"}"#));
+    }
+
+    #[test]
+    fn test_retryable_errors_are_not_final() {
+        let outcome = |status_code| BatchItemOutcome {
+            item_id: "user-1".to_string(),
+            status_code,
+            body: "{}".to_string(),
+            replayed: false,
+        };
+        assert!(outcome(200).is_final());
+        assert!(outcome(404).is_final());
+        assert!(!outcome(408).is_final());
+        assert!(!outcome(409).is_final());
+        assert!(!outcome(429).is_final());
+        assert!(!outcome(500).is_final());
+        assert!(!outcome(503).is_final());
+    }
+}
@@ -1,2 +1,6 @@
+pub mod audit_event;
+pub mod idempotency;
+pub mod organization;
 pub mod secrets;
 pub mod user;
+pub mod user_response;
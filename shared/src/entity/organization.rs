@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+}
+
+impl Organization {
+    pub fn new(id: String, name: String) -> Self {
+        Organization { id, name }
+    }
+
+    /// Canonical form of an organization name used for lookups, so "Acme", "acme" and "Acme "
+    /// are the same organization while `name` keeps the display form
+    pub fn normalize_name(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name_ignores_case_and_surrounding_whitespace() {
+        for name in ["Acme", "acme", "Acme ", "  ACME\t"] {
+            assert_eq!(Organization::normalize_name(name), "acme", "{name:?}");
+        }
+        assert_eq!(Organization::normalize_name("Acme Corp"), "acme corp");
+        assert_ne!(
+            Organization::normalize_name("Acme"),
+            Organization::normalize_name("Acme Corp")
+        );
+    }
+}
@@ -1,9 +1,19 @@
+use crate::entity::organization::Organization;
+use crate::utils::crypto::{Crypto, BLIND_INDEX_PREFIX, ENCRYPTED_PREFIX};
+use crate::validation::normalize_email;
+
 use anyhow::{anyhow, Error};
 use aws_sdk_dynamodb::types::AttributeValue;
 use bitflags::bitflags;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use std::collections::{HashMap, HashSet};
 
+/// Attributes holding personal data, encrypted at rest when `ENCRYPT_PII` is enabled
+const PII_ATTRIBUTES: &[&str] = &["email"];
+/// Lookup attribute stored as a blind index when `ENCRYPT_PII` is enabled, since randomized
+/// ciphertext could never match a query
+const PII_INDEX_ATTRIBUTE: &str = "email_lower";
+
 bitflags! {
     #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
     pub struct Permissions: u32 {
@@ -12,41 +22,48 @@ bitflags! {
         const CREATE  = 0b0100;
         const DELETE  = 0b1000;
         const UPDATE = 0b1_0000;
+        const MANAGE_ORG = 0b10_0000;
+        const SUSPEND_ORG = 0b100_0000;
+    }
+}
+
+impl Permissions {
+    /// Names of the granted permissions, in declaration order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.iter_names().map(|(name, _)| name).collect()
     }
 }
 
 impl std::fmt::Display for Permissions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut perms = Vec::new();
-        if self.contains(Permissions::READ) {
-            perms.push("READ");
-        }
-        if self.contains(Permissions::WRITE) {
-            perms.push("WRITE");
-        }
-        if self.contains(Permissions::CREATE) {
-            perms.push("CREATE");
-        }
-        if self.contains(Permissions::DELETE) {
-            perms.push("DELETE");
-        }
-        if self.contains(Permissions::UPDATE) {
-            perms.push("UPDATE");
-        }
-        write!(f, "{}", perms.join(", "))
+        write!(f, "{}", self.names().join(", "))
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Ordered by declaration, which is the canonical order for serialized roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Role {
+    /// Platform operator, able to manage every organization
+    SuperAdmin,
     Admin,
     Reader,
     Writer,
 }
 
+/// Serialize a role set as a list in canonical order instead of hash order
+pub fn serialize_sorted_roles<S: Serializer>(
+    roles: &HashSet<Role>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut sorted: Vec<&Role> = roles.iter().collect();
+    sorted.sort();
+    serializer.collect_seq(sorted)
+}
+
 impl Role {
     pub fn permissions(&self) -> Permissions {
         match self {
+            Role::SuperAdmin => Permissions::all(),
             Role::Admin => {
                 Permissions::READ
                     | Permissions::WRITE
@@ -58,11 +75,29 @@ impl Role {
             Role::Writer => Permissions::READ | Permissions::WRITE | Permissions::CREATE,
         }
     }
+
+    /// Name of the Cognito group that mirrors this role in the `cognito:groups` claim
+    pub fn to_cognito_group(&self) -> &'static str {
+        match self {
+            Role::SuperAdmin => "SuperAdmin",
+            Role::Admin => "Admin",
+            Role::Reader => "Reader",
+            Role::Writer => "Writer",
+        }
+    }
+
+    /// Role mirrored by a Cognito group, or `None` for groups unrelated to roles
+    pub fn from_cognito_group(group: &str) -> Option<Role> {
+        [Role::SuperAdmin, Role::Admin, Role::Reader, Role::Writer]
+            .into_iter()
+            .find(|role| role.to_cognito_group() == group)
+    }
 }
 
 impl std::fmt::Display for Role {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let role_str = match self {
+            Role::SuperAdmin => "SuperAdmin",
             Role::Admin => "Admin",
             Role::Reader => "Reader",
             Role::Writer => "Writer",
@@ -71,6 +106,25 @@ impl std::fmt::Display for Role {
     }
 }
 
+/// Whether a user may sign in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UserStatus {
+    #[default]
+    Active,
+    /// Blocked from signing in, e.g. while the organization is suspended
+    Suspended,
+}
+
+impl std::fmt::Display for UserStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status_str = match self {
+            UserStatus::Active => "Active",
+            UserStatus::Suspended => "Suspended",
+        };
+        write!(f, "{status_str}")
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: String,
@@ -78,7 +132,26 @@ pub struct User {
     pub email: String,
     pub organization_id: String,
     pub organization_name: String,
+    #[serde(serialize_with = "serialize_sorted_roles")]
     pub roles: HashSet<Role>,
+    /// Cognito username when it differs from the email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cognito_username: Option<String>,
+    /// Contact phone number in E.164 format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    /// Preferred locale for emails and UI, e.g. `ja-JP`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Soft-delete time in milliseconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<u64>,
+    /// Whether the email address has been verified in Cognito
+    #[serde(default)]
+    pub email_verified: bool,
+    /// Suspended users cannot sign in
+    #[serde(default)]
+    pub status: UserStatus,
 }
 
 impl User {
@@ -97,9 +170,59 @@ impl User {
             organization_id,
             organization_name,
             roles,
+            cognito_username: None,
+            phone: None,
+            locale: None,
+            deleted_at: None,
+            email_verified: false,
+            status: UserStatus::Active,
         }
     }
 
+    pub fn with_cognito_username(mut self, cognito_username: Option<String>) -> Self {
+        self.cognito_username = cognito_username;
+        self
+    }
+
+    pub fn with_phone(mut self, phone: Option<String>) -> Self {
+        self.phone = phone;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: Option<String>) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn with_email_verified(mut self, email_verified: bool) -> Self {
+        self.email_verified = email_verified;
+        self
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Lowercased email, stored as `email_lower` for lookups while `email` keeps the original
+    pub fn email_lower(&self) -> String {
+        normalize_email(&self.email)
+    }
+
+    /// Normalized organization name, stored as `organization_name_normalized` for lookups
+    pub fn organization_name_normalized(&self) -> String {
+        Organization::normalize_name(&self.organization_name)
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.status == UserStatus::Suspended
+    }
+
+    /// Username used for Cognito operations. Users are created with it set; rows written before
+    /// that have none and were created in Cognito with the email as entered.
+    pub fn cognito_username(&self) -> &str {
+        self.cognito_username.as_deref().unwrap_or(&self.email)
+    }
+
     pub fn permissions(&self) -> Permissions {
         self.roles
             .iter()
@@ -130,23 +253,77 @@ impl User {
         self.roles.contains(&role)
     }
 
+    /// Whether this user may read `target`: same organization, or a SuperAdmin
+    pub fn can_access(&self, target: &User) -> bool {
+        self.has_role(Role::SuperAdmin) || self.organization_id == target.organization_id
+    }
+
+    /// Roles in canonical order
     pub fn roles(&self) -> Vec<Role> {
-        self.roles.iter().cloned().collect()
+        let mut roles: Vec<Role> = self.roles.iter().copied().collect();
+        roles.sort();
+        roles
     }
 
+    /// Colon-joined roles in canonical order, so the same set always serializes identically
     pub fn join_roles(&self) -> String {
-        self.roles
+        self.roles()
             .iter()
             .map(|role| role.to_string())
             .collect::<Vec<String>>()
             .join(":")
     }
 
+    /// Roles as a DynamoDB string set; DynamoDB rejects empty sets, so users need at least one role
+    pub fn roles_to_attribute_value(&self) -> AttributeValue {
+        AttributeValue::Ss(self.roles().iter().map(Role::to_string).collect())
+    }
+
     pub fn get_roles(&self) -> HashSet<Role> {
         // Get 'roles' attribute and convert to HashSet<Role>
         self.roles.clone()
     }
 
+    /// Replace the PII attributes of an item with their ciphertext, and the lookup attribute
+    /// with its blind index
+    pub async fn encrypt_pii(
+        item: &mut HashMap<String, AttributeValue>,
+        crypto: &dyn Crypto,
+    ) -> Result<(), Error> {
+        for name in PII_ATTRIBUTES {
+            if let Some(AttributeValue::S(value)) = item.get_mut(*name) {
+                *value = format!("{ENCRYPTED_PREFIX}{}", crypto.encrypt(value).await?);
+            }
+        }
+        if let Some(AttributeValue::S(value)) = item.get_mut(PII_INDEX_ATTRIBUTE) {
+            *value = Self::email_index(value, crypto).await?;
+        }
+        Ok(())
+    }
+
+    /// Blind index stored in `email_lower` for an already normalized email
+    pub async fn email_index(email_lower: &str, crypto: &dyn Crypto) -> Result<String, Error> {
+        Ok(format!(
+            "{BLIND_INDEX_PREFIX}{}",
+            crypto.blind_index(email_lower).await?
+        ))
+    }
+
+    /// Decrypt the PII attributes of an item, keeping values stored before encryption was enabled
+    pub async fn decrypt_pii(
+        item: &mut HashMap<String, AttributeValue>,
+        crypto: &dyn Crypto,
+    ) -> Result<(), Error> {
+        for name in PII_ATTRIBUTES {
+            if let Some(AttributeValue::S(value)) = item.get_mut(*name) {
+                if let Some(ciphertext) = value.strip_prefix(ENCRYPTED_PREFIX) {
+                    *value = crypto.decrypt(ciphertext).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn from_item(item: &HashMap<String, AttributeValue>) -> Result<User, Error> {
         let id = item
             .get("id")
@@ -154,8 +331,10 @@ impl User {
             .ok_or_else(|| anyhow!("Missing or invalid 'id' attribute".to_string()))?
             .to_string();
 
+        // The repository writes `user_name`; `name` is the legacy attribute
         let name = item
-            .get("name")
+            .get("user_name")
+            .or_else(|| item.get("name"))
             .and_then(|v| v.as_s().ok())
             .ok_or_else(|| anyhow!("Missing or invalid 'name' attribute".to_string()))?
             .to_string();
@@ -181,14 +360,17 @@ impl User {
             .to_string();
 
         // Get 'roles' attribute and convert to HashSet<Role>
-        let roles_attr = item
-            .get("roles")
-            .and_then(|v| v.as_s().ok())
-            .ok_or_else(|| anyhow!("Missing or invalid 'roles' attribute".to_string()))?;
+        let role_names: Vec<&str> = match item.get("roles") {
+            Some(AttributeValue::Ss(names)) => names.iter().map(String::as_str).collect(),
+            // Legacy colon-joined format
+            Some(AttributeValue::S(joined)) => joined.split(':').collect(),
+            _ => return Err(anyhow!("Missing or invalid 'roles' attribute".to_string())),
+        };
 
         let mut roles = HashSet::new();
-        for role_str in roles_attr.split(':') {
+        for role_str in role_names {
             let role = match role_str.trim() {
+                "SuperAdmin" => Role::SuperAdmin,
                 "Admin" => Role::Admin,
                 "Reader" => Role::Reader,
                 "Writer" => Role::Writer,
@@ -199,6 +381,39 @@ impl User {
             roles.insert(role);
         }
 
+        let cognito_username = item
+            .get("cognito_username")
+            .and_then(|v| v.as_s().ok())
+            .cloned();
+
+        let phone = item.get("phone").and_then(|v| v.as_s().ok()).cloned();
+        let locale = item.get("locale").and_then(|v| v.as_s().ok()).cloned();
+
+        let deleted_at = item
+            .get("deleted_at")
+            .and_then(|v| v.as_n().ok())
+            .map(|n| n.parse::<u64>())
+            .transpose()
+            .map_err(|e| anyhow!("Invalid 'deleted_at' attribute: {}", e))?;
+
+        // Rows written before verification was tracked count as unverified
+        let email_verified = item
+            .get("email_verified")
+            .and_then(|v| v.as_bool().ok())
+            .copied()
+            .unwrap_or(false);
+
+        // Rows written before statuses were tracked are active
+        let status = match item
+            .get("status")
+            .and_then(|v| v.as_s().ok())
+            .map(String::as_str)
+        {
+            None | Some("Active") => UserStatus::Active,
+            Some("Suspended") => UserStatus::Suspended,
+            Some(other) => return Err(anyhow!("Unknown status: {}", other)),
+        };
+
         Ok(User {
             id,
             name,
@@ -206,6 +421,12 @@ impl User {
             organization_id,
             organization_name,
             roles,
+            cognito_username,
+            phone,
+            locale,
+            deleted_at,
+            email_verified,
+            status,
         })
     }
 }
@@ -214,6 +435,25 @@ impl User {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_permission_names_follow_declaration_order() {
+        let permissions = Permissions::UPDATE | Permissions::READ;
+        assert_eq!(permissions.names(), ["READ", "UPDATE"]);
+        assert_eq!(permissions.to_string(), "READ, UPDATE");
+        assert!(Permissions::empty().names().is_empty());
+    }
+
+    #[test]
+    fn test_cognito_group_mapping_round_trips() {
+        for role in [Role::SuperAdmin, Role::Admin, Role::Reader, Role::Writer] {
+            assert_eq!(
+                Role::from_cognito_group(role.to_cognito_group()),
+                Some(role)
+            );
+        }
+        assert_eq!(Role::from_cognito_group("beta-testers"), None);
+    }
+
     #[tokio::test]
     async fn test_user_permissions() {
         let mut roles = HashSet::new();
@@ -236,6 +476,38 @@ mod tests {
         assert!(!permissions.contains(Permissions::DELETE));
     }
 
+    fn user_in_org(id: &str, organization_id: &str, role: Role) -> User {
+        User::new(
+            id.to_string(),
+            "Dana".to_string(),
+            "dana@example.com".to_string(),
+            organization_id.to_string(),
+            "ExampleOrg".to_string(),
+            HashSet::from([role]),
+        )
+    }
+
+    #[test]
+    fn test_can_access_same_organization() {
+        let caller = user_in_org("1", "org_a", Role::Reader);
+        let target = user_in_org("2", "org_a", Role::Writer);
+        assert!(caller.can_access(&target));
+    }
+
+    #[test]
+    fn test_can_access_rejects_other_organization() {
+        let caller = user_in_org("1", "org_a", Role::Writer);
+        let target = user_in_org("2", "org_b", Role::Reader);
+        assert!(!caller.can_access(&target));
+    }
+
+    #[test]
+    fn test_super_admin_can_access_any_organization() {
+        let caller = user_in_org("1", "org_a", Role::SuperAdmin);
+        let target = user_in_org("2", "org_b", Role::Reader);
+        assert!(caller.can_access(&target));
+    }
+
     #[tokio::test]
     async fn test_add_remove_role() {
         let mut roles = HashSet::new();
@@ -290,6 +562,76 @@ mod tests {
         assert!(roles.contains(&Role::Writer));
     }
 
+    #[tokio::test]
+    async fn test_cognito_username_defaults_to_email() {
+        let user = User::new(
+            "5".to_string(),
+            "Dave".to_string(),
+            "dave@example.com".to_string(),
+            "org_123".to_string(),
+            "ExampleOrg".to_string(),
+            HashSet::new(),
+        );
+        assert_eq!(user.cognito_username(), "dave@example.com");
+
+        let user = user.with_cognito_username(Some("dave".to_string()));
+        assert_eq!(user.cognito_username(), "dave");
+    }
+
+    #[tokio::test]
+    async fn test_from_item_cognito_username() {
+        let mut item = HashMap::from([
+            ("id".to_string(), AttributeValue::S("6".to_string())),
+            ("name".to_string(), AttributeValue::S("Eve".to_string())),
+            (
+                "email".to_string(),
+                AttributeValue::S("eve@example.com".to_string()),
+            ),
+            (
+                "organization_id".to_string(),
+                AttributeValue::S("org_123".to_string()),
+            ),
+            (
+                "organization_name".to_string(),
+                AttributeValue::S("ExampleOrg".to_string()),
+            ),
+            ("roles".to_string(), AttributeValue::S("Reader".to_string())),
+        ]);
+
+        let user = User::from_item(&item).unwrap();
+        assert_eq!(user.cognito_username, None);
+        assert_eq!(user.cognito_username(), "eve@example.com");
+
+        item.insert(
+            "cognito_username".to_string(),
+            AttributeValue::S("eve".to_string()),
+        );
+        let user = User::from_item(&item).unwrap();
+        assert_eq!(user.cognito_username(), "eve");
+        assert_eq!(user.phone, None);
+
+        item.insert(
+            "phone".to_string(),
+            AttributeValue::S("+819012345678".to_string()),
+        );
+        let user = User::from_item(&item).unwrap();
+        assert_eq!(user.phone.as_deref(), Some("+819012345678"));
+        assert_eq!(user.locale, None);
+
+        item.insert("locale".to_string(), AttributeValue::S("ja-JP".to_string()));
+        let user = User::from_item(&item).unwrap();
+        assert_eq!(user.locale.as_deref(), Some("ja-JP"));
+        assert!(!user.is_deleted());
+
+        item.insert(
+            "deleted_at".to_string(),
+            AttributeValue::N("1700000000000".to_string()),
+        );
+        let user = User::from_item(&item).unwrap();
+        assert_eq!(user.deleted_at, Some(1_700_000_000_000));
+        assert!(user.is_deleted());
+    }
+
     #[tokio::test]
     async fn test_role_permissions() {
         assert_eq!(
@@ -300,10 +642,224 @@ mod tests {
                 | Permissions::DELETE
                 | Permissions::UPDATE
         );
+        assert_eq!(Role::SuperAdmin.permissions(), Permissions::all());
+        assert!(Role::SuperAdmin
+            .permissions()
+            .contains(Permissions::MANAGE_ORG));
+        assert!(!Role::Admin.permissions().contains(Permissions::MANAGE_ORG));
         assert_eq!(Role::Reader.permissions(), Permissions::READ);
         assert_eq!(
             Role::Writer.permissions(),
             Permissions::READ | Permissions::WRITE | Permissions::CREATE
         );
     }
+
+    /// Reversible stand-in for KMS
+    struct MockCrypto;
+
+    #[async_trait::async_trait]
+    impl Crypto for MockCrypto {
+        async fn encrypt(&self, plaintext: &str) -> Result<String, Error> {
+            Ok(plaintext.chars().rev().collect())
+        }
+
+        async fn decrypt(&self, ciphertext: &str) -> Result<String, Error> {
+            Ok(ciphertext.chars().rev().collect())
+        }
+
+        async fn blind_index(&self, plaintext: &str) -> Result<String, Error> {
+            Ok(plaintext.to_uppercase())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pii_is_stored_encrypted_and_round_trips() {
+        let mut item = HashMap::from([
+            (
+                "email".to_string(),
+                AttributeValue::S("eve@example.com".to_string()),
+            ),
+            ("id".to_string(), AttributeValue::S("6".to_string())),
+        ]);
+
+        User::encrypt_pii(&mut item, &MockCrypto).await.unwrap();
+        let stored = item["email"].as_s().unwrap();
+        assert_eq!(stored, "enc:moc.elpmaxe@eve");
+        assert_eq!(item["id"].as_s().unwrap(), "6");
+
+        User::decrypt_pii(&mut item, &MockCrypto).await.unwrap();
+        assert_eq!(item["email"].as_s().unwrap(), "eve@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_email_lower_is_stored_as_deterministic_blind_index() {
+        let item = || {
+            HashMap::from([(
+                "email_lower".to_string(),
+                AttributeValue::S("eve@example.com".to_string()),
+            )])
+        };
+        let mut first = item();
+        let mut second = item();
+        User::encrypt_pii(&mut first, &MockCrypto).await.unwrap();
+        User::encrypt_pii(&mut second, &MockCrypto).await.unwrap();
+
+        assert_eq!(first["email_lower"].as_s().unwrap(), "idx:EVE@EXAMPLE.COM");
+        assert_eq!(first, second);
+        assert_eq!(
+            User::email_index("eve@example.com", &MockCrypto)
+                .await
+                .unwrap(),
+            *first["email_lower"].as_s().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_pii_keeps_legacy_plaintext() {
+        let mut item = HashMap::from([(
+            "email".to_string(),
+            AttributeValue::S("eve@example.com".to_string()),
+        )]);
+
+        User::decrypt_pii(&mut item, &MockCrypto).await.unwrap();
+        assert_eq!(item["email"].as_s().unwrap(), "eve@example.com");
+    }
+
+    fn create_roles_item(roles: AttributeValue) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S("7".to_string())),
+            ("name".to_string(), AttributeValue::S("Frank".to_string())),
+            (
+                "email".to_string(),
+                AttributeValue::S("frank@example.com".to_string()),
+            ),
+            (
+                "organization_id".to_string(),
+                AttributeValue::S("org_123".to_string()),
+            ),
+            (
+                "organization_name".to_string(),
+                AttributeValue::S("ExampleOrg".to_string()),
+            ),
+            ("roles".to_string(), roles),
+        ])
+    }
+
+    #[test]
+    fn test_roles_to_attribute_value_is_string_set() {
+        let mut user = user_in_org("1", "org_a", Role::Writer);
+        user.add_role(Role::Admin);
+
+        assert_eq!(
+            user.roles_to_attribute_value(),
+            AttributeValue::Ss(vec!["Admin".to_string(), "Writer".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_from_item_reads_string_set_and_legacy_roles() {
+        let expected = HashSet::from([Role::Admin, Role::Reader]);
+
+        let item = create_roles_item(AttributeValue::Ss(vec![
+            "Admin".to_string(),
+            "Reader".to_string(),
+        ]));
+        assert_eq!(User::from_item(&item).unwrap().roles, expected);
+
+        let item = create_roles_item(AttributeValue::S("Admin:Reader".to_string()));
+        assert_eq!(User::from_item(&item).unwrap().roles, expected);
+
+        let item = create_roles_item(AttributeValue::N("1".to_string()));
+        assert!(User::from_item(&item).is_err());
+    }
+
+    #[test]
+    fn test_from_item_reads_user_name_before_legacy_name() {
+        let mut item = create_roles_item(AttributeValue::Ss(vec!["Reader".to_string()]));
+        assert_eq!(User::from_item(&item).unwrap().name, "Frank");
+
+        // Rows written by the repository carry `user_name`, which wins over a stale `name`
+        item.insert(
+            "user_name".to_string(),
+            AttributeValue::S("Franklin".to_string()),
+        );
+        assert_eq!(User::from_item(&item).unwrap().name, "Franklin");
+
+        item.remove("name");
+        assert_eq!(User::from_item(&item).unwrap().name, "Franklin");
+
+        item.remove("user_name");
+        assert!(User::from_item(&item).is_err());
+    }
+
+    #[test]
+    fn test_from_item_reads_status() {
+        let mut item = create_roles_item(AttributeValue::Ss(vec!["Reader".to_string()]));
+        assert_eq!(User::from_item(&item).unwrap().status, UserStatus::Active);
+
+        item.insert(
+            "status".to_string(),
+            AttributeValue::S("Suspended".to_string()),
+        );
+        assert!(User::from_item(&item).unwrap().is_suspended());
+
+        item.insert(
+            "status".to_string(),
+            AttributeValue::S("Banned".to_string()),
+        );
+        assert!(User::from_item(&item).is_err());
+    }
+
+    #[test]
+    fn test_from_item_reads_email_verified() {
+        let mut item = create_roles_item(AttributeValue::Ss(vec!["Reader".to_string()]));
+        assert!(!User::from_item(&item).unwrap().email_verified);
+
+        item.insert("email_verified".to_string(), AttributeValue::Bool(true));
+        assert!(User::from_item(&item).unwrap().email_verified);
+    }
+
+    #[test]
+    fn test_join_roles_is_deterministic() {
+        let mut user = user_in_org("user-1", "org-1", Role::Writer);
+        user.add_role(Role::Admin);
+        user.add_role(Role::Reader);
+
+        let mut reordered = user_in_org("user-1", "org-1", Role::Reader);
+        reordered.add_role(Role::Writer);
+        reordered.add_role(Role::Admin);
+
+        assert_eq!(user.join_roles(), "Admin:Reader:Writer");
+        assert_eq!(reordered.join_roles(), user.join_roles());
+    }
+
+    #[test]
+    fn test_roles_sort_in_canonical_order() {
+        let mut roles = vec![Role::Writer, Role::SuperAdmin, Role::Reader, Role::Admin];
+        roles.sort();
+        assert_eq!(
+            roles,
+            vec![Role::SuperAdmin, Role::Admin, Role::Reader, Role::Writer]
+        );
+
+        let mut user = user_in_org("user-1", "org-1", Role::Writer);
+        user.add_role(Role::SuperAdmin);
+        user.add_role(Role::Reader);
+        assert_eq!(
+            user.roles(),
+            vec![Role::SuperAdmin, Role::Reader, Role::Writer]
+        );
+        assert_eq!(
+            serde_json::to_value(&user).unwrap()["roles"],
+            serde_json::json!(["SuperAdmin", "Reader", "Writer"])
+        );
+        assert_eq!(
+            user.roles_to_attribute_value(),
+            AttributeValue::Ss(vec![
+                "SuperAdmin".to_string(),
+                "Reader".to_string(),
+                "Writer".to_string()
+            ])
+        );
+    }
 }
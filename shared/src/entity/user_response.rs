@@ -0,0 +1,159 @@
+use crate::entity::user::{serialize_sorted_roles, Permissions, Role, User};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A role and the permissions it grants
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RolePermissions {
+    pub role: Role,
+    pub permissions: Vec<String>,
+}
+
+/// Permissions granted by each of a user's roles, returned with `?expandPermissions=true`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExpandedPermissions {
+    pub roles: Vec<RolePermissions>,
+    /// Union of the permissions of every role
+    pub effective: Vec<String>,
+}
+
+impl ExpandedPermissions {
+    pub fn from_roles(roles: &HashSet<Role>) -> Self {
+        let mut sorted: Vec<Role> = roles.iter().copied().collect();
+        sorted.sort();
+
+        let to_strings = |permissions: Permissions| -> Vec<String> {
+            permissions.names().into_iter().map(String::from).collect()
+        };
+        let effective = sorted
+            .iter()
+            .fold(Permissions::empty(), |acc, role| acc | role.permissions());
+
+        ExpandedPermissions {
+            roles: sorted
+                .into_iter()
+                .map(|role| RolePermissions {
+                    role,
+                    permissions: to_strings(role.permissions()),
+                })
+                .collect(),
+            effective: to_strings(effective),
+        }
+    }
+}
+
+/// Public view of a user returned by `GET /users/{id}` and `GET /me`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetUserResponse {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub organization_id: String,
+    pub organization_name: String,
+    #[serde(serialize_with = "serialize_sorted_roles")]
+    pub roles: HashSet<Role>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<ExpandedPermissions>,
+}
+
+impl GetUserResponse {
+    /// Attach the permissions granted by the user's roles
+    pub fn with_expanded_permissions(mut self) -> Self {
+        self.permissions = Some(ExpandedPermissions::from_roles(&self.roles));
+        self
+    }
+}
+
+impl From<User> for GetUserResponse {
+    fn from(user: User) -> Self {
+        GetUserResponse {
+            id: user.id,
+            name: user.name,
+            email: user.email,
+            organization_id: user.organization_id,
+            organization_name: user.organization_name,
+            roles: user.roles,
+            phone: user.phone,
+            permissions: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_user_response_exposes_public_fields_only() {
+        let user = User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Example".to_string(),
+            HashSet::from([Role::Reader]),
+        )
+        .with_cognito_username(Some("alice".to_string()))
+        .with_phone(Some("+15551234567".to_string()));
+
+        let value = serde_json::to_value(GetUserResponse::from(user)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "id": "user-1",
+                "name": "Alice",
+                "email": "alice@example.com",
+                "organization_id": "org-1",
+                "organization_name": "Example",
+                "roles": ["Reader"],
+                "phone": "+15551234567"
+            })
+        );
+        assert!(value.get("cognito_username").is_none());
+    }
+
+    #[test]
+    fn test_expanded_permissions_match_role_definitions() {
+        let roles = HashSet::from([Role::Writer, Role::Reader]);
+        let expanded = ExpandedPermissions::from_roles(&roles);
+
+        let listed: Vec<Role> = expanded.roles.iter().map(|entry| entry.role).collect();
+        assert_eq!(listed, [Role::Reader, Role::Writer]);
+        for entry in &expanded.roles {
+            assert_eq!(entry.permissions, entry.role.permissions().names());
+        }
+        assert_eq!(
+            expanded.effective,
+            (Role::Reader.permissions() | Role::Writer.permissions()).names()
+        );
+        assert_eq!(expanded.effective, ["READ", "WRITE", "CREATE"]);
+    }
+
+    #[test]
+    fn test_expanded_permissions_serialization() {
+        let user = User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "Example".to_string(),
+            HashSet::from([Role::Admin]),
+        );
+
+        let value =
+            serde_json::to_value(GetUserResponse::from(user).with_expanded_permissions()).unwrap();
+        assert_eq!(
+            value["permissions"],
+            serde_json::json!({
+                "roles": [{
+                    "role": "Admin",
+                    "permissions": ["READ", "WRITE", "CREATE", "DELETE", "UPDATE"]
+                }],
+                "effective": ["READ", "WRITE", "CREATE", "DELETE", "UPDATE"]
+            })
+        );
+    }
+}
@@ -1,5 +1,45 @@
+use crate::aws::cognito::error::CognitoError;
+use crate::aws::dynamodb::error::DynamoDbError;
+use crate::aws::secret_manager::error::SecretManagerError;
+use crate::config::get_config;
+use crate::validation::FieldError;
+
 use thiserror::Error;
 
+/// `Retry-After` value returned with throttled responses
+const THROTTLED_RETRY_AFTER_SECS: u64 = 1;
+/// `Retry-After` value for a temporarily unavailable dependency
+const SERVICE_UNAVAILABLE_RETRY_AFTER_SECS: u64 = 5;
+
+/// `error` of a 500 response in production, where the internal detail is withheld
+const INTERNAL_ERROR_SUMMARY: &str = "Internal server error";
+
+/// Language of `user_message`, used when the client asks for none we support
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Whether a language tag (e.g. `ja`, `ja-JP`) is Japanese
+fn is_japanese(lang: &str) -> bool {
+    lang.split(['-', '_'])
+        .next()
+        .is_some_and(|primary| primary.trim().eq_ignore_ascii_case("ja"))
+}
+
+/// Render an error with its `source()` chain, e.g. `UpdateItemError: service error: ...`,
+/// skipping sources whose message the outer error already includes
+pub fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        let message = e.to_string();
+        if !chain.contains(&message) {
+            chain.push_str(": ");
+            chain.push_str(&message);
+        }
+        source = e.source();
+    }
+    chain
+}
+
 /// Unified error type for all Lambda functions
 #[derive(Error, Debug)]
 pub enum LambdaError {
@@ -10,12 +50,16 @@ pub enum LambdaError {
     InvalidUsername,
     #[error("Invalid password format")]
     InvalidPassword,
+    #[error("Invalid phone number format")]
+    InvalidPhone,
     #[error("Invalid organization name")]
     InvalidOrganizationName,
     #[error("Invalid token format")]
     InvalidToken,
     #[error("Invalid refresh token")]
     InvalidRefreshToken,
+    #[error("Validation failed")]
+    ValidationFailed(Vec<FieldError>),
 
     // Authentication errors
     #[error("Authentication failed")]
@@ -28,10 +72,16 @@ pub enum LambdaError {
     UserNotFound,
     #[error("User already exists")]
     UserAlreadyExists,
+    #[error("Field '{0}' cannot be changed")]
+    ImmutableField(String),
 
     // Permission errors
     #[error("Insufficient permissions")]
     InsufficientPermissions,
+    #[error("User is suspended")]
+    UserSuspended,
+    #[error("User is not confirmed")]
+    UserNotConfirmed,
 
     // Resource errors
     #[error("Organization not found")]
@@ -42,10 +92,20 @@ pub enum LambdaError {
     MissingRoles,
 
     // Request errors
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
     #[error("Missing request body")]
     MissingBody,
+    #[error("Request body is not valid base64")]
+    InvalidBodyEncoding,
     #[error("Missing token")]
     MissingToken,
+    #[error("Missing authorizer context")]
+    MissingAuthContext,
+    #[error("Idempotency key reused with a different request")]
+    IdempotencyKeyMismatch,
+    #[error("A request with this idempotency key is still in progress")]
+    IdempotencyKeyInProgress,
 
     // Operation errors
     #[error("Failed to create user: {0}")]
@@ -59,12 +119,23 @@ pub enum LambdaError {
     #[error("Failed to refresh token: {0}")]
     TokenRefreshFailed(String),
 
+    // Throttling errors
+    #[error("Too many requests")]
+    Throttled,
+
     // Internal errors
     #[error("Internal server error: {0}")]
     InternalError(String),
+    #[error("Service unavailable")]
+    ServiceUnavailable,
 }
 
 impl LambdaError {
+    /// Internal error labelled with the step that failed, e.g. `set temporary password: ...`
+    pub fn internal(operation: &str, detail: impl std::fmt::Display) -> LambdaError {
+        LambdaError::InternalError(format!("{operation}: {detail}"))
+    }
+
     /// Convert to HTTP status code
     pub fn status_code(&self) -> i64 {
         match self {
@@ -72,27 +143,42 @@ impl LambdaError {
             LambdaError::InvalidEmail
             | LambdaError::InvalidUsername
             | LambdaError::InvalidPassword
+            | LambdaError::InvalidPhone
             | LambdaError::InvalidOrganizationName
             | LambdaError::InvalidToken
             | LambdaError::InvalidRefreshToken
+            | LambdaError::ValidationFailed(_)
+            | LambdaError::InvalidRequest(_)
             | LambdaError::MissingBody
+            | LambdaError::InvalidBodyEncoding
             | LambdaError::MissingToken
             | LambdaError::MissingOrganizationId
             | LambdaError::MissingRoles => 400,
 
             // 401 Unauthorized
             LambdaError::AuthenticationFailed
+            | LambdaError::MissingAuthContext
             | LambdaError::TokenExpired
             | LambdaError::InvalidSignature => 401,
 
             // 403 Forbidden
-            LambdaError::InsufficientPermissions => 403,
+            LambdaError::InsufficientPermissions
+            | LambdaError::UserSuspended
+            | LambdaError::UserNotConfirmed => 403,
 
             // 404 Not Found
             LambdaError::UserNotFound | LambdaError::OrganizationNotFound => 404,
 
             // 409 Conflict
-            LambdaError::UserAlreadyExists => 409,
+            LambdaError::UserAlreadyExists
+            | LambdaError::ImmutableField(_)
+            | LambdaError::IdempotencyKeyInProgress => 409,
+
+            // 422 Unprocessable Entity
+            LambdaError::IdempotencyKeyMismatch => 422,
+
+            // 429 Too Many Requests
+            LambdaError::Throttled => 429,
 
             // 500 Internal Server Error
             LambdaError::UserCreationFailed(_)
@@ -101,6 +187,9 @@ impl LambdaError {
             | LambdaError::UserRetrievalFailed(_)
             | LambdaError::TokenRefreshFailed(_)
             | LambdaError::InternalError(_) => 500,
+
+            // 503 Service Unavailable
+            LambdaError::ServiceUnavailable => 503,
         }
     }
 
@@ -112,29 +201,215 @@ impl LambdaError {
                 "Username must be 3-30 characters long and contain only letters, numbers, underscores, or hyphens",
             LambdaError::InvalidPassword =>
                 "Password must be at least 8 characters long and contain uppercase, lowercase, and numbers",
+            LambdaError::InvalidPhone =>
+                "Phone number must be in E.164 format (e.g. +819012345678)",
             LambdaError::InvalidOrganizationName =>
                 "Organization name must be between 2 and 100 characters",
             LambdaError::InvalidToken => "Invalid token provided",
             LambdaError::InvalidRefreshToken => "Invalid refresh token",
+            LambdaError::ValidationFailed(_) => "One or more fields are invalid",
             LambdaError::AuthenticationFailed => "Invalid credentials",
             LambdaError::TokenExpired => "Token has expired",
             LambdaError::InvalidSignature => "Token signature verification failed",
             LambdaError::UserNotFound => "User not found",
             LambdaError::UserAlreadyExists => "A user with this email already exists",
+            LambdaError::ImmutableField(_) => "This field cannot be changed after creation",
             LambdaError::InsufficientPermissions =>
                 "You don't have permission to perform this action",
+            LambdaError::UserSuspended =>
+                "This account is suspended. Please contact your administrator",
+            LambdaError::UserNotConfirmed =>
+                "Please verify your email address to finish signing up before logging in",
             LambdaError::OrganizationNotFound => "Organization not found",
             LambdaError::MissingOrganizationId => "Organization ID is required",
             LambdaError::MissingRoles => "At least one role must be specified",
+            LambdaError::InvalidRequest(_) => "The request is malformed",
             LambdaError::MissingBody => "Request body is required",
+            LambdaError::InvalidBodyEncoding => "Request body is not valid base64",
             LambdaError::MissingToken => "Token is required",
+            LambdaError::MissingAuthContext => "The request was not authorized",
+            LambdaError::IdempotencyKeyMismatch =>
+                "This Idempotency-Key was already used with a different request",
+            LambdaError::IdempotencyKeyInProgress =>
+                "A request with this Idempotency-Key is still being processed. Please retry shortly",
             LambdaError::UserCreationFailed(_) => "Failed to create user. Please try again later",
             LambdaError::UserDeletionFailed(_) => "Failed to delete user. Please try again later",
             LambdaError::UserUpdateFailed(_) => "Failed to update user. Please try again later",
             LambdaError::UserRetrievalFailed(_) =>
                 "Failed to retrieve user information. Please try again later",
             LambdaError::TokenRefreshFailed(_) => "Failed to refresh token. Please try again later",
+            LambdaError::Throttled => "Too many requests. Please retry after a short wait",
             LambdaError::InternalError(_) => "An internal error occurred. Please try again later",
+            LambdaError::ServiceUnavailable =>
+                "The service is temporarily unavailable. Please try again later",
+        }
+    }
+
+    /// User-friendly error message in the requested language: Japanese for `ja` (e.g. `ja-JP`),
+    /// English otherwise
+    pub fn user_message_localized(&self, lang: &str) -> String {
+        if is_japanese(lang) {
+            self.user_message_ja().to_string()
+        } else {
+            self.user_message().to_string()
+        }
+    }
+
+    fn user_message_ja(&self) -> &'static str {
+        match self {
+            LambdaError::InvalidEmail => "有効なメールアドレスを入力してください",
+            LambdaError::InvalidUsername => {
+                "ユーザー名は3〜30文字の英数字、アンダースコア、ハイフンで入力してください"
+            }
+            LambdaError::InvalidPassword => {
+                "パスワードは8文字以上で、大文字・小文字・数字を含めてください"
+            }
+            LambdaError::InvalidPhone => {
+                "電話番号はE.164形式で入力してください（例: +819012345678）"
+            }
+            LambdaError::InvalidOrganizationName => "組織名は2〜100文字で入力してください",
+            LambdaError::InvalidToken => "無効なトークンです",
+            LambdaError::InvalidRefreshToken => "無効なリフレッシュトークンです",
+            LambdaError::ValidationFailed(_) => "入力内容に誤りがあります",
+            LambdaError::AuthenticationFailed => "認証情報が正しくありません",
+            LambdaError::TokenExpired => "トークンの有効期限が切れています",
+            LambdaError::InvalidSignature => "トークンの署名を検証できませんでした",
+            LambdaError::UserNotFound => "ユーザーが見つかりません",
+            LambdaError::UserAlreadyExists => "このメールアドレスのユーザーは既に存在します",
+            LambdaError::ImmutableField(_) => "この項目は作成後に変更できません",
+            LambdaError::InsufficientPermissions => "この操作を行う権限がありません",
+            LambdaError::UserSuspended => {
+                "このアカウントは停止されています。管理者にお問い合わせください"
+            }
+            LambdaError::UserNotConfirmed => {
+                "登録を完了するため、ログインする前にメールアドレスを確認してください"
+            }
+            LambdaError::OrganizationNotFound => "組織が見つかりません",
+            LambdaError::MissingOrganizationId => "組織IDは必須です",
+            LambdaError::MissingRoles => "ロールを1つ以上指定してください",
+            LambdaError::InvalidRequest(_) => "リクエストの形式が正しくありません",
+            LambdaError::MissingBody => "リクエストボディは必須です",
+            LambdaError::InvalidBodyEncoding => "リクエストボディが有効なBase64ではありません",
+            LambdaError::MissingToken => "トークンは必須です",
+            LambdaError::MissingAuthContext => "リクエストが認可されていません",
+            LambdaError::IdempotencyKeyMismatch => {
+                "このIdempotency-Keyは別のリクエストで既に使用されています"
+            }
+            LambdaError::IdempotencyKeyInProgress => {
+                "このIdempotency-Keyのリクエストは処理中です。しばらくしてから再度お試しください"
+            }
+            LambdaError::UserCreationFailed(_) => {
+                "ユーザーの作成に失敗しました。しばらくしてから再度お試しください"
+            }
+            LambdaError::UserDeletionFailed(_) => {
+                "ユーザーの削除に失敗しました。しばらくしてから再度お試しください"
+            }
+            LambdaError::UserUpdateFailed(_) => {
+                "ユーザーの更新に失敗しました。しばらくしてから再度お試しください"
+            }
+            LambdaError::UserRetrievalFailed(_) => {
+                "ユーザー情報の取得に失敗しました。しばらくしてから再度お試しください"
+            }
+            LambdaError::TokenRefreshFailed(_) => {
+                "トークンの更新に失敗しました。しばらくしてから再度お試しください"
+            }
+            LambdaError::Throttled => {
+                "リクエストが多すぎます。しばらく待ってから再度お試しください"
+            }
+            LambdaError::InternalError(_) => {
+                "内部エラーが発生しました。しばらくしてから再度お試しください"
+            }
+            LambdaError::ServiceUnavailable => {
+                "サービスが一時的に利用できません。しばらくしてから再度お試しください"
+            }
+        }
+    }
+
+    /// Per-field validation failures, empty for other errors
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            LambdaError::ValidationFailed(errors) => errors,
+            _ => &[],
+        }
+    }
+
+    /// JSON body for error responses, with a `details` array for validation failures (also sent
+    /// as `errors` for clients written before `details`)
+    pub fn response_body(&self) -> serde_json::Value {
+        self.response_body_localized(DEFAULT_LANGUAGE)
+    }
+
+    /// Same as `response_body`, with `message` in the requested language; in production the
+    /// `error` of a 500 omits the internal detail
+    pub fn response_body_localized(&self, lang: &str) -> serde_json::Value {
+        self.response_body_with_detail(lang, !get_config().is_production())
+    }
+
+    fn response_body_with_detail(&self, lang: &str, include_detail: bool) -> serde_json::Value {
+        let error = if include_detail || self.status_code() != 500 {
+            self.to_string()
+        } else {
+            INTERNAL_ERROR_SUMMARY.to_string()
+        };
+        let mut body = serde_json::json!({
+            "error": error,
+            "message": self.user_message_localized(lang)
+        });
+        if !self.field_errors().is_empty() {
+            let details = serde_json::json!(self.field_errors());
+            body["errors"] = details.clone();
+            body["details"] = details;
+        }
+        body
+    }
+
+    /// Convert a repository error, surfacing DynamoDB throttling as `Throttled`
+    pub fn from_repository_error(
+        error: anyhow::Error,
+        fallback: impl FnOnce(String) -> LambdaError,
+    ) -> LambdaError {
+        match error.downcast_ref::<DynamoDbError>() {
+            Some(e) if e.is_throttling() => LambdaError::Throttled,
+            _ => fallback(error_chain(error.as_ref())),
+        }
+    }
+
+    /// Convert a Cognito error, surfacing a taken username or an alias collision (e.g. an email
+    /// change to an address already used by another account) as `UserAlreadyExists`, a missing
+    /// user as `UserNotFound`, a refused sign-in as `AuthenticationFailed` and a sign-in before
+    /// confirming sign-up as `UserNotConfirmed`
+    pub fn from_cognito_error(
+        error: CognitoError,
+        fallback: impl FnOnce(String) -> LambdaError,
+    ) -> LambdaError {
+        if error.is_username_exists() || error.is_alias_exists() {
+            LambdaError::UserAlreadyExists
+        } else if error.is_user_not_found() {
+            LambdaError::UserNotFound
+        } else if error.is_not_authorized() {
+            LambdaError::AuthenticationFailed
+        } else if error.is_user_not_confirmed() {
+            LambdaError::UserNotConfirmed
+        } else {
+            fallback(error_chain(&error))
+        }
+    }
+
+    /// Convert a secrets loading error: an unreachable Secrets Manager is `ServiceUnavailable`,
+    /// anything else (e.g. a missing or malformed secret) is a misconfiguration
+    pub fn from_secrets_error(error: anyhow::Error) -> LambdaError {
+        match error.downcast_ref::<SecretManagerError>() {
+            Some(e) if e.is_unavailable() => LambdaError::ServiceUnavailable,
+            _ => LambdaError::InternalError(error_chain(error.as_ref())),
+        }
+    }
+
+    /// Seconds the client should wait before retrying, if the error is retryable
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            LambdaError::Throttled => Some(THROTTLED_RETRY_AFTER_SECS),
+            LambdaError::ServiceUnavailable => Some(SERVICE_UNAVAILABLE_RETRY_AFTER_SECS),
+            _ => None,
         }
     }
 }
@@ -161,6 +436,242 @@ impl ToLambdaError for std::io::Error {
 
 impl ToLambdaError for anyhow::Error {
     fn to_lambda_error(self) -> LambdaError {
-        LambdaError::InternalError(self.to_string())
+        LambdaError::InternalError(error_chain(self.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_cognitoidentityprovider::config::http::HttpResponse;
+    use aws_sdk_cognitoidentityprovider::error::SdkError as CognitoSdkError;
+    use aws_sdk_cognitoidentityprovider::operation::admin_disable_user::AdminDisableUserError;
+    use aws_sdk_cognitoidentityprovider::operation::admin_update_user_attributes::AdminUpdateUserAttributesError;
+    use aws_sdk_cognitoidentityprovider::operation::initiate_auth::InitiateAuthError;
+    use aws_sdk_cognitoidentityprovider::types::error::{
+        AliasExistsException, InvalidParameterException, NotAuthorizedException,
+        UserNotConfirmedException, UserNotFoundException,
+    };
+    use aws_sdk_secretsmanager::error::{ConnectorError, SdkError};
+    use aws_smithy_types::body::SdkBody;
+
+    fn update_attributes_error(error: AdminUpdateUserAttributesError) -> CognitoError {
+        let response = HttpResponse::new(400.try_into().unwrap(), SdkBody::empty());
+        CognitoError::AdminUpdateUserAttributesError(CognitoSdkError::service_error(
+            error, response,
+        ))
+    }
+
+    #[test]
+    fn test_cognito_alias_exists_is_conflict() {
+        let error = update_attributes_error(AdminUpdateUserAttributesError::AliasExistsException(
+            AliasExistsException::builder()
+                .message("An account with the given email already exists.")
+                .build(),
+        ));
+
+        let error = LambdaError::from_cognito_error(error, LambdaError::UserUpdateFailed);
+        assert!(matches!(error, LambdaError::UserAlreadyExists));
+        assert_eq!(error.status_code(), 409);
+    }
+
+    #[test]
+    fn test_cognito_user_not_found_is_not_found() {
+        let response = HttpResponse::new(400.try_into().unwrap(), SdkBody::empty());
+        let error = CognitoError::AdminDisableUserError(CognitoSdkError::service_error(
+            AdminDisableUserError::UserNotFoundException(UserNotFoundException::builder().build()),
+            response,
+        ));
+
+        let error = LambdaError::from_cognito_error(error, LambdaError::UserUpdateFailed);
+        assert!(matches!(error, LambdaError::UserNotFound));
+        assert_eq!(error.status_code(), 404);
+    }
+
+    #[test]
+    fn test_cognito_user_not_confirmed_is_forbidden() {
+        let response = HttpResponse::new(400.try_into().unwrap(), SdkBody::empty());
+        let error = CognitoError::InitiateAuthError(CognitoSdkError::service_error(
+            InitiateAuthError::UserNotConfirmedException(
+                UserNotConfirmedException::builder()
+                    .message("User is not confirmed.")
+                    .build(),
+            ),
+            response,
+        ));
+
+        let error = LambdaError::from_cognito_error(error, |detail| {
+            LambdaError::internal("authenticate user", detail)
+        });
+        assert!(matches!(error, LambdaError::UserNotConfirmed));
+        assert_eq!(error.status_code(), 403);
+        assert_eq!(error.response_body()["error"], "User is not confirmed");
+    }
+
+    #[test]
+    fn test_cognito_not_authorized_is_authentication_failure() {
+        let response = HttpResponse::new(400.try_into().unwrap(), SdkBody::empty());
+        let error = CognitoError::InitiateAuthError(CognitoSdkError::service_error(
+            InitiateAuthError::NotAuthorizedException(
+                NotAuthorizedException::builder()
+                    .message("Incorrect username or password.")
+                    .build(),
+            ),
+            response,
+        ));
+
+        let error = LambdaError::from_cognito_error(error, |detail| {
+            LambdaError::internal("re-authenticate user", detail)
+        });
+        assert!(matches!(error, LambdaError::AuthenticationFailed));
+        assert_eq!(error.status_code(), 401);
+    }
+
+    #[test]
+    fn test_cognito_other_error_uses_fallback() {
+        let error =
+            update_attributes_error(AdminUpdateUserAttributesError::InvalidParameterException(
+                InvalidParameterException::builder().build(),
+            ));
+
+        let error = LambdaError::from_cognito_error(error, LambdaError::UserUpdateFailed);
+        assert!(matches!(error, LambdaError::UserUpdateFailed(_)));
+        assert_eq!(error.status_code(), 500);
+    }
+
+    #[test]
+    fn test_failed_update_keeps_operation_context() {
+        let error = anyhow::Error::new(DynamoDbError::MissingAttribute("Attributes".to_string()))
+            .context("Unable to update user user-1");
+
+        let error = LambdaError::from_repository_error(error, LambdaError::UserUpdateFailed);
+        assert_eq!(error.status_code(), 500);
+        assert_eq!(
+            error.to_string(),
+            "Failed to update user: Unable to update user user-1: MissingAttribute: Attributes"
+        );
+        assert!(error.response_body()["error"]
+            .as_str()
+            .unwrap()
+            .contains("Unable to update user user-1"));
+    }
+
+    #[test]
+    fn test_failed_cognito_call_keeps_operation_context() {
+        let response = HttpResponse::new(400.try_into().unwrap(), SdkBody::empty());
+        let error = CognitoError::AdminDisableUserError(CognitoSdkError::service_error(
+            AdminDisableUserError::InvalidParameterException(
+                InvalidParameterException::builder()
+                    .message("Username is malformed")
+                    .build(),
+            ),
+            response,
+        ));
+
+        let error = LambdaError::from_cognito_error(error, |detail| {
+            LambdaError::internal("disable Cognito user", detail)
+        });
+        let message = error.to_string();
+        assert!(message.starts_with("Internal server error: disable Cognito user: "));
+        assert!(message.contains("AdminDisableUserError"));
+        assert!(message.contains("Username is malformed"));
+    }
+
+    #[test]
+    fn test_production_response_withholds_internal_detail() {
+        let error = LambdaError::internal("set temporary password", "InvalidPasswordException");
+
+        let body = error.response_body_with_detail(DEFAULT_LANGUAGE, false);
+        assert_eq!(body["error"], INTERNAL_ERROR_SUMMARY);
+        assert!(!body.to_string().contains("set temporary password"));
+
+        let body = error.response_body_with_detail(DEFAULT_LANGUAGE, true);
+        assert_eq!(
+            body["error"],
+            "Internal server error: set temporary password: InvalidPasswordException"
+        );
+
+        let body = LambdaError::UserNotFound.response_body_with_detail(DEFAULT_LANGUAGE, false);
+        assert_eq!(body["error"], "User not found");
+    }
+
+    #[test]
+    fn test_user_message_localized_in_japanese() {
+        assert_eq!(
+            LambdaError::UserNotFound.user_message_localized("ja"),
+            "ユーザーが見つかりません"
+        );
+        assert_eq!(
+            LambdaError::InsufficientPermissions.user_message_localized("ja-JP"),
+            "この操作を行う権限がありません"
+        );
+    }
+
+    #[test]
+    fn test_user_message_localized_falls_back_to_english() {
+        for lang in ["en", "en-US", "fr", "", "jav"] {
+            assert_eq!(
+                LambdaError::UserNotFound.user_message_localized(lang),
+                LambdaError::UserNotFound.user_message()
+            );
+        }
+        assert_eq!(
+            LambdaError::Throttled.user_message_localized("de"),
+            "Too many requests. Please retry after a short wait"
+        );
+    }
+
+    #[test]
+    fn test_response_body_localized_keeps_error_and_field_errors() {
+        let error = LambdaError::ValidationFailed(vec![FieldError::new(
+            "email",
+            crate::validation::FieldErrorCode::EmailInvalid,
+        )]);
+        let body = error.response_body_localized("ja");
+
+        assert_eq!(body["error"], "Validation failed");
+        assert_eq!(body["message"], "入力内容に誤りがあります");
+        assert_eq!(body["details"][0]["field"], "email");
+        assert_eq!(body["errors"], body["details"]);
+        assert_eq!(error.response_body()["message"], error.user_message());
+    }
+
+    #[test]
+    fn test_error_chain_skips_repeated_sources() {
+        let error = anyhow::Error::new(std::io::Error::other("disk full"))
+            .context("write audit event")
+            .context("delete user");
+        assert_eq!(
+            error_chain(error.as_ref()),
+            "delete user: write audit event: disk full"
+        );
+    }
+
+    #[test]
+    fn test_secrets_network_error_is_service_unavailable() {
+        let sdk_error = SdkError::dispatch_failure(ConnectorError::io("connection refused".into()));
+        let error =
+            anyhow::Error::new(SecretManagerError::GetSecretValueError(Box::new(sdk_error)));
+
+        let error = LambdaError::from_secrets_error(error);
+        assert!(matches!(error, LambdaError::ServiceUnavailable));
+        assert_eq!(error.status_code(), 503);
+        assert_eq!(
+            error.retry_after_secs(),
+            Some(SERVICE_UNAVAILABLE_RETRY_AFTER_SECS)
+        );
+    }
+
+    #[test]
+    fn test_secrets_missing_secret_is_internal_error() {
+        let error = anyhow::Error::new(SecretManagerError::MissingAttribute(
+            "Missing secret string".to_string(),
+        ));
+        let error = LambdaError::from_secrets_error(error);
+        assert_eq!(error.status_code(), 500);
+
+        let error = LambdaError::from_secrets_error(anyhow::anyhow!("Missing secret string"));
+        assert_eq!(error.status_code(), 500);
+        assert_eq!(error.retry_after_secs(), None);
     }
 }
@@ -1,9 +1,15 @@
+pub mod audit_logger;
+pub mod authz;
 pub mod aws;
 pub mod cache_manager;
 pub mod client_manager;
 pub mod config;
 pub mod entity;
 pub mod errors;
+pub mod pagination;
 pub mod repository;
+pub mod session_store;
 pub mod tracer;
+pub mod user_deletion;
 pub mod utils;
+pub mod validation;
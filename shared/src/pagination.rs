@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde_json::{Map, Value};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::config::get_config;
+
+/// Number of HMAC-SHA256 bytes appended to a token as its signature
+const SIGNATURE_LEN: usize = 16;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PaginationError {
+    #[error("MalformedToken: {0}")]
+    MalformedToken(String),
+
+    #[error("IntegrityCheckFailed")]
+    IntegrityCheckFailed,
+
+    #[error("UnsupportedAttribute: {0}")]
+    UnsupportedAttribute(String),
+}
+
+/// Encode a DynamoDB `LastEvaluatedKey` into an opaque, URL-safe next-token signed with
+/// `PAGINATION_TOKEN_SECRET`
+pub fn encode_token(key: &HashMap<String, AttributeValue>) -> Result<String, PaginationError> {
+    encode_token_with_secret(key, get_config().pagination_token_secret.as_bytes())
+}
+
+/// Decode a token produced by `encode_token` back into an `ExclusiveStartKey`
+pub fn decode_token(token: &str) -> Result<HashMap<String, AttributeValue>, PaginationError> {
+    decode_token_with_secret(token, get_config().pagination_token_secret.as_bytes())
+}
+
+/// Encode a `LastEvaluatedKey` into a next-token signed with `secret`
+pub fn encode_token_with_secret(
+    key: &HashMap<String, AttributeValue>,
+    secret: &[u8],
+) -> Result<String, PaginationError> {
+    let map = key
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), attribute_to_json(value)?)))
+        .collect::<Result<Map<String, Value>, PaginationError>>()?;
+    let mut bytes = serde_json::to_vec(&Value::Object(map))
+        .map_err(|e| PaginationError::MalformedToken(e.to_string()))?;
+    let signature = signer(secret, &bytes).finalize().into_bytes();
+    bytes.extend_from_slice(&signature[..SIGNATURE_LEN]);
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Decode a token signed with `secret`, rejecting any whose signature does not match
+pub fn decode_token_with_secret(
+    token: &str,
+    secret: &[u8],
+) -> Result<HashMap<String, AttributeValue>, PaginationError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(token)
+        .map_err(|e| PaginationError::MalformedToken(e.to_string()))?;
+    if bytes.len() <= SIGNATURE_LEN {
+        return Err(PaginationError::MalformedToken(
+            "token too short".to_string(),
+        ));
+    }
+    let (payload, signature) = bytes.split_at(bytes.len() - SIGNATURE_LEN);
+    signer(secret, payload)
+        .verify_truncated_left(signature)
+        .map_err(|_| PaginationError::IntegrityCheckFailed)?;
+
+    match serde_json::from_slice(payload) {
+        Ok(Value::Object(map)) => map
+            .into_iter()
+            .map(|(name, value)| Ok((name, json_to_attribute(value)?)))
+            .collect(),
+        Ok(_) => Err(PaginationError::MalformedToken(
+            "expected a JSON object".to_string(),
+        )),
+        Err(e) => Err(PaginationError::MalformedToken(e.to_string())),
+    }
+}
+
+fn signer(secret: &[u8], payload: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(payload);
+    mac
+}
+
+/// Convert an attribute to its DynamoDB JSON form, e.g. `{"S": "value"}`
+fn attribute_to_json(value: &AttributeValue) -> Result<Value, PaginationError> {
+    let (tag, inner) = match value {
+        AttributeValue::S(s) => ("S", Value::from(s.as_str())),
+        AttributeValue::N(n) => ("N", Value::from(n.as_str())),
+        AttributeValue::B(b) => ("B", Value::from(STANDARD.encode(b.as_ref()))),
+        AttributeValue::Bool(b) => ("BOOL", Value::from(*b)),
+        AttributeValue::Null(n) => ("NULL", Value::from(*n)),
+        AttributeValue::Ss(ss) => ("SS", Value::from(ss.clone())),
+        AttributeValue::Ns(ns) => ("NS", Value::from(ns.clone())),
+        AttributeValue::L(list) => (
+            "L",
+            Value::Array(
+                list.iter()
+                    .map(attribute_to_json)
+                    .collect::<Result<_, _>>()?,
+            ),
+        ),
+        AttributeValue::M(map) => (
+            "M",
+            Value::Object(
+                map.iter()
+                    .map(|(k, v)| Ok((k.clone(), attribute_to_json(v)?)))
+                    .collect::<Result<_, PaginationError>>()?,
+            ),
+        ),
+        other => return Err(PaginationError::UnsupportedAttribute(format!("{other:?}"))),
+    };
+    let mut object = Map::new();
+    object.insert(tag.to_string(), inner);
+    Ok(Value::Object(object))
+}
+
+fn json_to_attribute(value: Value) -> Result<AttributeValue, PaginationError> {
+    let malformed = || PaginationError::MalformedToken("invalid attribute value".to_string());
+    let Value::Object(object) = value else {
+        return Err(malformed());
+    };
+    let mut entries = object.into_iter();
+    let (tag, inner) = match (entries.next(), entries.next()) {
+        (Some(entry), None) => entry,
+        _ => return Err(malformed()),
+    };
+
+    let strings = |inner: Value| -> Result<Vec<String>, PaginationError> {
+        serde_json::from_value(inner).map_err(|_| malformed())
+    };
+    match (tag.as_str(), inner) {
+        ("S", Value::String(s)) => Ok(AttributeValue::S(s)),
+        ("N", Value::String(n)) => Ok(AttributeValue::N(n)),
+        ("B", Value::String(b)) => STANDARD
+            .decode(b)
+            .map(|bytes| AttributeValue::B(Blob::new(bytes)))
+            .map_err(|_| malformed()),
+        ("BOOL", Value::Bool(b)) => Ok(AttributeValue::Bool(b)),
+        ("NULL", Value::Bool(n)) => Ok(AttributeValue::Null(n)),
+        ("SS", inner) => strings(inner).map(AttributeValue::Ss),
+        ("NS", inner) => strings(inner).map(AttributeValue::Ns),
+        ("L", Value::Array(list)) => list
+            .into_iter()
+            .map(json_to_attribute)
+            .collect::<Result<_, _>>()
+            .map(AttributeValue::L),
+        ("M", Value::Object(map)) => map
+            .into_iter()
+            .map(|(k, v)| Ok((k, json_to_attribute(v)?)))
+            .collect::<Result<_, PaginationError>>()
+            .map(AttributeValue::M),
+        (tag, _) => Err(PaginationError::UnsupportedAttribute(tag.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_key() -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S("user-1".to_string())),
+            (
+                "organization_id".to_string(),
+                AttributeValue::S("org-1".to_string()),
+            ),
+            (
+                "created_at".to_string(),
+                AttributeValue::N("42".to_string()),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = sample_key();
+        let token = encode_token(&key).unwrap();
+        assert_eq!(decode_token(&token).unwrap(), key);
+    }
+
+    #[test]
+    fn test_round_trip_nested_attributes() {
+        let key = HashMap::from([
+            ("flag".to_string(), AttributeValue::Bool(true)),
+            (
+                "blob".to_string(),
+                AttributeValue::B(Blob::new(vec![0, 1, 255])),
+            ),
+            (
+                "roles".to_string(),
+                AttributeValue::Ss(vec!["Admin".to_string(), "Reader".to_string()]),
+            ),
+            (
+                "meta".to_string(),
+                AttributeValue::M(HashMap::from([(
+                    "items".to_string(),
+                    AttributeValue::L(vec![AttributeValue::Null(true)]),
+                )])),
+            ),
+        ]);
+        let token = encode_token(&key).unwrap();
+        assert_eq!(decode_token(&token).unwrap(), key);
+    }
+
+    #[test]
+    fn test_token_is_url_safe() {
+        let token = encode_token(&sample_key()).unwrap();
+        assert!(token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_tampered_token_fails_to_decode() {
+        let token = encode_token(&sample_key()).unwrap();
+        let mut bytes = URL_SAFE_NO_PAD.decode(&token).unwrap();
+        bytes[2] ^= 0x01;
+        let tampered = URL_SAFE_NO_PAD.encode(bytes);
+
+        assert_eq!(
+            decode_token(&tampered),
+            Err(PaginationError::IntegrityCheckFailed)
+        );
+    }
+
+    #[test]
+    fn test_token_signed_with_another_secret_fails_to_decode() {
+        let token = encode_token_with_secret(&sample_key(), b"attacker").unwrap();
+
+        assert_eq!(
+            decode_token_with_secret(&token, b"server"),
+            Err(PaginationError::IntegrityCheckFailed)
+        );
+        assert_eq!(
+            decode_token_with_secret(&token, b"attacker").unwrap(),
+            sample_key()
+        );
+    }
+
+    #[test]
+    fn test_garbage_token_fails_to_decode() {
+        assert!(matches!(
+            decode_token("not a token!"),
+            Err(PaginationError::MalformedToken(_))
+        ));
+        assert!(matches!(
+            decode_token(""),
+            Err(PaginationError::MalformedToken(_))
+        ));
+    }
+}
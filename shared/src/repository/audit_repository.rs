@@ -0,0 +1,213 @@
+use crate::aws::dynamodb::client::{DynamoDbClient, IndexPageQuery};
+use crate::entity::audit_event::AuditEvent;
+use crate::pagination::{decode_token, encode_token};
+use crate::utils::env::get_env;
+
+use anyhow::{anyhow, Context, Error as AnyhowError};
+use async_trait::async_trait;
+use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
+use tracing::{debug, error};
+
+/// One page of audit events, newest first
+#[derive(Debug)]
+pub struct AuditPage {
+    pub events: Vec<AuditEvent>,
+    /// Opaque token for the next page, absent on the last page
+    pub next_token: Option<String>,
+}
+
+#[async_trait]
+pub trait AuditRepository {
+    async fn put_audit_event(&self, event: &AuditEvent) -> Result<(), AnyhowError>;
+
+    /// Events of an organization with `from_ts <= timestamp <= to_ts` (milliseconds),
+    /// optionally only those with the given action
+    async fn query(
+        &self,
+        organization_id: &str,
+        from_ts: u64,
+        to_ts: u64,
+        action: Option<String>,
+        limit: i32,
+        next_token: Option<String>,
+    ) -> Result<AuditPage, AnyhowError>;
+}
+
+pub struct AuditRepositoryImpl {
+    client: DynamoDbClient,
+    table_name: String,
+    /// GSI keyed by `organization_id` with `timestamp` as the sort key
+    organization_index: String,
+}
+
+impl AuditRepositoryImpl {
+    pub fn new(client: DynamoDbClient, table_name: String) -> Self {
+        Self {
+            client,
+            table_name,
+            organization_index: get_env("AUDIT_ORG_INDEX", "OrganizationTimestampIndex"),
+        }
+    }
+}
+
+/// Key condition, filter and attributes for an organization's events in a time range
+fn time_range_query(
+    organization_id: &str,
+    from_ts: u64,
+    to_ts: u64,
+    action: Option<&str>,
+) -> (
+    &'static str,
+    Option<&'static str>,
+    HashMap<String, String>,
+    HashMap<String, AttributeValue>,
+) {
+    let mut names = HashMap::from([
+        (
+            "#organization_id".to_string(),
+            "organization_id".to_string(),
+        ),
+        ("#timestamp".to_string(), "timestamp".to_string()),
+    ]);
+    let mut values = HashMap::from([
+        (
+            ":organization_id".to_string(),
+            AttributeValue::S(organization_id.to_string()),
+        ),
+        (":from".to_string(), AttributeValue::N(from_ts.to_string())),
+        (":to".to_string(), AttributeValue::N(to_ts.to_string())),
+    ]);
+    let filter = action.map(|action| {
+        names.insert("#action".to_string(), "action".to_string());
+        values.insert(":action".to_string(), AttributeValue::S(action.to_string()));
+        "#action = :action"
+    });
+
+    (
+        "#organization_id = :organization_id AND #timestamp BETWEEN :from AND :to",
+        filter,
+        names,
+        values,
+    )
+}
+
+#[async_trait]
+impl AuditRepository for AuditRepositoryImpl {
+    async fn put_audit_event(&self, event: &AuditEvent) -> Result<(), AnyhowError> {
+        let _ = self
+            .client
+            .put_item(&self.table_name, event.to_item())
+            .await
+            .map_err(|e| {
+                error!("DynamoDB PutItem failed for audit event: {:?}", e);
+                anyhow!("DynamoDB PutItem failed for audit event: {:?}", e)
+            })?;
+
+        debug!("dynamodb put item successful for audit event: {}", event.id);
+        Ok(())
+    }
+
+    async fn query(
+        &self,
+        organization_id: &str,
+        from_ts: u64,
+        to_ts: u64,
+        action: Option<String>,
+        limit: i32,
+        next_token: Option<String>,
+    ) -> Result<AuditPage, AnyhowError> {
+        let exclusive_start_key = next_token
+            .as_deref()
+            .map(decode_token)
+            .transpose()
+            .context("Invalid audit next token")?;
+        let (key_condition_expression, filter_expression, names, values) =
+            time_range_query(organization_id, from_ts, to_ts, action.as_deref());
+
+        let output = self
+            .client
+            .query_index_page(
+                &self.table_name,
+                &IndexPageQuery {
+                    index_name: &self.organization_index,
+                    key_condition_expression,
+                    filter_expression,
+                    expression_attribute_names: names,
+                    expression_attribute_values: values,
+                    limit,
+                    exclusive_start_key,
+                },
+            )
+            .await
+            .with_context(|| format!("Unable to query audit events of {organization_id}"))?;
+
+        let events = output
+            .items()
+            .iter()
+            .map(AuditEvent::from_item)
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_token = output
+            .last_evaluated_key()
+            .map(encode_token)
+            .transpose()
+            .context("Unable to encode audit next token")?;
+        debug!(
+            "Queried {} audit events for organization {}",
+            events.len(),
+            organization_id
+        );
+
+        Ok(AuditPage { events, next_token })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_range_query_bounds() {
+        let (key_condition, filter, names, values) = time_range_query("org-1", 1_000, 2_000, None);
+
+        assert_eq!(
+            key_condition,
+            "#organization_id = :organization_id AND #timestamp BETWEEN :from AND :to"
+        );
+        assert_eq!(filter, None);
+        assert_eq!(names["#timestamp"], "timestamp");
+        assert_eq!(values[":from"], AttributeValue::N("1000".to_string()));
+        assert_eq!(values[":to"], AttributeValue::N("2000".to_string()));
+        assert!(!values.contains_key(":action"));
+    }
+
+    #[test]
+    fn test_time_range_query_filters_by_action() {
+        let (_, filter, names, values) =
+            time_range_query("org-1", 1_000, 2_000, Some("DeleteUser"));
+
+        assert_eq!(filter, Some("#action = :action"));
+        assert_eq!(names["#action"], "action");
+        assert_eq!(
+            values[":action"],
+            AttributeValue::S("DeleteUser".to_string())
+        );
+    }
+
+    #[test]
+    fn test_next_token_round_trips_audit_key() {
+        let last_evaluated_key = HashMap::from([
+            ("id".to_string(), AttributeValue::S("event-1".to_string())),
+            (
+                "organization_id".to_string(),
+                AttributeValue::S("org-1".to_string()),
+            ),
+            (
+                "timestamp".to_string(),
+                AttributeValue::N("1700000000000".to_string()),
+            ),
+        ]);
+        let token = encode_token(&last_evaluated_key).unwrap();
+        assert_eq!(decode_token(&token).unwrap(), last_evaluated_key);
+    }
+}
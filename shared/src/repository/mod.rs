@@ -1 +1,2 @@
+pub mod audit_repository;
 pub mod user_repository;
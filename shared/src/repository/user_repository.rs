@@ -1,56 +1,640 @@
 use crate::aws::dynamodb::client::DynamoDbClient;
-use crate::entity::user::User;
+use crate::aws::dynamodb::error::DynamoDbError;
+use crate::aws::dynamodb::ops::DynamoDbOps;
+use crate::aws::kms::client::KmsCrypto;
+use crate::config::{get_config, TableConfig};
+use crate::entity::organization::Organization;
+use crate::entity::user::{Role, User, UserStatus};
+use crate::utils::crypto::Crypto;
+use crate::utils::env::get_env;
+use crate::validation::normalize_email;
 
-use anyhow::{anyhow, Error as AnyhowError, Result};
+use anyhow::{anyhow, Context, Error as AnyhowError, Result};
 use async_trait::async_trait;
+use aws_sdk_dynamodb::types::AttributeValue;
+use futures::future::try_join_all;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, error};
 
+/// Number of status updates issued concurrently when changing a whole organization
+const ORGANIZATION_STATUS_BATCH_SIZE: usize = 25;
+
 #[async_trait]
 pub trait UserRepository {
-    async fn get_user_by_id(&self, user_id: String) -> Result<User, AnyhowError>;
+    async fn get_user_by_id(
+        &self,
+        user_id: String,
+        include_deleted: bool,
+    ) -> Result<User, AnyhowError>;
+    /// `(user_id, organization_id)` of a visible user, reading only the attributes needed for it
+    async fn get_user_org(&self, user_id: &str) -> Result<(String, String), AnyhowError>;
     async fn get_users_by_organization_id(
         &self,
         organization_id: String,
+        include_deleted: bool,
     ) -> Result<Vec<User>, AnyhowError>;
+    async fn count_users_by_organization_id(
+        &self,
+        organization_id: String,
+    ) -> Result<u64, AnyhowError>;
+    async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, AnyhowError>;
     async fn create_user(&self, user: User) -> Result<User, AnyhowError>;
+    /// Create a user unless a row already exists under its key, deleted or not; `None` if one does
+    async fn create_user_if_absent(&self, user: User) -> Result<Option<User>, AnyhowError>;
     async fn delete_user_by_id(
         &self,
         user_id: String,
         organization_id: String,
     ) -> Result<(), AnyhowError>;
+    async fn soft_delete_user(
+        &self,
+        user_id: String,
+        organization_id: String,
+    ) -> Result<(), AnyhowError>;
+    /// Clear `deleted_at`. Soft deletion also disables the Cognito user, so callers must
+    /// re-enable it with `admin_enable_user` for the user to sign in again.
+    async fn restore_user(
+        &self,
+        user_id: String,
+        organization_id: String,
+    ) -> Result<(), AnyhowError>;
     async fn update_user(&self, user: User) -> Result<User, AnyhowError>;
+    async fn update_user_roles(
+        &self,
+        user: User,
+        roles: HashSet<Role>,
+    ) -> Result<User, AnyhowError>;
+
+    /// Mark every user of the organization `Suspended`, returning the users that changed
+    async fn suspend_organization(&self, organization_id: &str) -> Result<Vec<User>, AnyhowError>;
+    /// Mark every user of the organization `Active` again, returning the users that changed
+    async fn reactivate_organization(
+        &self,
+        organization_id: &str,
+    ) -> Result<Vec<User>, AnyhowError>;
 
     async fn find_organization_id_by_name(
         &self,
         organization_name: &str,
     ) -> Result<Option<String>, AnyhowError>;
     async fn organization_exists(&self, organization_name: &str) -> Result<bool, AnyhowError>;
+    async fn list_organizations(&self) -> Result<Vec<Organization>, AnyhowError>;
     async fn is_first_user_in_organization(
         &self,
         organization_name: &str,
     ) -> Result<bool, AnyhowError>;
 }
 
-pub struct UserRepositoryImpl {
-    client: DynamoDbClient,
+pub struct UserRepositoryImpl<D: DynamoDbOps = DynamoDbClient> {
+    client: D,
     table_name: String,
+    table_config: TableConfig,
+    crypto: Option<Arc<dyn Crypto>>,
+}
+
+impl<D: DynamoDbOps> UserRepositoryImpl<D> {
+    pub fn new(client: D, table_name: String) -> Self {
+        Self::with_table_config(client, table_name, get_config().table.clone())
+    }
+
+    pub fn with_table_config(client: D, table_name: String, table_config: TableConfig) -> Self {
+        Self {
+            client,
+            table_name,
+            table_config,
+            crypto: pii_crypto(),
+        }
+    }
+
+    /// Encrypt the PII attributes of an item when PII encryption is enabled
+    async fn encrypt_pii(&self, item: &mut HashMap<String, AttributeValue>) -> Result<()> {
+        if let Some(crypto) = &self.crypto {
+            User::encrypt_pii(item, crypto.as_ref()).await?;
+        }
+        Ok(())
+    }
+
+    /// Decrypt the PII attributes of read items when PII encryption is enabled
+    async fn decrypt_pii(&self, items: &mut [HashMap<String, AttributeValue>]) -> Result<()> {
+        if let Some(crypto) = &self.crypto {
+            for item in items {
+                User::decrypt_pii(item, crypto.as_ref()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Key condition values for looking up a user by email, matching any case. With PII
+    /// encryption the blind index comes first, then the plaintext of rows written before
+    /// encryption was enabled.
+    async fn email_lookup_values(
+        &self,
+        email: &str,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>> {
+        let email_lower = normalize_email(email);
+        let mut lookup_keys = Vec::new();
+        if let Some(crypto) = &self.crypto {
+            lookup_keys.push(User::email_index(&email_lower, crypto.as_ref()).await?);
+        }
+        lookup_keys.push(email_lower);
+        Ok(lookup_keys
+            .into_iter()
+            .map(|key| HashMap::from([(":email_lower".to_string(), AttributeValue::S(key))]))
+            .collect())
+    }
+
+    /// Every user item of the named organization, across all scan pages
+    async fn scan_organization_members(
+        &self,
+        organization_name: &str,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, AnyhowError> {
+        let normalized_name = Organization::normalize_name(organization_name);
+        let (filter_expression, names, values) =
+            organization_name_filter(NORMALIZED_ORGANIZATION_NAME_ATTRIBUTE, &normalized_name);
+        let items = self
+            .client
+            .scan_all(&self.table_name, Some(&filter_expression), &names, &values)
+            .await?;
+        if !items.is_empty() {
+            return Ok(items);
+        }
+
+        // Rows written before the normalized name was stored only match their exact name
+        let (filter_expression, names, values) =
+            organization_name_filter("organization_name", organization_name);
+        let items = self
+            .client
+            .scan_all(&self.table_name, Some(&filter_expression), &names, &values)
+            .await?;
+        Ok(items)
+    }
+
+    /// Set the status of one user
+    async fn set_status(&self, user: &User) -> Result<(), AnyhowError> {
+        let key = build_key(&self.table_config, &user.id, &user.organization_id);
+        let expression_attribute_names = self
+            .client
+            .generate_attribute_names(&[
+                ("#status", "status"),
+                ("#pk", self.table_config.partition_key.as_str()),
+            ])
+            .await;
+        let expression_attribute_values = HashMap::from([(
+            ":status".to_string(),
+            AttributeValue::S(user.status.to_string()),
+        )]);
+
+        // Never create a bare item for a user deleted since it was read
+        match self
+            .client
+            .update_item_with_condition(
+                &self.table_name,
+                &key,
+                "SET #status = :status",
+                "attribute_exists(#pk)",
+                &expression_attribute_names,
+                &expression_attribute_values,
+            )
+            .await
+        {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(DynamoDbError::NotFound.into()),
+            Err(e) => Err(AnyhowError::new(e).context("Unable to update user status")),
+        }
+    }
+
+    /// Item stored for a user, with PII encrypted and the key attributes under their configured
+    /// names
+    async fn user_item(&self, user: &User) -> Result<HashMap<String, AttributeValue>, AnyhowError> {
+        let mut items = self
+            .client
+            .generate_attribute_values(&[
+                ("id", &user.id),
+                ("user_name", &user.name),
+                ("email", &user.email),
+                ("organization_id", &user.organization_id),
+                ("organization_name", &user.organization_name),
+            ])
+            .await;
+        items.insert("roles".to_string(), user.roles_to_attribute_value());
+        items.insert(
+            "email_lower".to_string(),
+            AttributeValue::S(user.email_lower()),
+        );
+        items.insert(
+            NORMALIZED_ORGANIZATION_NAME_ATTRIBUTE.to_string(),
+            AttributeValue::S(user.organization_name_normalized()),
+        );
+
+        if let Some(cognito_username) = &user.cognito_username {
+            items.insert(
+                "cognito_username".to_string(),
+                AttributeValue::S(cognito_username.clone()),
+            );
+        }
+        if let Some(phone) = &user.phone {
+            items.insert("phone".to_string(), AttributeValue::S(phone.clone()));
+        }
+        if let Some(locale) = &user.locale {
+            items.insert("locale".to_string(), AttributeValue::S(locale.clone()));
+        }
+        items.insert(
+            "email_verified".to_string(),
+            AttributeValue::Bool(user.email_verified),
+        );
+        items.insert(
+            "status".to_string(),
+            AttributeValue::S(user.status.to_string()),
+        );
+
+        self.encrypt_pii(&mut items).await?;
+
+        // Store the key attributes under the configured names as well
+        items.extend(build_key(
+            &self.table_config,
+            &user.id,
+            &user.organization_id,
+        ));
+
+        Ok(items)
+    }
+
+    /// Set attributes of an existing user, failing with `DynamoDbError::NotFound` if it is gone
+    async fn set_attributes(
+        &self,
+        user: &User,
+        attributes: HashMap<String, AttributeValue>,
+    ) -> Result<(), AnyhowError> {
+        let key = build_key(&self.table_config, &user.id, &user.organization_id);
+        let mut expression_attribute_names =
+            HashMap::from([("#pk".to_string(), self.table_config.partition_key.clone())]);
+        let mut expression_attribute_values = HashMap::new();
+        let mut assignments = Vec::new();
+        for (name, value) in attributes {
+            assignments.push(format!("#{name} = :{name}"));
+            expression_attribute_names.insert(format!("#{name}"), name.clone());
+            expression_attribute_values.insert(format!(":{name}"), value);
+        }
+
+        match self
+            .client
+            .update_item_with_condition(
+                &self.table_name,
+                &key,
+                &format!("SET {}", assignments.join(", ")),
+                "attribute_exists(#pk)",
+                &expression_attribute_names,
+                &expression_attribute_values,
+            )
+            .await
+        {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(DynamoDbError::NotFound.into()),
+            Err(e) => Err(AnyhowError::new(e).context("Unable to update user attributes")),
+        }
+    }
+
+    /// Lookup attributes of a user as they are stored, with the blind index when PII
+    /// encryption is enabled
+    async fn lookup_attributes(&self, user: &User) -> Result<HashMap<String, AttributeValue>> {
+        let mut attributes = HashMap::from([
+            (
+                "email_lower".to_string(),
+                AttributeValue::S(user.email_lower()),
+            ),
+            (
+                NORMALIZED_ORGANIZATION_NAME_ATTRIBUTE.to_string(),
+                AttributeValue::S(user.organization_name_normalized()),
+            ),
+        ]);
+        self.encrypt_pii(&mut attributes).await?;
+        Ok(attributes)
+    }
+
+    /// Rewrite the lookup attributes of every user whose stored values are missing or stale,
+    /// e.g. rows written before `email_lower` or `organization_name_normalized` existed or holding
+    /// `enc:` ciphertext in `email_lower`.
+    /// Returns the number of users updated.
+    pub async fn backfill_lookup_attributes(&self) -> Result<usize, AnyhowError> {
+        let mut items = self
+            .client
+            .scan_all(&self.table_name, None, &HashMap::new(), &HashMap::new())
+            .await?;
+        self.decrypt_pii(&mut items).await?;
+
+        let mut updated = 0;
+        for item in &items {
+            let user = User::from_item(item)?;
+            let stale: HashMap<String, AttributeValue> = self
+                .lookup_attributes(&user)
+                .await?
+                .into_iter()
+                .filter(|(name, value)| item.get(name) != Some(value))
+                .collect();
+            if stale.is_empty() {
+                continue;
+            }
+            debug!("backfilling {:?} of user {}", stale.keys(), user.id);
+            self.set_attributes(&user, stale).await?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    /// Set the status of every user in an organization, in batches
+    async fn set_organization_status(
+        &self,
+        organization_id: &str,
+        status: UserStatus,
+    ) -> Result<Vec<User>, AnyhowError> {
+        let users = self
+            .get_users_by_organization_id(organization_id.to_string(), true)
+            .await?;
+        let changed = apply_status_change(users, status);
+
+        for batch in changed.chunks(ORGANIZATION_STATUS_BATCH_SIZE) {
+            try_join_all(batch.iter().map(|user| self.set_status(user))).await?;
+        }
+        debug!(
+            "set status {} on {} users in organization {}",
+            status,
+            changed.len(),
+            organization_id
+        );
+        Ok(changed)
+    }
+
+    /// Set or clear the `deleted_at` marker of an existing user
+    async fn set_deleted_at(
+        &self,
+        user_id: &str,
+        organization_id: &str,
+        deleted_at: Option<u64>,
+    ) -> Result<(), AnyhowError> {
+        let key = build_key(&self.table_config, user_id, organization_id);
+        let mut expression_attribute_values = HashMap::new();
+        let update_expression = match deleted_at {
+            Some(deleted_at) => {
+                expression_attribute_values.insert(
+                    ":deleted_at".to_string(),
+                    AttributeValue::N(deleted_at.to_string()),
+                );
+                "SET #deleted_at = :deleted_at"
+            }
+            None => "REMOVE #deleted_at",
+        };
+        // Never create a bare item for a user that does not exist
+        let condition_expression = "attribute_exists(#pk)";
+        let expression_attribute_names = self
+            .client
+            .generate_attribute_names(&[
+                ("#deleted_at", "deleted_at"),
+                ("#pk", self.table_config.partition_key.as_str()),
+            ])
+            .await;
+
+        match self
+            .client
+            .update_item_with_condition(
+                &self.table_name,
+                &key,
+                update_expression,
+                condition_expression,
+                &expression_attribute_names,
+                &expression_attribute_values,
+            )
+            .await
+        {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(DynamoDbError::NotFound.into()),
+            Err(e) => {
+                error!("DynamoDB conditional UpdateItem failed: {:?}", e);
+                Err(AnyhowError::new(e).context("Unable to update user deleted_at"))
+            }
+        }
+    }
+}
+
+/// Build the primary key of a user item using the configured key attribute names
+fn build_key(
+    table_config: &TableConfig,
+    user_id: &str,
+    organization_id: &str,
+) -> HashMap<String, AttributeValue> {
+    let mut key = HashMap::from([(
+        table_config.partition_key.clone(),
+        AttributeValue::S(user_id.to_string()),
+    )]);
+    if let Some(sort_key) = &table_config.sort_key {
+        key.insert(
+            sort_key.clone(),
+            AttributeValue::S(organization_id.to_string()),
+        );
+    }
+    key
+}
+
+/// Check whether a repository error means the requested item does not exist
+pub fn is_not_found(error: &AnyhowError) -> bool {
+    matches!(
+        error.downcast_ref::<DynamoDbError>(),
+        Some(DynamoDbError::NotFound)
+    )
+}
+
+/// Returned by `get_user_org` for a user that is suspended, e.g. because their organization is
+/// suspended
+#[derive(Debug, thiserror::Error)]
+#[error("User is suspended")]
+pub struct UserSuspendedError;
+
+/// Check whether a repository error means the user is suspended
+pub fn is_suspended(error: &AnyhowError) -> bool {
+    error.downcast_ref::<UserSuspendedError>().is_some()
+}
+
+/// Attribute holding `Organization::normalize_name` of the user's organization name
+const NORMALIZED_ORGANIZATION_NAME_ATTRIBUTE: &str = "organization_name_normalized";
+
+/// Scan filter matching the users of one organization on a name attribute
+fn organization_name_filter(
+    attribute: &str,
+    organization_name: &str,
+) -> (
+    String,
+    HashMap<String, String>,
+    HashMap<String, AttributeValue>,
+) {
+    (
+        format!("#{attribute} = :{attribute}"),
+        HashMap::from([(format!("#{attribute}"), attribute.to_string())]),
+        HashMap::from([(
+            format!(":{attribute}"),
+            AttributeValue::S(organization_name.to_string()),
+        )]),
+    )
+}
+
+/// Collect the distinct organizations referenced by user items, sorted by name
+fn collect_organizations(items: &[HashMap<String, AttributeValue>]) -> Vec<Organization> {
+    let organizations: BTreeMap<&str, &str> = items
+        .iter()
+        .filter_map(|item| {
+            let id = item.get("organization_id")?.as_s().ok()?;
+            let name = item.get("organization_name")?.as_s().ok()?;
+            Some((id.as_str(), name.as_str()))
+        })
+        .collect();
+
+    let mut organizations: Vec<Organization> = organizations
+        .into_iter()
+        .map(|(id, name)| Organization::new(id.to_string(), name.to_string()))
+        .collect();
+    organizations.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    organizations
+}
+
+/// Whether a user should be returned, hiding soft-deleted users unless requested
+fn is_visible(user: &User, include_deleted: bool) -> bool {
+    include_deleted || !user.is_deleted()
+}
+
+/// User from the first queried item, or `DynamoDbError::NotFound` if none is visible
+fn first_visible_user(
+    items: &[HashMap<String, AttributeValue>],
+    include_deleted: bool,
+) -> Result<User, AnyhowError> {
+    let Some(item) = items.first() else {
+        error!("No user found in table");
+        return Err(DynamoDbError::NotFound.into());
+    };
+
+    let user = User::from_item(item)?;
+    if is_visible(&user, include_deleted) {
+        Ok(user)
+    } else {
+        debug!("user {} is soft-deleted", user.id);
+        Err(DynamoDbError::NotFound.into())
+    }
+}
+
+/// Projection read by `get_user_org`; `deleted_at` and `status` are needed to hide soft-deleted
+/// users and reject suspended ones
+const USER_ORG_PROJECTION: &str = "#id, #organization_id, #deleted_at, #status";
+
+/// Attribute names referenced by `USER_ORG_PROJECTION` and the `#id` key condition
+fn user_org_attribute_names(table_config: &TableConfig) -> HashMap<String, String> {
+    HashMap::from([
+        ("#id".to_string(), table_config.partition_key.clone()),
+        (
+            "#organization_id".to_string(),
+            "organization_id".to_string(),
+        ),
+        ("#deleted_at".to_string(), "deleted_at".to_string()),
+        ("#status".to_string(), "status".to_string()),
+    ])
 }
 
-impl UserRepositoryImpl {
-    pub fn new(client: DynamoDbClient, table_name: String) -> Self {
-        Self { client, table_name }
+/// Organization ID from the first projected item, `DynamoDbError::NotFound` if none is visible
+/// or `UserSuspendedError` if the user is suspended
+fn user_org_from_items(items: &[HashMap<String, AttributeValue>]) -> Result<String, AnyhowError> {
+    let Some(item) = items.first() else {
+        return Err(DynamoDbError::NotFound.into());
+    };
+    if item.contains_key("deleted_at") {
+        return Err(DynamoDbError::NotFound.into());
     }
+    let status = item.get("status").and_then(|attr| attr.as_s().ok());
+    if status.is_some_and(|status| *status == UserStatus::Suspended.to_string()) {
+        return Err(UserSuspendedError.into());
+    }
+    item.get("organization_id")
+        .and_then(|attr| attr.as_s().ok())
+        .cloned()
+        .ok_or_else(|| anyhow!("Missing 'organization_id' attribute"))
+}
+
+/// KMS envelope encryption for PII, if enabled with `ENCRYPT_PII`
+fn pii_crypto() -> Option<Arc<dyn Crypto>> {
+    let config = get_config();
+    config.encrypt_pii.then(|| {
+        Arc::new(KmsCrypto::new(
+            get_env("AWS_REGION", "ap-northeast-1"),
+            config.pii_kms_key_id.clone(),
+            config.pii_index_kms_key_id.clone(),
+        )) as Arc<dyn Crypto>
+    })
+}
+
+/// User from the first queried item, if any
+fn first_user(items: &[HashMap<String, AttributeValue>]) -> Result<Option<User>, AnyhowError> {
+    items.first().map(User::from_item).transpose()
+}
+
+/// Current time in milliseconds since the Unix epoch
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Apply a status to users, keeping only those whose status changes
+fn apply_status_change(users: Vec<User>, status: UserStatus) -> Vec<User> {
+    users
+        .into_iter()
+        .filter(|user| user.status != status)
+        .map(|mut user| {
+            user.status = status;
+            user
+        })
+        .collect()
+}
+
+/// Apply new roles to a user, returning `None` when they match the current roles
+fn apply_roles_change(user: &User, roles: HashSet<Role>) -> Option<User> {
+    if user.roles == roles {
+        return None;
+    }
+
+    let mut updated_user = user.clone();
+    updated_user.roles = roles;
+    Some(updated_user)
 }
 
 #[async_trait]
-impl UserRepository for UserRepositoryImpl {
-    async fn get_user_by_id(&self, user_id: String) -> Result<User, AnyhowError> {
+impl<D: DynamoDbOps> UserRepository for UserRepositoryImpl<D> {
+    async fn get_user_by_id(
+        &self,
+        user_id: String,
+        include_deleted: bool,
+    ) -> Result<User, AnyhowError> {
+        // Without a sort key the id is the whole key; otherwise a lookup by id alone has to query
+        if self.table_config.sort_key.is_none() {
+            let key = HashMap::from([(
+                self.table_config.partition_key.clone(),
+                AttributeValue::S(user_id),
+            )]);
+            let mut items: Vec<_> = self
+                .client
+                .get_item(&self.table_name, &key)
+                .await?
+                .into_iter()
+                .collect();
+            self.decrypt_pii(&mut items).await?;
+            return first_visible_user(&items, include_deleted);
+        }
+
         let key_condition_expression = "#id = :id_value";
-        let expression_attribute_names =
-            self.client.generate_attribute_names(&[("#id", "id")]).await;
+        let expression_attribute_names = self
+            .client
+            .generate_attribute_names(&[("#id", &self.table_config.partition_key)])
+            .await;
         let expression_attribute_values = self
             .client
-            .generate_attribute_values(&[(":id", user_id)])
+            .generate_attribute_values(&[(":id_value", user_id)])
             .await;
 
         let opt = self
@@ -62,23 +646,38 @@ impl UserRepository for UserRepositoryImpl {
                 &expression_attribute_values,
             )
             .await?;
-        match opt.items {
-            Some(items) => {
-                let user = User::from_item(items.first().expect("user not found in table"))?;
-                Ok(user)
-            }
-            None => {
-                error!("No user found in table");
-                Err(anyhow!("Unable to get user by id"))
-            }
-        }
+        let mut items = opt.items.unwrap_or_default();
+        self.decrypt_pii(&mut items).await?;
+        first_visible_user(&items, include_deleted)
+    }
+
+    async fn get_user_org(&self, user_id: &str) -> Result<(String, String), AnyhowError> {
+        let expression_attribute_names = user_org_attribute_names(&self.table_config);
+        let expression_attribute_values = self
+            .client
+            .generate_attribute_values(&[(":id_value", user_id)])
+            .await;
+
+        let output = self
+            .client
+            .query_table_projected(
+                &self.table_name,
+                "#id = :id_value",
+                USER_ORG_PROJECTION,
+                &expression_attribute_names,
+                &expression_attribute_values,
+            )
+            .await?;
+        let organization_id = user_org_from_items(output.items())?;
+        Ok((user_id.to_string(), organization_id))
     }
 
     async fn get_users_by_organization_id(
         &self,
         organization_id: String,
+        include_deleted: bool,
     ) -> Result<Vec<User>, AnyhowError> {
-        let key_condition_expression = "#organization_id = :organization_id_value";
+        let key_condition_expression = "#organization_id = :organization_id";
         let expression_attribute_names = self
             .client
             .generate_attribute_names(&[("#organization_id", "organization_id")])
@@ -88,46 +687,100 @@ impl UserRepository for UserRepositoryImpl {
             .generate_attribute_values(&[(":organization_id", organization_id)])
             .await;
 
-        let opt = self
+        let mut items = self
             .client
-            .query_table(
+            .query_index_all(
                 &self.table_name,
+                &self.table_config.organization_index,
                 key_condition_expression,
                 &expression_attribute_names,
                 &expression_attribute_values,
             )
             .await?;
-
-        let items = opt
-            .items
-            .as_ref()
-            .ok_or_else(|| anyhow!("No items found"))?;
+        self.decrypt_pii(&mut items).await?;
         let users: Result<Vec<User>> = items
             .iter()
             .map(move |item| {
                 User::from_item(item).map_err(|e| anyhow!("Failed to parse user from item: {}", e))
             })
             .collect();
-        let users = users?;
+        let users = users?
+            .into_iter()
+            .filter(|user| is_visible(user, include_deleted))
+            .collect();
 
         Ok(users)
     }
 
-    async fn create_user(&self, user: User) -> Result<User, AnyhowError> {
-        debug!("Creating user in DynamoDB: {:?}", user);
-
-        let items = self
+    async fn count_users_by_organization_id(
+        &self,
+        organization_id: String,
+    ) -> Result<u64, AnyhowError> {
+        let key_condition_expression = "#organization_id = :organization_id";
+        let expression_attribute_names = self
             .client
-            .generate_attribute_values(&[
-                ("id", &user.id),
-                ("user_name", &user.name),
-                ("email", &user.email),
-                ("organization_id", &user.organization_id),
-                ("organization_name", &user.organization_name),
-                ("roles", &user.join_roles()),
+            .generate_attribute_names(&[
+                ("#organization_id", "organization_id"),
+                ("#deleted_at", "deleted_at"),
             ])
             .await;
+        let expression_attribute_values = self
+            .client
+            .generate_attribute_values(&[(":organization_id", organization_id)])
+            .await;
+
+        // Soft-deleted users do not count towards the organization
+        let count = self
+            .client
+            .count_index_query(
+                &self.table_name,
+                &self.table_config.organization_index,
+                key_condition_expression,
+                Some("attribute_not_exists(#deleted_at)"),
+                &expression_attribute_names,
+                &expression_attribute_values,
+            )
+            .await?;
+
+        Ok(count.max(0) as u64)
+    }
+
+    /// Matches case-insensitively on `email_lower`, which holds a blind index of the email when
+    /// PII encryption is enabled. Rows written before `email_lower` existed are not found.
+    async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, AnyhowError> {
+        let key_condition_expression = "#email_lower = :email_lower";
+        let expression_attribute_names = self
+            .client
+            .generate_attribute_names(&[("#email_lower", "email_lower")])
+            .await;
+
+        for expression_attribute_values in self.email_lookup_values(email).await? {
+            // Soft-deleted rows still hold the email, so they count as existing
+            let opt = self
+                .client
+                .query_index(
+                    &self.table_name,
+                    &self.table_config.email_index,
+                    key_condition_expression,
+                    &expression_attribute_names,
+                    &expression_attribute_values,
+                )
+                .await
+                .map_err(|e| AnyhowError::new(e).context("Unable to query users by email"))?;
+
+            let mut items = opt.items.unwrap_or_default();
+            if !items.is_empty() {
+                self.decrypt_pii(&mut items).await?;
+                return first_user(&items);
+            }
+        }
+        Ok(None)
+    }
 
+    async fn create_user(&self, user: User) -> Result<User, AnyhowError> {
+        debug!("Creating user in DynamoDB: {:?}", user);
+
+        let items = self.user_item(&user).await?;
         debug!("Generated DynamoDB items: {:?}", items);
 
         let _ = self
@@ -136,7 +789,7 @@ impl UserRepository for UserRepositoryImpl {
             .await
             .map_err(|e| {
                 error!("DynamoDB PutItem failed: {:?}", e);
-                anyhow!("DynamoDB PutItem failed: {:?}", e)
+                AnyhowError::new(e).context("DynamoDB PutItem failed")
             })?;
 
         // PutItem operation doesn't return attributes on success
@@ -147,132 +800,1081 @@ impl UserRepository for UserRepositoryImpl {
         Ok(user)
     }
 
+    async fn create_user_if_absent(&self, user: User) -> Result<Option<User>, AnyhowError> {
+        let items = self.user_item(&user).await?;
+        let expression_attribute_names =
+            HashMap::from([("#pk".to_string(), self.table_config.partition_key.clone())]);
+
+        let written = self
+            .client
+            .put_item_with_condition(
+                &self.table_name,
+                items,
+                "attribute_not_exists(#pk)",
+                &expression_attribute_names,
+            )
+            .await
+            .map_err(|e| AnyhowError::new(e).context("DynamoDB PutItem failed"))?;
+        if !written {
+            debug!("user {} already has a row, not creating it", user.id);
+            return Ok(None);
+        }
+        Ok(Some(user))
+    }
+
     async fn delete_user_by_id(
         &self,
         user_id: String,
         organization_id: String,
     ) -> Result<(), AnyhowError> {
-        let key = self
-            .client
-            .generate_attribute_values(&[("id", &user_id), ("organization_id", &organization_id)])
-            .await;
+        let key = build_key(&self.table_config, &user_id, &organization_id);
         let opt = self.client.delete_item(&self.table_name, &key).await;
         match opt {
             Ok(_) => Ok(()),
-            Err(e) => Err(anyhow!("Unable to delete user by id: {:?}", e)),
+            Err(e) => Err(AnyhowError::new(e).context("Unable to delete user by id")),
         }
     }
 
+    async fn soft_delete_user(
+        &self,
+        user_id: String,
+        organization_id: String,
+    ) -> Result<(), AnyhowError> {
+        self.set_deleted_at(&user_id, &organization_id, Some(now_millis()))
+            .await
+    }
+
+    async fn restore_user(
+        &self,
+        user_id: String,
+        organization_id: String,
+    ) -> Result<(), AnyhowError> {
+        self.set_deleted_at(&user_id, &organization_id, None).await
+    }
+
     async fn update_user(&self, user: User) -> Result<User, AnyhowError> {
-        let key = self
-            .client
-            .generate_attribute_values(&[
-                ("id", &user.id),
-                ("organization_id", &user.organization_id),
-            ])
-            .await;
-        let update_expression = "SET #email = :email, #user_name = :user_name, #organization_name = :organization_name, #roles = :roles";
-        let expression_attribute_names = self
+        let key = build_key(&self.table_config, &user.id, &user.organization_id);
+        let mut update_expression = "SET #email = :email, #email_lower = :email_lower, #user_name = :user_name, #organization_name = :organization_name, #organization_name_normalized = :organization_name_normalized, #roles = :roles".to_string();
+        let mut expression_attribute_names = self
             .client
             .generate_attribute_names(&[
                 ("#email", "email"),
+                ("#email_lower", "email_lower"),
                 ("#user_name", "user_name"),
                 ("#organization_name", "organization_name"),
+                (
+                    "#organization_name_normalized",
+                    NORMALIZED_ORGANIZATION_NAME_ATTRIBUTE,
+                ),
                 ("#roles", "roles"),
             ])
             .await;
-        let expression_attribute_values = self
+        let mut expression_attribute_values = self
             .client
             .generate_attribute_values(&[
-                (":email", &user.email),
                 (":user_name", &user.name),
                 (":organization_name", &user.organization_name),
-                (":roles", &user.join_roles()),
+                (
+                    ":organization_name_normalized",
+                    &user.organization_name_normalized(),
+                ),
             ])
             .await;
+        expression_attribute_values.insert(":roles".to_string(), user.roles_to_attribute_value());
+        let mut pii = HashMap::from([
+            ("email".to_string(), AttributeValue::S(user.email.clone())),
+            (
+                "email_lower".to_string(),
+                AttributeValue::S(user.email_lower()),
+            ),
+        ]);
+        self.encrypt_pii(&mut pii).await?;
+        expression_attribute_values.extend(
+            pii.into_iter()
+                .map(|(name, value)| (format!(":{name}"), value)),
+        );
+        if let Some(phone) = &user.phone {
+            update_expression.push_str(", #phone = :phone");
+            expression_attribute_names.insert("#phone".to_string(), "phone".to_string());
+            expression_attribute_values
+                .insert(":phone".to_string(), AttributeValue::S(phone.clone()));
+        }
+        if let Some(locale) = &user.locale {
+            update_expression.push_str(", #locale = :locale");
+            expression_attribute_names.insert("#locale".to_string(), "locale".to_string());
+            expression_attribute_values
+                .insert(":locale".to_string(), AttributeValue::S(locale.clone()));
+        }
         let output = self
             .client
             .update_item(
                 &self.table_name,
                 &key,
-                update_expression,
+                &update_expression,
                 &expression_attribute_names,
                 &expression_attribute_values,
             )
-            .await?;
+            .await
+            .with_context(|| format!("Unable to update user {}", user.id))?;
         match output.attributes() {
             Some(item) => {
                 debug!("dynamodb update item output: {:?}", item);
-                let user = User::from_item(item)?;
+                let mut items = [item.clone()];
+                self.decrypt_pii(&mut items).await?;
+                let user = User::from_item(&items[0])?;
                 Ok(user)
             }
             None => {
-                let err_msg = "dynamodb update item failed";
-                error!(err_msg);
-                Err(anyhow!(err_msg))
+                error!("UpdateItem returned no attributes for user {}", user.id);
+                Err(anyhow!(
+                    "Unable to update user {}: UpdateItem returned no attributes",
+                    user.id
+                ))
             }
         }
     }
 
+    async fn update_user_roles(
+        &self,
+        user: User,
+        roles: HashSet<Role>,
+    ) -> Result<User, AnyhowError> {
+        let Some(updated_user) = apply_roles_change(&user, roles) else {
+            debug!("roles unchanged for user: {}, skipping write", user.id);
+            return Ok(user);
+        };
+
+        let key = build_key(
+            &self.table_config,
+            &updated_user.id,
+            &updated_user.organization_id,
+        );
+        let update_expression = "SET #roles = :roles";
+        // Only write when the stored roles differ from the requested ones
+        let condition_expression = "#roles <> :roles";
+        let expression_attribute_names = self
+            .client
+            .generate_attribute_names(&[("#roles", "roles")])
+            .await;
+        let expression_attribute_values = HashMap::from([(
+            ":roles".to_string(),
+            updated_user.roles_to_attribute_value(),
+        )]);
+
+        match self
+            .client
+            .update_item_with_condition(
+                &self.table_name,
+                &key,
+                update_expression,
+                condition_expression,
+                &expression_attribute_names,
+                &expression_attribute_values,
+            )
+            .await
+        {
+            Ok(true) => {
+                debug!("dynamodb roles updated for user: {}", updated_user.id);
+                Ok(updated_user)
+            }
+            Ok(false) => {
+                debug!(
+                    "stored roles already up to date for user: {}",
+                    updated_user.id
+                );
+                Ok(updated_user)
+            }
+            Err(e) => {
+                error!("DynamoDB conditional UpdateItem failed: {:?}", e);
+                Err(AnyhowError::new(e).context("DynamoDB conditional UpdateItem failed"))
+            }
+        }
+    }
+
+    async fn suspend_organization(&self, organization_id: &str) -> Result<Vec<User>, AnyhowError> {
+        self.set_organization_status(organization_id, UserStatus::Suspended)
+            .await
+    }
+
+    async fn reactivate_organization(
+        &self,
+        organization_id: &str,
+    ) -> Result<Vec<User>, AnyhowError> {
+        self.set_organization_status(organization_id, UserStatus::Active)
+            .await
+    }
+
     async fn find_organization_id_by_name(
         &self,
         organization_name: &str,
     ) -> Result<Option<String>, AnyhowError> {
-        let response = self.client.scan_table(&self.table_name).await?;
+        let items = self.scan_organization_members(organization_name).await?;
 
-        let organization_id = response.items.as_ref().and_then(|items| {
-            items
-                .iter()
-                .filter_map(|item| {
-                    item.get("organization_name")
-                        .and_then(|attr| attr.as_s().ok())
-                        .filter(|&org_name| org_name == organization_name)
-                        .and_then(|_| item.get("organization_id"))
-                        .and_then(|attr| attr.as_s().ok())
-                        .map(|s| s.to_string())
-                })
-                .next()
-        });
+        let organization_ids: BTreeSet<&str> = items
+            .iter()
+            .filter_map(|item| {
+                item.get("organization_id")
+                    .and_then(|attr| attr.as_s().ok())
+            })
+            .map(String::as_str)
+            .collect();
 
-        Ok(organization_id)
+        // Names differing only in case or whitespace were once distinct organizations
+        if organization_ids.len() > 1 {
+            return Err(anyhow!(
+                "Organization name {:?} matches {} organizations: {:?}",
+                organization_name,
+                organization_ids.len(),
+                organization_ids
+            ));
+        }
+        Ok(organization_ids.first().map(|id| id.to_string()))
     }
 
     async fn organization_exists(&self, organization_name: &str) -> Result<bool, AnyhowError> {
-        let response = self.client.scan_table(&self.table_name).await?;
-
-        let exists = response
-            .items
-            .as_ref()
-            .map(|items| {
-                items.iter().any(|item| {
-                    item.get("organization_name")
-                        .and_then(|attr| attr.as_s().ok())
-                        .is_some_and(|org_name| org_name == organization_name)
-                })
-            })
-            .unwrap_or(false);
+        let items = self.scan_organization_members(organization_name).await?;
+        Ok(!items.is_empty())
+    }
 
-        Ok(exists)
+    async fn list_organizations(&self) -> Result<Vec<Organization>, AnyhowError> {
+        // Organizations only exist as attributes of their users; deleted users no longer count
+        let expression_attribute_names = HashMap::from([
+            (
+                "#organization_id".to_string(),
+                "organization_id".to_string(),
+            ),
+            (
+                "#organization_name".to_string(),
+                "organization_name".to_string(),
+            ),
+            ("#deleted_at".to_string(), "deleted_at".to_string()),
+        ]);
+        let items = self
+            .client
+            .scan_all_projected(
+                &self.table_name,
+                Some("attribute_not_exists(#deleted_at)"),
+                "#organization_id, #organization_name",
+                &expression_attribute_names,
+                &HashMap::new(),
+            )
+            .await?;
+        Ok(collect_organizations(&items))
     }
 
     async fn is_first_user_in_organization(
         &self,
         organization_name: &str,
     ) -> Result<bool, AnyhowError> {
-        let response = self.client.scan_table(&self.table_name).await?;
-
-        let has_existing_users = response
-            .items
-            .as_ref()
-            .map(|items| {
-                items.iter().any(|item| {
-                    item.get("organization_name")
-                        .and_then(|attr| attr.as_s().ok())
-                        .is_some_and(|org_name| org_name == organization_name)
-                })
-            })
-            .unwrap_or(false);
+        let items = self.scan_organization_members(organization_name).await?;
+        Ok(items.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws::dynamodb::in_memory::InMemoryDynamoDb;
+
+    #[test]
+    fn test_build_key_with_default_schema() {
+        let key = build_key(&TableConfig::default(), "user-1", "org-1");
+
+        assert_eq!(key.len(), 2);
+        assert_eq!(key["id"].as_s().unwrap(), "user-1");
+        assert_eq!(key["organization_id"].as_s().unwrap(), "org-1");
+    }
+
+    #[test]
+    fn test_build_key_with_configured_schema() {
+        let table_config = TableConfig {
+            partition_key: "PK".to_string(),
+            sort_key: Some("SK".to_string()),
+            ..TableConfig::default()
+        };
+        let key = build_key(&table_config, "user-1", "org-1");
+
+        assert_eq!(key.len(), 2);
+        assert_eq!(key["PK"].as_s().unwrap(), "user-1");
+        assert_eq!(key["SK"].as_s().unwrap(), "org-1");
+        assert!(!key.contains_key("id"));
+    }
+
+    #[test]
+    fn test_build_key_without_sort_key() {
+        let table_config = TableConfig {
+            sort_key: None,
+            ..TableConfig::default()
+        };
+        let key = build_key(&table_config, "user-1", "org-1");
+
+        assert_eq!(key.len(), 1);
+        assert_eq!(key["id"].as_s().unwrap(), "user-1");
+    }
+
+    fn create_test_user(roles: &[Role]) -> User {
+        User::new(
+            "user-1".to_string(),
+            "Alice".to_string(),
+            "alice@example.com".to_string(),
+            "org-1".to_string(),
+            "ExampleOrg".to_string(),
+            roles.iter().cloned().collect(),
+        )
+    }
+
+    #[test]
+    fn test_is_not_found() {
+        assert!(is_not_found(&DynamoDbError::NotFound.into()));
+        assert!(!is_not_found(&anyhow!("Unable to get user by id")));
+        assert!(!is_not_found(
+            &DynamoDbError::Unknown("throttled".to_string()).into()
+        ));
+    }
+
+    #[test]
+    fn test_user_org_projection_reads_only_needed_attributes() {
+        let names = user_org_attribute_names(&TableConfig::default());
+        let projected: HashSet<&str> = USER_ORG_PROJECTION
+            .split(", ")
+            .map(|placeholder| names[placeholder].as_str())
+            .collect();
+
+        assert_eq!(
+            projected,
+            HashSet::from(["id", "organization_id", "deleted_at", "status"])
+        );
+        assert_eq!(names.len(), projected.len());
+    }
+
+    #[test]
+    fn test_user_org_from_items() {
+        let item = || {
+            HashMap::from([
+                ("id".to_string(), AttributeValue::S("user-1".to_string())),
+                (
+                    "organization_id".to_string(),
+                    AttributeValue::S("org-1".to_string()),
+                ),
+            ])
+        };
+        assert_eq!(user_org_from_items(&[item()]).unwrap(), "org-1");
+
+        let mut deleted = item();
+        deleted.insert("deleted_at".to_string(), AttributeValue::N("1".to_string()));
+        assert!(is_not_found(&user_org_from_items(&[deleted]).unwrap_err()));
+        assert!(is_not_found(&user_org_from_items(&[]).unwrap_err()));
+
+        let mut suspended = item();
+        suspended.insert(
+            "status".to_string(),
+            AttributeValue::S("Suspended".to_string()),
+        );
+        assert!(is_suspended(
+            &user_org_from_items(&[suspended]).unwrap_err()
+        ));
+    }
+
+    #[test]
+    fn test_organization_name_filter() {
+        let (filter_expression, names, values) =
+            organization_name_filter("organization_name", "Example");
+
+        assert_eq!(filter_expression, "#organization_name = :organization_name");
+        assert_eq!(names["#organization_name"], "organization_name");
+        assert_eq!(values[":organization_name"].as_s().unwrap(), "Example");
+    }
+
+    #[test]
+    fn test_collect_organizations_deduplicates_by_id() {
+        let item = |org_id: &str, org_name: &str| {
+            HashMap::from([
+                (
+                    "organization_id".to_string(),
+                    AttributeValue::S(org_id.to_string()),
+                ),
+                (
+                    "organization_name".to_string(),
+                    AttributeValue::S(org_name.to_string()),
+                ),
+            ])
+        };
+        let items = vec![
+            item("org-2", "Beta"),
+            item("org-1", "Alpha"),
+            item("org-2", "Beta"),
+            HashMap::new(),
+        ];
+
+        assert_eq!(
+            collect_organizations(&items),
+            vec![
+                Organization::new("org-1".to_string(), "Alpha".to_string()),
+                Organization::new("org-2".to_string(), "Beta".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_user_from_index_items() {
+        assert!(first_user(&[]).unwrap().is_none());
+
+        let user = create_test_user(&[Role::Reader]);
+        let item = HashMap::from([
+            ("id".to_string(), AttributeValue::S(user.id.clone())),
+            ("name".to_string(), AttributeValue::S(user.name.clone())),
+            ("email".to_string(), AttributeValue::S(user.email.clone())),
+            (
+                "organization_id".to_string(),
+                AttributeValue::S(user.organization_id.clone()),
+            ),
+            (
+                "organization_name".to_string(),
+                AttributeValue::S(user.organization_name.clone()),
+            ),
+            ("roles".to_string(), AttributeValue::S(user.join_roles())),
+        ]);
+        let found = first_user(&[item]).unwrap().unwrap();
+        assert_eq!(found.email, user.email);
+    }
+
+    #[test]
+    fn test_first_visible_user_empty_result_is_not_found() {
+        let error = first_visible_user(&[], false).unwrap_err();
+        assert!(is_not_found(&error));
+    }
+
+    #[test]
+    fn test_is_visible_hides_soft_deleted_users() {
+        let mut user = create_test_user(&[Role::Reader]);
+        assert!(is_visible(&user, false));
+
+        user.deleted_at = Some(now_millis());
+        assert!(!is_visible(&user, false));
+        assert!(is_visible(&user, true));
+    }
+
+    #[tokio::test]
+    async fn test_email_lookup_matches_stored_row_regardless_of_case() {
+        let repository = in_memory_repository();
+        let mut user = create_test_user(&[Role::Reader]);
+        user.email = "User@Example.com".to_string();
+        let stored = AttributeValue::S(user.email_lower());
+
+        for email in ["User@Example.com", "user@example.com", " USER@example.COM "] {
+            let values = repository.email_lookup_values(email).await.unwrap();
+            assert_eq!(values.len(), 1);
+            assert_eq!(values[0][":email_lower"], stored);
+        }
+        // The original casing is kept for display
+        assert_eq!(user.email, "User@Example.com");
+    }
+
+    #[test]
+    fn test_apply_status_change_updates_whole_organization() {
+        let mut already_suspended = create_test_user(&[Role::Reader]);
+        already_suspended.id = "user-2".to_string();
+        already_suspended.status = UserStatus::Suspended;
+        let users = vec![create_test_user(&[Role::Admin]), already_suspended];
+
+        let changed = apply_status_change(users.clone(), UserStatus::Suspended);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].id, "user-1");
+        assert!(changed[0].is_suspended());
+
+        let changed = apply_status_change(users, UserStatus::Active);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].id, "user-2");
+        assert_eq!(changed[0].status, UserStatus::Active);
+    }
+
+    #[test]
+    fn test_apply_roles_change_no_op_skips_write() {
+        let user = create_test_user(&[Role::Reader, Role::Writer]);
+        let roles: HashSet<Role> = [Role::Writer, Role::Reader].into_iter().collect();
+
+        assert!(apply_roles_change(&user, roles).is_none());
+    }
+
+    #[test]
+    fn test_apply_roles_change_writes_on_real_change() {
+        let user = create_test_user(&[Role::Reader]);
+        let roles: HashSet<Role> = [Role::Admin].into_iter().collect();
+
+        let updated_user = apply_roles_change(&user, roles).expect("roles should change");
+        assert!(updated_user.has_role(Role::Admin));
+        assert!(!updated_user.has_role(Role::Reader));
+        assert_eq!(updated_user.id, user.id);
+    }
+
+    fn in_memory_repository() -> UserRepositoryImpl<InMemoryDynamoDb> {
+        UserRepositoryImpl::with_table_config(
+            InMemoryDynamoDb::new(),
+            "users".to_string(),
+            TableConfig::default(),
+        )
+    }
+
+    fn org_member(id: &str, organization_id: &str, organization_name: &str, role: Role) -> User {
+        User::new(
+            id.to_string(),
+            format!("User {id}"),
+            format!("{id}@Example.com"),
+            organization_id.to_string(),
+            organization_name.to_string(),
+            HashSet::from([role]),
+        )
+    }
 
-        Ok(!has_existing_users)
+    #[tokio::test]
+    async fn test_table_without_sort_key() {
+        let table_config = TableConfig {
+            sort_key: None,
+            ..TableConfig::default()
+        };
+        let repository = UserRepositoryImpl::with_table_config(
+            InMemoryDynamoDb::with_table_config(table_config.clone()),
+            "users".to_string(),
+            table_config,
+        );
+        let user = org_member("user-1", "org-1", "Acme", Role::Reader);
+        repository.create_user(user.clone()).await.unwrap();
+
+        let updated = repository
+            .update_user_roles(user, HashSet::from([Role::Writer]))
+            .await
+            .unwrap();
+        assert!(updated.has_role(Role::Writer));
+        let found = repository
+            .get_user_by_id("user-1".to_string(), false)
+            .await
+            .unwrap();
+        assert_eq!(found.organization_id, "org-1");
+        assert!(found.has_role(Role::Writer));
+        let missing = repository
+            .get_user_by_id("user-2".to_string(), false)
+            .await
+            .unwrap_err();
+        assert!(is_not_found(&missing));
+
+        repository
+            .soft_delete_user("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+        let deleted = repository
+            .get_user_by_id("user-1".to_string(), false)
+            .await
+            .unwrap_err();
+        assert!(is_not_found(&deleted));
+        assert!(repository
+            .get_user_by_id("user-1".to_string(), true)
+            .await
+            .unwrap()
+            .is_deleted());
+
+        repository
+            .delete_user_by_id("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+        assert!(repository.client.items("users").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_then_get_user() {
+        let repository = in_memory_repository();
+        let user = org_member("user-1", "org-1", "Acme", Role::Admin);
+        repository.create_user(user.clone()).await.unwrap();
+
+        let found = repository
+            .get_user_by_id("user-1".to_string(), false)
+            .await
+            .unwrap();
+        assert_eq!(found.email, user.email);
+        assert_eq!(found.organization_id, "org-1");
+        assert!(found.has_role(Role::Admin));
+
+        let missing = repository
+            .get_user_by_id("user-2".to_string(), false)
+            .await
+            .unwrap_err();
+        assert!(is_not_found(&missing));
+    }
+
+    #[tokio::test]
+    async fn test_first_user_in_organization() {
+        let repository = in_memory_repository();
+        assert!(repository
+            .is_first_user_in_organization("Acme")
+            .await
+            .unwrap());
+        assert!(!repository.organization_exists("Acme").await.unwrap());
+
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Admin))
+            .await
+            .unwrap();
+
+        assert!(!repository
+            .is_first_user_in_organization("Acme")
+            .await
+            .unwrap());
+        assert!(repository.organization_exists("Acme").await.unwrap());
+        assert!(repository
+            .is_first_user_in_organization("Other")
+            .await
+            .unwrap());
+        assert_eq!(
+            repository
+                .find_organization_id_by_name("Acme")
+                .await
+                .unwrap(),
+            Some("org-1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_organization_lookup_ignores_case_and_whitespace() {
+        let repository = in_memory_repository();
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Admin))
+            .await
+            .unwrap();
+
+        for name in ["Acme", "acme", "Acme "] {
+            assert_eq!(
+                repository.find_organization_id_by_name(name).await.unwrap(),
+                Some("org-1".to_string()),
+                "{name:?}"
+            );
+            assert!(!repository
+                .is_first_user_in_organization(name)
+                .await
+                .unwrap());
+        }
+        assert_eq!(
+            repository
+                .find_organization_id_by_name("Acme Corp")
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            repository.client.items("users")[0]["organization_name"]
+                .as_s()
+                .unwrap(),
+            "Acme"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_organization_lookup_matches_legacy_rows_by_exact_name() {
+        let repository = in_memory_repository();
+        let legacy = HashMap::from([
+            ("id".to_string(), AttributeValue::S("user-1".to_string())),
+            (
+                "organization_id".to_string(),
+                AttributeValue::S("org-1".to_string()),
+            ),
+            (
+                "organization_name".to_string(),
+                AttributeValue::S("Acme".to_string()),
+            ),
+        ]);
+        repository.client.put_item("users", legacy).await.unwrap();
+
+        assert_eq!(
+            repository
+                .find_organization_id_by_name("Acme")
+                .await
+                .unwrap(),
+            Some("org-1".to_string())
+        );
+        assert_eq!(
+            repository
+                .find_organization_id_by_name("acme")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_organization_lookup_rejects_ambiguous_names() {
+        let repository = in_memory_repository();
+        for user in [
+            org_member("user-1", "org-1", "Acme", Role::Admin),
+            org_member("user-2", "org-2", "acme", Role::Admin),
+        ] {
+            repository.create_user(user).await.unwrap();
+        }
+
+        let error = repository
+            .find_organization_id_by_name("ACME")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("matches 2 organizations"));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_normalizes_legacy_organization_names() {
+        let repository = in_memory_repository();
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Admin))
+            .await
+            .unwrap();
+        let mut legacy = repository.client.items("users").remove(0);
+        legacy.remove(NORMALIZED_ORGANIZATION_NAME_ATTRIBUTE);
+        repository.client.put_item("users", legacy).await.unwrap();
+        assert_eq!(
+            repository
+                .find_organization_id_by_name("acme")
+                .await
+                .unwrap(),
+            None
+        );
+
+        assert_eq!(repository.backfill_lookup_attributes().await.unwrap(), 1);
+        assert_eq!(
+            repository
+                .find_organization_id_by_name("acme")
+                .await
+                .unwrap(),
+            Some("org-1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_organization_queries_only_see_members() {
+        let repository = in_memory_repository();
+        for user in [
+            org_member("user-1", "org-1", "Acme", Role::Admin),
+            org_member("user-2", "org-1", "Acme", Role::Reader),
+            org_member("user-3", "org-2", "Beta", Role::Admin),
+        ] {
+            repository.create_user(user).await.unwrap();
+        }
+
+        let members = repository
+            .get_users_by_organization_id("org-1".to_string(), false)
+            .await
+            .unwrap();
+        let mut ids: Vec<&str> = members.iter().map(|user| user.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["user-1", "user-2"]);
+        assert_eq!(
+            repository
+                .count_users_by_organization_id("org-1".to_string())
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            repository.list_organizations().await.unwrap(),
+            vec![
+                Organization::new("org-1".to_string(), "Acme".to_string()),
+                Organization::new("org-2".to_string(), "Beta".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_organizations_skips_deleted_users() {
+        let repository = in_memory_repository();
+        for user in [
+            org_member("user-1", "org-1", "Acme", Role::Admin),
+            org_member("user-2", "org-2", "Beta", Role::Admin),
+        ] {
+            repository.create_user(user).await.unwrap();
+        }
+        repository
+            .soft_delete_user("user-2".to_string(), "org-2".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository.list_organizations().await.unwrap(),
+            vec![Organization::new("org-1".to_string(), "Acme".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_and_restore() {
+        let repository = in_memory_repository();
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Admin))
+            .await
+            .unwrap();
+
+        repository
+            .soft_delete_user("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+        let hidden = repository
+            .get_user_by_id("user-1".to_string(), false)
+            .await
+            .unwrap_err();
+        assert!(is_not_found(&hidden));
+        assert!(is_not_found(
+            &repository.get_user_org("user-1").await.unwrap_err()
+        ));
+        let deleted = repository
+            .get_user_by_id("user-1".to_string(), true)
+            .await
+            .unwrap();
+        assert!(deleted.is_deleted());
+        assert!(repository
+            .get_users_by_organization_id("org-1".to_string(), false)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repository
+                .count_users_by_organization_id("org-1".to_string())
+                .await
+                .unwrap(),
+            0
+        );
+
+        repository
+            .restore_user("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            repository.get_user_org("user-1").await.unwrap(),
+            ("user-1".to_string(), "org-1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_user_if_absent_keeps_existing_rows() {
+        let repository = in_memory_repository();
+        let created = repository
+            .create_user_if_absent(org_member("user-1", "org-1", "Acme", Role::Admin))
+            .await
+            .unwrap();
+        assert!(created.is_some());
+        repository
+            .soft_delete_user("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+
+        let created = repository
+            .create_user_if_absent(org_member("user-1", "org-1", "Acme", Role::Reader))
+            .await
+            .unwrap();
+        assert!(created.is_none());
+        let stored = repository
+            .get_user_by_id("user-1".to_string(), true)
+            .await
+            .unwrap();
+        assert!(stored.is_deleted());
+        assert_eq!(stored.roles, HashSet::from([Role::Admin]));
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_missing_user_is_not_found() {
+        let repository = in_memory_repository();
+
+        let error = repository
+            .soft_delete_user("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap_err();
+        assert!(is_not_found(&error));
+        assert!(repository.client.items("users").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_user_returns_stored_user() {
+        let repository = in_memory_repository();
+        let mut user = org_member("user-1", "org-1", "Acme", Role::Reader);
+        repository.create_user(user.clone()).await.unwrap();
+
+        user.name = "Renamed".to_string();
+        user.email = "New@Example.com".to_string();
+        user.locale = Some("ja-JP".to_string());
+        let updated = repository.update_user(user).await.unwrap();
+
+        assert_eq!(updated.name, "Renamed");
+        assert_eq!(updated.locale.as_deref(), Some("ja-JP"));
+        let found = repository
+            .find_user_by_email("new@example.com")
+            .await
+            .unwrap()
+            .expect("user should be found by its new email");
+        assert_eq!(found.id, "user-1");
+        assert!(repository
+            .find_user_by_email("user-1@example.com")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    /// Reversible stand-in for KMS with a deterministic blind index
+    struct MockCrypto;
+
+    #[async_trait]
+    impl Crypto for MockCrypto {
+        async fn encrypt(&self, plaintext: &str) -> Result<String, AnyhowError> {
+            Ok(plaintext.chars().rev().collect())
+        }
+
+        async fn decrypt(&self, ciphertext: &str) -> Result<String, AnyhowError> {
+            Ok(ciphertext.chars().rev().collect())
+        }
+
+        async fn blind_index(&self, plaintext: &str) -> Result<String, AnyhowError> {
+            Ok(plaintext.to_uppercase())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_user_by_email_with_encrypted_pii() {
+        let mut repository = in_memory_repository();
+        // Written before PII encryption was enabled
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Reader))
+            .await
+            .unwrap();
+        repository.crypto = Some(Arc::new(MockCrypto));
+        repository
+            .create_user(org_member("user-2", "org-1", "Acme", Role::Reader))
+            .await
+            .unwrap();
+
+        let stored = repository.client.items("users");
+        assert_eq!(
+            stored[1]["email_lower"].as_s().unwrap(),
+            "idx:USER-2@EXAMPLE.COM"
+        );
+        let found = repository
+            .find_user_by_email("USER-2@example.com")
+            .await
+            .unwrap()
+            .expect("user should be found by its blind index");
+        assert_eq!(found.id, "user-2");
+        assert_eq!(found.email, "user-2@Example.com");
+        let legacy = repository
+            .find_user_by_email("user-1@example.com")
+            .await
+            .unwrap()
+            .expect("plaintext rows should still be found");
+        assert_eq!(legacy.id, "user-1");
+    }
+
+    #[tokio::test]
+    async fn test_backfill_rewrites_stale_lookup_attributes() {
+        let mut repository = in_memory_repository();
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Reader))
+            .await
+            .unwrap();
+        let mut legacy = repository.client.items("users").remove(0);
+        legacy.remove("email_lower");
+        legacy.insert("id".to_string(), AttributeValue::S("user-2".to_string()));
+        repository.client.put_item("users", legacy).await.unwrap();
+
+        assert_eq!(repository.backfill_lookup_attributes().await.unwrap(), 1);
+        assert_eq!(repository.backfill_lookup_attributes().await.unwrap(), 0);
+
+        repository.crypto = Some(Arc::new(MockCrypto));
+        assert_eq!(repository.backfill_lookup_attributes().await.unwrap(), 2);
+        assert!(repository
+            .find_user_by_email("user-1@example.com")
+            .await
+            .unwrap()
+            .is_some());
+        assert!(repository
+            .client
+            .items("users")
+            .iter()
+            .all(|item| item["email_lower"].as_s().unwrap().starts_with("idx:")));
+    }
+
+    #[tokio::test]
+    async fn test_update_user_roles() {
+        let repository = in_memory_repository();
+        let user = org_member("user-1", "org-1", "Acme", Role::Reader);
+        repository.create_user(user.clone()).await.unwrap();
+
+        let updated = repository
+            .update_user_roles(user, HashSet::from([Role::Admin, Role::Writer]))
+            .await
+            .unwrap();
+        assert!(updated.has_role(Role::Admin));
+
+        let stored = repository
+            .get_user_by_id("user-1".to_string(), false)
+            .await
+            .unwrap();
+        assert_eq!(stored.roles, HashSet::from([Role::Writer, Role::Admin]));
+    }
+
+    #[tokio::test]
+    async fn test_suspend_and_reactivate_organization() {
+        let repository = in_memory_repository();
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Admin))
+            .await
+            .unwrap();
+
+        let suspended = repository.suspend_organization("org-1").await.unwrap();
+        assert_eq!(suspended.len(), 1);
+        let stored = repository
+            .get_user_by_id("user-1".to_string(), false)
+            .await
+            .unwrap();
+        assert!(stored.is_suspended());
+        assert!(is_suspended(
+            &repository.get_user_org("user-1").await.unwrap_err()
+        ));
+
+        assert!(repository
+            .suspend_organization("org-1")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repository
+                .reactivate_organization("org-1")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            repository.get_user_org("user-1").await.unwrap(),
+            ("user-1".to_string(), "org-1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_status_does_not_create_missing_user() {
+        let repository = in_memory_repository();
+        let mut user = org_member("user-1", "org-1", "Acme", Role::Admin);
+        user.status = UserStatus::Suspended;
+
+        let error = repository.set_status(&user).await.unwrap_err();
+        assert!(is_not_found(&error));
+        assert!(repository.client.items("users").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_user_removes_item() {
+        let repository = in_memory_repository();
+        repository
+            .create_user(org_member("user-1", "org-1", "Acme", Role::Admin))
+            .await
+            .unwrap();
+
+        repository
+            .delete_user_by_id("user-1".to_string(), "org-1".to_string())
+            .await
+            .unwrap();
+
+        assert!(repository.client.items("users").is_empty());
+        assert!(repository
+            .is_first_user_in_organization("Acme")
+            .await
+            .unwrap());
     }
 }
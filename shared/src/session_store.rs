@@ -0,0 +1,519 @@
+use crate::aws::dynamodb::client::DynamoDbClient;
+use crate::aws::dynamodb::error::DynamoDbError;
+use crate::cache_manager::get_cache_manager;
+use crate::config::get_config;
+use crate::entity::idempotency::{BatchItemOutcome, IdempotentResponse};
+use crate::errors::{LambdaError, ToLambdaError};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{instrument, warn};
+
+const KEY_ATTR: &str = "key";
+const VALUE_ATTR: &str = "value";
+const COUNT_ATTR: &str = "count";
+/// DynamoDB TTL attribute, in seconds since the Unix epoch
+const EXPIRES_AT_ATTR: &str = "expires_at";
+/// Value holding an idempotency key while the request that claimed it runs
+const IN_PROGRESS_MARKER: &str = "in-progress";
+/// How long a claim holds its key if the claiming invocation never completes; above the
+/// 30s function timeout
+const IDEMPOTENCY_CLAIM_TTL: Duration = Duration::from_secs(60);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Short-lived key/value entries shared by every Lambda instance, expired by DynamoDB TTL
+#[derive(Clone)]
+pub struct SessionStore {
+    client: DynamoDbClient,
+    table_name: String,
+}
+
+impl SessionStore {
+    pub fn new(client: DynamoDbClient, table_name: String) -> Self {
+        Self { client, table_name }
+    }
+
+    /// Store on the `SESSION_TABLE_NAME` table, or `None` when unset so callers keep
+    /// their per-instance caches
+    pub fn from_env(client: DynamoDbClient) -> Option<Self> {
+        get_config()
+            .session_table_name
+            .clone()
+            .map(|table_name| Self::new(client, table_name))
+    }
+
+    fn key(key: &str) -> HashMap<String, AttributeValue> {
+        HashMap::from([(KEY_ATTR.to_string(), AttributeValue::S(key.to_string()))])
+    }
+
+    fn names(attrs: &[&str]) -> HashMap<String, String> {
+        attrs
+            .iter()
+            .map(|attr| (format!("#{attr}"), attr.to_string()))
+            .collect()
+    }
+
+    /// Write `value` under `key` for `ttl` unless a live entry exists; returns whether it was written.
+    /// Entries past `expires_at` that DynamoDB has not reaped yet are overwritten.
+    #[instrument(skip(self, value), fields(table = %self.table_name), name = "session_store.put_if_absent")]
+    pub async fn put_if_absent(
+        &self,
+        key: &str,
+        value: &str,
+        ttl: Duration,
+    ) -> Result<bool, DynamoDbError> {
+        let now = now_secs();
+        let values = HashMap::from([
+            (":value".to_string(), AttributeValue::S(value.to_string())),
+            (
+                ":expires_at".to_string(),
+                AttributeValue::N((now + ttl.as_secs()).to_string()),
+            ),
+            (":now".to_string(), AttributeValue::N(now.to_string())),
+        ]);
+        self.client
+            .update_item_with_condition(
+                &self.table_name,
+                &Self::key(key),
+                "SET #value = :value, #expires_at = :expires_at",
+                "attribute_not_exists(#key) OR #expires_at <= :now",
+                &Self::names(&[KEY_ATTR, VALUE_ATTR, EXPIRES_AT_ATTR]),
+                &values,
+            )
+            .await
+    }
+
+    /// Overwrite `key` with `value` for `ttl` if it still holds `expected`; returns whether it was written
+    #[instrument(skip(self, expected, value), fields(table = %self.table_name), name = "session_store.replace")]
+    pub async fn replace(
+        &self,
+        key: &str,
+        expected: &str,
+        value: &str,
+        ttl: Duration,
+    ) -> Result<bool, DynamoDbError> {
+        let values = HashMap::from([
+            (
+                ":expected".to_string(),
+                AttributeValue::S(expected.to_string()),
+            ),
+            (":value".to_string(), AttributeValue::S(value.to_string())),
+            (
+                ":expires_at".to_string(),
+                AttributeValue::N((now_secs() + ttl.as_secs()).to_string()),
+            ),
+        ]);
+        self.client
+            .update_item_with_condition(
+                &self.table_name,
+                &Self::key(key),
+                "SET #value = :value, #expires_at = :expires_at",
+                "#value = :expected",
+                &Self::names(&[VALUE_ATTR, EXPIRES_AT_ATTR]),
+                &values,
+            )
+            .await
+    }
+
+    /// Value stored under `key`, treating expired entries as absent
+    #[instrument(skip(self), fields(table = %self.table_name), name = "session_store.get")]
+    pub async fn get(&self, key: &str) -> Result<Option<String>, DynamoDbError> {
+        let item = self
+            .client
+            .get_item(&self.table_name, &Self::key(key))
+            .await?;
+        Ok(item.and_then(|item| live_value(&item, now_secs())))
+    }
+
+    /// Count a hit on `key` unless it already reached `max`; returns whether the hit was counted.
+    /// The counter expires after `ttl`, so callers put the window into the key.
+    #[instrument(skip(self), fields(table = %self.table_name), name = "session_store.increment_below")]
+    pub async fn increment_below(
+        &self,
+        key: &str,
+        max: u32,
+        ttl: Duration,
+    ) -> Result<bool, DynamoDbError> {
+        let values = HashMap::from([
+            (":one".to_string(), AttributeValue::N("1".to_string())),
+            (":max".to_string(), AttributeValue::N(max.to_string())),
+            (
+                ":expires_at".to_string(),
+                AttributeValue::N((now_secs() + ttl.as_secs()).to_string()),
+            ),
+        ]);
+        self.client
+            .update_item_with_condition(
+                &self.table_name,
+                &Self::key(key),
+                "ADD #count :one SET #expires_at = :expires_at",
+                "attribute_not_exists(#count) OR #count < :max",
+                &Self::names(&[COUNT_ATTR, EXPIRES_AT_ATTR]),
+                &values,
+            )
+            .await
+    }
+}
+
+/// String value of a session item, or `None` once `expires_at` has passed
+fn live_value(item: &HashMap<String, AttributeValue>, now: u64) -> Option<String> {
+    let expires_at = item
+        .get(EXPIRES_AT_ATTR)
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse::<u64>().ok())?;
+    if expires_at <= now {
+        return None;
+    }
+    item.get(VALUE_ATTR).and_then(|v| v.as_s().ok()).cloned()
+}
+
+/// Key of the fixed rate-limit window containing `now`
+fn rate_limit_key(user_id: &str, window: Duration, now: u64) -> String {
+    let window = window.as_secs().max(1);
+    format!("ratelimit#{user_id}#{}", now / window)
+}
+
+/// Count a request by `user_id` against the shared store, or the instance-local limiter without one.
+/// A `max` of 0 disables the limit; store errors fall back to the local limiter.
+pub async fn check_rate_limit(
+    store: Option<&SessionStore>,
+    user_id: &str,
+    max: u32,
+    window: Duration,
+) -> bool {
+    if let (Some(store), true) = (store, max > 0) {
+        let key = rate_limit_key(user_id, window, now_secs());
+        match store.increment_below(&key, max, window).await {
+            Ok(allowed) => return allowed,
+            Err(e) => warn!("Shared rate limit unavailable, using local limiter: {}", e),
+        }
+    }
+    get_cache_manager()
+        .check_rate_limit(user_id, max, window)
+        .await
+}
+
+/// Session store key of an idempotency key
+fn idempotency_store_key(key: &str) -> String {
+    format!("idempotency#{key}")
+}
+
+/// Result of claiming an idempotency key before running the request it guards
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyClaim {
+    /// The caller holds the key and runs the request
+    Claimed,
+    /// Another invocation holds the key and has not finished yet
+    InProgress,
+    /// The request already completed with this response
+    Recorded(IdempotentResponse),
+}
+
+/// Claim an idempotency key, so concurrent retries cannot both run the request. The claim is
+/// an in-progress marker written with `put_if_absent`; it lapses after `IDEMPOTENCY_CLAIM_TTL`
+/// if the invocation never calls `complete_idempotent` or `release_idempotent`. Without a
+/// shared store, or when it fails, only responses recorded by this instance are seen.
+pub async fn claim_idempotent(store: Option<&SessionStore>, key: &str) -> IdempotencyClaim {
+    if let Some(store) = store {
+        let store_key = idempotency_store_key(key);
+        let claimed = match store
+            .put_if_absent(&store_key, IN_PROGRESS_MARKER, IDEMPOTENCY_CLAIM_TTL)
+            .await
+        {
+            Ok(true) => Ok(IdempotencyClaim::Claimed),
+            Ok(false) => store.get(&store_key).await.map(|value| match value {
+                Some(value) if value != IN_PROGRESS_MARKER => serde_json::from_str(&value)
+                    .map(IdempotencyClaim::Recorded)
+                    .unwrap_or_else(|e| {
+                        warn!("Ignoring unreadable idempotent response: {}", e);
+                        IdempotencyClaim::InProgress
+                    }),
+                // Held, or expired since the write was refused; the client retries either way
+                _ => IdempotencyClaim::InProgress,
+            }),
+            Err(e) => Err(e),
+        };
+        match claimed {
+            Ok(claim) => return claim,
+            Err(e) => warn!(
+                "Shared idempotency store unavailable, using local cache: {}",
+                e
+            ),
+        }
+    }
+    match get_cache_manager().get_idempotent(key).await {
+        Some(recorded) => IdempotencyClaim::Recorded(recorded),
+        None => IdempotencyClaim::Claimed,
+    }
+}
+
+/// Record the response for a claimed idempotency key, replacing the in-progress marker
+pub async fn complete_idempotent(
+    store: Option<&SessionStore>,
+    key: String,
+    response: IdempotentResponse,
+) {
+    if let Some(store) = store {
+        let completed = match serde_json::to_string(&response) {
+            Ok(value) => store
+                .replace(
+                    &idempotency_store_key(&key),
+                    IN_PROGRESS_MARKER,
+                    &value,
+                    get_config().idempotency_ttl,
+                )
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match completed {
+            Ok(true) => {}
+            Ok(false) => warn!("Idempotency claim lapsed before completing: {}", key),
+            Err(e) => warn!(
+                "Failed to record idempotent response in shared store: {}",
+                e
+            ),
+        }
+    }
+    get_cache_manager().set_idempotent(key, response).await;
+}
+
+/// Give up a claimed idempotency key without recording a response, so a retry runs again
+pub async fn release_idempotent(store: Option<&SessionStore>, key: &str) {
+    if let Some(store) = store {
+        // Expiring the marker frees the key for `put_if_absent` right away
+        if let Err(e) = store
+            .replace(
+                &idempotency_store_key(key),
+                IN_PROGRESS_MARKER,
+                IN_PROGRESS_MARKER,
+                Duration::ZERO,
+            )
+            .await
+        {
+            warn!("Failed to release idempotency key {}: {}", key, e);
+        }
+    }
+}
+
+/// Idempotency key of one item of an organization's batch operation. Keys are scoped per
+/// organization so a key reused by another organization never replays its outcomes.
+fn batch_item_key(organization_id: &str, key: &str, item_id: &str) -> String {
+    format!("org:{organization_id}:{key}:{item_id}")
+}
+
+/// Apply a batch operation under an org-scoped idempotency key. Each item is claimed before
+/// it is applied and its outcome recorded as it completes, so a batch retried after a partial
+/// failure replays the outcomes of items already processed and applies only the rest, and an
+/// item still being applied by a concurrent attempt gets a 409. Server errors are not recorded,
+/// so those items are applied again; an item whose body changed under the same key is rejected.
+pub async fn idempotent_batch<T, F, Fut>(
+    store: Option<&SessionStore>,
+    organization_id: &str,
+    key: &str,
+    items: Vec<(String, T)>,
+    apply: F,
+) -> Result<Vec<BatchItemOutcome>, LambdaError>
+where
+    T: Serialize,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = BatchItemOutcome>,
+{
+    let mut outcomes = Vec::with_capacity(items.len());
+    for (item_id, item) in items {
+        let item_key = batch_item_key(organization_id, key, &item_id);
+        let item_body = serde_json::to_vec(&item).map_err(|e| e.to_lambda_error())?;
+
+        let outcome = match claim_idempotent(store, &item_key).await {
+            IdempotencyClaim::Recorded(recorded) if recorded.matches(&item_body) => {
+                BatchItemOutcome::replayed(item_id, recorded)
+            }
+            IdempotencyClaim::Recorded(_) => {
+                BatchItemOutcome::failed(item_id, &LambdaError::IdempotencyKeyMismatch)
+            }
+            IdempotencyClaim::InProgress => {
+                BatchItemOutcome::failed(item_id, &LambdaError::IdempotencyKeyInProgress)
+            }
+            IdempotencyClaim::Claimed => {
+                let outcome = apply(item).await;
+                if outcome.is_final() {
+                    let response = IdempotentResponse::new(
+                        &item_body,
+                        outcome.status_code,
+                        outcome.body.clone(),
+                    );
+                    complete_idempotent(store, item_key, response).await;
+                } else {
+                    release_idempotent(store, &item_key).await;
+                }
+                outcome
+            }
+        };
+        outcomes.push(outcome);
+    }
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(value: &str, expires_at: u64) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            (VALUE_ATTR.to_string(), AttributeValue::S(value.to_string())),
+            (
+                EXPIRES_AT_ATTR.to_string(),
+                AttributeValue::N(expires_at.to_string()),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_live_value_ignores_expired_items() {
+        assert_eq!(live_value(&item("v", 200), 100).as_deref(), Some("v"));
+        // Past its TTL but not yet reaped by DynamoDB
+        assert_eq!(live_value(&item("v", 100), 100), None);
+        assert_eq!(live_value(&item("v", 50), 100), None);
+    }
+
+    #[test]
+    fn test_live_value_requires_expiry() {
+        let mut item = item("v", 200);
+        item.remove(EXPIRES_AT_ATTR);
+        assert_eq!(live_value(&item, 100), None);
+    }
+
+    #[test]
+    fn test_rate_limit_key_is_per_window() {
+        let window = Duration::from_secs(60);
+        assert_eq!(rate_limit_key("user-1", window, 120), "ratelimit#user-1#2");
+        assert_eq!(
+            rate_limit_key("user-1", window, 179),
+            rate_limit_key("user-1", window, 120)
+        );
+        assert_ne!(
+            rate_limit_key("user-1", window, 180),
+            rate_limit_key("user-1", window, 120)
+        );
+    }
+
+    #[test]
+    fn test_batch_item_key_is_scoped_per_organization() {
+        assert_eq!(
+            batch_item_key("org-1", "key-1", "user-1"),
+            "org:org-1:key-1:user-1"
+        );
+        assert_ne!(
+            batch_item_key("org-1", "key-1", "user-1"),
+            batch_item_key("org-2", "key-1", "user-1")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_claim_without_store_replays_completed_response() {
+        let key = "user-1:claim-key";
+        assert_eq!(claim_idempotent(None, key).await, IdempotencyClaim::Claimed);
+
+        let response = IdempotentResponse::new("{}", 200, r#"{"id":"user-2"}"#.to_string());
+        complete_idempotent(None, key.to_string(), response.clone()).await;
+        assert_eq!(
+            claim_idempotent(None, key).await,
+            IdempotencyClaim::Recorded(response)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_released_claim_without_store_can_be_claimed_again() {
+        let key = "user-1:released-key";
+        assert_eq!(claim_idempotent(None, key).await, IdempotencyClaim::Claimed);
+        release_idempotent(None, key).await;
+        assert_eq!(claim_idempotent(None, key).await, IdempotencyClaim::Claimed);
+    }
+
+    fn outcome(item_id: &str, status_code: i64) -> BatchItemOutcome {
+        BatchItemOutcome {
+            item_id: item_id.to_string(),
+            status_code,
+            body: format!(r#"{{"id":"{item_id}"}}"#),
+            replayed: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retried_batch_does_not_reapply_completed_items() {
+        let applied = std::sync::Mutex::new(Vec::new());
+        let items = || {
+            vec![
+                ("user-1".to_string(), "Admin"),
+                ("user-2".to_string(), "Reader"),
+            ]
+        };
+        let mut fail_user_2 = true;
+
+        for _ in 0..2 {
+            let outcomes = idempotent_batch(None, "org-retry", "key-1", items(), |role| {
+                let item_id = if role == "Admin" { "user-1" } else { "user-2" };
+                applied.lock().unwrap().push(item_id);
+                let status_code = if item_id == "user-2" && fail_user_2 {
+                    500
+                } else {
+                    200
+                };
+                async move { outcome(item_id, status_code) }
+            })
+            .await
+            .unwrap();
+            assert_eq!(outcomes.len(), 2);
+            if fail_user_2 {
+                assert_eq!(outcomes[1].status_code, 500);
+            } else {
+                assert!(outcomes[0].replayed);
+                assert_eq!(outcomes[0].body, r#"{"id":"user-1"}"#);
+                assert!(!outcomes[1].replayed);
+                assert_eq!(outcomes[1].status_code, 200);
+            }
+            fail_user_2 = false;
+        }
+
+        // user-1 was applied once; the failed user-2 was applied again on retry
+        assert_eq!(*applied.lock().unwrap(), ["user-1", "user-2", "user-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_item_reused_with_different_body_is_rejected() {
+        let first = idempotent_batch(
+            None,
+            "org-mismatch",
+            "key-1",
+            vec![("user-1".to_string(), "Admin")],
+            |_| async { outcome("user-1", 200) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(first[0].status_code, 200);
+
+        let retried = idempotent_batch(
+            None,
+            "org-mismatch",
+            "key-1",
+            vec![("user-1".to_string(), "Reader")],
+            |_| async { panic!("a mismatched item must not be applied") },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            retried[0].status_code,
+            LambdaError::IdempotencyKeyMismatch.status_code()
+        );
+        assert!(!retried[0].replayed);
+    }
+}
@@ -1,14 +1,64 @@
 use crate::utils::env::get_env;
 
-use opentelemetry::trace::TracerProvider;
+use once_cell::sync::OnceCell;
+use opentelemetry::trace::{TraceContextExt, TraceId, TracerProvider};
 use opentelemetry::KeyValue;
 use opentelemetry_sdk::trace as sdktrace;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_subscriber::{fmt, layer::SubscriberExt, EnvFilter, Registry};
 
+/// Provider built by `init_tracing`, kept so `shutdown_tracing` can flush it
+static TRACER_PROVIDER: OnceCell<sdktrace::TracerProvider> = OnceCell::new();
+
+/// Output format of the log layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One JSON object per event, queryable in CloudWatch Logs Insights
+    Json,
+    /// Human-readable lines for local development
+    Text,
+}
+
+/// `LOG_FORMAT` (`json` or `text`) if set, otherwise JSON everywhere but the `local` environment
+fn log_format(log_format: Option<&str>, service_environment: &str) -> LogFormat {
+    match log_format.map(str::to_ascii_lowercase).as_deref() {
+        Some("json") => LogFormat::Json,
+        Some("text") => LogFormat::Text,
+        _ if service_environment == "local" => LogFormat::Text,
+        _ => LogFormat::Json,
+    }
+}
+
+/// Filter from a `RUST_LOG`-style directive string, falling back to `info` when unset or invalid
+fn env_filter(rust_log: Option<&str>) -> EnvFilter {
+    rust_log
+        .and_then(|directives| EnvFilter::try_new(directives).ok())
+        .unwrap_or_else(|| EnvFilter::new("info"))
+}
+
+/// Format an OpenTelemetry trace ID the way X-Ray displays it, e.g. `1-5759e988-bd862e3fe1be46a994272793`
+fn xray_trace_id(trace_id: TraceId) -> String {
+    let hex = format!("{trace_id:032x}");
+    format!("1-{}-{}", &hex[..8], &hex[8..])
+}
+
+/// X-Ray trace ID of `span`, if it belongs to a sampled trace
+pub fn current_xray_trace_id(span: &tracing::Span) -> Option<String> {
+    let context = span.context();
+    let span_context = context.span().span_context().clone();
+    span_context
+        .is_valid()
+        .then(|| xray_trace_id(span_context.trace_id()))
+}
+
 pub fn init_tracing() {
     let service_name = get_env("SERVICE_NAME", "local");
     let service_version = get_env("SERVICE_VERSION", "local");
     let service_environment = get_env("SERVICE_ENVIRONMENT", "local");
+    let log_format = log_format(
+        std::env::var("LOG_FORMAT").ok().as_deref(),
+        &service_environment,
+    );
 
     let tracer_provider = sdktrace::TracerProvider::builder()
         .with_config(
@@ -17,10 +67,11 @@ pub fn init_tracing() {
                 .with_resource(opentelemetry_sdk::resource::Resource::new(vec![
                     KeyValue::new("service.name", service_name.clone()),
                     KeyValue::new("service.version", service_version),
-                    KeyValue::new("environment", service_environment),
+                    KeyValue::new("environment", service_environment.clone()),
                 ])),
         )
         .build();
+    let _ = TRACER_PROVIDER.set(tracer_provider.clone());
 
     let tracer = tracer_provider
         .tracer_builder(service_name.clone())
@@ -28,12 +79,109 @@ pub fn init_tracing() {
         .build();
 
     let telemetry_layer = tracing_opentelemetry::layer().with_tracer(tracer);
-    let filter_layer = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    // Registered before every other layer, so it also gates what reaches X-Ray
+    let filter_layer = env_filter(std::env::var(EnvFilter::DEFAULT_ENV).ok().as_deref());
+    // JSON events carry the span list, including the `trace_id` recorded by `handle_requests`
+    let json_layer = (log_format == LogFormat::Json).then(|| {
+        fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+    });
+    let text_layer = (log_format == LogFormat::Text).then(fmt::layer);
     let subscriber = Registry::default()
         .with(filter_layer)
         .with(telemetry_layer)
-        .with(fmt::layer());
+        .with(json_layer)
+        .with(text_layer);
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set tracing subscriber.");
 
-    tracing::info!("Tracing initialized for AWS X‑Ray");
+    tracing::info!(?log_format, "Tracing initialized for AWS X‑Ray");
+}
+
+/// Export buffered spans and shut the provider down; call once the runtime stops
+/// so the last spans of an invocation are not lost when the environment freezes
+pub fn shutdown_tracing() {
+    let Some(tracer_provider) = TRACER_PROVIDER.get() else {
+        return;
+    };
+    for result in tracer_provider.force_flush() {
+        if let Err(e) = result {
+            tracing::warn!("Failed to flush spans: {}", e);
+        }
+    }
+    if let Err(e) = tracer_provider.shutdown() {
+        tracing::warn!("Failed to shut down tracer provider: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format_defaults_by_environment() {
+        assert_eq!(log_format(None, "local"), LogFormat::Text);
+        assert_eq!(log_format(None, "prod"), LogFormat::Json);
+        assert_eq!(log_format(Some("bogus"), "dev"), LogFormat::Json);
+    }
+
+    #[test]
+    fn test_log_format_env_overrides_environment() {
+        assert_eq!(log_format(Some("json"), "local"), LogFormat::Json);
+        assert_eq!(log_format(Some("TEXT"), "prod"), LogFormat::Text);
+    }
+
+    /// Count the events that make it through `filter`
+    fn count_emitted(filter: EnvFilter) -> usize {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tracing_subscriber::layer::{Context, Layer};
+
+        struct Counter(Arc<AtomicUsize>);
+        impl<S: tracing::Subscriber> Layer<S> for Counter {
+            fn on_event(&self, _event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let subscriber = Registry::default()
+            .with(filter)
+            .with(Counter(count.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("debug");
+            tracing::info!("info");
+            tracing::warn!("warn");
+            tracing::error!("error");
+        });
+        count.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_env_filter_warn_suppresses_debug_and_info() {
+        assert_eq!(count_emitted(env_filter(Some("warn"))), 2);
+    }
+
+    #[test]
+    fn test_env_filter_defaults_to_info() {
+        assert_eq!(count_emitted(env_filter(None)), 3);
+        assert_eq!(count_emitted(env_filter(Some("not a [directive"))), 3);
+        assert_eq!(count_emitted(env_filter(Some("debug"))), 4);
+    }
+
+    #[test]
+    fn test_xray_trace_id_format() {
+        let trace_id = TraceId::from_hex("5759e988bd862e3fe1be46a994272793").unwrap();
+        assert_eq!(
+            xray_trace_id(trace_id),
+            "1-5759e988-bd862e3fe1be46a994272793"
+        );
+    }
+
+    #[test]
+    fn test_shutdown_tracing_without_init_is_noop() {
+        assert!(TRACER_PROVIDER.get().is_none());
+        shutdown_tracing();
+    }
 }
@@ -0,0 +1,75 @@
+use crate::audit_logger::log_audit_event;
+use crate::aws::cognito::client::CognitoClient;
+use crate::aws::eventbridge::{
+    client::EventBridgePublisher,
+    publisher::{publish_user_event, USER_DELETED},
+};
+use crate::cache_manager::get_cache_manager;
+use crate::entity::audit_event::{AuditAction, AuditEvent, AuditOutcome};
+use crate::entity::user::User;
+use crate::errors::{LambdaError, LambdaResult};
+use crate::repository::audit_repository::AuditRepository;
+use crate::repository::user_repository::UserRepository;
+
+/// Delete `user` on behalf of `actor_user_id`, then drop its cached entries, record the audit
+/// event and publish `USER_DELETED`.
+///
+/// A soft delete disables the Cognito user and marks the row deleted so the account can be
+/// audited or restored; a hard delete removes both.
+pub async fn delete_user(
+    cognito_client: &CognitoClient,
+    repository: &impl UserRepository,
+    audit_repository: &impl AuditRepository,
+    actor_user_id: &str,
+    user: &User,
+    hard: bool,
+) -> LambdaResult<()> {
+    let cognito_username = user.cognito_username().to_string();
+    if hard {
+        cognito_client
+            .admin_delete_user(cognito_username)
+            .await
+            .map_err(|e| LambdaError::from_cognito_error(e, LambdaError::UserDeletionFailed))?;
+
+        repository
+            .delete_user_by_id(user.id.clone(), user.organization_id.clone())
+            .await
+            .map_err(|e| LambdaError::from_repository_error(e, LambdaError::UserDeletionFailed))?;
+    } else {
+        // Keep a soft-deleted user from signing in while the row is kept
+        cognito_client
+            .admin_disable_user(cognito_username)
+            .await
+            .map_err(|e| LambdaError::from_cognito_error(e, LambdaError::UserDeletionFailed))?;
+
+        repository
+            .soft_delete_user(user.id.clone(), user.organization_id.clone())
+            .await
+            .map_err(|e| LambdaError::from_repository_error(e, LambdaError::UserDeletionFailed))?;
+    }
+
+    // Drop cached permission decisions so the deleted user is not served from cache
+    let cache_manager = get_cache_manager();
+    cache_manager.invalidate_user(&user.id).await;
+    cache_manager
+        .invalidate_org_users(&user.organization_id)
+        .await;
+
+    let audit_event = AuditEvent::new(
+        actor_user_id.to_string(),
+        AuditAction::DeleteUser,
+        Some(user.id.clone()),
+        user.organization_id.clone(),
+        AuditOutcome::Success,
+    );
+    log_audit_event(audit_repository, audit_event).await;
+    publish_user_event(
+        &EventBridgePublisher::from_env(),
+        USER_DELETED,
+        &user.id,
+        &user.organization_id,
+    )
+    .await;
+
+    Ok(())
+}
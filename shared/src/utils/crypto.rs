@@ -0,0 +1,22 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+/// Prefix marking a stored attribute value as ciphertext
+pub const ENCRYPTED_PREFIX: &str = "enc:";
+
+/// Prefix marking a stored attribute value as a blind index
+pub const BLIND_INDEX_PREFIX: &str = "idx:";
+
+/// Encryption of individual attribute values
+#[async_trait]
+pub trait Crypto: Send + Sync {
+    /// Encrypt `plaintext` into an opaque, storable string
+    async fn encrypt(&self, plaintext: &str) -> Result<String, Error>;
+
+    /// Decrypt a string produced by `encrypt`
+    async fn decrypt(&self, ciphertext: &str) -> Result<String, Error>;
+
+    /// Deterministic keyed digest of `plaintext`, so equal values can be looked up without
+    /// storing them
+    async fn blind_index(&self, plaintext: &str) -> Result<String, Error>;
+}
@@ -1,3 +1,4 @@
+pub mod crypto;
 pub mod env;
 pub mod password;
 pub mod regex;
@@ -1,16 +1,356 @@
+use crate::config::get_config;
+use crate::errors::LambdaError;
+use crate::validation::FieldErrorCode;
+
 use passwords::PasswordGenerator;
 
 const PASSWORD_LENGTH: usize = 24;
 
+/// Character set and length of a generated password
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordGeneratorOptions {
+    pub length: usize,
+    pub include_symbols: bool,
+    pub include_spaces: bool,
+}
+
+/// Password rules shared by generated passwords and password validators
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    /// Minimum number of characters
+    pub min_length: usize,
+    /// Require at least one uppercase letter
+    pub require_upper: bool,
+    /// Require at least one lowercase letter
+    pub require_lower: bool,
+    /// Require at least one digit
+    pub require_digit: bool,
+    /// Require at least one symbol
+    pub require_symbol: bool,
+    /// Allow whitespace characters
+    pub allow_spaces: bool,
+    /// Include spaces in generated passwords; off by default since some clients mishandle them
+    pub generate_spaces: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_upper: true,
+            require_lower: true,
+            require_digit: true,
+            require_symbol: false,
+            allow_spaces: true,
+            generate_spaces: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Get the password policy from environment variables
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let bool_var = |name: &str, default: bool| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<bool>().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            min_length: std::env::var("PASSWORD_MIN_LENGTH")
+                .ok()
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(default.min_length),
+            require_upper: bool_var("PASSWORD_REQUIRE_UPPER", default.require_upper),
+            require_lower: bool_var("PASSWORD_REQUIRE_LOWER", default.require_lower),
+            require_digit: bool_var("PASSWORD_REQUIRE_DIGIT", default.require_digit),
+            require_symbol: bool_var("PASSWORD_REQUIRE_SYMBOL", default.require_symbol),
+            allow_spaces: bool_var("PASSWORD_ALLOW_SPACES", default.allow_spaces),
+            generate_spaces: bool_var("PASSWORD_GENERATE_SPACES", default.generate_spaces),
+        }
+    }
+
+    /// Validate a password against the policy
+    pub fn validate(&self, password: &str) -> Result<(), LambdaError> {
+        if self.check(password).is_empty() {
+            Ok(())
+        } else {
+            Err(LambdaError::InvalidPassword)
+        }
+    }
+
+    /// List every policy rule the password breaks
+    pub fn check(&self, password: &str) -> Vec<FieldErrorCode> {
+        let mut codes = Vec::new();
+        if password.chars().count() < self.min_length {
+            codes.push(FieldErrorCode::PasswordTooShort);
+        }
+
+        if !self.allow_spaces && password.chars().any(char::is_whitespace) {
+            codes.push(FieldErrorCode::PasswordContainsWhitespace);
+        }
+
+        let has_upper = password.chars().any(|c| c.is_uppercase());
+        let has_lower = password.chars().any(|c| c.is_lowercase());
+        let has_digit = password.chars().any(|c| c.is_ascii_digit());
+        let has_symbol = password
+            .chars()
+            .any(|c| !c.is_alphanumeric() && !c.is_whitespace());
+
+        if self.require_upper && !has_upper {
+            codes.push(FieldErrorCode::PasswordMissingUppercase);
+        }
+        if self.require_lower && !has_lower {
+            codes.push(FieldErrorCode::PasswordMissingLowercase);
+        }
+        if self.require_digit && !has_digit {
+            codes.push(FieldErrorCode::PasswordMissingDigit);
+        }
+        if self.require_symbol && !has_symbol {
+            codes.push(FieldErrorCode::PasswordMissingSymbol);
+        }
+
+        codes
+    }
+
+    /// Default generator options: 24 characters (or `min_length` if longer) with symbols, and
+    /// spaces only with `generate_spaces`
+    pub fn generator_options(&self) -> PasswordGeneratorOptions {
+        PasswordGeneratorOptions {
+            length: PASSWORD_LENGTH.max(self.min_length),
+            include_symbols: true,
+            include_spaces: self.generate_spaces,
+        }
+    }
+
+    /// Generate a random password satisfying the policy
+    pub fn generate(&self) -> Result<String, &'static str> {
+        self.generate_with(self.generator_options())
+    }
+
+    /// Generate a random password with the given options, still satisfying the policy: the
+    /// length is raised to `min_length` and symbols are kept if the policy requires one
+    pub fn generate_with(&self, options: PasswordGeneratorOptions) -> Result<String, &'static str> {
+        PasswordGenerator::new()
+            .length(options.length.max(self.min_length))
+            .numbers(true)
+            .lowercase_letters(true)
+            .uppercase_letters(true)
+            .symbols(options.include_symbols || self.require_symbol)
+            .spaces(options.include_spaces && self.allow_spaces)
+            .exclude_similar_characters(true)
+            .strict(true)
+            .generate_one()
+    }
+}
+
+/// Generate a random password using the configured policy
 pub fn generate_password() -> Result<String, &'static str> {
-    PasswordGenerator::new()
-        .length(PASSWORD_LENGTH)
-        .numbers(true)
-        .lowercase_letters(true)
-        .uppercase_letters(true)
-        .symbols(true)
-        .spaces(true)
-        .exclude_similar_characters(true)
-        .strict(true)
-        .generate_one()
+    generate_password_with(get_config().password_policy.generator_options())
+}
+
+/// Generate a random password with the given options under the configured policy
+pub fn generate_password_with(options: PasswordGeneratorOptions) -> Result<String, &'static str> {
+    get_config().password_policy.generate_with(options)
+}
+
+/// Points per character, counted up to `STRENGTH_LENGTH_CAP` characters
+const STRENGTH_POINTS_PER_CHAR: i32 = 3;
+const STRENGTH_LENGTH_CAP: i32 = 20;
+/// Points per character class used: lowercase, uppercase, digit, symbol
+const STRENGTH_POINTS_PER_CLASS: i32 = 10;
+/// Points lost per character that repeats the one before it
+const STRENGTH_REPEAT_PENALTY: i32 = 3;
+
+/// Heuristic strength score from 0 to 100, for strength meters only. It rewards length and
+/// character-class diversity and penalizes runs of the same character; the policy is enforced
+/// separately by `PasswordPolicy::validate`.
+pub fn password_strength(password: &str) -> u8 {
+    let length = password.chars().count() as i32;
+    let classes = [
+        password.chars().any(char::is_lowercase),
+        password.chars().any(char::is_uppercase),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password
+            .chars()
+            .any(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+    ]
+    .into_iter()
+    .filter(|&present| present)
+    .count() as i32;
+    let repeats = password
+        .chars()
+        .zip(password.chars().skip(1))
+        .filter(|(a, b)| a == b)
+        .count() as i32;
+
+    let score = length.min(STRENGTH_LENGTH_CAP) * STRENGTH_POINTS_PER_CHAR
+        + classes * STRENGTH_POINTS_PER_CLASS
+        - repeats * STRENGTH_REPEAT_PENALTY;
+    score.clamp(0, 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_every_broken_rule() {
+        let policy = PasswordPolicy {
+            allow_spaces: false,
+            ..PasswordPolicy::default()
+        };
+
+        assert!(policy.check("Password123").is_empty());
+        assert_eq!(
+            policy.check("pass word"),
+            vec![
+                FieldErrorCode::PasswordContainsWhitespace,
+                FieldErrorCode::PasswordMissingUppercase,
+                FieldErrorCode::PasswordMissingDigit,
+            ]
+        );
+        assert_eq!(
+            policy.check("ab"),
+            vec![
+                FieldErrorCode::PasswordTooShort,
+                FieldErrorCode::PasswordMissingUppercase,
+                FieldErrorCode::PasswordMissingDigit,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_policy_validation() {
+        let policy = PasswordPolicy::default();
+
+        assert!(policy.validate("Password123").is_ok());
+        assert!(policy.validate("Pass12").is_err()); // Too short
+        assert!(policy.validate("password123").is_err()); // No uppercase
+        assert!(policy.validate("PASSWORD123").is_err()); // No lowercase
+        assert!(policy.validate("PasswordABC").is_err()); // No digit
+        assert!(policy.validate("Password 123").is_ok()); // Spaces are allowed
+
+        let policy = PasswordPolicy {
+            allow_spaces: false,
+            ..PasswordPolicy::default()
+        };
+        assert!(policy.validate("Password 123").is_err()); // Contains a space
+    }
+
+    #[test]
+    fn test_custom_policy_validation() {
+        let policy = PasswordPolicy {
+            min_length: 12,
+            require_symbol: true,
+            allow_spaces: true,
+            ..PasswordPolicy::default()
+        };
+
+        assert!(policy.validate("Password 123!").is_ok());
+        assert!(policy.validate("Password123!").is_ok());
+        assert!(policy.validate("Pass 123!").is_err()); // Too short
+        assert!(policy.validate("Password 1234").is_err()); // No symbol
+    }
+
+    #[test]
+    fn test_generated_password_satisfies_policy() {
+        let policy = PasswordPolicy {
+            require_symbol: true,
+            ..PasswordPolicy::default()
+        };
+
+        for _ in 0..20 {
+            let password = policy.generate().unwrap();
+            assert_eq!(password.chars().count(), PASSWORD_LENGTH);
+            assert!(!password.contains(' '));
+            assert!(policy.validate(&password).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_generated_password_spaces_are_opt_in() {
+        let policy = PasswordPolicy::default();
+        assert!(!policy.generator_options().include_spaces);
+
+        let policy = PasswordPolicy {
+            generate_spaces: true,
+            ..PasswordPolicy::default()
+        };
+        assert!(policy.generator_options().include_spaces);
+
+        let policy = PasswordPolicy {
+            allow_spaces: false,
+            generate_spaces: true,
+            ..PasswordPolicy::default()
+        };
+        for _ in 0..20 {
+            assert!(!policy.generate().unwrap().contains(' '));
+        }
+    }
+
+    #[test]
+    fn test_generated_password_respects_min_length() {
+        let policy = PasswordPolicy {
+            min_length: 32,
+            ..PasswordPolicy::default()
+        };
+
+        assert_eq!(policy.generate().unwrap().chars().count(), 32);
+    }
+
+    #[test]
+    fn test_generate_with_options_without_symbols() {
+        let policy = PasswordPolicy::default();
+        let options = PasswordGeneratorOptions {
+            length: 16,
+            include_symbols: false,
+            include_spaces: false,
+        };
+
+        for _ in 0..20 {
+            let password = policy.generate_with(options).unwrap();
+            assert_eq!(password.chars().count(), 16);
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+            assert!(policy.validate(&password).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_generate_with_keeps_symbols_required_by_policy() {
+        let policy = PasswordPolicy {
+            require_symbol: true,
+            ..PasswordPolicy::default()
+        };
+        let options = PasswordGeneratorOptions {
+            include_symbols: false,
+            ..policy.generator_options()
+        };
+
+        let password = policy.generate_with(options).unwrap();
+        assert!(policy.validate(&password).is_ok());
+    }
+
+    #[test]
+    fn test_password_strength_scores() {
+        assert_eq!(password_strength(""), 0);
+        assert_eq!(password_strength("aaaaaaaa"), 13);
+        assert_eq!(password_strength("password"), 31);
+        assert_eq!(password_strength("Password123"), 60);
+        assert_eq!(password_strength("correct horse battery staple"), 64);
+        assert_eq!(password_strength("xK#9vL!2qW@7zR$4mN&8"), 100);
+    }
+
+    #[test]
+    fn test_password_strength_ranks_weak_below_strong() {
+        let weak = password_strength("Passw0rd");
+        let strong = password_strength("Passw0rd-with-more-length");
+        assert!(weak < strong);
+        assert!(password_strength("Aaaaaaaa1") < password_strength("Abcdefgh1"));
+
+        let password = PasswordPolicy::default().generate().unwrap();
+        assert!(password_strength(&password) >= 80);
+    }
 }
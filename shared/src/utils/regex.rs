@@ -12,6 +12,13 @@ pub static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
 pub static USERNAME_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[\p{L}][\p{L}'\.\-]*(?:\s+[\p{L}][\p{L}'\.\-]*){0,2}$").unwrap());
 
+// E.164 phone number pattern (leading +, country code, up to 15 digits)
+pub static PHONE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\+[1-9]\d{1,14}$").unwrap());
+
+// Cognito username pattern (letters, marks, symbols, numbers and punctuation without whitespace)
+pub static COGNITO_USERNAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[\p{L}\p{M}\p{S}\p{N}\p{P}]{1,128}$").unwrap());
+
 // Additional validation for name length (1-50 characters)
 fn is_valid_username_length(name: &str) -> bool {
     let len = name.chars().count();
@@ -65,6 +72,26 @@ pub fn is_valid_username(name: &str) -> bool {
         && is_well_formatted_username(name)
 }
 
+// Phone number validation in E.164 format
+pub fn is_valid_phone(phone: &str) -> bool {
+    PHONE_REGEX.is_match(phone)
+}
+
+// Languages with localized emails and UI
+pub const SUPPORTED_LANGUAGES: &[&str] = &["de", "en", "es", "fr", "ja", "ko", "pt", "zh"];
+
+// Locale validation: a supported language, optionally with an ISO 3166 region (e.g. `en`, `ja-JP`)
+pub fn is_valid_locale(locale: &str) -> bool {
+    let (language, region) = match locale.split_once('-') {
+        Some((language, region)) => (language, Some(region)),
+        None => (locale, None),
+    };
+    SUPPORTED_LANGUAGES.contains(&language)
+        && region.is_none_or(|region| {
+            region.len() == 2 && region.chars().all(|c| c.is_ascii_uppercase())
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -344,6 +371,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_valid_locale() {
+        for locale in ["en", "ja", "en-US", "ja-JP", "zh-TW"] {
+            assert!(is_valid_locale(locale), "Locale should be valid: {locale}");
+        }
+        for locale in [
+            "zz!", "zz", "", "EN", "en-us", "en-USA", "en-", "ja_JP", "en-US-x",
+        ] {
+            assert!(
+                !is_valid_locale(locale),
+                "Locale should be invalid: {locale}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_valid_phone() {
+        let valid_phones = ["+819012345678", "+14155552671", "+447911123456", "+12"];
+        for phone in &valid_phones {
+            assert!(is_valid_phone(phone), "Phone should be valid: {phone}");
+        }
+
+        let invalid_phones = [
+            "",
+            "09012345678",
+            "+0123456789",
+            "+81 90 1234 5678",
+            "+81-90-1234-5678",
+            "+1234567890123456",
+            "+1",
+            "+81abc",
+        ];
+        for phone in &invalid_phones {
+            assert!(!is_valid_phone(phone), "Phone should be invalid: {phone}");
+        }
+    }
+
+    #[test]
+    fn test_cognito_username_regex() {
+        let valid_usernames = ["alice", "alice_01", "alice@example.com", "アリス"];
+        for username in &valid_usernames {
+            assert!(
+                COGNITO_USERNAME_REGEX.is_match(username),
+                "Cognito username should be valid: {username}"
+            );
+        }
+
+        let long_username = "a".repeat(129);
+        let invalid_usernames = ["", "alice smith", " alice", long_username.as_str()];
+        for username in &invalid_usernames {
+            assert!(
+                !COGNITO_USERNAME_REGEX.is_match(username),
+                "Cognito username should be invalid: {username}"
+            );
+        }
+    }
+
     #[test]
     fn test_regex_compilation() {
         // Test that regex patterns compile without panicking
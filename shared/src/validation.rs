@@ -0,0 +1,184 @@
+use crate::errors::LambdaError;
+
+use serde::{Deserialize, Serialize};
+
+/// Canonical form of an email used for lookups: Cognito matches emails case-insensitively
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Request payloads that check their own fields before being handled
+pub trait Validate {
+    fn validate(&self) -> Result<(), LambdaError>;
+}
+
+/// Stable, machine-readable code for a request validation failure
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FieldErrorCode {
+    UsernameInvalid,
+    CognitoUsernameInvalid,
+    EmailInvalid,
+    PhoneInvalid,
+    LocaleInvalid,
+    PasswordEmpty,
+    PasswordTooShort,
+    PasswordContainsWhitespace,
+    PasswordMissingUppercase,
+    PasswordMissingLowercase,
+    PasswordMissingDigit,
+    PasswordMissingSymbol,
+    OrganizationIdMissing,
+    OrganizationNameInvalid,
+    RolesMissing,
+    RoleNotAssignable,
+    TempPasswordLengthInvalid,
+    TokenMissing,
+    TokenInvalid,
+    GrantTypeInvalid,
+    RefreshTokenMissing,
+    AssignmentsInvalid,
+}
+
+impl FieldErrorCode {
+    /// Human readable description of the failure
+    pub fn message(&self) -> &'static str {
+        match self {
+            FieldErrorCode::UsernameInvalid => {
+                "Name must be 1-50 characters of letters, up to three words"
+            }
+            FieldErrorCode::CognitoUsernameInvalid => {
+                "Cognito username must be 1-128 characters without whitespace"
+            }
+            FieldErrorCode::EmailInvalid => "Please provide a valid email address",
+            FieldErrorCode::PhoneInvalid => {
+                "Phone number must be in E.164 format (e.g. +819012345678)"
+            }
+            FieldErrorCode::LocaleInvalid => {
+                "Locale must be a supported language, optionally with a region (e.g. en, ja-JP)"
+            }
+            FieldErrorCode::PasswordEmpty => "Password must not be empty",
+            FieldErrorCode::PasswordTooShort => "Password is too short",
+            FieldErrorCode::PasswordContainsWhitespace => "Password must not contain whitespace",
+            FieldErrorCode::PasswordMissingUppercase => "Password must contain an uppercase letter",
+            FieldErrorCode::PasswordMissingLowercase => "Password must contain a lowercase letter",
+            FieldErrorCode::PasswordMissingDigit => "Password must contain a number",
+            FieldErrorCode::PasswordMissingSymbol => "Password must contain a symbol",
+            FieldErrorCode::OrganizationIdMissing => "Organization ID is required",
+            FieldErrorCode::OrganizationNameInvalid => {
+                "Organization name must be between 2 and 100 characters"
+            }
+            FieldErrorCode::RolesMissing => "At least one role must be specified",
+            FieldErrorCode::RoleNotAssignable => {
+                "SuperAdmin can only be granted by platform operators"
+            }
+            FieldErrorCode::TempPasswordLengthInvalid => {
+                "Temporary password length must be between 12 and 64 characters"
+            }
+            FieldErrorCode::TokenMissing => "Token is required",
+            FieldErrorCode::TokenInvalid => "Invalid token provided",
+            FieldErrorCode::GrantTypeInvalid => "Grant type must be refresh_token",
+            FieldErrorCode::RefreshTokenMissing => "Refresh token is required",
+            FieldErrorCode::AssignmentsInvalid => {
+                "Between 1 and 100 assignments with distinct user IDs are required"
+            }
+        }
+    }
+}
+
+/// A single validation failure for a request field
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub code: FieldErrorCode,
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: FieldErrorCode) -> Self {
+        FieldError {
+            code,
+            field: field.to_string(),
+            message: code.message().to_string(),
+        }
+    }
+}
+
+/// Collects every validation failure of a request before reporting them together
+#[derive(Debug, Default)]
+pub struct ValidationErrors {
+    errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a failure for `field`
+    pub fn add(&mut self, field: &str, code: FieldErrorCode) {
+        self.errors.push(FieldError::new(field, code));
+    }
+
+    /// Record every code in `codes` as a failure for `field`
+    pub fn extend(&mut self, field: &str, codes: impl IntoIterator<Item = FieldErrorCode>) {
+        for code in codes {
+            self.add(field, code);
+        }
+    }
+
+    /// `Ok` when nothing was recorded, otherwise `LambdaError::ValidationFailed`
+    pub fn into_result(self) -> Result<(), LambdaError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(LambdaError::ValidationFailed(self.errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_validation_errors_is_ok() {
+        assert!(ValidationErrors::new().into_result().is_ok());
+    }
+
+    #[test]
+    fn test_validation_errors_serialize_with_codes() {
+        let mut errors = ValidationErrors::new();
+        errors.add("email", FieldErrorCode::EmailInvalid);
+        errors.extend(
+            "password",
+            [
+                FieldErrorCode::PasswordTooShort,
+                FieldErrorCode::PasswordMissingDigit,
+            ],
+        );
+
+        let error = errors.into_result().unwrap_err();
+        assert_eq!(error.status_code(), 400);
+        assert_eq!(
+            serde_json::to_value(error.field_errors()).unwrap(),
+            serde_json::json!([
+                {
+                    "code": "EMAIL_INVALID",
+                    "field": "email",
+                    "message": "Please provide a valid email address"
+                },
+                {
+                    "code": "PASSWORD_TOO_SHORT",
+                    "field": "password",
+                    "message": "Password is too short"
+                },
+                {
+                    "code": "PASSWORD_MISSING_DIGIT",
+                    "field": "password",
+                    "message": "Password must contain a number"
+                }
+            ])
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! End-to-end tests of the user handlers against LocalStack Cognito, DynamoDB and Secrets Manager.
+//!
+//! Run with `TEST_LOCALSTACK_ENDPOINT=http://localhost:4566 cargo make test-integration`.
+
+use integration_tests::{
+    api_event, configure_env, json_body, localstack_endpoint, provision, ORG_USER_QUOTA, REGION,
+    TABLE_NAME,
+};
+
+use aws_lambda_events::http::Method;
+use shared::aws::dynamodb::client::DynamoDbClient;
+use shared::entity::user::{Role, User};
+use shared::repository::user_repository::{UserRepository, UserRepositoryImpl};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const USERS_RESOURCE: &str = "/organizations/{organizationId}/users";
+const USER_RESOURCE: &str = "/organizations/{organizationId}/users/{userId}";
+
+#[tokio::test]
+async fn test_user_lifecycle_through_handlers() {
+    let Some(endpoint) = localstack_endpoint() else {
+        eprintln!("TEST_LOCALSTACK_ENDPOINT is not set, skipping");
+        return;
+    };
+    configure_env(&endpoint);
+    let user_pool_id = provision(&endpoint).await;
+
+    let dynamodb = DynamoDbClient::new(REGION.to_string()).await.unwrap();
+    let repository = UserRepositoryImpl::new(dynamodb, TABLE_NAME.to_string());
+
+    // The authorizer only forwards callers that already have a row, so seed the admin directly
+    let organization_id = Uuid::new_v4().to_string();
+    let admin = User::new(
+        Uuid::new_v4().to_string(),
+        "Integration Admin".to_string(),
+        format!("admin-{}@example.com", Uuid::new_v4()),
+        organization_id.clone(),
+        "Integration Org".to_string(),
+        HashSet::from([Role::Admin]),
+    );
+    repository.create_user(admin.clone()).await.unwrap();
+    let email = format!("user-{}@example.com", Uuid::new_v4());
+
+    // Create
+    let response = users_create::handler(api_event(
+        Method::POST,
+        USERS_RESOURCE,
+        &admin.id,
+        &organization_id,
+        &[("organizationId", &organization_id)],
+        Some(serde_json::json!({
+            "user_name": "Integration User",
+            "email": email,
+            "organization_id": organization_id,
+            "organization_name": "Integration Org",
+            "roles": ["Admin"],
+        })),
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status_code, 200, "{:?}", response.body);
+    assert_eq!(json_body(&response)["user_email"], email);
+    let created = repository
+        .find_user_by_email(&email)
+        .await
+        .unwrap()
+        .expect("created user has a row");
+    assert!(created.email_verified);
+
+    // Get
+    let response = users_get::handler(api_event(
+        Method::GET,
+        USER_RESOURCE,
+        &admin.id,
+        &organization_id,
+        &[
+            ("organizationId", &organization_id),
+            ("userId", &created.id),
+        ],
+        None,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status_code, 200, "{:?}", response.body);
+    let body = json_body(&response);
+    assert_eq!(body["id"], created.id);
+    assert_eq!(body["email"], email);
+    assert_eq!(body["roles"], serde_json::json!(["Admin"]));
+
+    // List and count through the organization index
+    let response = users_get::handler(api_event(
+        Method::GET,
+        USERS_RESOURCE,
+        &admin.id,
+        &organization_id,
+        &[("organizationId", &organization_id)],
+        None,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status_code, 200, "{:?}", response.body);
+    let mut listed: Vec<String> = json_body(&response)["users"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|user| user["id"].as_str().unwrap().to_string())
+        .collect();
+    listed.sort();
+    let mut expected = vec![admin.id.clone(), created.id.clone()];
+    expected.sort();
+    assert_eq!(listed, expected);
+    assert_eq!(
+        response.headers.get("X-Org-Usage").unwrap(),
+        &format!("2/{ORG_USER_QUOTA}")
+    );
+
+    // Update: the handler changes the caller's own record
+    let response = users_update::handler(api_event(
+        Method::PUT,
+        USER_RESOURCE,
+        &created.id,
+        &organization_id,
+        &[
+            ("organizationId", &organization_id),
+            ("userId", &created.id),
+        ],
+        Some(serde_json::json!({
+            "user_name": "Renamed User",
+            "organization_name": "Integration Org",
+            "roles": [],
+            "locale": "ja-JP",
+        })),
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status_code, 200, "{:?}", response.body);
+    let updated = repository
+        .get_user_by_id(created.id.clone(), false)
+        .await
+        .unwrap();
+    assert_eq!(updated.name, "Renamed User");
+    assert_eq!(updated.locale.as_deref(), Some("ja-JP"));
+    assert_eq!(updated.roles, created.roles);
+
+    // Delete: the handler soft-deletes the caller
+    let response = users_delete::handler(api_event(
+        Method::DELETE,
+        USER_RESOURCE,
+        &created.id,
+        &organization_id,
+        &[
+            ("organizationId", &organization_id),
+            ("userId", &created.id),
+        ],
+        None,
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status_code, 200, "{:?}", response.body);
+    assert!(repository
+        .get_user_by_id(created.id.clone(), false)
+        .await
+        .is_err());
+    let deleted = repository
+        .get_user_by_id(created.id.clone(), true)
+        .await
+        .unwrap();
+    assert!(deleted.deleted_at.is_some());
+
+    let config = aws_config::from_env().region(REGION).load().await;
+    let cognito = aws_sdk_cognitoidentityprovider::Client::new(&config);
+    let cognito_user = cognito
+        .admin_get_user()
+        .user_pool_id(&user_pool_id)
+        .username(created.cognito_username())
+        .send()
+        .await
+        .unwrap();
+    assert!(!cognito_user.enabled(), "soft deletion disables sign-in");
+
+    // Clean up what soft deletion leaves behind
+    cognito
+        .admin_delete_user()
+        .user_pool_id(user_pool_id)
+        .username(created.cognito_username())
+        .send()
+        .await
+        .unwrap();
+    for user in [created, admin] {
+        repository
+            .delete_user_by_id(user.id, organization_id.clone())
+            .await
+            .unwrap();
+    }
+}